@@ -45,6 +45,12 @@ fn bench_legal_moves_19x19(c: &mut Criterion) {
     });
 }
 
+fn bench_new_game_19x19(c: &mut Criterion) {
+    c.bench_function("new_game_19x19", |b| {
+        b.iter(|| black_box(Game::<{ nw_for_board(19, 19) }>::new(19, 19)))
+    });
+}
+
 fn bench_make_move(c: &mut Criterion) {
     let game = setup_midgame::<{ nw_for_board(9, 9) }>(9, 9);
     // Pick the first legal placement move
@@ -88,22 +94,26 @@ fn bench_make_unmake(c: &mut Criterion) {
 fn bench_encode_game_planes_9x9(c: &mut Criterion) {
     let game = setup_midgame::<{ nw_for_board(9, 9) }>(9, 9);
     c.bench_function("encode_game_planes_9x9", |b| {
-        b.iter_batched(
-            || game.clone(),
-            |mut g| black_box(encode_game_planes(&mut g)),
-            criterion::BatchSize::SmallInput,
-        )
+        b.iter(|| black_box(encode_game_planes(&game)))
     });
 }
 
 fn bench_encode_game_planes_19x19(c: &mut Criterion) {
     let game = setup_midgame::<{ nw_for_board(19, 19) }>(19, 19);
     c.bench_function("encode_game_planes_19x19", |b| {
-        b.iter_batched(
-            || game.clone(),
-            |mut g| black_box(encode_game_planes(&mut g)),
-            criterion::BatchSize::SmallInput,
-        )
+        b.iter(|| black_box(encode_game_planes(&game)))
+    });
+}
+
+fn bench_score_19x19(c: &mut Criterion) {
+    let game = setup_midgame::<{ nw_for_board(19, 19) }>(19, 19);
+    c.bench_function("score_19x19", |b| b.iter(|| black_box(game.score())));
+}
+
+fn bench_score_fast_19x19(c: &mut Criterion) {
+    let game = setup_midgame::<{ nw_for_board(19, 19) }>(19, 19);
+    c.bench_function("score_fast_19x19", |b| {
+        b.iter(|| black_box(game.score_fast()))
     });
 }
 
@@ -157,7 +167,7 @@ fn bench_self_play_step(c: &mut Criterion) {
             || game.clone(),
             |mut g| {
                 let moves = g.legal_moves();
-                let _planes = encode_game_planes(&mut g);
+                let _planes = encode_game_planes(&g);
                 // Pick the first legal placement (simulating a policy choice)
                 let mv = moves
                     .iter()
@@ -178,10 +188,13 @@ criterion_group!(
     targets =
         bench_legal_moves_9x9,
         bench_legal_moves_19x19,
+        bench_new_game_19x19,
         bench_make_move,
         bench_make_unmake,
         bench_encode_game_planes_9x9,
         bench_encode_game_planes_19x19,
+        bench_score_19x19,
+        bench_score_fast_19x19,
         bench_outcome,
         bench_self_play_step,
 );
@@ -0,0 +1,16 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The gRPC service is generated from its protobuf definition with a
+    // vendored protoc, so no system install is needed.
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/spooky_go.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_prost_build::configure()
+            .build_client(true)
+            .compile_protos(&["proto/spooky_go.proto"], &["proto"])
+            .expect("compiles proto/spooky_go.proto");
+    }
+}
@@ -0,0 +1,85 @@
+/* C API for spooky_go, built with `cargo build --release --features ffi`.
+ *
+ * Games are opaque handles; every handle from spooky_go_new,
+ * spooky_go_new_with_options, spooky_go_from_position_string or
+ * spooky_go_clone must be released with spooky_go_free. A handle may be used
+ * from one thread at a time. Moves are action indices: row * width + col for
+ * a placement, width * height for pass, and width * height + 1 for swap when
+ * the swap rule is on.
+ */
+
+#ifndef SPOOKY_GO_H
+#define SPOOKY_GO_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SPOOKY_GO_ABI_VERSION 2
+
+typedef struct SpookyGoGame SpookyGoGame;
+
+uint32_t spooky_go_abi_version(void);
+
+/* NULL if a side is outside 2..32, or if max_moves is 0 or below
+ * min_moves_before_pass_possible. */
+SpookyGoGame *spooky_go_new(uint8_t width, uint8_t height);
+SpookyGoGame *spooky_go_new_with_options(uint8_t width, uint8_t height, float komi,
+                                         uint16_t min_moves_before_pass_possible,
+                                         uint16_t max_moves, bool superko);
+/* NULL if the string cannot be read. */
+SpookyGoGame *spooky_go_from_position_string(const char *text);
+SpookyGoGame *spooky_go_clone(const SpookyGoGame *game);
+void spooky_go_free(SpookyGoGame *game);
+
+uint8_t spooky_go_width(const SpookyGoGame *game);
+uint8_t spooky_go_height(const SpookyGoGame *game);
+/* 1 for Black, -1 for White. */
+int8_t spooky_go_turn(const SpookyGoGame *game);
+bool spooky_go_is_over(const SpookyGoGame *game);
+/* 1 for Black, -1 for White, 0 for a draw or a game in progress. */
+int8_t spooky_go_winner(const SpookyGoGame *game);
+/* Komi is added to White. Either pointer may be NULL. */
+void spooky_go_score(const SpookyGoGame *game, float *black, float *white);
+/* Moves left before the move limit ends the game, 0 once it is over. */
+uint16_t spooky_go_moves_remaining(const SpookyGoGame *game);
+
+size_t spooky_go_total_actions(const SpookyGoGame *game);
+/* Writes up to capacity action indices and returns the number of legal
+ * moves, which never exceeds spooky_go_total_actions. */
+size_t spooky_go_legal_moves(const SpookyGoGame *game, uint32_t *out, size_t capacity);
+/* false, with the game unchanged, for an illegal or out-of-range action. */
+bool spooky_go_make_move(SpookyGoGame *game, uint32_t action);
+bool spooky_go_resign(SpookyGoGame *game);
+bool spooky_go_unmake_move(SpookyGoGame *game);
+
+size_t spooky_go_num_planes(void);
+/* Returns num_planes * width * height and writes the planes (plane-major,
+ * then row-major) only if len is at least that. */
+size_t spooky_go_encode_planes(const SpookyGoGame *game, float *out, size_t len);
+
+/* Evaluates one position for spooky_go_search: planes as written by
+ * spooky_go_encode_planes in, a prior for each of the policy_len actions and
+ * the value for the player to move (-1 to 1) out. Return 0 on success;
+ * anything else stops the search. */
+typedef int32_t (*SpookyGoEvaluator)(void *user_data, const float *planes, size_t planes_len,
+                                     float *policy, size_t policy_len, float *value);
+
+/* Tree search guided by evaluator for iterations evaluations. Returns the
+ * action of the most visited move, or -1 if the game is over or the
+ * evaluator failed. If visits is not NULL and visits_len is at least
+ * spooky_go_total_actions, the visit count of every action is written
+ * there. */
+int64_t spooky_go_search(const SpookyGoGame *game, SpookyGoEvaluator evaluator, void *user_data,
+                         uint32_t iterations, float exploration, uint32_t *visits,
+                         size_t visits_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SPOOKY_GO_H */
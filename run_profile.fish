@@ -1,4 +1,4 @@
 set SCRIPT_DIR (dirname (status --current-filename))
 cd $SCRIPT_DIR
 
-cargo run --features hotpath,hotpath-alloc,hotpath-mcp --bin profile --release
+cargo run --features search,hotpath,hotpath-alloc,hotpath-mcp --bin profile --release
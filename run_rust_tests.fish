@@ -3,3 +3,4 @@ cd $SCRIPT_DIR
 
 echo "Running Rust tests..."
 cargo test --no-default-features; or exit 1
+cargo test --features full,config,cli,ffi,grpc; or exit 1
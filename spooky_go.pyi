@@ -1,23 +1,77 @@
-from typing import Final
+from collections.abc import Buffer
+from typing import Any, Final, overload
 
 BLACK: Final[int]
 WHITE: Final[int]
 TOTAL_INPUT_PLANES: Final[int]
+HISTORY_LENGTH: Final[int]
+
+def total_actions(
+    width: int, height: int, swap: bool = False, resign: bool = False
+) -> int: ...
+@overload
+def encode_move(move_: Move | Point, width: int, height: int) -> int: ...
+@overload
+def encode_move(col: int, row: int, width: int, height: int) -> int: ...
+def decode_move(action: int, width: int, height: int) -> Move | None: ...
+def nw_for_board(width: int, height: int) -> int: ...
+def decode_wire_messages(
+    data: bytes,
+) -> tuple[list[GameRecord | TrainingSample], int]: ...
+def random_playouts_batch(
+    games: list[Game],
+    playouts: int,
+    seed: int = 0,
+    threads: int = 1,
+    cycle_window: int = 32,
+    cycle_outcome: str = "score",
+) -> tuple[Any, Any]: ...
+def encode_batch(games: list[Game], threads: int = 1) -> Any: ...
+def play_interactive(
+    size: int = 9,
+    policy: str | Policy | None = None,
+    visits: int = 800,
+    computer: str | None = "white",
+    komi: float = 7.5,
+    seed: int = 0,
+    theme: str = "coordinates",
+) -> Game: ...
+
+class IllegalMoveError(ValueError): ...
+class GameOverError(IllegalMoveError): ...
+class InvalidPlayerError(ValueError): ...
+
+Point = Position | tuple[int, int]
 
 class Board:
     def __init__(self, width: int, height: int) -> None: ...
     @staticmethod
     def standard() -> Board: ...
+    @staticmethod
+    def from_showboard(text: str) -> Board: ...
+    @staticmethod
+    def from_numpy(array: Any) -> Board: ...
+    def to_numpy(self) -> Any: ...
     def width(self) -> int: ...
     def height(self) -> int: ...
+    @overload
     def get_piece(self, col: int, row: int) -> int | None: ...
+    @overload
+    def get_piece(self, point: Point) -> int | None: ...
+    @overload
     def set_piece(self, col: int, row: int, piece: int | None) -> None: ...
+    @overload
+    def set_piece(self, point: Point, piece: int | None) -> None: ...
     def clear(self) -> None: ...
+    def __getitem__(self, key: Point) -> int | None: ...
+    def __setitem__(self, key: Point, piece: int | None) -> None: ...
+    def to_svg(self) -> str: ...
+    def render(self, theme: str = "text") -> str: ...
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
 
 class Game:
-    def __init__(self, width: int, height: int) -> None: ...
+    def __init__(self, width: int, height: int, strict: bool = False) -> None: ...
     @staticmethod
     def with_options(
         width: int,
@@ -26,67 +80,362 @@ class Game:
         min_moves_before_pass_possible: int,
         max_moves: int,
         superko: bool,
+        handicap: int = 0,
+        ruleset: str = "chinese",
+        scoring: str = "all_alive",
+        strict: bool = False,
     ) -> Game: ...
     @staticmethod
+    def from_config(path: str) -> Game: ...
+    @staticmethod
+    def self_play_from_config(path: str) -> list[GameRecord]: ...
+    @staticmethod
+    def from_sgf(text: str) -> Game: ...
+    @staticmethod
+    def from_position_string(text: str) -> Game: ...
+    @staticmethod
     def standard() -> Game: ...
+    def strict(self) -> bool: ...
     def komi(self) -> float: ...
     def min_moves_before_pass_possible(self) -> int: ...
     def max_moves(self) -> int: ...
+    def moves_remaining(self) -> int: ...
     def move_count(self) -> int: ...
     def score(self) -> tuple[float, float]: ...
+    def score_half_points(self) -> tuple[int, int]: ...
+    def score_fast(self) -> tuple[float, float]: ...
+    def score_details(self) -> ScoreDetails: ...
     def width(self) -> int: ...
     def height(self) -> int: ...
+    @overload
     def get_piece(self, col: int, row: int) -> int | None: ...
+    @overload
+    def get_piece(self, point: Point) -> int | None: ...
+    def set_position(
+        self,
+        board_array: Any,
+        to_play: int,
+        ko: Point | None = None,
+        move_number: int = 0,
+    ) -> None: ...
+    def __getitem__(self, key: Point) -> int | None: ...
+    @overload
     def set_piece(self, col: int, row: int, piece: int | None) -> None: ...
+    @overload
+    def set_piece(self, point: Point, piece: int | None) -> None: ...
     def turn(self) -> int: ...
     def is_over(self) -> bool: ...
-    def legal_action_indices(self) -> list[int]: ...
-    def apply_action(self, action: int) -> bool: ...
-    def encode_game_planes(self) -> tuple[list[float], int, int, int]: ...
-    def decode_action(self, action: int) -> Move | None: ...
-    def total_actions(self) -> int: ...
+    def legal_action_indices(self, resign: bool = False) -> list[int]: ...
+    def legal_action_mask(self, resign: bool = False) -> Any: ...
+    def legal_action_mask_into(self, out: Buffer, resign: bool = False) -> None: ...
+    def apply_action(self, action: int, resign: bool = False) -> None: ...
+    def encode_game_planes(
+        self, cache: EncodingCache | None = None
+    ) -> tuple[list[float], int, int, int]: ...
+    def encode_game_planes_into(
+        self, out: Buffer, cache: EncodingCache | None = None
+    ) -> tuple[int, int, int]: ...
+    def encode_game_planes_numpy(
+        self, cache: EncodingCache | None = None
+    ) -> Any: ...
+    def symmetries(self, resign: bool = False) -> list[tuple[Any, Any]]: ...
+    def decode_action(self, action: int, resign: bool = False) -> Move | None: ...
+    def total_actions(self, resign: bool = False) -> int: ...
     def board_shape(self) -> tuple[int, int]: ...
     def input_plane_count(self) -> int: ...
     def reward_absolute(self) -> float: ...
     def reward_from_perspective(self, perspective: int) -> float: ...
     def outcome(self) -> GameOutcome | None: ...
     def legal_moves(self) -> list[Move]: ...
+    def capturing_moves(self) -> list[Move]: ...
     def is_legal_move(self, move_: Move) -> bool: ...
-    def make_move(self, move_: Move) -> bool: ...
+    def why_illegal(self, move_: Move) -> str | None: ...
+    def make_move(self, move_: Move) -> None: ...
     def unmake_move(self) -> bool: ...
+    def play_sequence(self, moves: list[Move | int], resign: bool = False) -> int: ...
+    def rewind(self, n: int) -> int: ...
+    def reset_keep_config(self) -> None: ...
+    def play_frequency_map(self) -> list[int]: ...
+    def captures(self) -> tuple[int, int]: ...
+    def passes(self, player: int) -> int: ...
+    def consecutive_passes(self) -> int: ...
+    def movelist_string(self, style: str = "gtp") -> str: ...
+    def to_position_string(self) -> str: ...
+    def move_number(self) -> int: ...
+    def to_sgf(self) -> str: ...
+    def info(self) -> GameInfo: ...
+    def set_info(self, info: GameInfo) -> None: ...
+    def resign(self, player: int) -> None: ...
     def board(self) -> Board: ...
+    def history(self) -> list[tuple[Move, Board]]: ...
     def superko(self) -> bool: ...
+    def allow_suicide(self) -> bool: ...
+    def set_allow_suicide(self, allow_suicide: bool) -> None: ...
+    def swap_rule(self) -> bool: ...
+    def set_swap_rule(self, swap_rule: bool) -> None: ...
+    def dead_stone_marking(self) -> bool: ...
+    def set_dead_stone_marking(self, dead_stone_marking: bool) -> None: ...
+    def phase(self) -> str: ...
+    def mark_dead(self, positions: list[Point]) -> bool: ...
+    def estimate_dead_stones(
+        self,
+        rollouts: int = 64,
+        dead_threshold: float = 0.5,
+        seed: int = 0,
+        cycle_window: int = 32,
+        cycle_outcome: str = "score",
+    ) -> list[tuple[int, int]]: ...
+    def score_with_rollouts(
+        self,
+        rollouts: int = 64,
+        dead_threshold: float = 0.5,
+        seed: int = 0,
+        cycle_window: int = 32,
+        cycle_outcome: str = "score",
+    ) -> ScoreDetails: ...
+    def random_playouts(
+        self,
+        playouts: int,
+        seed: int = 0,
+        threads: int = 1,
+        cycle_window: int = 32,
+        cycle_outcome: str = "score",
+    ) -> tuple[Any, Any]: ...
+    def random_playout(
+        self,
+        seed: int | None = None,
+        cycle_window: int = 32,
+        cycle_outcome: str = "score",
+    ) -> tuple[int | None, Any]: ...
+    def ownership(self, perspective: int | None = None) -> Any: ...
+    @overload
+    def toggle_dead_group(self, col: int, row: int) -> bool: ...
+    @overload
+    def toggle_dead_group(self, point: Point) -> bool: ...
+    @overload
+    def is_dead(self, col: int, row: int) -> bool: ...
+    @overload
+    def is_dead(self, point: Point) -> bool: ...
+    @overload
+    def group_at(self, col: int, row: int) -> list[tuple[int, int]]: ...
+    @overload
+    def group_at(self, point: Point) -> list[tuple[int, int]]: ...
+    @overload
+    def liberties_at(self, col: int, row: int) -> int | None: ...
+    @overload
+    def liberties_at(self, point: Point) -> int | None: ...
+    def pass_alive(self, player: int) -> Any: ...
+    def accept_score(self) -> bool: ...
+    def policy_to_svg(self, policy: list[float], top_k: int = 10) -> str: ...
+    def handicap(self) -> int: ...
+    def place_handicap(self, stones: int) -> bool: ...
+    def effective_komi(self) -> float: ...
+    def ruleset(self) -> str: ...
+    def set_ruleset(self, ruleset: str) -> None: ...
+    def name(self) -> str: ...
     def ko_point(self) -> tuple[int, int] | None: ...
+    def position_hash(self) -> int: ...
+    def analyze_move(self, move_: Move, depth: int = 1) -> MoveAnalysis | None: ...
+    def count_ko_threats(self, player: int) -> int: ...
+    def opening_tree(
+        self, depth: int, symmetry_reduce: bool = True
+    ) -> list[list[Move]]: ...
+    def play_match(
+        self, first: Policy, second: Policy, num_games: int = 2, seed: int = 0
+    ) -> MatchResult: ...
+    def self_play(
+        self, policy: Policy, num_games: int = 1, seed: int = 0
+    ) -> list[GameRecord]: ...
+    def write_leela_zero(self, records: list[GameRecord], path: str) -> int: ...
     def clone(self) -> Game: ...
     def __hash__(self) -> int: ...
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
 
 class Move:
+    @overload
     @staticmethod
     def place(col: int, row: int) -> Move: ...
+    @overload
+    @staticmethod
+    def place(point: Point) -> Move: ...
     @staticmethod
     def pass_move() -> Move: ...
+    @staticmethod
+    def resign() -> Move: ...
+    @staticmethod
+    def swap() -> Move: ...
     def is_pass(self) -> bool: ...
+    def is_resign(self) -> bool: ...
+    def is_swap(self) -> bool: ...
     def col(self) -> int | None: ...
     def row(self) -> int | None: ...
+    def position(self) -> Position | None: ...
     def encode(self, board_width: int, board_height: int) -> int: ...
     @staticmethod
     def decode(action: int, board_width: int, board_height: int) -> Move: ...
+    def to_gtp(self, board_height: int) -> str: ...
+    @staticmethod
+    def from_gtp(s: str, board_width: int, board_height: int) -> Move: ...
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
     def __eq__(self, other: Move) -> bool: ...
     def __hash__(self) -> int: ...
 
+class Position:
+    def __init__(self, col: int, row: int) -> None: ...
+    @staticmethod
+    def from_index(index: int, board_width: int) -> Position: ...
+    @staticmethod
+    def from_gtp(s: str, board_width: int, board_height: int) -> Position: ...
+    def col(self) -> int: ...
+    def row(self) -> int: ...
+    def to_index(self, board_width: int) -> int: ...
+    def is_valid(self, board_width: int, board_height: int) -> bool: ...
+    def to_gtp(self, board_height: int) -> str: ...
+    def to_tuple(self) -> tuple[int, int]: ...
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
 class GameOutcome:
     def winner(self) -> int | None: ...
     def encode_winner_absolute(self) -> float: ...
     def encode_winner_from_perspective(self, perspective: int) -> float: ...
     def is_draw(self) -> bool: ...
+    def margin(self) -> float | None: ...
+    def margin_half_points(self) -> int | None: ...
+    def margin_from_perspective(self, perspective: int) -> float | None: ...
+    def reason(self) -> str: ...
+    def to_result_string(self) -> str: ...
+    @staticmethod
+    def from_result_string(s: str) -> GameOutcome: ...
     def __str__(self) -> str: ...
     def __repr__(self) -> str: ...
     def __eq__(self, other: GameOutcome) -> bool: ...
 
+class ScoreDetails:
+    def stones(self, player: int) -> int: ...
+    def territory(self, player: int) -> int: ...
+    def prisoners(self, player: int) -> int: ...
+    def total(self, player: int) -> float: ...
+    def komi(self) -> float: ...
+    def dame(self) -> int: ...
+    def ruleset(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class GameInfo:
+    def __init__(
+        self,
+        black_player: str | None = None,
+        white_player: str | None = None,
+        black_rank: str | None = None,
+        white_rank: str | None = None,
+        date: str | None = None,
+        event: str | None = None,
+        result: str | None = None,
+    ) -> None: ...
+    def black_player(self) -> str | None: ...
+    def white_player(self) -> str | None: ...
+    def black_rank(self) -> str | None: ...
+    def white_rank(self) -> str | None: ...
+    def date(self) -> str | None: ...
+    def event(self) -> str | None: ...
+    def result(self) -> str | None: ...
+    def __eq__(self, other: GameInfo) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class EncodingCache:
+    def __init__(
+        self,
+        capacity: int = 65536,
+        history_length: int = 8,
+        capture_plane: bool = False,
+    ) -> None: ...
+    def capacity(self) -> int: ...
+    def hits(self) -> int: ...
+    def misses(self) -> int: ...
+    def clear(self) -> None: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class BatchGame:
+    def __init__(self, game: Game, num_games: int, threads: int = 1) -> None: ...
+    def __len__(self) -> int: ...
+    def total_actions(self) -> int: ...
+    def game(self, index: int) -> Game: ...
+    def legal_action_masks(self, out: Buffer) -> None: ...
+    def apply_actions(self, actions: Buffer) -> None: ...
+    def encode_all(self, out: Buffer) -> tuple[int, int, int, int]: ...
+    def dones(self) -> list[bool]: ...
+    def rewards(self, out: Buffer) -> None: ...
+    def rewards_from_perspective(self, perspectives: Buffer, out: Buffer) -> None: ...
+    def outcomes(self) -> list[GameOutcome | None]: ...
+    def turns(self, out: Buffer) -> None: ...
+    def reset_finished(self) -> list[int]: ...
+    def __repr__(self) -> str: ...
+
+class MoveAnalysis:
+    def captures(self) -> int: ...
+    def liberties(self) -> int | None: ...
+    def self_atari(self) -> bool: ...
+    def ataris_created(self) -> int: ...
+    def ataris_resolved(self) -> int: ...
+    def evaluation(self) -> float: ...
+    def __repr__(self) -> str: ...
+
+class Policy:
+    def __init__(self) -> None: ...
+    def choose(self, game: Game) -> Move: ...
+
+class UniformPolicy(Policy):
+    def __init__(self, seed: int = 0) -> None: ...
+
+class NoEyeFillPolicy(Policy):
+    def __init__(self, seed: int = 0) -> None: ...
+
+class HeuristicPolicy(Policy):
+    def __init__(self, seed: int = 0) -> None: ...
+
+class MctsPolicy(Policy):
+    def __init__(
+        self,
+        iterations: int = 1000,
+        exploration: float = ...,
+        seed: int = 0,
+        cycle_window: int = 32,
+        cycle_outcome: str = "score",
+    ) -> None: ...
+    @staticmethod
+    def from_config(path: str) -> MctsPolicy: ...
+
+class GameRecord:
+    def index(self) -> int: ...
+    def seed(self) -> int: ...
+    def moves(self) -> list[Move]: ...
+    def outcome(self) -> GameOutcome | None: ...
+    def to_wire(self) -> bytes: ...
+    def __repr__(self) -> str: ...
+
+class TrainingSample:
+    def planes(self) -> list[float]: ...
+    def num_planes(self) -> int: ...
+    def height(self) -> int: ...
+    def width(self) -> int: ...
+    def policy(self) -> list[float]: ...
+    def value(self) -> float: ...
+    def moves_remaining(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class MatchResult:
+    def first_wins(self) -> int: ...
+    def second_wins(self) -> int: ...
+    def draws(self) -> int: ...
+    def num_games(self) -> int: ...
+    def first_score(self) -> float: ...
+    def records(self) -> list[GameRecord]: ...
+    def __repr__(self) -> str: ...
+
 class GtpEngine:
     def __init__(
         self,
@@ -95,10 +444,16 @@ class GtpEngine:
         size: int = 19,
         komi: float = 7.5,
     ) -> None: ...
+    @staticmethod
+    def connect(
+        host: str, port: int, size: int = 19, komi: float = 7.5
+    ) -> GtpEngine: ...
     def play(self, m: Move) -> None: ...
     def play_as(self, player: int, m: Move) -> None: ...
     def genmove(self) -> Move | None: ...
     def genmove_as(self, player: int) -> Move | None: ...
+    def genmove_cleanup(self) -> Move | None: ...
+    def final_status_list(self, status: str) -> list[tuple[int, int]]: ...
     def undo(self) -> None: ...
     def clear_board(self) -> None: ...
     def set_komi(self, komi: float) -> None: ...
@@ -106,6 +461,7 @@ class GtpEngine:
     def is_over(self) -> bool: ...
     def legal_moves(self) -> list[Move]: ...
     def score(self) -> tuple[float, float]: ...
+    def render(self, theme: str = "text") -> str: ...
     def size(self) -> int: ...
     def engine_name(self) -> str | None: ...
     def engine_version(self) -> str | None: ...
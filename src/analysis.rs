@@ -0,0 +1,632 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::bitboard::{Bitboard, BoardGeometry};
+use crate::board::Board;
+use crate::encode;
+use crate::game::Game;
+use crate::gtp::col_to_letter;
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+use crate::render::{BoardRenderer, BoardView};
+
+/// Number of moves labelled by `policy_to_svg`.
+pub const DEFAULT_TOP_K: usize = 10;
+
+const CELL: f32 = 32.0;
+const MARGIN: f32 = 32.0;
+const STONE_RADIUS: f32 = CELL * 0.47;
+
+#[hotpath::measure]
+fn x(col: u8) -> f32 {
+    MARGIN + col as f32 * CELL
+}
+
+#[hotpath::measure]
+fn y(row: u8, height: u8) -> f32 {
+    MARGIN + (height - 1 - row) as f32 * CELL
+}
+
+/// Distance of the corner star points from the edge: the fourth line on
+/// 13x13 and larger, the third line from 7x7 up, none on smaller boards.
+#[hotpath::measure]
+fn hoshi_edge(size: u8) -> Option<u8> {
+    match size {
+        13.. => Some(3),
+        7..=12 => Some(2),
+        _ => None,
+    }
+}
+
+/// Star points: the four corner points, the center on odd boards from 9x9 up,
+/// and the side points on odd boards from 15x15 up.
+#[hotpath::measure]
+fn hoshi_points(width: u8, height: u8) -> Vec<(u8, u8)> {
+    let (Some(ex), Some(ey)) = (hoshi_edge(width), hoshi_edge(height)) else {
+        return Vec::new();
+    };
+    let odd = width % 2 == 1 && height % 2 == 1;
+    let (cx, cy) = (width / 2, height / 2);
+
+    let mut cols = vec![ex, width - 1 - ex];
+    let mut rows = vec![ey, height - 1 - ey];
+    if odd && width.min(height) >= 15 {
+        cols.push(cx);
+        rows.push(cy);
+    }
+
+    let mut points: Vec<(u8, u8)> = cols
+        .iter()
+        .flat_map(|&c| rows.iter().map(move |&r| (c, r)))
+        .collect();
+    if odd && width.min(height) >= 9 && !points.contains(&(cx, cy)) {
+        points.push((cx, cy));
+    }
+    points
+}
+
+/// Grid, star points, coordinates and stones, without the surrounding `<svg>`.
+#[hotpath::measure]
+fn board_elements(board: &BoardView, out: &mut String) -> std::fmt::Result {
+    let (w, h) = (board.width(), board.height());
+    let right = x(w - 1);
+    let bottom = y(0, h);
+
+    writeln!(
+        out,
+        r##"<rect width="100%" height="100%" fill="#dcb35c"/>"##
+    )?;
+    for col in 0..w {
+        writeln!(
+            out,
+            r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="black"/>"#,
+            x(col),
+            MARGIN,
+            bottom
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="12" text-anchor="middle">{}</text>"#,
+            x(col),
+            MARGIN / 2.0,
+            col_to_letter(col)
+        )?;
+    }
+    for row in 0..h {
+        writeln!(
+            out,
+            r#"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}" stroke="black"/>"#,
+            y(row, h),
+            MARGIN,
+            right
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="12" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            MARGIN / 2.0,
+            y(row, h),
+            row + 1
+        )?;
+    }
+
+    for (col, row) in hoshi_points(w, h) {
+        writeln!(
+            out,
+            r#"<circle cx="{}" cy="{}" r="3" fill="black"/>"#,
+            x(col),
+            y(row, h)
+        )?;
+    }
+
+    for row in 0..h {
+        for col in 0..w {
+            let Some(player) = board.get_piece(&Position::new(col, row)) else {
+                continue;
+            };
+            let fill = match player {
+                Player::Black => "black",
+                Player::White => "white",
+            };
+            writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="black"/>"#,
+                x(col),
+                y(row, h),
+                STONE_RADIUS,
+                fill
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[hotpath::measure]
+fn svg_document(width: u8, height: u8, extra_height: f32, body: &str) -> String {
+    let px_w = 2.0 * MARGIN + (width - 1) as f32 * CELL;
+    let px_h = 2.0 * MARGIN + (height - 1) as f32 * CELL + extra_height;
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif">
+{2}</svg>
+"#,
+        px_w, px_h, body
+    )
+}
+
+/// Render `board` as a standalone SVG image.
+#[hotpath::measure]
+pub fn board_to_svg<const NW: usize>(board: &Board<NW>) -> String {
+    board.render(&SvgRenderer)
+}
+
+/// The board as a standalone SVG image, the `svg` theme of
+/// `render::renderer_for_theme`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SvgRenderer;
+
+#[hotpath::measure_all]
+impl BoardRenderer for SvgRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        let mut body = String::new();
+        board_elements(board, &mut body).expect("writing to a String cannot fail");
+        svg_document(board.width(), board.height(), 0.0, &body)
+    }
+}
+
+/// Render `game`'s board with the `DEFAULT_TOP_K` most likely moves of
+/// `policy` overlaid. See `policy_to_svg_top_k`.
+#[hotpath::measure]
+pub fn policy_to_svg<const NW: usize>(policy: &[f32], game: &Game<NW>) -> String {
+    policy_to_svg_top_k(policy, game, DEFAULT_TOP_K)
+}
+
+/// Render `game`'s board with the `top_k` most likely empty points of `policy`
+/// marked by circles sized by prior and labelled with their percentage.
+///
+/// `policy` uses the action layout of `encode::encode_move` (row-major points,
+/// then pass); the pass probability is written under the board. Missing
+/// entries count as zero.
+#[hotpath::measure]
+pub fn policy_to_svg_top_k<const NW: usize>(
+    policy: &[f32],
+    game: &Game<NW>,
+    top_k: usize,
+) -> String {
+    let board = game.board();
+    let (w, h) = (board.width(), board.height());
+    let points = w as usize * h as usize;
+    let prior = |idx: usize| policy.get(idx).copied().unwrap_or(0.0);
+
+    let mut candidates: Vec<usize> = (0..points)
+        .filter(|&idx| board.get_piece(&Position::from_index(idx, w)).is_none() && prior(idx) > 0.0)
+        .collect();
+    candidates.sort_by(|&a, &b| prior(b).total_cmp(&prior(a)));
+    candidates.truncate(top_k);
+
+    let mut body = String::new();
+    board_elements(&BoardView::from_board(board), &mut body)
+        .expect("writing to a String cannot fail");
+
+    let max_prior = candidates.first().map_or(1.0, |&idx| prior(idx));
+    for &idx in &candidates {
+        let pos = Position::from_index(idx, w);
+        let p = prior(idx);
+        let radius = STONE_RADIUS * (p / max_prior).sqrt().max(0.3);
+        let label = if p >= 0.1 {
+            format!("{:.0}%", p * 100.0)
+        } else {
+            format!("{:.1}%", p * 100.0)
+        };
+        write!(
+            body,
+            r##"<circle cx="{0}" cy="{1}" r="{2}" fill="#1f77b4" fill-opacity="0.6"/>
+<text x="{0}" y="{1}" font-size="10" text-anchor="middle" dominant-baseline="central">{3}</text>
+"##,
+            x(pos.col),
+            y(pos.row, h),
+            radius,
+            label
+        )
+        .expect("writing to a String cannot fail");
+    }
+
+    writeln!(
+        body,
+        r#"<text x="{}" y="{}" font-size="12">pass {:.1}%</text>"#,
+        MARGIN / 2.0,
+        y(0, h) + MARGIN,
+        prior(points) * 100.0
+    )
+    .expect("writing to a String cannot fail");
+
+    svg_document(w, h, MARGIN / 2.0, &body)
+}
+
+/// Consequences of a move, for previewing it before it is played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveAnalysis {
+    /// Opponent stones the move captures.
+    pub captures: u32,
+    /// Liberties of the placed stone's group after the move (`None` for a pass).
+    pub liberties: Option<u32>,
+    /// Whether the placed group is left in atari.
+    pub self_atari: bool,
+    /// Opponent groups put in atari by the move.
+    pub ataris_created: u32,
+    /// The mover's groups in atari before the move that are out of it afterwards.
+    pub ataris_resolved: u32,
+    /// Score margin for the mover after a `depth`-ply minimax search over the
+    /// replies (area count of the final position, komi included).
+    pub evaluation: f32,
+}
+
+/// The connected groups of `stones`.
+#[hotpath::measure]
+fn groups<const NW: usize>(geo: &BoardGeometry<NW>, stones: Bitboard<NW>) -> Vec<Bitboard<NW>> {
+    let mut groups = Vec::new();
+    let mut remaining = stones;
+    while let Some(idx) = remaining.lowest_bit_index() {
+        let group = geo.flood_fill(Bitboard::single(idx), stones);
+        remaining &= !group;
+        groups.push(group);
+    }
+    groups
+}
+
+#[hotpath::measure]
+fn count_liberties<const NW: usize>(
+    geo: &BoardGeometry<NW>,
+    board: &Board<NW>,
+    group: Bitboard<NW>,
+) -> u32 {
+    (geo.neighbors(&group) & board.empty_squares(geo.board_mask)).count()
+}
+
+/// Best score margin the side to move can reach in `depth` plies, with both
+/// sides maximizing their own margin.
+#[hotpath::measure]
+fn negamax<const NW: usize>(game: &mut Game<NW>, depth: u32, mut alpha: f32, beta: f32) -> f32 {
+    let player = game.turn();
+    if depth == 0 || game.is_over() {
+        return game.score_margin_from_perspective(player);
+    }
+
+    let mut best = f32::NEG_INFINITY;
+    for move_ in game.legal_moves() {
+        game.make_move(&move_);
+        let value = -negamax(game, depth - 1, -beta, -alpha);
+        game.unmake_move();
+
+        best = best.max(value);
+        alpha = alpha.max(value);
+        if alpha >= beta {
+            break;
+        }
+    }
+    if best == f32::NEG_INFINITY {
+        game.score_margin_from_perspective(player)
+    } else {
+        best
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Game<NW> {
+    /// What `move_` would do if played now: captures, the placed group's
+    /// liberties, ataris made and escaped, and a shallow search evaluation.
+    /// Returns `None` if the move is not legal (resignation included).
+    ///
+    /// The search looks `depth` plies past the move and costs roughly
+    /// `legal_moves ^ depth` scorings, so keep `depth` small on big boards.
+    pub fn analyze_move(&self, move_: &Move, depth: u32) -> Option<MoveAnalysis> {
+        if move_.is_resign() || !self.is_legal_move(move_) {
+            return None;
+        }
+
+        let geo = BoardGeometry::<NW>::cached(self.width(), self.height());
+        let player = self.turn();
+        let opponent = player.opposite();
+        let before = *self.board();
+
+        let mut after_game = self.clone();
+        after_game.make_move(move_);
+        let after = *after_game.board();
+
+        let captures = before
+            .stones_for(opponent)
+            .count()
+            .saturating_sub(after.stones_for(opponent).count());
+
+        let liberties = move_.position().and_then(|pos| {
+            let bit = Bitboard::single(pos.to_index(geo.width));
+            let own = after.stones_for(player);
+            (own & bit)
+                .is_nonzero()
+                .then(|| count_liberties(&geo, &after, geo.flood_fill(bit, own)))
+        });
+        let self_atari = liberties == Some(1);
+
+        let ataris_created = groups(&geo, after.stones_for(opponent))
+            .into_iter()
+            .filter(|&group| count_liberties(&geo, &after, group) == 1)
+            .filter(|&group| {
+                let seed =
+                    Bitboard::single(group.lowest_bit_index().expect("groups are not empty"));
+                let earlier = geo.flood_fill(seed, before.stones_for(opponent));
+                count_liberties(&geo, &before, earlier) > 1
+            })
+            .count() as u32;
+
+        let own_after = after.stones_for(player);
+        let ataris_resolved = groups(&geo, before.stones_for(player))
+            .into_iter()
+            .filter(|&group| count_liberties(&geo, &before, group) == 1)
+            .filter(|&group| (group & own_after) == group)
+            .filter(|&group| {
+                let now = geo.flood_fill(group, own_after);
+                count_liberties(&geo, &after, now) > 1
+            })
+            .count() as u32;
+
+        let evaluation = -negamax(&mut after_game, depth, f32::NEG_INFINITY, f32::INFINITY);
+
+        Some(MoveAnalysis {
+            captures,
+            liberties,
+            self_atari,
+            ataris_created,
+            ataris_resolved,
+            evaluation,
+        })
+    }
+}
+
+/// Estimate the ko threats `player` has: opponent groups that a stone of
+/// theirs can put in atari while keeping two liberties itself, so the
+/// opponent must answer or lose the group. Each group counts once however
+/// many points threaten it. Turn order, the ko point and superko are
+/// ignored, and ladders are not read out.
+#[hotpath::measure]
+pub fn count_ko_threats<const NW: usize>(game: &Game<NW>, player: Player) -> u32 {
+    let geo = BoardGeometry::<NW>::cached(game.width(), game.height());
+    let board = game.board();
+    let opponent = player.opposite();
+    let empty = board.empty_squares(geo.board_mask);
+
+    // One stone can only put a group with two liberties in atari.
+    groups(&geo, board.stones_for(opponent))
+        .into_iter()
+        .filter(|&group| {
+            let liberties = geo.neighbors(&group) & empty;
+            liberties.count() == 2
+                && liberties.iter_ones().any(|idx| {
+                    let after = game.simulate_placement(idx, player);
+                    let own = geo.flood_fill(Bitboard::single(idx), after.stones_for(player));
+                    let target = geo.flood_fill(group, after.stones_for(opponent));
+                    count_liberties(&geo, &after, own) >= 2
+                        && count_liberties(&geo, &after, target) == 1
+                })
+        })
+        .count() as u32
+}
+
+/// A distinct position found by `opening_tree`.
+#[derive(Clone, Debug)]
+pub struct OpeningPosition<const NW: usize> {
+    /// The first line found from the root to the position.
+    pub moves: Vec<Move>,
+    /// The game after `moves`.
+    pub game: Game<NW>,
+}
+
+/// A key equal for two games exactly when their stones, ko point and player
+/// to move match under one of the first `symmetries` transformations: the
+/// smallest of the transformed point lists.
+#[hotpath::measure]
+fn position_key<const NW: usize>(game: &Game<NW>, symmetries: usize) -> Vec<u8> {
+    let (width, height) = (game.width(), game.height());
+    let ko = game.ko_point();
+    (0..symmetries)
+        .map(|symmetry| {
+            let mut points = vec![0u8; width as usize * height as usize + 1];
+            for row in 0..height {
+                for col in 0..width {
+                    let pos = Position::new(col, row);
+                    let value = match game.board().get_piece(&pos) {
+                        Some(Player::Black) => 1,
+                        Some(Player::White) => 2,
+                        None if ko == Some(pos) => 3,
+                        None => 0,
+                    };
+                    let to = pos.transform(symmetry, width, height);
+                    points[to.to_index(width)] = value;
+                }
+            }
+            points[width as usize * height as usize] = game.turn() as u8;
+            points
+        })
+        .min()
+        .expect("there is at least one symmetry")
+}
+
+/// Every distinct position reachable from `game` in at most `depth` plies,
+/// in breadth-first order starting with `game` itself. Positions are the
+/// same when their stones, ko point and player to move are; with
+/// `symmetry_reduce`, also when they match under a rotation or reflection
+/// of the board (all eight on square boards, the four flips otherwise).
+/// Each position is expanded from the first line that reaches it, and games
+/// that have ended are not expanded.
+///
+/// The tree grows roughly as `points ^ depth`, so it is meant for opening
+/// statistics and exhaustive books on small boards.
+#[hotpath::measure]
+pub fn opening_tree<const NW: usize>(
+    game: &Game<NW>,
+    depth: u32,
+    symmetry_reduce: bool,
+) -> Vec<OpeningPosition<NW>> {
+    let symmetries = if symmetry_reduce {
+        encode::symmetry_count(game.width(), game.height())
+    } else {
+        1
+    };
+    let mut seen = HashSet::new();
+    seen.insert(position_key(game, symmetries));
+    let mut positions = vec![OpeningPosition {
+        moves: Vec::new(),
+        game: game.clone(),
+    }];
+
+    let mut level = 0..1;
+    for _ in 0..depth {
+        let next_start = positions.len();
+        for parent in level.clone() {
+            if positions[parent].game.is_over() {
+                continue;
+            }
+            for move_ in positions[parent].game.legal_moves() {
+                let mut child = positions[parent].game.clone();
+                child.make_move(&move_);
+                if !seen.insert(position_key(&child, symmetries)) {
+                    continue;
+                }
+                let mut moves = positions[parent].moves.clone();
+                moves.push(move_);
+                positions.push(OpeningPosition { moves, game: child });
+            }
+        }
+        level = next_start..positions.len();
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::encode::{encode_move, total_actions};
+    use crate::r#move::Move;
+
+    #[test]
+    fn test_board_svg_draws_stones_and_hoshi() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(2, 2));
+        game.make_move(&Move::place(6, 6));
+
+        let svg = board_to_svg(game.board());
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches(r#"fill="black" stroke="black""#).count(), 1);
+        assert_eq!(svg.matches(r#"fill="white" stroke="black""#).count(), 1);
+        assert_eq!(svg.matches(r#"r="3""#).count(), 5);
+        assert!(svg.contains(">J</text>"));
+
+        assert_eq!(hoshi_points(19, 19).len(), 9);
+        assert_eq!(hoshi_points(13, 13).len(), 5);
+        assert!(hoshi_points(5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_policy_svg_labels_top_k_empty_points() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(4, 4));
+
+        let mut policy = vec![0.0; total_actions(9, 9)];
+        policy[encode_move(&Move::place(4, 4), 9, 9)] = 0.5; // occupied, skipped
+        policy[encode_move(&Move::place(2, 2), 9, 9)] = 0.25;
+        policy[encode_move(&Move::place(6, 6), 9, 9)] = 0.2;
+        policy[encode_move(&Move::place(6, 2), 9, 9)] = 0.05;
+        policy[encode_move(&Move::pass(), 9, 9)] = 0.02;
+
+        let svg = policy_to_svg_top_k(&policy, &game, 2);
+        assert!(svg.contains(">25%</text>"));
+        assert!(svg.contains(">20%</text>"));
+        assert!(!svg.contains(">5.0%</text>"));
+        assert!(!svg.contains(">50%</text>"));
+        assert!(svg.contains("pass 2.0%"));
+
+        assert!(policy_to_svg(&policy, &game).contains(">5.0%</text>"));
+    }
+
+    #[test]
+    fn test_analyze_move_reports_capture_and_atari() {
+        // White C3 is in atari with its last liberty at D3; White D4 has two
+        // liberties left after Black D3.
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 100, true);
+        for (col, row) in [(2, 1), (2, 2), (1, 2), (3, 3), (2, 3), (4, 4)] {
+            assert!(game.make_move(&Move::place(col, row)));
+        }
+
+        let analysis = game
+            .analyze_move(&Move::place(3, 2), 0)
+            .expect("legal move");
+        assert_eq!(analysis.captures, 1);
+        assert_eq!(analysis.liberties, Some(3));
+        assert!(!analysis.self_atari);
+        assert_eq!(analysis.ataris_created, 0);
+        assert_eq!(analysis.ataris_resolved, 0);
+        assert_eq!(analysis.evaluation, {
+            let mut after = game.clone();
+            after.make_move(&Move::place(3, 2));
+            after.score_margin_from_perspective(Player::Black)
+        });
+
+        // White saves C3 by extending; Black's reply then has more to do.
+        game.make_move(&Move::pass());
+        let escape = game
+            .analyze_move(&Move::place(3, 2), 1)
+            .expect("legal move");
+        assert_eq!(escape.ataris_resolved, 1);
+        assert!(escape.evaluation.is_finite());
+
+        assert!(game.analyze_move(&Move::place(2, 2), 1).is_none());
+        assert!(game.analyze_move(&Move::resign(), 1).is_none());
+    }
+
+    #[test]
+    fn test_count_ko_threats() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        assert_eq!(count_ko_threats(&game, Player::Black), 0);
+
+        // Lone corner stones have two liberties; a lone centre stone has four.
+        game.set_piece(&Position::new(0, 0), Some(Player::White));
+        game.set_piece(&Position::new(4, 4), Some(Player::White));
+        game.set_piece(&Position::new(8, 8), Some(Player::Black));
+        assert_eq!(count_ko_threats(&game, Player::Black), 1);
+        assert_eq!(count_ko_threats(&game, Player::White), 1);
+
+        // Both ataris on the corner stone would now leave Black in atari.
+        game.set_piece(&Position::new(2, 0), Some(Player::White));
+        game.set_piece(&Position::new(1, 1), Some(Player::White));
+        assert_eq!(count_ko_threats(&game, Player::Black), 0);
+    }
+
+    #[test]
+    fn test_opening_tree_counts_distinct_positions() {
+        let game = Game::<{ nw_for_board(3, 3) }>::new(3, 3);
+
+        // Passing is not yet allowed: the root, then the corner, edge and
+        // center openings.
+        let tree = opening_tree(&game, 1, true);
+        assert_eq!(tree.len(), 4);
+        assert!(tree[0].moves.is_empty());
+        assert_eq!(tree[1].moves, vec![Move::place(0, 0)]);
+
+        assert_eq!(opening_tree(&game, 1, false).len(), 1 + 9);
+        assert_eq!(opening_tree(&game, 2, false).len(), 1 + 9 + 9 * 8);
+        // By Burnside's lemma, the 72 two-stone positions fall into
+        // (72 + 4 reflections * 6 fixed pairs) / 8 = 12 classes.
+        let reduced = opening_tree(&game, 2, true);
+        assert_eq!(reduced.len(), 1 + 3 + 12);
+        for position in &reduced {
+            let mut replayed = game.clone();
+            for m in &position.moves {
+                assert!(replayed.make_move(m));
+            }
+            assert_eq!(replayed.board(), position.game.board());
+        }
+
+        // A 3x2 board only has the four flips: corner and edge openings.
+        let wide = Game::<{ nw_for_board(3, 2) }>::new(3, 2);
+        assert_eq!(opening_tree(&wide, 1, true).len(), 1 + 2);
+    }
+}
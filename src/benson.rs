@@ -0,0 +1,133 @@
+use crate::bitboard::{Bitboard, BoardGeometry};
+use crate::board::Board;
+use crate::player::Player;
+
+/// The connected components of `points`.
+#[hotpath::measure]
+fn components<const NW: usize>(
+    geo: &BoardGeometry<NW>,
+    mut points: Bitboard<NW>,
+) -> Vec<Bitboard<NW>> {
+    let mut parts = Vec::new();
+    while let Some(idx) = points.lowest_bit_index() {
+        let part = geo.flood_fill(Bitboard::single(idx), points);
+        points = points.andnot(part);
+        parts.push(part);
+    }
+    parts
+}
+
+/// The stones of `player` that are pass-alive by Benson's algorithm, and
+/// the regions they enclose for certain: nothing the opponent plays can
+/// capture them, even if `player` passes every move.
+///
+/// A region, a connected set of points without `player`'s stones, is vital
+/// to a chain when all its empty points are liberties of the chain. Chains
+/// with fewer than two vital regions are dropped, then regions bordering a
+/// dropped chain, until nothing changes. The regions left that are vital to
+/// a chain count as `player`'s, opponent stones in them included.
+#[hotpath::measure]
+pub fn pass_alive<const NW: usize>(board: &Board<NW>, player: Player) -> Bitboard<NW> {
+    let geo = BoardGeometry::<NW>::cached(board.width(), board.height());
+    let own = board.stones_for(player);
+    let empty = board.empty_squares(geo.board_mask);
+    let chains = components(&geo, own);
+    let regions = components(&geo, geo.board_mask.andnot(own));
+
+    let chain_neighbors: Vec<_> = chains.iter().map(|c| geo.neighbors(c)).collect();
+    let vital: Vec<Vec<bool>> = chain_neighbors
+        .iter()
+        .map(|neighbors| {
+            let liberties = *neighbors & empty;
+            regions
+                .iter()
+                .map(|&r| {
+                    let borders = (r & *neighbors).is_nonzero();
+                    borders && (r & empty).andnot(liberties).is_empty()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut chain_alive = vec![true; chains.len()];
+    let mut region_alive = vec![true; regions.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (c, alive) in chain_alive.iter_mut().enumerate() {
+            let vital_regions = (0..regions.len())
+                .filter(|&r| region_alive[r] && vital[c][r])
+                .count();
+            if *alive && vital_regions < 2 {
+                *alive = false;
+                changed = true;
+            }
+        }
+        for (r, alive) in region_alive.iter_mut().enumerate() {
+            let borders_dead = (0..chains.len())
+                .any(|c| !chain_alive[c] && (regions[r] & chain_neighbors[c]).is_nonzero());
+            if *alive && borders_dead {
+                *alive = false;
+                changed = true;
+            }
+        }
+    }
+
+    let mut area = Bitboard::empty();
+    for (c, chain) in chains.iter().enumerate() {
+        if chain_alive[c] {
+            area |= *chain;
+        }
+    }
+    for (r, region) in regions.iter().enumerate() {
+        if region_alive[r] && (0..chains.len()).any(|c| chain_alive[c] && vital[c][r]) {
+            area |= *region;
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::position::Position;
+
+    fn board_from(rows: &[&str]) -> Board<{ nw_for_board(5, 5) }> {
+        let mut board = Board::new(5, 5);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let player = match c {
+                    'X' => Some(Player::Black),
+                    'O' => Some(Player::White),
+                    _ => None,
+                };
+                board.set_piece(&Position::new(col as u8, row as u8), player);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_two_eyes_are_pass_alive() {
+        let board = board_from(&[".X.X.", "XXXXX", ".....", "OOOOO", "O.O.O"]);
+        // The stones and their eyes; the open middle row is not settled.
+        let black = pass_alive(&board, Player::Black);
+        assert_eq!(
+            black.iter_ones().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        let white = pass_alive(&board, Player::White);
+        assert_eq!(
+            white.iter_ones().collect::<Vec<_>>(),
+            (15..25).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_one_eye_is_not_pass_alive() {
+        let board = board_from(&[".X...", "XX...", ".....", ".....", "....."]);
+        assert!(pass_alive(&board, Player::Black).is_empty());
+        assert!(pass_alive(&board, Player::White).is_empty());
+    }
+}
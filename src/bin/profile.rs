@@ -16,7 +16,7 @@ fn play_random_game(rng: &mut SmallRng) -> GameOutcome {
         }
 
         if game.is_over() {
-            return game.outcome().unwrap_or(GameOutcome::Draw);
+            return game.outcome().unwrap_or(GameOutcome::no_result());
         }
 
         let moves = game.legal_moves();
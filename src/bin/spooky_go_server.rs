@@ -0,0 +1,34 @@
+use std::net::SocketAddr;
+use std::process::ExitCode;
+
+/// Address served when none is given.
+const DEFAULT_ADDR: &str = "127.0.0.1:50051";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let addr = match args.as_slice() {
+        [] => DEFAULT_ADDR,
+        [addr] => addr.as_str(),
+        _ => {
+            eprintln!("usage: spooky-go-server [ADDR]");
+            return ExitCode::from(2);
+        }
+    };
+    let addr: SocketAddr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            eprintln!("spooky-go-server: bad address {}: {}", addr, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    eprintln!("spooky-go-server: listening on {}", addr);
+    match spooky_go::grpc::serve(addr).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("spooky-go-server: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
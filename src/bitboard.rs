@@ -1,3 +1,6 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
 
 /// Compute the number of u64 words needed for a board of given dimensions.
@@ -188,8 +191,50 @@ impl<const NW: usize> Bitboard<NW> {
             word_index: 0,
         }
     }
+
+    /// Iterate over indices of set bits, jumping straight between non-empty
+    /// words. Faster than `iter_ones` when only a few bits are set.
+    #[inline]
+    pub fn iter_ones_sparse(&self) -> SparseBitIterator<NW> {
+        debug_assert!(NW <= 32);
+        let mut nonzero_words = 0u32;
+        let mut i = 0;
+        while i < NW {
+            if self.words[i] != 0 {
+                nonzero_words |= 1 << i;
+            }
+            i += 1;
+        }
+        SparseBitIterator {
+            words: self.words,
+            nonzero_words,
+        }
+    }
+
+    /// Iterate over indices that are clear in `self` but set in `mask`, e.g.
+    /// the empty points of a board given its occupancy and board mask.
+    #[inline]
+    pub fn iter_zeros_w(&self, mask: Bitboard<NW>) -> BitIterator<NW> {
+        mask.andnot(*self).iter_ones()
+    }
+
+    /// Like `iter_zeros_w`, but picks the sparse iterator when few bits
+    /// remain (late game, nearly full board).
+    #[inline]
+    pub fn iter_zeros_adaptive(&self, mask: Bitboard<NW>) -> AdaptiveBitIterator<NW> {
+        let zeros = mask.andnot(*self);
+        if zeros.count() < (NW as u32) * SPARSE_BITS_PER_WORD {
+            AdaptiveBitIterator::Sparse(zeros.iter_ones_sparse())
+        } else {
+            AdaptiveBitIterator::Dense(zeros.iter_ones())
+        }
+    }
 }
 
+/// Average set bits per word below which `iter_zeros_adaptive` switches to
+/// the sparse iterator.
+const SPARSE_BITS_PER_WORD: u32 = 8;
+
 #[hotpath::measure_all]
 impl<const NW: usize> BitAnd for Bitboard<NW> {
     type Output = Bitboard<NW>;
@@ -285,6 +330,57 @@ impl<const NW: usize> Iterator for BitIterator<NW> {
     }
 }
 
+/// Iterator over set-bit indices that skips empty words via a word mask.
+pub struct SparseBitIterator<const NW: usize> {
+    words: [u64; NW],
+    nonzero_words: u32,
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Iterator for SparseBitIterator<NW> {
+    type Item = usize;
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        if self.nonzero_words == 0 {
+            return None;
+        }
+        let wi = self.nonzero_words.trailing_zeros() as usize;
+        let w = self.words[wi];
+        let bit = w.trailing_zeros() as usize;
+        let rest = w & (w - 1);
+        self.words[wi] = rest;
+        if rest == 0 {
+            self.nonzero_words &= self.nonzero_words - 1;
+        }
+        Some(wi * 64 + bit)
+    }
+}
+
+/// Either a dense or a sparse set-bit iterator, chosen by population count.
+pub enum AdaptiveBitIterator<const NW: usize> {
+    Dense(BitIterator<NW>),
+    Sparse(SparseBitIterator<NW>),
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Iterator for AdaptiveBitIterator<NW> {
+    type Item = usize;
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            AdaptiveBitIterator::Dense(it) => it.next(),
+            AdaptiveBitIterator::Sparse(it) => it.next(),
+        }
+    }
+}
+
+thread_local! {
+    /// Per-thread cache of built geometries, keyed by (width, height). The key
+    /// determines `NW`, so each entry holds a `BoardGeometry<NW>` for one `NW`.
+    static GEOMETRY_CACHE: RefCell<HashMap<(u8, u8), Box<dyn Any>>> =
+        RefCell::new(HashMap::new());
+}
+
 /// Precomputed masks for a given board geometry. Created once per Game.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct BoardGeometry<const NW: usize> {
@@ -299,48 +395,93 @@ pub struct BoardGeometry<const NW: usize> {
     pub not_col_last: Bitboard<NW>,
 }
 
-#[hotpath::measure_all]
 impl<const NW: usize> BoardGeometry<NW> {
-    /// Build geometry for a `width × height` board.
-    pub fn new(width: u8, height: u8) -> Self {
-        debug_assert!((2..=32).contains(&width));
-        debug_assert!((2..=32).contains(&height));
+    /// Build geometry for a `width × height` board. Usable in const context,
+    /// see `GEOMETRY_9X9` and friends.
+    pub const fn new(width: u8, height: u8) -> Self {
+        debug_assert!(width >= 2 && width <= 32);
+        debug_assert!(height >= 2 && height <= 32);
         let area = width as u16 * height as u16;
         assert!(
-            NW == (area as usize).div_ceil(64),
-            "NW={} does not match board {}x{} (need {})",
-            NW,
-            width,
-            height,
-            (area as usize).div_ceil(64)
+            NW == nw_for_board(width, height),
+            "NW does not match board size (see nw_for_board)"
         );
         let w = width as usize;
         let h = height as usize;
 
-        let mut board_mask = Bitboard::empty();
-        for i in 0..area as usize {
-            board_mask.set(i);
+        let mut mask = [0u64; NW];
+        let mut i = 0;
+        while i < area as usize {
+            mask[i / 64] |= 1u64 << (i % 64);
+            i += 1;
         }
 
-        let mut not_col0 = board_mask;
-        for row in 0..h {
-            not_col0.clear(row * w); // column 0
+        let mut col0 = [0u64; NW];
+        let mut col_last = [0u64; NW];
+        let mut row = 0;
+        while row < h {
+            let first = row * w; // column 0
+            let last = row * w + w - 1; // last column
+            col0[first / 64] |= 1u64 << (first % 64);
+            col_last[last / 64] |= 1u64 << (last % 64);
+            row += 1;
         }
 
-        let mut not_col_last = board_mask;
-        for row in 0..h {
-            not_col_last.clear(row * w + w - 1); // last column
+        let mut not_col0 = [0u64; NW];
+        let mut not_col_last = [0u64; NW];
+        let mut wi = 0;
+        while wi < NW {
+            not_col0[wi] = mask[wi] & !col0[wi];
+            not_col_last[wi] = mask[wi] & !col_last[wi];
+            wi += 1;
         }
 
         BoardGeometry {
             width,
             height,
             area,
-            board_mask,
-            not_col0,
-            not_col_last,
+            board_mask: Bitboard::from_words(mask),
+            not_col0: Bitboard::from_words(not_col0),
+            not_col_last: Bitboard::from_words(not_col_last),
         }
     }
+}
+
+/// Geometry for the standard board sizes, computed at compile time.
+pub const GEOMETRY_9X9: BoardGeometry<{ nw_for_board(9, 9) }> = BoardGeometry::new(9, 9);
+pub const GEOMETRY_13X13: BoardGeometry<{ nw_for_board(13, 13) }> = BoardGeometry::new(13, 13);
+pub const GEOMETRY_19X19: BoardGeometry<{ nw_for_board(19, 19) }> = BoardGeometry::new(19, 19);
+
+#[hotpath::measure_all]
+impl<const NW: usize> BoardGeometry<NW> {
+    /// The compile-time geometry for 9x9, 13x13 and 19x19 boards.
+    pub fn standard(width: u8, height: u8) -> Option<Self> {
+        let geo: &dyn Any = match (width, height) {
+            (9, 9) => &GEOMETRY_9X9,
+            (13, 13) => &GEOMETRY_13X13,
+            (19, 19) => &GEOMETRY_19X19,
+            _ => return None,
+        };
+        geo.downcast_ref::<Self>().copied()
+    }
+
+    /// Like `new`, but built at most once per thread for each board size and
+    /// copied from a cache afterwards. Standard sizes come from the
+    /// compile-time constants and skip the cache.
+    pub fn cached(width: u8, height: u8) -> Self {
+        if let Some(geo) = Self::standard(width, height) {
+            return geo;
+        }
+        GEOMETRY_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let entry = cache
+                .entry((width, height))
+                .or_insert_with(|| Box::new(Self::new(width, height)));
+            *entry
+                .downcast_ref::<Self>()
+                .expect("BoardGeometry::cached: NW does not match board size")
+        })
+    }
 
     /// Compute the set of all orthogonal neighbors of every bit in `bb`.
     #[inline]
@@ -494,6 +635,41 @@ mod tests {
         assert_eq!(indices, vec![3, 64, 200]);
     }
 
+    #[test]
+    fn test_iter_ones_sparse_matches_iter_ones() {
+        let bb = Bitboard::<6>::single(0)
+            | Bitboard::<6>::single(63)
+            | Bitboard::<6>::single(64)
+            | Bitboard::<6>::single(300)
+            | Bitboard::<6>::single(383);
+        let dense: Vec<usize> = bb.iter_ones().collect();
+        let sparse: Vec<usize> = bb.iter_ones_sparse().collect();
+        assert_eq!(sparse, dense);
+        assert_eq!(Bitboard::<6>::empty().iter_ones_sparse().count(), 0);
+    }
+
+    #[test]
+    fn test_iter_zeros_w() {
+        let geo = BoardGeometry::<{ nw_for_board(3, 3) }>::new(3, 3);
+        let occupied = Bitboard::single(0) | Bitboard::single(4) | Bitboard::single(8);
+        let zeros: Vec<usize> = occupied.iter_zeros_w(geo.board_mask).collect();
+        assert_eq!(zeros, vec![1, 2, 3, 5, 6, 7]);
+
+        // Nearly full board takes the sparse path and yields the same indices
+        let full = geo.board_mask.andnot(Bitboard::single(5));
+        assert!(matches!(
+            full.iter_zeros_adaptive(geo.board_mask),
+            AdaptiveBitIterator::Sparse(_)
+        ));
+        let adaptive: Vec<usize> = full.iter_zeros_adaptive(geo.board_mask).collect();
+        assert_eq!(adaptive, vec![5]);
+
+        let dense: Vec<usize> = Bitboard::empty()
+            .iter_zeros_adaptive(geo.board_mask)
+            .collect();
+        assert_eq!(dense, (0..9).collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_iter_ones_empty() {
         let bb = Bitboard::<2>::empty();
@@ -501,6 +677,37 @@ mod tests {
         assert!(indices.is_empty());
     }
 
+    #[test]
+    fn test_geometry_cached_matches_new() {
+        let a = BoardGeometry::<{ nw_for_board(9, 9) }>::cached(9, 9);
+        let b = BoardGeometry::<{ nw_for_board(9, 9) }>::cached(9, 9);
+        assert_eq!(a, BoardGeometry::new(9, 9));
+        assert_eq!(a, b);
+
+        // Same NW, different size: must not share an entry.
+        let c = BoardGeometry::<{ nw_for_board(9, 8) }>::cached(9, 8);
+        assert_eq!(c, BoardGeometry::new(9, 8));
+        assert_ne!(c.board_mask, a.board_mask);
+    }
+
+    #[test]
+    fn test_standard_geometry_constants() {
+        const AREA: u16 = GEOMETRY_19X19.area;
+        assert_eq!(AREA, 361);
+
+        assert_eq!(GEOMETRY_9X9, BoardGeometry::new(9, 9));
+        assert_eq!(GEOMETRY_13X13, BoardGeometry::new(13, 13));
+        assert_eq!(GEOMETRY_19X19, BoardGeometry::new(19, 19));
+        assert_eq!(
+            BoardGeometry::<{ nw_for_board(19, 19) }>::standard(19, 19),
+            Some(GEOMETRY_19X19)
+        );
+        assert_eq!(
+            BoardGeometry::<{ nw_for_board(9, 8) }>::standard(9, 8),
+            None
+        );
+    }
+
     #[test]
     fn test_geometry_9x9() {
         let geo = BoardGeometry::<{ nw_for_board(9, 9) }>::new(9, 9);
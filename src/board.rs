@@ -4,6 +4,7 @@ use std::hash::{Hash, Hasher};
 use crate::bitboard::{nw_for_board, Bitboard};
 use crate::player::Player;
 use crate::position::Position;
+use crate::render::{BoardRenderer, BoardView, TextRenderer};
 
 pub const STANDARD_COLS: u8 = 19;
 pub const STANDARD_ROWS: u8 = 19;
@@ -138,6 +139,223 @@ impl<const NW: usize> Board<NW> {
         self.black.clear(idx);
         self.white.clear(idx);
     }
+
+    /// This board drawn by `renderer` (see `render::renderer_for_theme`).
+    pub fn render(&self, renderer: &dyn BoardRenderer) -> String {
+        renderer.render(&BoardView::from_board(self))
+    }
+}
+
+/// The points that differ between two boards of one size, e.g. the stones
+/// placed and captured by a move. Sending diffs instead of whole boards
+/// keeps updates to spectators small.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoardDiff {
+    pub width: u8,
+    pub height: u8,
+    /// Each changed point with its new contents, in row-major order.
+    pub changes: Vec<(Position, Option<Player>)>,
+}
+
+/// Why a `BoardDiff` could not be read or applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardDiffError {
+    /// The JSON is not in the layout written by `BoardDiff::to_json`.
+    Json(String),
+    /// The diff is for a board of another size.
+    SizeMismatch { diff: (u8, u8), board: (u8, u8) },
+    /// A change lies off the board.
+    OffBoard(Position),
+}
+
+impl fmt::Display for BoardDiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardDiffError::Json(msg) => write!(f, "Invalid board diff: {}", msg),
+            BoardDiffError::SizeMismatch { diff, board } => write!(
+                f,
+                "Board diff is for {}x{}, board is {}x{}",
+                diff.0, diff.1, board.0, board.1
+            ),
+            BoardDiffError::OffBoard(pos) => {
+                write!(
+                    f,
+                    "Board diff changes ({}, {}), off the board",
+                    pos.col, pos.row
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardDiffError {}
+
+#[hotpath::measure_all]
+impl BoardDiff {
+    /// The changes that turn `before` into `after`, which must be the same
+    /// size.
+    pub fn between<const NW: usize>(before: &Board<NW>, after: &Board<NW>) -> Self {
+        debug_assert_eq!((before.width, before.height), (after.width, after.height));
+        let changed = before.black.andnot(after.black)
+            | after.black.andnot(before.black)
+            | before.white.andnot(after.white)
+            | after.white.andnot(before.white);
+        let changes = changed
+            .iter_ones_sparse()
+            .map(|idx| {
+                let pos = Position::from_index(idx, after.width);
+                (pos, after.get_piece(&pos))
+            })
+            .collect();
+        BoardDiff {
+            width: after.width,
+            height: after.height,
+            changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Make the changes on `board`. Nothing is changed if the diff is for
+    /// another size or reaches off the board.
+    pub fn apply<const NW: usize>(&self, board: &mut Board<NW>) -> Result<(), BoardDiffError> {
+        if (self.width, self.height) != (board.width, board.height) {
+            return Err(BoardDiffError::SizeMismatch {
+                diff: (self.width, self.height),
+                board: (board.width, board.height),
+            });
+        }
+        if let Some(&(pos, _)) = self
+            .changes
+            .iter()
+            .find(|(pos, _)| !pos.is_valid(self.width, self.height))
+        {
+            return Err(BoardDiffError::OffBoard(pos));
+        }
+        for (pos, player) in &self.changes {
+            board.set_piece(pos, *player);
+        }
+        Ok(())
+    }
+
+    /// Compact JSON: `{"width":9,"height":9,"changes":[[col,row,piece],...]}`
+    /// with `piece` 1 for Black, -1 for White and 0 for an emptied point.
+    pub fn to_json(&self) -> String {
+        let changes: Vec<String> = self
+            .changes
+            .iter()
+            .map(|(pos, player)| {
+                format!(
+                    "[{},{},{}]",
+                    pos.col,
+                    pos.row,
+                    player.map_or(0, |p| p as i8)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"width\":{},\"height\":{},\"changes\":[{}]}}",
+            self.width,
+            self.height,
+            changes.join(",")
+        )
+    }
+
+    /// Read a diff written by `to_json`. Whitespace between tokens is
+    /// allowed; the keys must come in the order `to_json` writes them.
+    pub fn from_json(text: &str) -> Result<Self, BoardDiffError> {
+        let mut json = JsonCursor { rest: text };
+        json.expect("{")?;
+        json.expect("\"width\"")?;
+        json.expect(":")?;
+        let width = json.int()?;
+        json.expect(",")?;
+        json.expect("\"height\"")?;
+        json.expect(":")?;
+        let height = json.int()?;
+        json.expect(",")?;
+        json.expect("\"changes\"")?;
+        json.expect(":")?;
+        json.expect("[")?;
+        let mut changes = Vec::new();
+        if !json.eat("]") {
+            loop {
+                json.expect("[")?;
+                let col = json.int()?;
+                json.expect(",")?;
+                let row = json.int()?;
+                json.expect(",")?;
+                let piece = json.int()?;
+                json.expect("]")?;
+                let player = match piece {
+                    0 => None,
+                    p => Some(Player::from_int(p as i8).ok_or_else(|| {
+                        BoardDiffError::Json(format!("piece {} is not 1, -1 or 0", p))
+                    })?),
+                };
+                changes.push((Position::new(to_u8(col)?, to_u8(row)?), player));
+                if json.eat("]") {
+                    break;
+                }
+                json.expect(",")?;
+            }
+        }
+        json.expect("}")?;
+        if !json.rest.trim().is_empty() {
+            return Err(BoardDiffError::Json("trailing characters".to_string()));
+        }
+        Ok(BoardDiff {
+            width: to_u8(width)?,
+            height: to_u8(height)?,
+            changes,
+        })
+    }
+}
+
+#[hotpath::measure]
+fn to_u8(value: i64) -> Result<u8, BoardDiffError> {
+    u8::try_from(value).map_err(|_| BoardDiffError::Json(format!("{} is out of range", value)))
+}
+
+/// Reads the fixed layout of `BoardDiff::to_json`.
+struct JsonCursor<'a> {
+    rest: &'a str,
+}
+
+#[hotpath::measure_all]
+impl JsonCursor<'_> {
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest.trim_start().strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), BoardDiffError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(BoardDiffError::Json(format!("expected {}", token)))
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, BoardDiffError> {
+        let rest = self.rest.trim_start();
+        let end = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+            .map_or(rest.len(), |(i, _)| i);
+        let value = rest[..end]
+            .parse()
+            .map_err(|_| BoardDiffError::Json("expected an integer".to_string()))?;
+        self.rest = &rest[end..];
+        Ok(value)
+    }
 }
 
 #[hotpath::measure_all]
@@ -147,6 +365,13 @@ impl Default for Board<{ nw_for_board(STANDARD_COLS, STANDARD_ROWS) }> {
     }
 }
 
+#[hotpath::measure_all]
+impl<const NW: usize> fmt::Display for Board<NW> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(&TextRenderer::default()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,36 +391,49 @@ mod tests {
         // 19x19 (NW=6): ~104 bytes vs old 258
         assert!(size_19x19 <= 104, "19x19 Board too large: {}", size_19x19);
     }
-}
-
-#[hotpath::measure_all]
-impl<const NW: usize> fmt::Display for Board<NW> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in (0..self.height as usize).rev() {
-            write!(f, "|")?;
-
-            for col in 0..self.width as usize {
-                let pos = Position::new(col as u8, row as u8);
-                let c = if let Some(player) = self.get_piece(&pos) {
-                    player.to_char()
-                } else {
-                    '.'
-                };
 
-                write!(f, "{}", c)?;
-                write!(f, "|")?;
-            }
+    #[test]
+    fn test_board_diff_round_trip() {
+        type Board5 = Board<{ nw_for_board(5, 5) }>;
+        let mut before = Board5::new(5, 5);
+        before.set_piece(&Position::new(1, 0), Some(Player::White));
+        before.set_piece(&Position::new(4, 4), Some(Player::Black));
+        let mut after = before;
+        after.set_piece(&Position::new(1, 0), None);
+        after.set_piece(&Position::new(0, 0), Some(Player::Black));
 
-            writeln!(f)?;
-        }
+        let diff = BoardDiff::between(&before, &after);
+        assert!(BoardDiff::between(&after, &after).is_empty());
+        let json = diff.to_json();
+        assert_eq!(
+            json,
+            r#"{"width":5,"height":5,"changes":[[0,0,1],[1,0,0]]}"#
+        );
+        let read = BoardDiff::from_json(
+            " { \"width\": 5, \"height\":5,\n\"changes\": [ [0, 0, 1], [1,0,0] ] } ",
+        )
+        .expect("reads");
+        assert_eq!(read, diff);
+        let mut board = before;
+        read.apply(&mut board).expect("applies");
+        assert_eq!(board, after);
 
-        // Column numbers
-        write!(f, " ")?;
-        for col in 0..self.width as usize {
-            write!(f, "{} ", col)?;
+        assert!(matches!(
+            diff.apply(&mut Board::<{ nw_for_board(9, 9) }>::new(9, 9)),
+            Err(BoardDiffError::SizeMismatch { .. })
+        ));
+        for bad in [
+            r#"{"width":5,"height":5,"changes":[[0,0,2]]}"#,
+            r#"{"width":5,"height":5,"changes":[[0,0,1]"#,
+            r#"{"width":5,"height":5,"changes":[]} x"#,
+        ] {
+            assert!(BoardDiff::from_json(bad).is_err(), "{}", bad);
         }
-        writeln!(f)?;
-
-        Ok(())
+        let off_board =
+            BoardDiff::from_json(r#"{"width":5,"height":5,"changes":[[7,0,1]]}"#).expect("reads");
+        assert_eq!(
+            off_board.apply(&mut board),
+            Err(BoardDiffError::OffBoard(Position::new(7, 0)))
+        );
     }
 }
@@ -0,0 +1,929 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, from_json_str, Config, ConfigError, RulesConfig};
+use crate::dataset::{convert_sgf_dirs, ConvertError, ShardOptions};
+use crate::dispatch::{make_game_inner_with_options, GameInner};
+use crate::encode::EncoderConfig;
+use crate::eval::{sweep, SweepConfig};
+use crate::game::Game;
+use crate::outcome::{EndReason, GameOutcome};
+use crate::player::Player;
+use crate::policy::{preset, Difficulty, MctsPolicy, MovePolicy};
+use crate::position::Position;
+use crate::position_string::{parse_position_string, PositionStringError};
+use crate::r#move::Move;
+use crate::render::renderer_for_theme;
+use crate::replay::IllegalMovePolicy;
+use crate::rollout::{score_with_rollouts, RolloutConfig};
+use crate::selfplay::run_self_play;
+use crate::sgf::{parse_sgf, SgfError};
+use crate::terminal::play_in_terminal;
+
+const USAGE: &str = "\
+usage: spooky-go <command> [options]
+
+commands:
+  play [--size N] [--komi K] [--computer black|white] [--playouts N] [--seed N]
+       [--theme coordinates|text|unicode]
+      Play a game in the terminal. Enter moves as GTP vertices (D4), `pass`,
+      `resign`, `undo` or `quit`.
+  score FILE [--rollouts N]
+      Score a game, removing the stones random playouts judge dead
+      (--rollouts 0 scores the board as it stands).
+  convert FILE --to sgf|position|json
+      Convert a game between SGF, position strings and JSON.
+  perft DEPTH [FILE] [--size N] [--divide]
+      Count the move sequences DEPTH moves long, from FILE or an empty board.
+  dataset DIR... --encoder ENCODER.toml --out DIR [--shard-size N]
+          [--illegal skip|repair]
+      Encode every game of the SGF files under the DIRs as training shards
+      and print a summary of the games kept and dropped.
+  self-play CONFIG
+      Play the [selfplay] games of a TOML or JSON config with the [rules]
+      and [search] sections, and print each result.
+  sweep --first POLICY --second POLICY [--sizes N,...] [--komis K,...]
+        [--handicaps N,...] [--games N] [--seed N] [--format csv|parquet]
+      Play a match between two policies (random, weak, medium or strong)
+      for every combination of board size, komi and handicap, and print a
+      row of results for each. Parquet needs the parquet feature.
+
+FILE is SGF, a position string or JSON, detected from its contents; `-`
+reads standard input.";
+
+/// Errors from the `spooky-go` command line.
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad arguments; the message is shown above the usage text.
+    Usage(String),
+    Io(std::io::Error),
+    Sgf(SgfError),
+    PositionString(PositionStringError),
+    /// A JSON game could not be read.
+    Json(ConfigError),
+    /// A config file could not be read or is invalid.
+    Config(ConfigError),
+    Dataset(ConvertError),
+    /// A move of a JSON game cannot be read or played.
+    InvalidMove(String),
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<SgfError> for CliError {
+    fn from(e: SgfError) -> Self {
+        CliError::Sgf(e)
+    }
+}
+
+impl From<PositionStringError> for CliError {
+    fn from(e: PositionStringError) -> Self {
+        CliError::PositionString(e)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            CliError::Io(e) => write!(f, "I/O error: {}", e),
+            CliError::Sgf(e) => write!(f, "{}", e),
+            CliError::PositionString(e) => write!(f, "{}", e),
+            CliError::Json(e) => write!(f, "cannot read JSON game: {}", e),
+            CliError::Config(e) => write!(f, "{}", e),
+            CliError::Dataset(e) => write!(f, "{}", e),
+            CliError::InvalidMove(msg) => write!(f, "invalid move: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CliError::Io(e) => Some(e),
+            CliError::Sgf(e) => Some(e),
+            CliError::PositionString(e) => Some(e),
+            CliError::Json(e) | CliError::Config(e) => Some(e),
+            CliError::Dataset(e) => Some(e),
+            CliError::Usage(_) | CliError::InvalidMove(_) => None,
+        }
+    }
+}
+
+/// The JSON form of a game for `convert`: the rules, the starting position
+/// as a position string when there are setup stones, and the moves in GTP
+/// notation.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameFile {
+    rules: RulesConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(default)]
+    moves: Vec<String>,
+}
+
+/// Positional arguments and `--name value` options (or bare `--name` for
+/// the names in `flags`).
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    options: HashMap<&'a str, &'a str>,
+}
+
+#[hotpath::measure_all]
+impl<'a> Args<'a> {
+    fn parse(args: &'a [String], known: &[&str], flags: &[&str]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                positional.push(arg.as_str());
+                continue;
+            };
+            if flags.contains(&name) {
+                options.insert(name, "");
+            } else if known.contains(&name) {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?;
+                options.insert(name, value.as_str());
+            } else {
+                return Err(CliError::Usage(format!("unknown option --{}", name)));
+            }
+        }
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    /// The value of `--name` parsed as a `T`, or `default` when absent.
+    fn get<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.options.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| CliError::Usage(format!("invalid value for --{}: {}", name, value))),
+            None => Ok(default),
+        }
+    }
+
+    /// The comma-separated values of `--name` parsed as `T`s, or `default`
+    /// when absent.
+    fn get_list<T: std::str::FromStr>(
+        &self,
+        name: &str,
+        default: Vec<T>,
+    ) -> Result<Vec<T>, CliError> {
+        match self.options.get(name) {
+            Some(values) => values
+                .split(',')
+                .map(|value| {
+                    value.trim().parse().map_err(|_| {
+                        CliError::Usage(format!("invalid value for --{}: {}", name, value))
+                    })
+                })
+                .collect(),
+            None => Ok(default),
+        }
+    }
+}
+
+/// The policy `preset` gives for a difficulty, on boards of any size.
+struct PresetPolicy(Difficulty);
+
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for PresetPolicy {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        preset::<NW>(self.0).choose(game, rng)
+    }
+
+    fn dead_stones(&mut self, game: &Game<NW>) -> Vec<Position> {
+        preset::<NW>(self.0).dead_stones(game)
+    }
+}
+
+/// Run the `spooky-go` command line with `args` (without the program name).
+/// `play` and `-` files read from `input`; results are written to `out`.
+#[hotpath::measure]
+pub fn run<R: BufRead, W: Write>(args: &[String], input: R, out: &mut W) -> Result<(), CliError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(CliError::Usage("no command given".to_string()));
+    };
+    match command.as_str() {
+        "play" => play(rest, input, out),
+        "score" => score(rest, input, out),
+        "convert" => convert(rest, input, out),
+        "perft" => perft_command(rest, input, out),
+        "dataset" => dataset(rest, out),
+        "self-play" => self_play(rest, out),
+        "sweep" => sweep_command(rest, out),
+        "help" | "--help" | "-h" => {
+            writeln!(out, "{}", USAGE)?;
+            Ok(())
+        }
+        _ => Err(CliError::Usage(format!("unknown command {}", command))),
+    }
+}
+
+/// The contents of `path`, or all of `input` for `-`.
+#[hotpath::measure]
+fn read_source<R: BufRead>(path: &str, mut input: R) -> Result<String, CliError> {
+    if path == "-" {
+        let mut text = String::new();
+        input.read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+/// A game with `rules` and nothing played.
+#[hotpath::measure]
+fn new_game(rules: &RulesConfig) -> GameInner {
+    let mut inner = make_game_inner_with_options(
+        rules.width,
+        rules.height,
+        rules.komi,
+        rules.min_moves_before_pass_possible(),
+        rules.max_moves(),
+        rules.superko,
+    );
+    dispatch_game_mut!(&mut inner, g => rules.apply(g));
+    inner
+}
+
+/// Check rules built from command-line options; values they cannot hold,
+/// such as `--size 40`, are usage errors.
+#[hotpath::measure]
+fn check_option_rules(rules: &RulesConfig) -> Result<(), CliError> {
+    rules.validate().map_err(|e| match e {
+        ConfigError::Invalid(msg) => CliError::Usage(msg),
+        e => CliError::Config(e),
+    })
+}
+
+/// Read a game from SGF (text starting with `(`), JSON (starting with `{`)
+/// or a position string.
+#[hotpath::measure]
+fn load_game(text: &str) -> Result<GameInner, CliError> {
+    let text = text.trim();
+    if text.starts_with('(') {
+        let record = parse_sgf(text)?;
+        let mut inner = make_game_inner_with_options(
+            record.width,
+            record.height,
+            record.komi,
+            0,
+            u16::MAX,
+            true,
+        );
+        dispatch_game_mut!(&mut inner, g => {
+            record.set_up(g);
+            record.replay_into(g, IllegalMovePolicy::Fail)?;
+        });
+        Ok(inner)
+    } else if text.starts_with('{') {
+        let file: GameFile = from_json_str(text).map_err(CliError::Json)?;
+        file.rules.validate().map_err(CliError::Json)?;
+        let mut inner = new_game(&file.rules);
+        dispatch_game_mut!(&mut inner, g => {
+            if let Some(start) = &file.start {
+                let record = parse_position_string(start)?;
+                if (record.width, record.height) != (g.width(), g.height()) {
+                    return Err(CliError::PositionString(
+                        PositionStringError::InvalidBoardSize(format!(
+                            "start position is {}x{}, the rules {}x{}",
+                            record.width,
+                            record.height,
+                            g.width(),
+                            g.height()
+                        )),
+                    ));
+                }
+                record.set_up(g);
+            }
+            for text in &file.moves {
+                match Move::from_gtp(text, g.width(), g.height()) {
+                    Some(m) if g.make_move(&m) => {}
+                    _ => return Err(CliError::InvalidMove(text.clone())),
+                }
+            }
+        });
+        Ok(inner)
+    } else {
+        let record = parse_position_string(text)?;
+        let rules = RulesConfig {
+            width: record.width,
+            height: record.height,
+            ..RulesConfig::default()
+        };
+        let mut inner = new_game(&rules);
+        dispatch_game_mut!(&mut inner, g => record.set_up(g));
+        Ok(inner)
+    }
+}
+
+/// `game` as JSON (see `GameFile`). Handicap stones are written as part of
+/// the start position.
+#[hotpath::measure]
+fn game_to_json<const NW: usize>(game: &Game<NW>) -> Result<String, CliError> {
+    let mut start = game.clone();
+    while start.unmake_move() {}
+    let has_setup = start.board().black_stones().is_nonzero()
+        || start.board().white_stones().is_nonzero()
+        || start.turn() != Player::Black;
+
+    let file = GameFile {
+        rules: RulesConfig {
+            width: game.width(),
+            height: game.height(),
+            komi: game.komi(),
+            min_moves_before_pass_possible: Some(game.min_moves_before_pass_possible()),
+            max_moves: Some(game.max_moves()),
+            superko: game.superko(),
+            allow_suicide: game.allow_suicide(),
+            dead_stone_marking: game.dead_stone_marking(),
+            ruleset: game.ruleset(),
+            handicap: 0,
+            swap_rule: game.swap_rule(),
+        },
+        start: has_setup.then(|| start.to_position_string()),
+        moves: game
+            .moves_by_player()
+            .map(|(_, m)| m.to_gtp(game.height()))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| CliError::Json(e.into()))
+}
+
+/// Number of move sequences `depth` moves long from `game`, with the moves
+/// of `Game::legal_moves` at every step. Sequences that end the game early
+/// are not counted.
+#[hotpath::measure]
+pub fn perft<const NW: usize>(game: &Game<NW>, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = game.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut child = game.clone();
+    let mut nodes = 0;
+    for m in &moves {
+        if child.make_move(m) {
+            nodes += perft(&child, depth - 1);
+            child.unmake_move();
+        }
+    }
+    nodes
+}
+
+/// `perft DEPTH [FILE] [--size N] [--divide]`: node counts for each depth up
+/// to DEPTH, or with `--divide` the count below each first move.
+#[hotpath::measure]
+fn perft_command<R: BufRead, W: Write>(
+    args: &[String],
+    input: R,
+    out: &mut W,
+) -> Result<(), CliError> {
+    let args = Args::parse(args, &["size"], &["divide"])?;
+    let (depth, file) = match args.positional.as_slice() {
+        [depth] => (depth, None),
+        [depth, file] => (depth, Some(file)),
+        _ => {
+            return Err(CliError::Usage(
+                "perft takes a depth and an optional file".to_string(),
+            ))
+        }
+    };
+    let depth: u32 = depth
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid depth: {}", depth)))?;
+    let inner = match file {
+        Some(path) => load_game(&read_source(path, input)?)?,
+        None => {
+            let size = args.get("size", 9u8)?;
+            let rules = RulesConfig {
+                width: size,
+                height: size,
+                ..RulesConfig::default()
+            };
+            check_option_rules(&rules)?;
+            new_game(&rules)
+        }
+    };
+
+    dispatch_game!(&inner, g => {
+        if args.has("divide") {
+            let mut total = 0;
+            let mut child = g.clone();
+            for m in g.legal_moves() {
+                if child.make_move(&m) {
+                    let nodes = perft(&child, depth.saturating_sub(1));
+                    child.unmake_move();
+                    writeln!(out, "{}: {}", m.to_gtp(g.height()), nodes)?;
+                    total += nodes;
+                }
+            }
+            writeln!(out, "total: {}", total)?;
+        } else {
+            for d in 1..=depth {
+                writeln!(out, "depth {}: {}", d, perft(g, d))?;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// `convert FILE --to sgf|position|json`.
+#[hotpath::measure]
+fn convert<R: BufRead, W: Write>(args: &[String], input: R, out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(args, &["to"], &[])?;
+    let [path] = args.positional.as_slice() else {
+        return Err(CliError::Usage("convert takes one file".to_string()));
+    };
+    let format = args
+        .options
+        .get("to")
+        .ok_or_else(|| CliError::Usage("convert needs --to".to_string()))?;
+    let inner = load_game(&read_source(path, input)?)?;
+    let text = match *format {
+        "sgf" => dispatch_game!(&inner, g => g.to_sgf()),
+        "position" => dispatch_game!(&inner, g => g.to_position_string()),
+        "json" => dispatch_game!(&inner, g => game_to_json(g))?,
+        _ => return Err(CliError::Usage(format!("unknown format {}", format))),
+    };
+    writeln!(out, "{}", text)?;
+    Ok(())
+}
+
+/// `score FILE [--rollouts N]`.
+#[hotpath::measure]
+fn score<R: BufRead, W: Write>(args: &[String], input: R, out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(args, &["rollouts"], &[])?;
+    let [path] = args.positional.as_slice() else {
+        return Err(CliError::Usage("score takes one file".to_string()));
+    };
+    let rollouts = args.get("rollouts", RolloutConfig::default().rollouts)?;
+    let inner = load_game(&read_source(path, input)?)?;
+
+    dispatch_game!(&inner, g => {
+        let details = if rollouts > 0 {
+            let config = RolloutConfig {
+                rollouts,
+                ..RolloutConfig::default()
+            };
+            score_with_rollouts(g, &config)
+        } else {
+            g.score_details()
+        };
+        for player in [Player::Black, Player::White] {
+            let b = details.breakdown(player);
+            writeln!(
+                out,
+                "{}: {} (stones {}, territory {}, prisoners {})",
+                player,
+                details.total(player),
+                b.stones,
+                b.territory,
+                b.prisoners
+            )?;
+        }
+        let (black, white) = details.totals_half_points();
+        let result = GameOutcome::from_half_points(black, white, EndReason::DoublePass);
+        writeln!(out, "Result: {}", result.to_result_string())?;
+        let recorded = g
+            .outcome()
+            .map(|o| o.to_result_string())
+            .or_else(|| g.info().result.clone());
+        if let Some(recorded) = recorded {
+            writeln!(out, "Recorded result: {}", recorded)?;
+        }
+    });
+    Ok(())
+}
+
+/// `play [--size N] [--komi K] [--computer black|white] [--playouts N]
+/// [--seed N] [--theme NAME]`.
+#[hotpath::measure]
+fn play<R: BufRead, W: Write>(args: &[String], input: R, out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(
+        args,
+        &["size", "komi", "computer", "playouts", "seed", "theme"],
+        &[],
+    )?;
+    if !args.positional.is_empty() {
+        return Err(CliError::Usage("play takes no files".to_string()));
+    }
+    let size = args.get("size", 9u8)?;
+    let rules = RulesConfig {
+        width: size,
+        height: size,
+        komi: args.get("komi", RulesConfig::default().komi)?,
+        min_moves_before_pass_possible: Some(0),
+        ..RulesConfig::default()
+    };
+    check_option_rules(&rules)?;
+    let computer = match args.options.get("computer").copied() {
+        None => None,
+        Some("black") => Some(Player::Black),
+        Some("white") => Some(Player::White),
+        Some(other) => return Err(CliError::Usage(format!("unknown color {}", other))),
+    };
+    let mut policy = MctsPolicy::new(args.get("playouts", MctsPolicy::default().iterations)?);
+    let mut rng = StdRng::seed_from_u64(args.get("seed", 0u64)?);
+    let theme = args.options.get("theme").copied().unwrap_or("coordinates");
+    let renderer = renderer_for_theme(theme)
+        .ok_or_else(|| CliError::Usage(format!("unknown theme {}", theme)))?;
+
+    let mut inner = new_game(&rules);
+    dispatch_game_mut!(&mut inner, g => {
+        play_in_terminal(g, computer, &mut policy, &mut rng, renderer.as_ref(), input, out)
+    })?;
+    Ok(())
+}
+
+/// `dataset DIR... --encoder ENCODER.toml --out DIR [--shard-size N]
+/// [--illegal skip|repair]`, with `args` the words after `dataset`. The
+/// `spooky-convert` binary runs this alone.
+#[hotpath::measure]
+pub fn dataset<W: Write>(args: &[String], out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(args, &["encoder", "out", "shard-size", "illegal"], &[])?;
+    if args.positional.is_empty() {
+        return Err(CliError::Usage(
+            "dataset takes one or more directories".to_string(),
+        ));
+    }
+    let (Some(encoder), Some(out_dir)) = (args.options.get("encoder"), args.options.get("out"))
+    else {
+        return Err(CliError::Usage(
+            "dataset needs --encoder and --out".to_string(),
+        ));
+    };
+    let config: EncoderConfig = config::load(encoder).map_err(CliError::Config)?;
+    let illegal_moves = match args.options.get("illegal").copied() {
+        None | Some("skip") => IllegalMovePolicy::Skip,
+        Some("repair") => IllegalMovePolicy::Repair,
+        Some(other) => {
+            return Err(CliError::Usage(format!(
+                "unknown illegal move handling {}",
+                other
+            )))
+        }
+    };
+    let options = ShardOptions {
+        samples_per_shard: args.get("shard-size", ShardOptions::default().samples_per_shard)?,
+        illegal_moves,
+    };
+    let dirs: Vec<_> = args.positional.iter().map(PathBuf::from).collect();
+    let report = convert_sgf_dirs(&dirs, Path::new(out_dir), &config, &options)
+        .map_err(CliError::Dataset)?;
+    write!(out, "{}", report)?;
+    Ok(())
+}
+
+/// `self-play CONFIG`: one line per game, then the wins of each color.
+#[hotpath::measure]
+fn self_play<W: Write>(args: &[String], out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(args, &[], &[])?;
+    let [path] = args.positional.as_slice() else {
+        return Err(CliError::Usage(
+            "self-play takes one config file".to_string(),
+        ));
+    };
+    let config: Config = config::load(path).map_err(CliError::Config)?;
+    config.validate().map_err(CliError::Config)?;
+    let inner = new_game(&config.rules);
+
+    let records = dispatch_game!(&inner, g => {
+        run_self_play(
+            || g.clone(),
+            || config.search.mcts_policy(),
+            config.selfplay.games,
+            config.selfplay.seed,
+            config.selfplay.threads,
+        )
+    });
+    let (mut black_wins, mut white_wins) = (0, 0);
+    for record in &records {
+        let result = match record.outcome {
+            Some(outcome) => {
+                match outcome.winner() {
+                    Some(Player::Black) => black_wins += 1,
+                    Some(Player::White) => white_wins += 1,
+                    None => {}
+                }
+                outcome.to_result_string()
+            }
+            None => "?".to_string(),
+        };
+        writeln!(
+            out,
+            "game {}: {} in {} moves (seed {})",
+            record.index,
+            result,
+            record.moves.len(),
+            record.seed
+        )?;
+    }
+    writeln!(
+        out,
+        "Black wins: {}, White wins: {}",
+        black_wins, white_wins
+    )?;
+    Ok(())
+}
+
+/// `sweep --first POLICY --second POLICY [--sizes N,...] [--komis K,...]
+/// [--handicaps N,...] [--games N] [--seed N] [--format csv|parquet]`.
+#[hotpath::measure]
+fn sweep_command<W: Write>(args: &[String], out: &mut W) -> Result<(), CliError> {
+    let args = Args::parse(
+        args,
+        &[
+            "first",
+            "second",
+            "sizes",
+            "komis",
+            "handicaps",
+            "games",
+            "seed",
+            "format",
+        ],
+        &[],
+    )?;
+    if !args.positional.is_empty() {
+        return Err(CliError::Usage("sweep takes no files".to_string()));
+    }
+    let policy = |name: &str| {
+        let value = args
+            .options
+            .get(name)
+            .ok_or_else(|| CliError::Usage(format!("sweep needs --{}", name)))?;
+        Difficulty::from_name(value)
+            .map(PresetPolicy)
+            .ok_or_else(|| CliError::Usage(format!("unknown policy {}", value)))
+    };
+    let (mut first, mut second) = (policy("first")?, policy("second")?);
+    let format = args.options.get("format").copied().unwrap_or("csv");
+    match format {
+        "csv" => {}
+        #[cfg(feature = "parquet")]
+        "parquet" => {}
+        _ => return Err(CliError::Usage(format!("unknown format {}", format))),
+    }
+    let defaults = SweepConfig::default();
+    let config = SweepConfig {
+        sizes: args.get_list("sizes", defaults.sizes)?,
+        komis: args.get_list("komis", defaults.komis)?,
+        handicaps: args.get_list("handicaps", defaults.handicaps)?,
+        games: args.get("games", defaults.games)?,
+        seed: args.get("seed", defaults.seed)?,
+        ruleset: defaults.ruleset,
+    };
+
+    let results =
+        sweep(&config, &mut first, &mut second).map_err(|e| CliError::Usage(e.to_string()))?;
+    match format {
+        #[cfg(feature = "parquet")]
+        "parquet" => {
+            let mut bytes = Vec::new();
+            results
+                .write_parquet(&mut bytes)
+                .map_err(|e| CliError::Io(std::io::Error::other(e)))?;
+            out.write_all(&bytes)?;
+        }
+        _ => results.write_csv(out)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+
+    fn run_with(args: &[&str], input: &str) -> Result<String, CliError> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, input.as_bytes(), &mut out)?;
+        Ok(String::from_utf8(out).expect("utf-8"))
+    }
+
+    #[test]
+    fn test_perft_counts() {
+        let game = Game::<{ nw_for_board(3, 3) }>::with_options(3, 3, 0.5, 0, 100, true);
+        assert_eq!(perft(&game, 1), 10);
+        // 9 placements each answered by 8 placements or a pass, and a pass
+        // answered by 9 placements or the second pass.
+        assert_eq!(perft(&game, 2), 9 * 9 + 10);
+
+        let output = run_with(&["perft", "2", "--size", "5"], "").expect("runs");
+        assert_eq!(output, "depth 1: 25\ndepth 2: 600\n");
+        let divide = run_with(
+            &["perft", "1", "-", "--divide"],
+            "2W6/9/9/9/9/9/9/9/9 b - 0 0 0",
+        )
+        .expect("runs");
+        assert!(divide.starts_with("A1: 1\n"));
+        assert!(divide.ends_with("total: 80\n"));
+    }
+
+    #[test]
+    fn test_convert_round_trips() {
+        let sgf = "(;GM[1]FF[4]SZ[9]KM[6.5]AB[cc];W[ee];B[ge])";
+        let position = run_with(&["convert", "-", "--to", "position"], sgf).expect("converts");
+        assert_eq!(position, "9/9/2B6/9/4W1B2/9/9/9/9 w - 0 0 2\n");
+
+        let json = run_with(&["convert", "-", "--to", "json"], sgf).expect("converts");
+        assert!(json.contains("\"moves\": [\n    \"E5\",\n    \"G5\"\n  ]"));
+        let back = run_with(&["convert", "-", "--to", "sgf"], &json).expect("converts");
+        assert!(back.contains("KM[6.5]"));
+        assert!(back.contains("AB[cc]"));
+        assert!(back.contains(";W[ee]\n;B[ge]"));
+
+        assert!(matches!(
+            run_with(&["convert", "-", "--to", "png"], sgf),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            run_with(&["convert", "-", "--to", "sgf"], "9/8 b - 0 0 0"),
+            Err(CliError::PositionString(_))
+        ));
+    }
+
+    #[test]
+    fn test_score_and_play() {
+        let output = run_with(
+            &["score", "-", "--rollouts", "0"],
+            "4B4/9/9/9/9/9/9/9/9 w - 0 0 1",
+        )
+        .expect("scores");
+        assert!(output.starts_with("Black: 81 (stones 1, territory 80, prisoners 0)\n"));
+        assert!(output.contains("Result: B+73.5\n"));
+
+        let output = run_with(
+            &["play", "--size", "5"],
+            "C3\nZ9\nC3\nD4\nundo\npass\npass\n",
+        )
+        .expect("plays");
+        assert!(output.contains("unknown move: Z9"));
+        assert!(output.contains("illegal move: C3"));
+        assert!(output.contains(" 3  . . B . .  3"));
+        assert!(output.ends_with("Result: B+17.5\n"));
+
+        let output =
+            run_with(&["play", "--size", "5", "--theme", "unicode"], "C3\nquit\n").expect("plays");
+        assert!(output.contains(" 3 ├─┼─●─┼─┤ 3"));
+        assert!(matches!(
+            run_with(&["play", "--theme", "html"], ""),
+            Err(CliError::Usage(_))
+        ));
+
+        // Sizes the rules cannot hold are usage errors, not JSON errors.
+        for args in [
+            &["play", "--size", "1"][..],
+            &["perft", "1", "--size", "40"],
+        ] {
+            let error = run_with(args, "").expect_err("rejects the size");
+            assert!(matches!(error, CliError::Usage(_)));
+            assert!(error
+                .to_string()
+                .starts_with("Board width must be between 2 and 32\n"));
+        }
+    }
+
+    #[test]
+    fn test_sweep() {
+        let args = [
+            "sweep", "--first", "random", "--second", "weak", "--sizes", "5", "--komis", "0.5,7.5",
+            "--games", "2",
+        ];
+        let output = run_with(&args, "").expect("sweeps");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], crate::eval::SWEEP_CSV_HEADER);
+        assert!(lines[1].starts_with("5,0.5,0,2,"));
+        assert!(lines[2].starts_with("5,7.5,0,2,"));
+        assert_eq!(run_with(&args, "").expect("sweeps"), output);
+
+        for bad in [
+            &["sweep", "--first", "random"][..],
+            &["sweep", "--first", "random", "--second", "expert"],
+            &[
+                "sweep", "--first", "random", "--second", "weak", "--komis", "6.5,x",
+            ],
+            &[
+                "sweep",
+                "--first",
+                "random",
+                "--second",
+                "weak",
+                "--sizes",
+                "5",
+                "--handicaps",
+                "9",
+            ],
+            &[
+                "sweep", "--first", "random", "--second", "weak", "--format", "xlsx",
+            ],
+        ] {
+            assert!(matches!(run_with(bad, ""), Err(CliError::Usage(_))));
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_sweep_to_parquet() {
+        let args: Vec<String> = [
+            "sweep", "--first", "random", "--second", "random", "--sizes", "5", "--games", "1",
+            "--format", "parquet",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        let mut out = Vec::new();
+        run(&args, "".as_bytes(), &mut out).expect("sweeps");
+        assert!(out.starts_with(b"PAR1"));
+        assert!(out.ends_with(b"PAR1"));
+    }
+
+    #[test]
+    fn test_self_play_from_config() {
+        let path = std::env::temp_dir().join("spooky_go_self_play.toml");
+        std::fs::write(
+            &path,
+            "[rules]\nwidth = 5\nheight = 5\n\n[search]\niterations = 20\n\n\
+             [selfplay]\nseed = 3\ngames = 3\nthreads = 2\n",
+        )
+        .expect("writes config");
+        let path = path.to_str().expect("utf-8 path");
+        let output = run_with(&["self-play", path], "").expect("plays");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("game 0: "));
+        assert!(lines[2].starts_with("game 2: "));
+        assert!(lines[3].starts_with("Black wins: "));
+        // Seeds come from the config, so the run is reproducible.
+        assert_eq!(run_with(&["self-play", path], "").expect("plays"), output);
+
+        std::fs::write(path, "[search]\niterations = 0\n").expect("writes config");
+        assert!(matches!(
+            run_with(&["self-play", path], ""),
+            Err(CliError::Config(ConfigError::Invalid(_)))
+        ));
+        std::fs::remove_file(path).expect("removes config");
+    }
+
+    #[test]
+    fn test_dataset_from_sgf_directory() {
+        let root = std::env::temp_dir().join("spooky_go_cli_dataset");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("sgf")).expect("creates fixture");
+        std::fs::write(
+            root.join("sgf").join("game.sgf"),
+            "(;GM[1]SZ[9]RE[W+R];B[ee];W[cc])",
+        )
+        .expect("writes fixture");
+        std::fs::write(root.join("encoder.toml"), "history_length = 2\n").expect("writes config");
+        let path = |name: &str| root.join(name).to_str().expect("utf-8 path").to_string();
+
+        let output = run_with(
+            &[
+                "dataset",
+                &path("sgf"),
+                "--encoder",
+                &path("encoder.toml"),
+                "--out",
+                &path("shards"),
+            ],
+            "",
+        )
+        .expect("converts");
+        assert_eq!(
+            output,
+            "files: 1\ngames: 1 kept, 0 dropped\npositions: 2\nshards: 1\n9x9: 1 games\n"
+        );
+        assert!(matches!(
+            run_with(&["dataset", &path("sgf"), "--out", &path("shards")], ""),
+            Err(CliError::Usage(_))
+        ));
+        std::fs::remove_dir_all(&root).expect("removes fixture");
+    }
+}
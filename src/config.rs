@@ -0,0 +1,448 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::encode::EncoderConfig;
+use crate::game::{
+    default_max_moves, default_min_moves_before_pass_possible, validate_move_limits, Game,
+    DEFAULT_KOMI,
+};
+use crate::handicap::max_fixed_handicap;
+#[cfg(feature = "search")]
+use crate::policy::{CycleDetection, CycleOutcome, MctsPolicy};
+use crate::rules::Ruleset;
+
+/// Rules and limits used to create a `Game`.
+///
+/// `min_moves_before_pass_possible` and `max_moves` default to the same
+/// size-dependent values as `Game::new` when left out.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    pub width: u8,
+    pub height: u8,
+    pub komi: f32,
+    pub min_moves_before_pass_possible: Option<u16>,
+    pub max_moves: Option<u16>,
+    pub superko: bool,
+    pub allow_suicide: bool,
+    pub dead_stone_marking: bool,
+    pub ruleset: Ruleset,
+    /// Fixed handicap stones for Black (0 for an even game).
+    pub handicap: u8,
+    /// Let White swap colors after Black's first move (see `Game::swap_rule`).
+    pub swap_rule: bool,
+}
+
+#[hotpath::measure_all]
+impl Default for RulesConfig {
+    fn default() -> Self {
+        RulesConfig {
+            width: 19,
+            height: 19,
+            komi: DEFAULT_KOMI,
+            min_moves_before_pass_possible: None,
+            max_moves: None,
+            superko: true,
+            allow_suicide: false,
+            dead_stone_marking: false,
+            ruleset: Ruleset::default(),
+            handicap: 0,
+            swap_rule: false,
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl RulesConfig {
+    pub fn min_moves_before_pass_possible(&self) -> u16 {
+        self.min_moves_before_pass_possible
+            .unwrap_or(default_min_moves_before_pass_possible(
+                self.width,
+                self.height,
+            ))
+    }
+
+    pub fn max_moves(&self) -> u16 {
+        self.max_moves
+            .unwrap_or(default_max_moves(self.width, self.height))
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(2..=32).contains(&self.width) {
+            return Err(ConfigError::Invalid(
+                "Board width must be between 2 and 32".to_string(),
+            ));
+        }
+        if !(2..=32).contains(&self.height) {
+            return Err(ConfigError::Invalid(
+                "Board height must be between 2 and 32".to_string(),
+            ));
+        }
+        let max_handicap = max_fixed_handicap(self.width, self.height);
+        if self.handicap != 0 && !(2..=max_handicap).contains(&self.handicap) {
+            return Err(ConfigError::Invalid(format!(
+                "Handicap must be 0 or between 2 and {} on a {}x{} board",
+                max_handicap, self.width, self.height
+            )));
+        }
+        validate_move_limits(self.min_moves_before_pass_possible(), self.max_moves())
+            .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Create a game with these rules. `NW` must be `nw_for_board(width, height)`.
+    pub fn new_game<const NW: usize>(&self) -> Game<NW> {
+        let mut game = Game::with_options(
+            self.width,
+            self.height,
+            self.komi,
+            self.min_moves_before_pass_possible(),
+            self.max_moves(),
+            self.superko,
+        );
+        self.apply(&mut game);
+        game
+    }
+
+    /// Apply the settings that `Game::with_options` does not take.
+    pub fn apply<const NW: usize>(&self, game: &mut Game<NW>) {
+        game.set_allow_suicide(self.allow_suicide);
+        game.set_swap_rule(self.swap_rule);
+        game.set_dead_stone_marking(self.dead_stone_marking);
+        game.set_ruleset(self.ruleset);
+        if self.handicap > 0 {
+            game.place_handicap(self.handicap);
+        }
+    }
+}
+
+/// Settings of an `MctsPolicy` search. Defaults are `MctsPolicy::default()`.
+#[cfg(feature = "search")]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    pub iterations: u32,
+    /// UCT exploration constant.
+    pub exploration: f32,
+    /// Recent positions a playout position is compared with (0 turns cycle
+    /// detection off).
+    pub cycle_window: usize,
+    pub cycle_outcome: CycleOutcome,
+}
+
+#[cfg(feature = "search")]
+#[hotpath::measure_all]
+impl Default for SearchConfig {
+    fn default() -> Self {
+        let policy = MctsPolicy::default();
+        SearchConfig {
+            iterations: policy.iterations,
+            exploration: policy.exploration,
+            cycle_window: policy.cycle.window,
+            cycle_outcome: policy.cycle.outcome,
+        }
+    }
+}
+
+#[cfg(feature = "search")]
+#[hotpath::measure_all]
+impl SearchConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.iterations == 0 {
+            return Err(ConfigError::Invalid(
+                "Search iterations must be at least 1".to_string(),
+            ));
+        }
+        if !self.exploration.is_finite() || self.exploration < 0.0 {
+            return Err(ConfigError::Invalid(
+                "Exploration constant must be a non-negative number".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// A policy searching with these settings.
+    pub fn mcts_policy(&self) -> MctsPolicy {
+        MctsPolicy {
+            iterations: self.iterations,
+            exploration: self.exploration,
+            cycle: CycleDetection {
+                window: self.cycle_window,
+                outcome: self.cycle_outcome,
+            },
+        }
+    }
+}
+
+/// How many self-play games to play, on how many threads, and the seed
+/// each game's seed is derived from (see `derive_game_seed`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SelfPlayConfig {
+    pub seed: u64,
+    pub games: u64,
+    pub threads: usize,
+}
+
+#[hotpath::measure_all]
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        SelfPlayConfig {
+            seed: 0,
+            games: 1,
+            threads: 1,
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl SelfPlayConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.threads == 0 {
+            return Err(ConfigError::Invalid(
+                "Self-play needs at least 1 thread".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Top-level experiment configuration, one section per component.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub rules: RulesConfig,
+    pub encoder: EncoderConfig,
+    #[cfg(feature = "search")]
+    pub search: SearchConfig,
+    pub selfplay: SelfPlayConfig,
+}
+
+#[hotpath::measure_all]
+impl Config {
+    /// Check every section.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.rules.validate()?;
+        #[cfg(feature = "search")]
+        self.search.validate()?;
+        self.selfplay.validate()
+    }
+}
+
+/// Errors that can occur while loading a configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    UnknownFormat(PathBuf),
+    Invalid(String),
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        ConfigError::Json(e)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "config I/O error: {}", e),
+            ConfigError::Toml(e) => write!(f, "invalid TOML config: {}", e),
+            ConfigError::Json(e) => write!(f, "invalid JSON config: {}", e),
+            ConfigError::UnknownFormat(p) => write!(
+                f,
+                "unknown config format (expected .toml or .json): {}",
+                p.display()
+            ),
+            ConfigError::Invalid(msg) => write!(f, "invalid config: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Toml(e) => Some(e),
+            ConfigError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[hotpath::measure]
+pub fn from_toml_str<T: DeserializeOwned>(s: &str) -> Result<T, ConfigError> {
+    Ok(toml::from_str(s)?)
+}
+
+#[hotpath::measure]
+pub fn from_json_str<T: DeserializeOwned>(s: &str) -> Result<T, ConfigError> {
+    Ok(serde_json::from_str(s)?)
+}
+
+/// Load a configuration file, choosing TOML or JSON from the file extension.
+#[hotpath::measure]
+pub fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, ConfigError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => from_toml_str(&text),
+        Some("json") => from_json_str(&text),
+        _ => Err(ConfigError::UnknownFormat(path.to_path_buf())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+
+    #[test]
+    fn test_toml_sections_with_defaults() {
+        let config: Config = from_toml_str(
+            r#"
+            [rules]
+            width = 9
+            height = 9
+            komi = 6.5
+            ruleset = "japanese"
+
+            [encoder]
+            history_length = 4
+            "#,
+        )
+        .expect("valid TOML config");
+
+        assert_eq!(config.rules.width, 9);
+        assert_eq!(config.rules.ruleset, Ruleset::Japanese);
+        assert!(config.rules.superko);
+        assert_eq!(config.rules.min_moves_before_pass_possible(), 40);
+        assert_eq!(config.encoder.history_length, 4);
+
+        let game: Game<{ nw_for_board(9, 9) }> = config.rules.new_game();
+        assert_eq!(game.komi(), 6.5);
+        assert_eq!(game.ruleset(), Ruleset::Japanese);
+        assert_eq!(game.max_moves(), 243);
+    }
+
+    #[test]
+    fn test_handicap_game_from_config() {
+        let rules: RulesConfig =
+            from_toml_str("width = 9\nheight = 9\nkomi = 0.5\nhandicap = 3").expect("parses");
+        rules.validate().expect("valid");
+
+        let game: Game<{ nw_for_board(9, 9) }> = rules.new_game();
+        assert_eq!(game.handicap(), 3);
+        assert_eq!(game.effective_komi(), 3.5);
+    }
+
+    #[test]
+    fn test_json_and_toml_round_trip() {
+        let config = Config {
+            rules: RulesConfig {
+                width: 13,
+                height: 13,
+                allow_suicide: true,
+                swap_rule: true,
+                max_moves: Some(500),
+                ..RulesConfig::default()
+            },
+            encoder: EncoderConfig {
+                history_length: 1,
+                ..EncoderConfig::default()
+            },
+            #[cfg(feature = "search")]
+            search: SearchConfig {
+                iterations: 200,
+                exploration: 0.5,
+                cycle_window: 8,
+                cycle_outcome: CycleOutcome::Draw,
+            },
+            selfplay: SelfPlayConfig {
+                seed: 7,
+                games: 12,
+                threads: 4,
+            },
+        };
+        let json = serde_json::to_string(&config).expect("serialize");
+        let parsed: Config = from_json_str(&json).expect("parse");
+        assert_eq!(parsed, config);
+
+        let toml = toml::to_string(&config).expect("serialize");
+        assert!(toml.contains("[selfplay]"));
+        let parsed: Config = from_toml_str(&toml).expect("parse");
+        assert_eq!(parsed, config);
+        parsed.validate().expect("valid");
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn test_search_and_selfplay_sections() {
+        let config: Config = from_toml_str(
+            r#"
+            [search]
+            iterations = 50
+            cycle_outcome = "draw"
+
+            [selfplay]
+            games = 3
+            "#,
+        )
+        .expect("valid TOML config");
+        let policy = config.search.mcts_policy();
+        assert_eq!(policy.iterations, 50);
+        assert_eq!(policy.exploration, MctsPolicy::default().exploration);
+        assert_eq!(policy.cycle.outcome, CycleOutcome::Draw);
+        assert_eq!(config.selfplay.games, 3);
+        assert_eq!(config.selfplay.threads, 1);
+
+        assert!(from_toml_str::<Config>("[search]\nplayouts = 5").is_err());
+        let zero: Config = from_toml_str("[search]\niterations = 0").expect("parses");
+        assert!(matches!(zero.validate(), Err(ConfigError::Invalid(_))));
+        let zero: Config = from_toml_str("[selfplay]\nthreads = 0").expect("parses");
+        assert!(matches!(zero.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields_and_bad_sizes() {
+        assert!(from_toml_str::<RulesConfig>("widht = 9").is_err());
+
+        let rules: RulesConfig = from_toml_str("width = 40").expect("parses");
+        assert!(matches!(rules.validate(), Err(ConfigError::Invalid(_))));
+
+        let rules: RulesConfig =
+            from_toml_str("width = 9\nheight = 9\nhandicap = 10").expect("parses");
+        assert!(matches!(rules.validate(), Err(ConfigError::Invalid(_))));
+
+        let rules: RulesConfig = from_toml_str("max_moves = 0").expect("parses");
+        assert!(matches!(rules.validate(), Err(ConfigError::Invalid(_))));
+        // The default pass threshold on 19x19 is 180.
+        let rules: RulesConfig = from_toml_str("max_moves = 100").expect("parses");
+        assert!(matches!(rules.validate(), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_load_requires_known_extension() {
+        assert!(matches!(
+            load::<Config>("Cargo.lock"),
+            Err(ConfigError::UnknownFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dispatch::{make_game_inner_with_options, GameInner};
+use crate::encode::EncoderConfig;
+use crate::replay::IllegalMovePolicy;
+use crate::selfplay::GameRecord;
+use crate::sgf::{sgf_records, SgfGame};
+use crate::training::{training_samples, TrainingSample};
+use crate::wire::{write_message, WireError, WireMessage};
+
+/// Settings of `convert_sgf_dirs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShardOptions {
+    /// Training samples written to each shard before the next is started.
+    pub samples_per_shard: usize,
+    /// How moves that cannot be played are handled; games that still fail
+    /// to replay are dropped.
+    pub illegal_moves: IllegalMovePolicy,
+}
+
+#[hotpath::measure_all]
+impl Default for ShardOptions {
+    fn default() -> Self {
+        ShardOptions {
+            samples_per_shard: 65536,
+            illegal_moves: IllegalMovePolicy::Skip,
+        }
+    }
+}
+
+/// A game left out of the shards, and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DroppedGame {
+    pub path: PathBuf,
+    /// Position of the game in its file's collection, from 0.
+    pub index: usize,
+    pub reason: String,
+}
+
+/// What `convert_sgf_dirs` read and wrote.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub files: usize,
+    pub games_kept: usize,
+    pub dropped: Vec<DroppedGame>,
+    /// Training samples written, one per position.
+    pub positions: usize,
+    pub shards: usize,
+    /// Games kept for each board size, `(width, height)`.
+    pub size_classes: BTreeMap<(u8, u8), usize>,
+}
+
+#[hotpath::measure_all]
+impl ConversionReport {
+    pub fn games_dropped(&self) -> usize {
+        self.dropped.len()
+    }
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "files: {}", self.files)?;
+        writeln!(
+            f,
+            "games: {} kept, {} dropped",
+            self.games_kept,
+            self.games_dropped()
+        )?;
+        writeln!(f, "positions: {}", self.positions)?;
+        writeln!(f, "shards: {}", self.shards)?;
+        for ((width, height), games) in &self.size_classes {
+            writeln!(f, "{}x{}: {} games", width, height, games)?;
+        }
+        for dropped in &self.dropped {
+            writeln!(
+                f,
+                "dropped {} game {}: {}",
+                dropped.path.display(),
+                dropped.index + 1,
+                dropped.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors that stop a conversion. Problems with single games only drop
+/// them (see `ConversionReport::dropped`).
+#[derive(Debug)]
+pub enum ConvertError {
+    Io(std::io::Error),
+    Wire(WireError),
+}
+
+impl From<std::io::Error> for ConvertError {
+    fn from(e: std::io::Error) -> Self {
+        ConvertError::Io(e)
+    }
+}
+
+impl From<WireError> for ConvertError {
+    fn from(e: WireError) -> Self {
+        ConvertError::Wire(e)
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Io(e) => write!(f, "dataset I/O error: {}", e),
+            ConvertError::Wire(e) => write!(f, "cannot write shard: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvertError::Io(e) => Some(e),
+            ConvertError::Wire(e) => Some(e),
+        }
+    }
+}
+
+/// Writes training samples to `shard-00000.wire`, `shard-00001.wire`, ...
+/// in a directory, as `wire` messages, starting a new shard every
+/// `samples_per_shard` samples.
+struct ShardWriter {
+    dir: PathBuf,
+    samples_per_shard: usize,
+    current: Option<BufWriter<File>>,
+    in_current: usize,
+    shards: usize,
+}
+
+#[hotpath::measure_all]
+impl ShardWriter {
+    fn new(dir: &Path, samples_per_shard: usize) -> Self {
+        ShardWriter {
+            dir: dir.to_path_buf(),
+            samples_per_shard: samples_per_shard.max(1),
+            current: None,
+            in_current: 0,
+            shards: 0,
+        }
+    }
+
+    fn write(&mut self, sample: TrainingSample) -> Result<(), ConvertError> {
+        if self.in_current == self.samples_per_shard {
+            self.finish_shard()?;
+        }
+        let out = match &mut self.current {
+            Some(out) => out,
+            None => {
+                let path = self.dir.join(format!("shard-{:05}.wire", self.shards));
+                self.shards += 1;
+                self.current.insert(BufWriter::new(File::create(path)?))
+            }
+        };
+        write_message(out, &WireMessage::TrainingSample(sample))?;
+        self.in_current += 1;
+        Ok(())
+    }
+
+    fn finish_shard(&mut self) -> Result<(), ConvertError> {
+        if let Some(mut out) = self.current.take() {
+            out.flush()?;
+        }
+        self.in_current = 0;
+        Ok(())
+    }
+}
+
+/// The `.sgf` files under `dir`, in every subdirectory, sorted by path.
+#[hotpath::measure]
+fn sgf_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            sgf_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("sgf"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The training samples of one record, encoded with `config`, or why the
+/// game is dropped. The value target comes from the result the moves reach,
+/// or the recorded `RE` result when they stop before the end.
+#[hotpath::measure]
+fn record_samples(
+    record: &SgfGame,
+    config: &EncoderConfig,
+    illegal_moves: IllegalMovePolicy,
+) -> Result<Vec<TrainingSample>, String> {
+    let mut inner =
+        make_game_inner_with_options(record.width, record.height, record.komi, 0, u16::MAX, true);
+    dispatch_game_mut!(&mut inner, g => {
+        record.set_up(g);
+        let start = g.clone();
+        record
+            .replay_into(g, illegal_moves)
+            .map_err(|e| e.to_string())?;
+        let game_record = GameRecord {
+            index: 0,
+            seed: 0,
+            moves: g.move_history(),
+            outcome: g.outcome().or(record.result),
+        };
+        training_samples(&start, &game_record, config).map_err(|e| e.to_string())
+    })
+}
+
+/// Convert the SGF records under `dirs` into training samples encoded with
+/// `config` and write them as shards to `out_dir` (see `ShardWriter`).
+/// Every game of a collection is used; games that cannot be read or
+/// replayed under `options.illegal_moves` are dropped and listed in the
+/// report.
+#[hotpath::measure]
+pub fn convert_sgf_dirs(
+    dirs: &[PathBuf],
+    out_dir: &Path,
+    config: &EncoderConfig,
+    options: &ShardOptions,
+) -> Result<ConversionReport, ConvertError> {
+    let mut files = Vec::new();
+    for dir in dirs {
+        sgf_files(dir, &mut files)?;
+    }
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut report = ConversionReport {
+        files: files.len(),
+        ..ConversionReport::default()
+    };
+    let mut shards = ShardWriter::new(out_dir, options.samples_per_shard);
+    for path in files {
+        let text = std::fs::read_to_string(&path)?;
+        for (index, record) in sgf_records(&text).enumerate() {
+            let samples = record
+                .map_err(|e| e.to_string())
+                .and_then(|r| Ok((record_samples(&r, config, options.illegal_moves)?, r)));
+            match samples {
+                Ok((samples, record)) => {
+                    report.games_kept += 1;
+                    report.positions += samples.len();
+                    *report
+                        .size_classes
+                        .entry((record.width, record.height))
+                        .or_insert(0) += 1;
+                    for sample in samples {
+                        shards.write(sample)?;
+                    }
+                }
+                Err(reason) => report.dropped.push(DroppedGame {
+                    path: path.clone(),
+                    index,
+                    reason,
+                }),
+            }
+        }
+    }
+    shards.finish_shard()?;
+    report.shards = shards.shards;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire::read_message;
+
+    #[test]
+    fn test_convert_fixture_directory() {
+        let root = std::env::temp_dir().join("spooky_go_dataset_fixture");
+        let (input, output) = (root.join("sgf"), root.join("shards"));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(input.join("more")).expect("creates fixture");
+        // Two games: the second has an occupied point and then a move out
+        // of turn, both skipped.
+        std::fs::write(
+            input.join("a.sgf"),
+            "(;GM[1]SZ[9]KM[6.5]RE[B+R];B[ee];W[cc];B[gg])\n(;GM[1]SZ[5];B[cc];W[cc];B[dd])",
+        )
+        .expect("writes fixture");
+        std::fs::write(input.join("more").join("b.SGF"), "(;GM[1]SZ[9];B[ee]")
+            .expect("writes fixture");
+        std::fs::write(input.join("notes.txt"), "not a record").expect("writes fixture");
+
+        let options = ShardOptions {
+            samples_per_shard: 3,
+            ..ShardOptions::default()
+        };
+        let report = convert_sgf_dirs(
+            std::slice::from_ref(&input),
+            &output,
+            &EncoderConfig::default(),
+            &options,
+        )
+        .expect("converts");
+
+        assert_eq!(report.files, 2);
+        assert_eq!(report.games_kept, 2);
+        assert_eq!(report.games_dropped(), 1);
+        assert_eq!(report.dropped[0].path, input.join("more").join("b.SGF"));
+        assert_eq!(report.positions, 4);
+        assert_eq!(report.shards, 2);
+        assert_eq!(
+            report.size_classes,
+            BTreeMap::from([((5, 5), 1), ((9, 9), 1)])
+        );
+
+        let mut samples = Vec::new();
+        for shard in 0..2 {
+            let path = output.join(format!("shard-{:05}.wire", shard));
+            let mut file = File::open(path).expect("shard exists");
+            while let Some(message) = read_message(&mut file).expect("reads") {
+                let WireMessage::TrainingSample(sample) = message else {
+                    panic!("expected a training sample");
+                };
+                samples.push(sample);
+            }
+        }
+        assert_eq!(samples.len(), 4);
+        // Black won the first game by resignation.
+        assert_eq!(samples[0].value, 1.0);
+        assert_eq!(samples[1].value, -1.0);
+        assert_eq!(samples[3].width, 5);
+
+        // Repairing inserts a White pass before the out-of-turn move.
+        let options = ShardOptions {
+            illegal_moves: IllegalMovePolicy::Repair,
+            ..options
+        };
+        let report = convert_sgf_dirs(&[input], &output, &EncoderConfig::default(), &options)
+            .expect("converts");
+        assert_eq!(report.positions, 6);
+        std::fs::remove_dir_all(&root).expect("removes fixture");
+    }
+}
@@ -1,6 +1,8 @@
 use crate::bitboard::nw_for_board;
 use crate::board::Board;
 use crate::game::Game;
+#[cfg(feature = "search")]
+use crate::policy::MovePolicy;
 
 // -----------------------------------------------------------------------
 // Enum dispatch via paste! for Game<NW> and Board<NW>
@@ -21,6 +23,29 @@ macro_rules! define_dispatch {
                 $( [<Nw $nw>](Board<$nw>), )*
             }
 
+            /// A policy that plays on every board size: one implementing
+            /// `MovePolicy<NW>` for every `NW` a game can have, as the
+            /// policies generic over `NW` do.
+            #[cfg(feature = "search")]
+            pub trait AnySizePolicy: $( MovePolicy<$nw> + )* {}
+
+            #[cfg(feature = "search")]
+            impl<P: $( MovePolicy<$nw> + )* ?Sized> AnySizePolicy for P {}
+
+            $(
+                impl From<Game<$nw>> for GameInner {
+                    fn from(game: Game<$nw>) -> Self {
+                        GameInner::[<Nw $nw>](game)
+                    }
+                }
+
+                impl From<Board<$nw>> for BoardInner {
+                    fn from(board: Board<$nw>) -> Self {
+                        BoardInner::[<Nw $nw>](board)
+                    }
+                }
+            )*
+
             macro_rules! dispatch_game {
                 ($self_:expr, $g:ident => $body:expr) => {
                     match $self_ {
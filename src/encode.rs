@@ -1,6 +1,19 @@
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "dataset")]
+use std::fmt;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "dataset")]
+use std::io::Read;
+
+use crate::board::Board;
 use crate::game::Game;
 use crate::player::Player;
+use crate::position::Position;
 use crate::r#move::Move;
+#[cfg(feature = "dataset")]
+use crate::replay::{ReplayError, ReplayIssue};
+#[cfg(feature = "dataset")]
+use crate::sgf::{sgf_records, SgfError};
 
 /// Number of planes for piece positions (1 for WHITE + 1 for BLACK)
 const PIECE_PLANES: usize = 1 + 1;
@@ -14,40 +27,95 @@ const CONSTANT_PLANES: usize = 1;
 /// Total number of input planes for the neural network
 pub const TOTAL_INPUT_PLANES: usize = (HISTORY_LENGTH * PIECE_PLANES) + CONSTANT_PLANES;
 
+/// Options controlling how positions are encoded into input planes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct EncoderConfig {
+    /// Number of positions (current + previous) to encode.
+    pub history_length: usize,
+    /// Add resignation to the end of the action space (see
+    /// `total_actions_with`), for agents that learn when to resign.
+    pub resign_action: bool,
+    /// Add a plane after the color plane marking the legal moves that
+    /// capture at least one stone.
+    pub capture_plane: bool,
+}
+
+#[hotpath::measure_all]
+impl EncoderConfig {
+    pub fn num_planes(&self) -> usize {
+        (self.history_length * PIECE_PLANES) + CONSTANT_PLANES + self.capture_plane as usize
+    }
+}
+
+#[hotpath::measure_all]
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        EncoderConfig {
+            history_length: HISTORY_LENGTH,
+            resign_action: false,
+            capture_plane: false,
+        }
+    }
+}
+
 /// Encode the full game state into a flat f32 array for efficient transfer to Python/numpy
 /// Returns (flat_data, num_planes, height, width), where flat_data is in row-major order
 #[hotpath::measure]
-pub fn encode_game_planes<const NW: usize>(game: &mut Game<NW>) -> (Vec<f32>, usize, usize, usize) {
+pub fn encode_game_planes<const NW: usize>(game: &Game<NW>) -> (Vec<f32>, usize, usize, usize) {
+    encode_game_planes_with(game, &EncoderConfig::default())
+}
+
+/// Like `encode_game_planes`, but with the plane layout given by `config`.
+#[hotpath::measure]
+pub fn encode_game_planes_with<const NW: usize>(
+    game: &Game<NW>,
+    config: &EncoderConfig,
+) -> (Vec<f32>, usize, usize, usize) {
+    let mut data = vec![0.0f32; encoded_len(game, config)];
+    let (num_planes, height, width) = encode_game_planes_into(game, config, &mut data);
+    (data, num_planes, height, width)
+}
+
+/// Number of values `encode_game_planes_with(game, config)` produces.
+#[hotpath::measure]
+pub fn encoded_len<const NW: usize>(game: &Game<NW>, config: &EncoderConfig) -> usize {
+    config.num_planes() * game.width() as usize * game.height() as usize
+}
+
+/// Like `encode_game_planes_with`, but writing into `data`, e.g. a row of a
+/// caller's batch array, instead of allocating. Returns (num_planes, height,
+/// width).
+///
+/// # Panics
+///
+/// If `data.len()` is not `encoded_len(game, config)`.
+#[hotpath::measure]
+pub fn encode_game_planes_into<const NW: usize>(
+    game: &Game<NW>,
+    config: &EncoderConfig,
+    data: &mut [f32],
+) -> (usize, usize, usize) {
+    assert_eq!(
+        data.len(),
+        encoded_len(game, config),
+        "encoding buffer size"
+    );
     let perspective = game.turn();
     let width = game.width() as usize;
     let height = game.height() as usize;
-    let num_planes = TOTAL_INPUT_PLANES;
+    let num_planes = config.num_planes();
     let board_size = height * width;
-    let total_size = num_planes * board_size;
-    let mut data = vec![0.0f32; total_size];
-
-    let history_len = game.move_count();
-    let steps_back = (HISTORY_LENGTH - 1).min(history_len);
-
-    // Save moves we'll need to replay
-    let moves_to_replay: Vec<Move> = game.move_history()[(history_len - steps_back)..].to_vec();
-
-    // T=0: current position
-    fill_go_planes(&mut data, game, perspective, 0, board_size);
-
-    // T=1..steps_back: walk backward through history
-    for t in 1..=steps_back {
-        game.unmake_move();
-        fill_go_planes(&mut data, game, perspective, t, board_size);
-    }
+    data.fill(0.0);
 
-    // Replay saved moves to restore game state
-    for mv in &moves_to_replay {
-        game.make_move(mv);
+    // T=0 is the current position, T=1.. walk backward through history
+    for (t, board) in game.board_history().take(config.history_length).enumerate() {
+        fill_go_planes(data, &board, perspective, t, board_size);
     }
 
     // Color plane (last plane)
-    let color_plane_offset = (HISTORY_LENGTH * PIECE_PLANES) * board_size;
+    let color_plane_offset = (config.history_length * PIECE_PLANES) * board_size;
     let color_value = if perspective == Player::Black {
         1.0
     } else {
@@ -57,18 +125,24 @@ pub fn encode_game_planes<const NW: usize>(game: &mut Game<NW>) -> (Vec<f32>, us
         data[color_plane_offset + i] = color_value;
     }
 
-    (data, num_planes, height, width)
+    if config.capture_plane {
+        let capture_plane_offset = color_plane_offset + board_size;
+        for idx in game.capture_points().iter_ones() {
+            data[capture_plane_offset + idx] = 1.0;
+        }
+    }
+
+    (num_planes, height, width)
 }
 
 #[hotpath::measure]
 fn fill_go_planes<const NW: usize>(
     data: &mut [f32],
-    game: &Game<NW>,
+    board: &Board<NW>,
     perspective: Player,
     t: usize,
     board_size: usize,
 ) {
-    let board = game.board();
     let (own_bb, opp_bb) = match perspective {
         Player::Black => (board.black_stones(), board.white_stones()),
         Player::White => (board.white_stones(), board.black_stones()),
@@ -85,12 +159,20 @@ fn fill_go_planes<const NW: usize>(
     }
 }
 
-/// Encode a move as an action index for the policy head
+/// Encode a move as an action index for the policy head.
+/// Swap follows pass and is only part of the action space of games played
+/// with the swap rule (see `total_actions_for` and `decode_move_for`).
+/// Resign is not part of the action space and encodes to one past the last
+/// action, unless added with `EncoderConfig::resign_action` (see
+/// `encode_move_with`).
 #[hotpath::measure]
 pub fn encode_move(move_: &Move, board_width: u8, board_height: u8) -> usize {
+    let board_size = board_width as usize * board_height as usize;
     match move_ {
         Move::Place { col, row } => *row as usize * board_width as usize + *col as usize,
-        Move::Pass => board_width as usize * board_height as usize,
+        Move::Pass => board_size,
+        Move::Swap => board_size + 1,
+        Move::Resign => board_size + 2,
     }
 }
 
@@ -119,6 +201,420 @@ pub fn total_actions(board_width: u8, board_height: u8) -> usize {
     board_width as usize * board_height as usize + 1
 }
 
+/// Size of the action space for games played with the swap rule: the board
+/// points, pass and swap.
+#[hotpath::measure]
+pub fn total_actions_with_swap(board_width: u8, board_height: u8) -> usize {
+    total_actions(board_width, board_height) + 1
+}
+
+/// Like `decode_move`, but also decodes swap when `game`'s swap rule is on.
+#[hotpath::measure]
+pub fn decode_move_for<const NW: usize>(action: usize, game: &Game<NW>) -> Option<Move> {
+    let (w, h) = (game.width(), game.height());
+    if game.swap_rule() && action == encode_move(&Move::swap(), w, h) {
+        return Some(Move::swap());
+    }
+    decode_move(action, w, h)
+}
+
+/// Size of `game`'s action space, including swap when its swap rule is on.
+#[hotpath::measure]
+pub fn total_actions_for<const NW: usize>(game: &Game<NW>) -> usize {
+    if game.swap_rule() {
+        total_actions_with_swap(game.width(), game.height())
+    } else {
+        total_actions(game.width(), game.height())
+    }
+}
+
+/// Size of `game`'s action space with the layout of `config`: the points,
+/// pass, swap when the swap rule is on, and resign last when
+/// `config.resign_action` is set.
+#[hotpath::measure]
+pub fn total_actions_with<const NW: usize>(game: &Game<NW>, config: &EncoderConfig) -> usize {
+    total_actions_for(game) + config.resign_action as usize
+}
+
+/// Like `encode_move`, but with resign at the end of `game`'s action space
+/// when `config.resign_action` is set.
+#[hotpath::measure]
+pub fn encode_move_with<const NW: usize>(
+    move_: &Move,
+    game: &Game<NW>,
+    config: &EncoderConfig,
+) -> usize {
+    if config.resign_action && move_.is_resign() {
+        total_actions_for(game)
+    } else {
+        encode_move(move_, game.width(), game.height())
+    }
+}
+
+/// Like `decode_move_for`, but also decodes resign when
+/// `config.resign_action` is set.
+#[hotpath::measure]
+pub fn decode_move_with<const NW: usize>(
+    action: usize,
+    game: &Game<NW>,
+    config: &EncoderConfig,
+) -> Option<Move> {
+    if config.resign_action && action == total_actions_for(game) {
+        return Some(Move::resign());
+    }
+    decode_move_for(action, game)
+}
+
+/// Write 1 for each legal action of `game` and 0 elsewhere into `mask`,
+/// which must hold `total_actions_with(game, config)` values. A finished
+/// game has no legal actions.
+#[hotpath::measure]
+pub fn legal_action_mask_into<const NW: usize>(
+    game: &Game<NW>,
+    config: &EncoderConfig,
+    mask: &mut [u8],
+) {
+    assert_eq!(
+        mask.len(),
+        total_actions_with(game, config),
+        "mask length does not match the action space"
+    );
+    mask.fill(0);
+    if game.is_over() {
+        return;
+    }
+    for move_ in game.legal_moves() {
+        mask[encode_move_with(&move_, game, config)] = 1;
+    }
+    if config.resign_action && game.is_legal_move(&Move::resign()) {
+        mask[total_actions_for(game)] = 1;
+    }
+}
+
+/// Number of symmetries of a `width` x `height` board, as numbered by
+/// `Position::transform`: all 8 rotations and reflections on square boards,
+/// the 4 flips otherwise.
+#[hotpath::measure]
+pub fn symmetry_count(width: u8, height: u8) -> usize {
+    if width == height {
+        8
+    } else {
+        4
+    }
+}
+
+/// The index permutation of a policy over `total_actions` actions in the
+/// layout of `encode_move` under `symmetry`: the transformed policy is
+/// `policy[permutation[i]]` for each action `i`. Pass and the actions after
+/// it stay in place.
+#[hotpath::measure]
+pub fn symmetry_permutation(
+    symmetry: usize,
+    width: u8,
+    height: u8,
+    total_actions: usize,
+) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..total_actions).collect();
+    for from in 0..width as usize * height as usize {
+        let to = Position::from_index(from, width).transform(symmetry, width, height);
+        permutation[to.to_index(width)] = from;
+    }
+    permutation
+}
+
+/// `data`, planes as written by `encode_game_planes_into` for a `width` x
+/// `height` board, under `symmetry`.
+#[hotpath::measure]
+pub fn transform_planes(data: &[f32], symmetry: usize, width: u8, height: u8) -> Vec<f32> {
+    let board_size = width as usize * height as usize;
+    let permutation = symmetry_permutation(symmetry, width, height, board_size);
+    let mut out = vec![0.0; data.len()];
+    for (plane, out_plane) in data.chunks(board_size).zip(out.chunks_mut(board_size)) {
+        for (value, &from) in out_plane.iter_mut().zip(&permutation) {
+            *value = plane[from];
+        }
+    }
+    out
+}
+
+/// `game` encoded with `config` under each symmetry of its board (see
+/// `symmetry_count`), for training augmentation. Each comes with the
+/// `symmetry_permutation` of its `total_actions_with(game, config)`
+/// actions; the first is the untransformed encoding.
+#[hotpath::measure]
+pub fn symmetries<const NW: usize>(
+    game: &Game<NW>,
+    config: &EncoderConfig,
+) -> Vec<(Vec<f32>, Vec<usize>)> {
+    let (width, height) = (game.width(), game.height());
+    let (data, ..) = encode_game_planes_with(game, config);
+    let total = total_actions_with(game, config);
+    (0..symmetry_count(width, height))
+        .map(|symmetry| {
+            (
+                transform_planes(&data, symmetry, width, height),
+                symmetry_permutation(symmetry, width, height, total),
+            )
+        })
+        .collect()
+}
+
+/// Hash of everything `encode_game_planes_with` reads from `game`: the
+/// boards in the history window, the player to move and, with the capture
+/// plane, the capturing points.
+#[hotpath::measure]
+fn encoding_key<const NW: usize>(game: &Game<NW>, config: &EncoderConfig) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (game.turn() as i8).hash(&mut hasher);
+    let mut boards = 0usize;
+    for board in game.board_history().take(config.history_length) {
+        board.hash(&mut hasher);
+        boards += 1;
+    }
+    boards.hash(&mut hasher);
+    if config.capture_plane {
+        game.capture_points().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Least-recently-used cache of encoded planes, keyed by a hash of the
+/// encoded positions. Positions reached by transposition or repeated across
+/// many environments are encoded once.
+#[derive(Clone, Debug)]
+pub struct EncodingCache {
+    config: EncoderConfig,
+    capacity: usize,
+    /// Encoded planes, shape and last use for each key.
+    entries: HashMap<u64, (Vec<f32>, usize, usize, usize, u64)>,
+    /// Keys by last use, oldest first.
+    order: BTreeMap<u64, u64>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+#[hotpath::measure_all]
+impl EncodingCache {
+    /// A cache holding up to `capacity` encodings made with `config`.
+    pub fn new(capacity: usize, config: EncoderConfig) -> Self {
+        EncodingCache {
+            config,
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// `encode_game_planes_with(game, config)`, from the cache when possible.
+    pub fn encode<const NW: usize>(&mut self, game: &Game<NW>) -> (Vec<f32>, usize, usize, usize) {
+        let key = encoding_key(game, &self.config);
+        self.tick += 1;
+
+        if let Some((data, planes, height, width, last_used)) = self.entries.get_mut(&key) {
+            self.order.remove(last_used);
+            self.order.insert(self.tick, key);
+            *last_used = self.tick;
+            self.hits += 1;
+            return (data.clone(), *planes, *height, *width);
+        }
+
+        self.misses += 1;
+        let (data, planes, height, width) = encode_game_planes_with(game, &self.config);
+        self.insert(key, &data, (planes, height, width));
+        (data, planes, height, width)
+    }
+
+    /// `encode_game_planes_into(game, config, data)`, copying from the cache
+    /// when possible. Returns (num_planes, height, width).
+    ///
+    /// # Panics
+    ///
+    /// If `data.len()` is not `encoded_len(game, config)`.
+    pub fn encode_into<const NW: usize>(
+        &mut self,
+        game: &Game<NW>,
+        data: &mut [f32],
+    ) -> (usize, usize, usize) {
+        let key = encoding_key(game, &self.config);
+        self.tick += 1;
+
+        if let Some((cached, planes, height, width, last_used)) = self.entries.get_mut(&key) {
+            data.copy_from_slice(cached);
+            self.order.remove(last_used);
+            self.order.insert(self.tick, key);
+            *last_used = self.tick;
+            self.hits += 1;
+            return (*planes, *height, *width);
+        }
+
+        self.misses += 1;
+        let shape = encode_game_planes_into(game, &self.config, data);
+        self.insert(key, data, shape);
+        shape
+    }
+
+    fn insert(&mut self, key: u64, data: &[f32], (planes, height, width): (usize, usize, usize)) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries
+            .insert(key, (data.to_vec(), planes, height, width, self.tick));
+        self.order.insert(self.tick, key);
+    }
+
+    pub fn config(&self) -> &EncoderConfig {
+        &self.config
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Drop all entries and reset the hit and miss counts.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// One position of a recorded game, passed to the callback of
+/// `stream_positions`.
+#[cfg(feature = "dataset")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamedPosition {
+    /// Index of the game in the input, from 0.
+    pub game: usize,
+    /// Index of `played` among the game's moves, from 0.
+    pub move_index: usize,
+    /// The position before `played`, as from `encode_game_planes_with`.
+    pub planes: Vec<f32>,
+    pub num_planes: usize,
+    pub height: usize,
+    pub width: usize,
+    pub player: Player,
+    pub played: Move,
+}
+
+/// Errors from `stream_positions`.
+#[cfg(feature = "dataset")]
+#[derive(Debug)]
+pub enum StreamError {
+    Io(std::io::Error),
+    /// Game `game` (from 0) could not be read or replayed.
+    Sgf {
+        game: usize,
+        error: SgfError,
+    },
+}
+
+#[cfg(feature = "dataset")]
+impl From<std::io::Error> for StreamError {
+    fn from(e: std::io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+#[cfg(feature = "dataset")]
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "I/O error reading SGF: {}", e),
+            StreamError::Sgf { game, error } => write!(f, "game {}: {}", game + 1, error),
+        }
+    }
+}
+
+#[cfg(feature = "dataset")]
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Sgf { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Encode every position of every game in an SGF collection, calling
+/// `callback` with the planes and the move played from each one, in order.
+/// Each game is replayed once, so a position's history comes from the moves
+/// already played rather than being rebuilt. Positions are passed on as the
+/// games are replayed, so an error is reported after every position before
+/// it. Returns the number of positions.
+///
+/// `NW` must be `nw_for_board(width, height)` for every game's board size.
+#[cfg(feature = "dataset")]
+#[hotpath::measure]
+pub fn stream_positions<const NW: usize, R: Read, F: FnMut(StreamedPosition)>(
+    mut sgf_reader: R,
+    config: &EncoderConfig,
+    mut callback: F,
+) -> Result<usize, StreamError> {
+    let mut text = String::new();
+    sgf_reader.read_to_string(&mut text)?;
+
+    let mut positions = 0;
+    for (index, record) in sgf_records(&text).enumerate() {
+        let fail = |error: SgfError| StreamError::Sgf { game: index, error };
+        let record = record.map_err(fail)?;
+        let mut game: Game<NW> = record.start_game().map_err(fail)?;
+        for (move_index, &(player, played)) in record.moves.iter().enumerate() {
+            let (planes, num_planes, height, width) = encode_game_planes_with(&game, config);
+            if player != game.turn() || !game.make_move(&played) {
+                let issue = if player != game.turn() {
+                    ReplayIssue::OutOfTurn
+                } else {
+                    ReplayIssue::Illegal
+                };
+                return Err(fail(SgfError::Replay(ReplayError {
+                    index: move_index,
+                    player,
+                    move_: played,
+                    issue,
+                })));
+            }
+            callback(StreamedPosition {
+                game: index,
+                move_index,
+                planes,
+                num_planes,
+                height,
+                width,
+                player,
+                played,
+            });
+            positions += 1;
+        }
+    }
+    Ok(positions)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,8 +633,8 @@ mod tests {
 
     #[test]
     fn test_encode_game_empty() {
-        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
-        let (data, num_planes, height, width) = encode_game_planes(&mut game);
+        let game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let (data, num_planes, height, width) = encode_game_planes(&game);
 
         assert_eq!(num_planes, TOTAL_INPUT_PLANES);
         assert_eq!(height, 9);
@@ -155,6 +651,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encoder_config_history_length() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 0));
+
+        assert_eq!(EncoderConfig::default().num_planes(), TOTAL_INPUT_PLANES);
+
+        let config = EncoderConfig {
+            history_length: 2,
+            ..EncoderConfig::default()
+        };
+        let (data, num_planes, height, width) = encode_game_planes_with(&game, &config);
+        assert_eq!(num_planes, 5);
+        assert_eq!(data.len(), num_planes * height * width);
+
+        // T=1 is the position before White's move: only Black's stone at (0,0).
+        assert_eq!(get_plane_value(&data, 2, 0, 0, height, width), 1.0);
+        assert_eq!(get_plane_value(&data, 3, 0, 1, height, width), 0.0);
+        // Color plane is last: Black to move.
+        assert_eq!(get_plane_value(&data, 4, 0, 0, height, width), 1.0);
+    }
+
+    #[test]
+    fn test_capture_plane() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        for m in [
+            Move::place(1, 0),
+            Move::place(0, 0),
+            Move::place(5, 5),
+            Move::place(6, 6),
+        ] {
+            assert!(game.make_move(&m));
+        }
+        assert_eq!(game.capturing_moves(), vec![Move::place(0, 1)]);
+
+        let config = EncoderConfig {
+            capture_plane: true,
+            ..EncoderConfig::default()
+        };
+        assert_eq!(config.num_planes(), TOTAL_INPUT_PLANES + 1);
+        let (data, num_planes, height, width) = encode_game_planes_with(&game, &config);
+        let plane = num_planes - 1;
+        let marked: Vec<(usize, usize)> = (0..height)
+            .flat_map(|r| (0..width).map(move |c| (r, c)))
+            .filter(|&(r, c)| get_plane_value(&data, plane, r, c, height, width) == 1.0)
+            .collect();
+        assert_eq!(marked, vec![(1, 0)]);
+
+        // Black takes a ko; White's recapture is not a legal capture.
+        let mut ko = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        for m in [
+            Move::place(1, 0),
+            Move::place(2, 0),
+            Move::place(0, 1),
+            Move::place(3, 1),
+            Move::place(1, 2),
+            Move::place(2, 2),
+            Move::place(8, 8),
+            Move::place(1, 1),
+            Move::place(2, 1),
+        ] {
+            assert!(ko.make_move(&m));
+        }
+        assert!(ko.ko_point().is_some());
+        assert!(ko.capturing_moves().is_empty());
+    }
+
     #[test]
     fn test_encode_decode_move() {
         let width: u8 = 9;
@@ -178,6 +742,39 @@ mod tests {
         let decoded_pass = decode_move(encoded_pass, width, height)
             .expect("test_encode_decode_move: failed to decode pass move");
         assert_eq!(decoded_pass, pass);
+
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let encoded_swap = encode_move(&Move::swap(), width, height);
+        assert_eq!(encoded_swap, total_actions(width, height));
+        assert_eq!(decode_move_for(encoded_swap, &game), None);
+        game.set_swap_rule(true);
+        assert_eq!(decode_move_for(encoded_swap, &game), Some(Move::swap()));
+        assert_eq!(decode_move_for(0, &game), Some(Move::place(0, 0)));
+        assert_eq!(total_actions_for(&game), encoded_swap + 1);
+    }
+
+    #[test]
+    fn test_resign_action() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let config = EncoderConfig {
+            resign_action: true,
+            ..EncoderConfig::default()
+        };
+        assert_eq!(total_actions_with(&game, &config), 83);
+        assert_eq!(encode_move_with(&Move::resign(), &game, &config), 82);
+        assert_eq!(decode_move_with(82, &game, &config), Some(Move::resign()));
+        assert_eq!(decode_move_with(81, &game, &config), Some(Move::pass()));
+        assert_eq!(decode_move_with(83, &game, &config), None);
+
+        let off = EncoderConfig::default();
+        assert_eq!(total_actions_with(&game, &off), 82);
+        assert_eq!(decode_move_with(82, &game, &off), None);
+
+        // Resign follows swap.
+        game.set_swap_rule(true);
+        assert_eq!(encode_move_with(&Move::resign(), &game, &config), 83);
+        assert_eq!(decode_move_with(82, &game, &config), Some(Move::swap()));
+        assert_eq!(decode_move_with(83, &game, &config), Some(Move::resign()));
     }
 
     #[test]
@@ -185,6 +782,7 @@ mod tests {
         assert_eq!(total_actions(9, 9), 82);
         assert_eq!(total_actions(19, 19), 362);
         assert_eq!(total_actions(5, 5), 26);
+        assert_eq!(total_actions_with_swap(5, 5), 27);
     }
 
     #[test]
@@ -198,7 +796,7 @@ mod tests {
         game.make_move(&move2);
 
         // Now it's Black's turn again, so encode from Black's perspective
-        let (data, _num_planes, height, width) = encode_game_planes(&mut game);
+        let (data, _num_planes, height, width) = encode_game_planes(&game);
 
         // From Black's perspective: Black's piece at (0,0) should be in plane 0, White's at (1,0) in plane 1
         assert_eq!(get_plane_value(&data, 0, 0, 0, height, width), 1.0);
@@ -250,7 +848,7 @@ mod tests {
                             break;
                         }
 
-                        let (data, num_planes, height, width) = encode_game_planes(&mut game);
+                        let (data, num_planes, height, width) = encode_game_planes(&game);
                         assert_eq!(num_planes, TOTAL_INPUT_PLANES);
                         assert_eq!(height, game.height() as usize);
                         assert_eq!(width, game.width() as usize);
@@ -326,8 +924,8 @@ mod tests {
                 break;
             }
 
-            let encoding1 = encode_game_planes(&mut game);
-            let encoding2 = encode_game_planes(&mut game);
+            let encoding1 = encode_game_planes(&game);
+            let encoding2 = encode_game_planes(&game);
             assert_eq!(encoding1, encoding2, "Encoding should be deterministic");
 
             let chosen_move = legal_moves
@@ -337,11 +935,139 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encoding_cache_hits_and_evicts() {
+        let mut cache = EncodingCache::new(2, EncoderConfig::default());
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let empty = encode_game_planes(&game);
+
+        assert_eq!(cache.encode(&game), empty);
+        assert_eq!(cache.encode(&game), empty);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        assert!(game.make_move(&Move::place(4, 4)));
+        assert_eq!(cache.encode(&game), encode_game_planes(&game));
+        assert!(game.make_move(&Move::place(2, 2)));
+        cache.encode(&game);
+        assert_eq!(cache.len(), 2);
+
+        // The empty position was used least recently and has been evicted.
+        assert!(game.unmake_move() && game.unmake_move());
+        assert_eq!(cache.encode(&game), empty);
+        assert_eq!((cache.hits(), cache.misses()), (1, 4));
+
+        // Same stones and player to move, different history: not the same
+        // encoding.
+        assert!(game.make_move(&Move::place(4, 4)) && game.make_move(&Move::place(2, 2)));
+        cache.encode(&game);
+        let mut other = Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, 7.5, 0, 100, true);
+        for m in [
+            Move::pass(),
+            Move::place(2, 2),
+            Move::place(4, 4),
+            Move::pass(),
+        ] {
+            assert!(other.make_move(&m));
+        }
+        assert_eq!(other.board(), game.board());
+        assert_eq!(other.turn(), game.turn());
+        let before = cache.hits();
+        assert_eq!(cache.encode(&other), encode_game_planes(&other));
+        assert_eq!(cache.hits(), before);
+    }
+
+    #[test]
+    fn test_legal_action_mask_matches_legal_moves() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 100, true);
+        game.make_move(&Move::place(2, 2));
+        let config = EncoderConfig {
+            resign_action: true,
+            ..EncoderConfig::default()
+        };
+        let mut mask = vec![7u8; total_actions_with(&game, &config)];
+        legal_action_mask_into(&game, &config, &mut mask);
+        assert_eq!(mask.len(), 27);
+        assert_eq!((mask[12], mask[13], mask[25], mask[26]), (0, 1, 1, 1));
+        assert_eq!(mask.iter().map(|&v| v as usize).sum::<usize>(), 26);
+    }
+
+    #[test]
+    fn test_encode_into_reuses_buffer() {
+        let config = EncoderConfig::default();
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        assert!(game.make_move(&Move::place(2, 2)));
+        let (expected, planes, height, width) = encode_game_planes(&game);
+
+        // A dirty buffer is overwritten, not added to.
+        let mut data = vec![9.0f32; encoded_len(&game, &config)];
+        assert_eq!(
+            encode_game_planes_into(&game, &config, &mut data),
+            (planes, height, width)
+        );
+        assert_eq!(data, expected);
+
+        let mut cache = EncodingCache::new(4, config);
+        for _ in 0..2 {
+            data.fill(9.0);
+            cache.encode_into(&game, &mut data);
+            assert_eq!(data, expected);
+        }
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[cfg(feature = "dataset")]
+    #[test]
+    fn test_stream_positions_matches_replayed_games() {
+        let sgf = "(;SZ[9]KM[6.5];B[ee];W[cc];B[gg])\n(;SZ[9]HA[2]AB[cg][gc];W[ee];B[cc])";
+        let config = EncoderConfig {
+            history_length: 2,
+            ..EncoderConfig::default()
+        };
+        let mut streamed = Vec::new();
+        let count =
+            stream_positions::<{ nw_for_board(9, 9) }, _, _>(sgf.as_bytes(), &config, |position| {
+                streamed.push(position)
+            })
+            .expect("streams");
+        assert_eq!(count, 5);
+        assert_eq!(
+            streamed
+                .iter()
+                .map(|p| (p.game, p.move_index))
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)]
+        );
+
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(4, 4));
+        game.make_move(&Move::place(2, 6));
+        let third = &streamed[2];
+        assert_eq!(third.planes, encode_game_planes_with(&game, &config).0);
+        assert_eq!(
+            (third.player, third.played),
+            (Player::Black, Move::place(6, 2))
+        );
+        assert_eq!(streamed[3].player, Player::White);
+
+        let error = stream_positions::<{ nw_for_board(9, 9) }, _, _>(
+            "(;SZ[9];B[ee])(;SZ[9];B[ee];W[ee])".as_bytes(),
+            &config,
+            |_| {},
+        );
+        assert!(matches!(
+            error,
+            Err(StreamError::Sgf {
+                game: 1,
+                error: SgfError::Replay(_)
+            })
+        ));
+    }
+
     #[test]
     fn test_encoding_after_undo() {
         let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
 
-        let initial_encoding = encode_game_planes(&mut game);
+        let initial_encoding = encode_game_planes(&game);
 
         let move1 = Move::place(0, 0);
         game.make_move(&move1);
@@ -352,7 +1078,7 @@ mod tests {
         game.unmake_move();
         game.unmake_move();
 
-        let final_encoding = encode_game_planes(&mut game);
+        let final_encoding = encode_game_planes(&game);
         assert_eq!(
             initial_encoding, final_encoding,
             "Encoding after undo should match initial state"
@@ -361,8 +1087,8 @@ mod tests {
 
     #[test]
     fn test_plane_sizes() {
-        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
-        let (data, num_planes, height, width) = encode_game_planes(&mut game);
+        let game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let (data, num_planes, height, width) = encode_game_planes(&game);
 
         assert_eq!(num_planes, TOTAL_INPUT_PLANES);
         assert_eq!(height, game.height() as usize);
@@ -378,8 +1104,8 @@ mod tests {
         game1.make_move(&Move::place(0, 0));
         game2.make_move(&Move::place(1, 0));
 
-        let encoding1 = encode_game_planes(&mut game1);
-        let encoding2 = encode_game_planes(&mut game2);
+        let encoding1 = encode_game_planes(&game1);
+        let encoding2 = encode_game_planes(&game2);
 
         assert_ne!(
             encoding1, encoding2,
@@ -400,12 +1126,12 @@ mod tests {
 
     #[test]
     fn test_encode_arbitrary_board_size_19x19() {
-        let mut game = Game::<{ nw_for_board(19, 19) }>::new(19, 19);
+        let game = Game::<{ nw_for_board(19, 19) }>::new(19, 19);
 
         assert_eq!(game.width(), 19u8);
         assert_eq!(game.height(), 19u8);
 
-        let (data, num_planes, height, width) = encode_game_planes(&mut game);
+        let (data, num_planes, height, width) = encode_game_planes(&game);
         assert_eq!(num_planes, TOTAL_INPUT_PLANES);
         assert_eq!(height, 19);
         assert_eq!(width, 19);
@@ -426,12 +1152,12 @@ mod tests {
 
     #[test]
     fn test_encode_arbitrary_board_size_5x5() {
-        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        let game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
 
         assert_eq!(game.width(), 5u8);
         assert_eq!(game.height(), 5u8);
 
-        let (data, num_planes, height, width) = encode_game_planes(&mut game);
+        let (data, num_planes, height, width) = encode_game_planes(&game);
         assert_eq!(num_planes, TOTAL_INPUT_PLANES);
         assert_eq!(height, 5);
         assert_eq!(width, 5);
@@ -440,11 +1166,11 @@ mod tests {
 
     #[test]
     fn test_encode_different_board_sizes_different_encodings() {
-        let mut game1 = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
-        let mut game2 = Game::<{ nw_for_board(19, 19) }>::new(19, 19);
+        let game1 = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let game2 = Game::<{ nw_for_board(19, 19) }>::new(19, 19);
 
-        let (data1, num_planes1, height1, width1) = encode_game_planes(&mut game1);
-        let (data2, num_planes2, height2, width2) = encode_game_planes(&mut game2);
+        let (data1, num_planes1, height1, width1) = encode_game_planes(&game1);
+        let (data2, num_planes2, height2, width2) = encode_game_planes(&game2);
 
         assert_eq!(num_planes1, TOTAL_INPUT_PLANES);
         assert_eq!(num_planes2, TOTAL_INPUT_PLANES);
@@ -472,4 +1198,39 @@ mod tests {
             .expect("test_pass_move_encoding: failed to decode pass action 81 for 9x9");
         assert!(decoded.is_pass());
     }
+
+    #[test]
+    fn test_symmetries_move_stones_and_policy_together() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.make_move(&Move::place(1, 0));
+        let config = EncoderConfig::default();
+        let symmetries = symmetries(&game, &config);
+        assert_eq!(symmetries.len(), 8);
+
+        let (data, ..) = encode_game_planes(&game);
+        assert_eq!(symmetries[0].0, data);
+        assert_eq!(symmetries[0].1, (0..26).collect::<Vec<_>>());
+
+        let mut policy = [0.0; 26];
+        policy[encode_move(&Move::place(1, 0), 5, 5)] = 1.0;
+        for (symmetry, (planes, permutation)) in symmetries.iter().enumerate() {
+            let to = Position::new(1, 0).transform(symmetry, 5, 5);
+            // Black's stone is on plane 1 from White's perspective.
+            assert_eq!(
+                get_plane_value(planes, 1, to.row as usize, to.col as usize, 5, 5),
+                1.0
+            );
+            let transformed: Vec<f32> = permutation.iter().map(|&i| policy[i]).collect();
+            assert_eq!(transformed[to.to_index(5)], 1.0);
+            assert_eq!(permutation[25], 25);
+        }
+    }
+
+    #[test]
+    fn test_rectangular_boards_have_four_symmetries() {
+        let game = Game::<{ nw_for_board(5, 3) }>::new(5, 3);
+        assert_eq!(symmetry_count(5, 3), 4);
+        assert_eq!(symmetries(&game, &EncoderConfig::default()).len(), 4);
+        assert_eq!(Position::new(0, 0).transform(3, 5, 3), Position::new(4, 2));
+    }
 }
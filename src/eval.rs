@@ -0,0 +1,352 @@
+use std::fmt;
+use std::io::Write;
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+
+use crate::dispatch::{make_game_inner_with_options, GameInner};
+use crate::game::{default_max_moves, default_min_moves_before_pass_possible};
+use crate::policy::AnySizePolicy;
+use crate::rules::Ruleset;
+use crate::selfplay::derive_game_seed;
+use crate::tournament::{run_match, MatchResult};
+
+/// A grid of matches between two policies: every combination of board
+/// size, komi and handicap, in that order of nesting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepConfig {
+    /// Sizes of the square boards.
+    pub sizes: Vec<u8>,
+    pub komis: Vec<f32>,
+    /// Fixed handicap stones for Black, 0 for an even game.
+    pub handicaps: Vec<u8>,
+    /// Games in each cell of the grid; the first policy plays Black in the
+    /// even-numbered ones.
+    pub games: u64,
+    /// Cell `i` plays its match from the seed `derive_game_seed(seed, i)`.
+    pub seed: u64,
+    pub ruleset: Ruleset,
+}
+
+#[hotpath::measure_all]
+impl Default for SweepConfig {
+    fn default() -> Self {
+        SweepConfig {
+            sizes: vec![9],
+            komis: vec![7.5],
+            handicaps: vec![0],
+            games: 10,
+            seed: 0,
+            ruleset: Ruleset::default(),
+        }
+    }
+}
+
+/// A grid cell the sweep cannot play.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SweepError {
+    /// Boards run from 2x2 to 32x32.
+    BoardSize(u8),
+    /// The handicap is not 0 or a fixed handicap of the board size.
+    Handicap { size: u8, handicap: u8 },
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepError::BoardSize(size) => write!(f, "unsupported board size: {}", size),
+            SweepError::Handicap { size, handicap } => write!(
+                f,
+                "no fixed handicap of {} stones on a {}x{} board",
+                handicap, size, size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+/// The match played in one cell of the grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepRow {
+    pub size: u8,
+    pub komi: f32,
+    pub handicap: u8,
+    pub result: MatchResult,
+}
+
+/// The rows of a sweep, one per grid cell in the order they were played.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SweepResults {
+    pub rows: Vec<SweepRow>,
+}
+
+/// Column names of `SweepResults::write_csv`.
+pub const SWEEP_CSV_HEADER: &str =
+    "size,komi,handicap,games,first_wins,second_wins,draws,first_score";
+
+#[hotpath::measure_all]
+impl SweepResults {
+    /// The results as CSV: `SWEEP_CSV_HEADER`, then one line per row, wins
+    /// counted from the first policy's side.
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        writeln!(out, "{}", SWEEP_CSV_HEADER)?;
+        for row in &self.rows {
+            let r = &row.result;
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{}",
+                row.size,
+                row.komi,
+                row.handicap,
+                r.num_games(),
+                r.first_wins,
+                r.second_wins,
+                r.draws,
+                r.first_score()
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = Vec::new();
+        self.write_csv(&mut out)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("CSV is ASCII")
+    }
+
+    /// The results as a Parquet file with the columns of `write_csv`, in a
+    /// single row group.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(&self, out: W) -> parquet::errors::Result<()> {
+        use parquet::data_type::{FloatType, Int32Type, Int64Type};
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::parser::parse_message_type;
+
+        let schema = parse_message_type(SWEEP_PARQUET_SCHEMA)?;
+        let mut writer = SerializedFileWriter::new(
+            out,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+        let mut group = writer.next_row_group()?;
+        let rows = &self.rows;
+        write_column::<_, Int32Type>(&mut group, &column(rows, |r| r.size.into()))?;
+        write_column::<_, FloatType>(&mut group, &column(rows, |r| r.komi))?;
+        write_column::<_, Int32Type>(&mut group, &column(rows, |r| r.handicap.into()))?;
+        write_column::<_, Int64Type>(&mut group, &column(rows, |r| r.result.num_games() as i64))?;
+        write_column::<_, Int64Type>(&mut group, &column(rows, |r| r.result.first_wins.into()))?;
+        write_column::<_, Int64Type>(&mut group, &column(rows, |r| r.result.second_wins.into()))?;
+        write_column::<_, Int64Type>(&mut group, &column(rows, |r| r.result.draws.into()))?;
+        write_column::<_, FloatType>(&mut group, &column(rows, |r| r.result.first_score()))?;
+        group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// One value per row, for a Parquet column.
+#[cfg(feature = "parquet")]
+#[hotpath::measure]
+fn column<T>(rows: &[SweepRow], value: impl Fn(&SweepRow) -> T) -> Vec<T> {
+    rows.iter().map(value).collect()
+}
+
+/// Write `values` as the next column of `group`.
+#[cfg(feature = "parquet")]
+#[hotpath::measure]
+fn write_column<W: Write + Send, T: parquet::data_type::DataType>(
+    group: &mut parquet::file::writer::SerializedRowGroupWriter<'_, W>,
+    values: &[T::T],
+) -> parquet::errors::Result<()> {
+    let mut column = group.next_column()?.ok_or_else(|| {
+        parquet::errors::ParquetError::General("more columns than the schema".to_string())
+    })?;
+    column.typed::<T>().write_batch(values, None, None)?;
+    column.close()
+}
+
+/// Schema of `SweepResults::write_parquet`: the CSV columns, with the
+/// counts as 64-bit integers.
+#[cfg(feature = "parquet")]
+pub const SWEEP_PARQUET_SCHEMA: &str = "
+message sweep {
+    REQUIRED INT32 size (INTEGER(8, false));
+    REQUIRED FLOAT komi;
+    REQUIRED INT32 handicap (INTEGER(8, false));
+    REQUIRED INT64 games;
+    REQUIRED INT64 first_wins;
+    REQUIRED INT64 second_wins;
+    REQUIRED INT64 draws;
+    REQUIRED FLOAT first_score;
+}";
+
+/// A game for one grid cell, with the default move limits of its size.
+#[hotpath::measure]
+fn cell_game(size: u8, komi: f32, handicap: u8, ruleset: Ruleset) -> Result<GameInner, SweepError> {
+    if !(2..=32).contains(&size) {
+        return Err(SweepError::BoardSize(size));
+    }
+    let mut inner = make_game_inner_with_options(
+        size,
+        size,
+        komi,
+        default_min_moves_before_pass_possible(size, size),
+        default_max_moves(size, size),
+        true,
+    );
+    let placed = dispatch_game_mut!(&mut inner, g => {
+        g.set_ruleset(ruleset);
+        handicap == 0 || g.place_handicap(handicap)
+    });
+    if !placed {
+        return Err(SweepError::Handicap { size, handicap });
+    }
+    Ok(inner)
+}
+
+/// Play `config`'s grid of matches between `first` and `second` with
+/// `tournament::run_match`. Every cell is checked before any game is played.
+#[hotpath::measure]
+pub fn sweep<A, B>(
+    config: &SweepConfig,
+    first: &mut A,
+    second: &mut B,
+) -> Result<SweepResults, SweepError>
+where
+    A: AnySizePolicy,
+    B: AnySizePolicy,
+{
+    let mut cells = Vec::new();
+    for &size in &config.sizes {
+        for &komi in &config.komis {
+            for &handicap in &config.handicaps {
+                cells.push((
+                    size,
+                    komi,
+                    handicap,
+                    cell_game(size, komi, handicap, config.ruleset)?,
+                ));
+            }
+        }
+    }
+
+    let mut results = SweepResults::default();
+    for (index, (size, komi, handicap, inner)) in cells.into_iter().enumerate() {
+        let seed = derive_game_seed(config.seed, index as u64);
+        let result = dispatch_game!(&inner, g => {
+            run_match(|| g.clone(), first, second, config.games, seed)
+        });
+        results.rows.push(SweepRow {
+            size,
+            komi,
+            handicap,
+            result,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{NoEyeFillPolicy, UniformPolicy};
+
+    #[test]
+    fn test_sweep_grid_to_csv() {
+        let config = SweepConfig {
+            sizes: vec![7],
+            komis: vec![0.5, 7.5],
+            handicaps: vec![0, 2],
+            games: 4,
+            seed: 1,
+            ..SweepConfig::default()
+        };
+        let results = sweep(&config, &mut UniformPolicy, &mut NoEyeFillPolicy).expect("valid grid");
+
+        let csv = results.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            "size,komi,handicap,games,first_wins,second_wins,draws,first_score"
+        );
+        let cells: Vec<Vec<&str>> = lines[1..].iter().map(|l| l.split(',').collect()).collect();
+        let grid: Vec<_> = cells.iter().map(|c| (c[0], c[1], c[2], c[3])).collect();
+        assert_eq!(
+            grid,
+            [
+                ("7", "0.5", "0", "4"),
+                ("7", "0.5", "2", "4"),
+                ("7", "7.5", "0", "4"),
+                ("7", "7.5", "2", "4"),
+            ]
+        );
+        for c in &cells {
+            let counts: Vec<u32> = c[4..7]
+                .iter()
+                .map(|v| v.parse().expect("a count"))
+                .collect();
+            assert_eq!(counts.iter().sum::<u32>(), 4);
+        }
+
+        // Same seed, same games.
+        let again = sweep(&config, &mut UniformPolicy, &mut NoEyeFillPolicy).expect("valid grid");
+        assert_eq!(again.to_csv(), csv);
+
+        let bad = SweepConfig {
+            sizes: vec![5],
+            handicaps: vec![2],
+            ..config
+        };
+        assert_eq!(
+            sweep(&bad, &mut UniformPolicy, &mut NoEyeFillPolicy),
+            Err(SweepError::Handicap {
+                size: 5,
+                handicap: 2
+            })
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_sweep_results_to_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::RowAccessor;
+
+        let config = SweepConfig {
+            sizes: vec![5, 7],
+            games: 2,
+            ..SweepConfig::default()
+        };
+        let results = sweep(&config, &mut UniformPolicy, &mut NoEyeFillPolicy).expect("valid grid");
+        let path = std::env::temp_dir().join("spooky_go_sweep.parquet");
+        let file = std::fs::File::create(&path).expect("creates file");
+        results.write_parquet(file).expect("writes Parquet");
+
+        let file = std::fs::File::open(&path).expect("opens file");
+        let reader = SerializedFileReader::new(file).expect("reads Parquet");
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .expect("iterates rows")
+            .map(|row| row.expect("reads row"))
+            .collect();
+        assert_eq!(rows.len(), 2);
+        for (row, expected) in rows.iter().zip(&results.rows) {
+            assert_eq!(row.get_ubyte(0).expect("size"), expected.size);
+            assert_eq!(row.get_float(1).expect("komi"), 7.5);
+            assert_eq!(row.get_ubyte(2).expect("handicap"), 0);
+            assert_eq!(row.get_long(3).expect("games"), 2);
+            assert_eq!(
+                row.get_long(4).expect("first wins"),
+                i64::from(expected.result.first_wins)
+            );
+            assert_eq!(
+                row.get_float(7).expect("first score"),
+                expected.result.first_score()
+            );
+        }
+        std::fs::remove_file(&path).expect("removes file");
+    }
+}
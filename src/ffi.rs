@@ -0,0 +1,505 @@
+use std::ffi::{c_char, c_void, CStr};
+
+use crate::dispatch::{make_game_inner, make_game_inner_with_options, GameInner};
+use crate::encode;
+use crate::game::{validate_move_limits, Game};
+use crate::policy::{Evaluation, Evaluator, PuctPolicy};
+use crate::position_string::parse_position_string;
+use crate::r#move::Move;
+
+/// Version of the C API. Bumped whenever a function or its meaning changes.
+pub const SPOOKY_GO_ABI_VERSION: u32 = 2;
+
+/// An opaque game handle for the C API, created with `spooky_go_new` and
+/// released with `spooky_go_free`. Moves cross the API as action indices in
+/// the layout of `encode::encode_move`. The declarations are in
+/// `include/spooky_go.h`.
+pub struct SpookyGoGame {
+    inner: GameInner,
+}
+
+/// An evaluator for `spooky_go_search`, e.g. a C++ or CUDA inference
+/// engine. Called with the caller's `user_data` and a position encoded as
+/// by `spooky_go_encode_planes`, it writes a prior for each of the
+/// `policy_len` actions to `policy` and the expected result for the player
+/// to move, from -1 to 1, to `value`. Returns 0 on success; anything else
+/// stops the search.
+pub type SpookyGoEvaluator = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    planes: *const f32,
+    planes_len: usize,
+    policy: *mut f32,
+    policy_len: usize,
+    value: *mut f32,
+) -> i32;
+
+/// `SpookyGoEvaluator` as a search `Evaluator`.
+struct CallbackEvaluator {
+    callback: SpookyGoEvaluator,
+    user_data: *mut c_void,
+    failed: bool,
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Evaluator<NW> for CallbackEvaluator {
+    fn evaluate(&mut self, game: &Game<NW>) -> Option<Evaluation> {
+        let (planes, _, _, _) = encode::encode_game_planes(game);
+        let mut policy = vec![0.0; encode::total_actions_for(game)];
+        let mut value = 0.0;
+        // SAFETY: `spooky_go_search`'s caller vouches for the callback; the
+        // buffers are live and sized as passed.
+        let status = unsafe {
+            (self.callback)(
+                self.user_data,
+                planes.as_ptr(),
+                planes.len(),
+                policy.as_mut_ptr(),
+                policy.len(),
+                &mut value,
+            )
+        };
+        if status != 0 {
+            self.failed = true;
+            return None;
+        }
+        Some(Evaluation { policy, value })
+    }
+}
+
+#[hotpath::measure]
+fn valid_size(width: u8, height: u8) -> bool {
+    (2..=32).contains(&width) && (2..=32).contains(&height)
+}
+
+#[hotpath::measure]
+fn into_handle(inner: GameInner) -> *mut SpookyGoGame {
+    Box::into_raw(Box::new(SpookyGoGame { inner }))
+}
+
+/// The `SPOOKY_GO_ABI_VERSION` the library was built with.
+#[no_mangle]
+pub extern "C" fn spooky_go_abi_version() -> u32 {
+    SPOOKY_GO_ABI_VERSION
+}
+
+/// A new game with the default rules for its size, or null if the size is
+/// outside 2 to 32.
+#[no_mangle]
+pub extern "C" fn spooky_go_new(width: u8, height: u8) -> *mut SpookyGoGame {
+    if !valid_size(width, height) {
+        return std::ptr::null_mut();
+    }
+    into_handle(make_game_inner(width, height))
+}
+
+/// A new game with explicit options (see `Game::with_options`), or null if
+/// the size is outside 2 to 32 or the move limits fail
+/// `game::validate_move_limits`.
+#[no_mangle]
+pub extern "C" fn spooky_go_new_with_options(
+    width: u8,
+    height: u8,
+    komi: f32,
+    min_moves_before_pass_possible: u16,
+    max_moves: u16,
+    superko: bool,
+) -> *mut SpookyGoGame {
+    if !valid_size(width, height)
+        || validate_move_limits(min_moves_before_pass_possible, max_moves).is_err()
+    {
+        return std::ptr::null_mut();
+    }
+    into_handle(make_game_inner_with_options(
+        width,
+        height,
+        komi,
+        min_moves_before_pass_possible,
+        max_moves,
+        superko,
+    ))
+}
+
+/// A game with the default rules starting from a position string (see
+/// `position_string::parse_position_string`), or null if it cannot be read.
+///
+/// # Safety
+///
+/// `text` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_from_position_string(text: *const c_char) -> *mut SpookyGoGame {
+    if text.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return std::ptr::null_mut();
+    };
+    let Ok(record) = parse_position_string(text) else {
+        return std::ptr::null_mut();
+    };
+    let mut inner = make_game_inner(record.width, record.height);
+    dispatch_game_mut!(&mut inner, g => record.set_up(g));
+    into_handle(inner)
+}
+
+/// An independent copy of `game`, e.g. for a search thread.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_clone(game: *const SpookyGoGame) -> *mut SpookyGoGame {
+    into_handle((*game).inner.clone())
+}
+
+/// Release `game`. Null is ignored.
+///
+/// # Safety
+///
+/// `game` must be null or a live handle, which is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_free(game: *mut SpookyGoGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_width(game: *const SpookyGoGame) -> u8 {
+    dispatch_game!(&(*game).inner, g => g.width())
+}
+
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_height(game: *const SpookyGoGame) -> u8 {
+    dispatch_game!(&(*game).inner, g => g.height())
+}
+
+/// The player to move: 1 for Black, -1 for White.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_turn(game: *const SpookyGoGame) -> i8 {
+    dispatch_game!(&(*game).inner, g => g.turn() as i8)
+}
+
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_is_over(game: *const SpookyGoGame) -> bool {
+    dispatch_game!(&(*game).inner, g => g.is_over())
+}
+
+/// The winner once the game is over: 1 for Black, -1 for White, 0 for a
+/// draw or a game still in progress.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_winner(game: *const SpookyGoGame) -> i8 {
+    dispatch_game!(&(*game).inner, g => g.outcome())
+        .and_then(|o| o.winner())
+        .map_or(0, |p| p as i8)
+}
+
+/// Write the score, with komi added to White's, to `black` and `white`.
+/// Null pointers are skipped.
+///
+/// # Safety
+///
+/// `game` must be a live handle; `black` and `white` must be null or valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_score(
+    game: *const SpookyGoGame,
+    black: *mut f32,
+    white: *mut f32,
+) {
+    let (b, w) = dispatch_game!(&(*game).inner, g => g.score());
+    if !black.is_null() {
+        *black = b;
+    }
+    if !white.is_null() {
+        *white = w;
+    }
+}
+
+/// Moves left before the move limit ends the game, or 0 once it is over.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_moves_remaining(game: *const SpookyGoGame) -> u16 {
+    dispatch_game!(&(*game).inner, g => g.moves_remaining())
+}
+
+/// Size of `game`'s action space: the points, pass, and swap when the swap
+/// rule is on.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_total_actions(game: *const SpookyGoGame) -> usize {
+    dispatch_game!(&(*game).inner, g => encode::total_actions_for(g))
+}
+
+/// Write the legal moves as action indices to `out`, up to `capacity` of
+/// them. Returns the number of legal moves, which may exceed `capacity`;
+/// `spooky_go_total_actions` is always enough.
+///
+/// # Safety
+///
+/// `game` must be a live handle; `out` must be valid for `capacity` writes
+/// (or null with `capacity` 0).
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_legal_moves(
+    game: *const SpookyGoGame,
+    out: *mut u32,
+    capacity: usize,
+) -> usize {
+    dispatch_game!(&(*game).inner, g => {
+        let moves = g.legal_moves();
+        for (i, m) in moves.iter().take(capacity).enumerate() {
+            *out.add(i) = encode::encode_move(m, g.width(), g.height()) as u32;
+        }
+        moves.len()
+    })
+}
+
+/// Play the move of action index `action`. Returns false, leaving the game
+/// unchanged, if the index is out of range or the move is illegal.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_make_move(game: *mut SpookyGoGame, action: u32) -> bool {
+    dispatch_game_mut!(&mut (*game).inner, g => {
+        match encode::decode_move_for(action as usize, g) {
+            Some(move_) => g.make_move(&move_),
+            None => false,
+        }
+    })
+}
+
+/// Resign for the player to move. Returns false if the game is over.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_resign(game: *mut SpookyGoGame) -> bool {
+    dispatch_game_mut!(&mut (*game).inner, g => g.make_move(&Move::resign()))
+}
+
+/// Take back the last move. Returns false if there is none.
+///
+/// # Safety
+///
+/// `game` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_unmake_move(game: *mut SpookyGoGame) -> bool {
+    dispatch_game_mut!(&mut (*game).inner, g => g.unmake_move())
+}
+
+/// Number of input planes written by `spooky_go_encode_planes`.
+#[no_mangle]
+pub extern "C" fn spooky_go_num_planes() -> usize {
+    encode::TOTAL_INPUT_PLANES
+}
+
+/// Encode `game` into `out` as `encode::encode_game_planes` lays the planes
+/// out: plane-major, then row-major. Returns the number of values, and
+/// writes nothing if `len` is smaller than that.
+///
+/// # Safety
+///
+/// `game` must be a live handle; `out` must be valid for `len` writes (or
+/// null with `len` 0).
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_encode_planes(
+    game: *const SpookyGoGame,
+    out: *mut f32,
+    len: usize,
+) -> usize {
+    let (planes, _, _, _) = dispatch_game!(&(*game).inner, g => encode::encode_game_planes(g));
+    if len >= planes.len() {
+        std::ptr::copy_nonoverlapping(planes.as_ptr(), out, planes.len());
+    }
+    planes.len()
+}
+
+/// Search `game` for `iterations` evaluations with `evaluator` (see
+/// `policy::PuctPolicy`) and return the action of the most visited move.
+/// If `visits` is not null and `visits_len` is at least
+/// `spooky_go_total_actions`, the visit count of every action is written
+/// there. Returns -1, writing nothing, if the game is over or the evaluator
+/// fails.
+///
+/// # Safety
+///
+/// `game` must be a live handle; `evaluator` must be safe to call with
+/// `user_data` and buffers of the lengths it is given; `visits` must be
+/// null or valid for `visits_len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn spooky_go_search(
+    game: *const SpookyGoGame,
+    evaluator: SpookyGoEvaluator,
+    user_data: *mut c_void,
+    iterations: u32,
+    exploration: f32,
+    visits: *mut u32,
+    visits_len: usize,
+) -> i64 {
+    let mut policy = PuctPolicy {
+        exploration,
+        ..PuctPolicy::new(
+            CallbackEvaluator {
+                callback: evaluator,
+                user_data,
+                failed: false,
+            },
+            iterations,
+        )
+    };
+    dispatch_game!(&(*game).inner, g => {
+        let root = policy.search(g);
+        if policy.evaluator.failed || root.is_empty() {
+            return -1;
+        }
+        let total = encode::total_actions_for(g);
+        if !visits.is_null() && visits_len >= total {
+            let out = std::slice::from_raw_parts_mut(visits, total);
+            out.fill(0);
+            for &(move_, count) in &root {
+                out[encode::encode_move_with(&move_, g, &policy.encoder)] = count;
+            }
+        }
+        let (best, _) = root
+            .iter()
+            .max_by_key(|&&(_, count)| count)
+            .expect("checked non-empty");
+        encode::encode_move_with(best, g, &policy.encoder) as i64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_c_api_plays_and_encodes() {
+        assert!(spooky_go_new(1, 9).is_null());
+        assert!(spooky_go_new_with_options(5, 5, 0.5, 10, 5, true).is_null());
+        let game = spooky_go_new_with_options(5, 5, 0.5, 0, 100, true);
+        assert!(!game.is_null());
+        unsafe {
+            let total = spooky_go_total_actions(game);
+            assert_eq!(total, 26);
+            let mut moves = vec![0u32; total];
+            assert_eq!(spooky_go_legal_moves(game, moves.as_mut_ptr(), total), 26);
+            assert_eq!(moves[25], 25);
+
+            // C3 for Black, then C3 again is illegal.
+            assert!(spooky_go_make_move(game, 12));
+            assert!(!spooky_go_make_move(game, 12));
+            assert!(!spooky_go_make_move(game, 99));
+            assert_eq!(spooky_go_turn(game), -1);
+            assert_eq!(spooky_go_moves_remaining(game), 99);
+
+            let copy = spooky_go_clone(game);
+            assert!(spooky_go_unmake_move(copy));
+            assert_eq!(spooky_go_turn(copy), 1);
+            spooky_go_free(copy);
+
+            let len = spooky_go_num_planes() * 25;
+            let mut planes = vec![0.0f32; len];
+            assert_eq!(spooky_go_encode_planes(game, planes.as_mut_ptr(), 3), len);
+            assert!(planes.iter().all(|&v| v == 0.0));
+            assert_eq!(spooky_go_encode_planes(game, planes.as_mut_ptr(), len), len);
+            // White to move: Black's stone is on the opponent plane.
+            assert_eq!(planes[25 + 12], 1.0);
+
+            assert!(spooky_go_make_move(game, 25));
+            assert!(spooky_go_make_move(game, 25));
+            assert!(spooky_go_is_over(game));
+            assert_eq!(spooky_go_winner(game), 1);
+            let (mut black, mut white) = (0.0, 0.0);
+            spooky_go_score(game, &mut black, &mut white);
+            assert_eq!((black, white), (25.0, 0.5));
+            spooky_go_free(game);
+
+            let text = CString::new("5/5/2B2/5/5 w - 0 0 1").expect("no NUL");
+            let game = spooky_go_from_position_string(text.as_ptr());
+            assert!(!game.is_null());
+            assert_eq!(spooky_go_width(game), 5);
+            assert_eq!(spooky_go_turn(game), -1);
+            spooky_go_free(game);
+            assert!(spooky_go_from_position_string(std::ptr::null()).is_null());
+        }
+    }
+
+    unsafe extern "C" fn prefer_center(
+        user_data: *mut c_void,
+        _planes: *const f32,
+        _planes_len: usize,
+        policy: *mut f32,
+        policy_len: usize,
+        value: *mut f32,
+    ) -> i32 {
+        let calls = &mut *(user_data as *mut u32);
+        *calls += 1;
+        if *calls > 20 {
+            return 1;
+        }
+        *policy.add(12.min(policy_len - 1)) = 1.0;
+        *value = 0.0;
+        0
+    }
+
+    #[test]
+    fn test_c_api_search_with_callback() {
+        let game = spooky_go_new_with_options(5, 5, 0.5, 0, 100, true);
+        unsafe {
+            let mut calls = 0u32;
+            let user_data = &mut calls as *mut u32 as *mut c_void;
+            let mut visits = vec![0u32; 26];
+            let action = spooky_go_search(
+                game,
+                prefer_center,
+                user_data,
+                10,
+                1.5,
+                visits.as_mut_ptr(),
+                visits.len(),
+            );
+            assert_eq!(action, 12);
+            assert_eq!(calls, 10);
+            assert_eq!(visits.iter().sum::<u32>(), 9);
+
+            // The callback fails from its 21st call on.
+            let action = spooky_go_search(
+                game,
+                prefer_center,
+                user_data,
+                50,
+                1.5,
+                std::ptr::null_mut(),
+                0,
+            );
+            assert_eq!(action, -1);
+            spooky_go_free(game);
+        }
+    }
+}
@@ -1,12 +1,18 @@
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
-use crate::bitboard::{nw_for_board, Bitboard, BoardGeometry};
-use crate::board::{Board, STANDARD_COLS, STANDARD_ROWS};
-use crate::outcome::GameOutcome;
+use crate::bitboard::{nw_for_board, AdaptiveBitIterator, Bitboard, BoardGeometry};
+use crate::board::{Board, BoardDiff, STANDARD_COLS, STANDARD_ROWS};
+use crate::handicap::fixed_handicap_positions;
+use crate::log::{GameEvent, GameLog};
+use crate::outcome::{EndReason, GameOutcome};
 use crate::player::Player;
 use crate::position::Position;
-use crate::r#move::Move;
+use crate::r#move::{Move, MoveListStyle};
+use crate::rules::Ruleset;
+#[cfg(feature = "stream")]
+use crate::stream::{StreamEvent, Subscribers};
+use crate::zobrist;
 
 #[hotpath::measure]
 fn compute_position_hash<const NW: usize>(board: &Board<NW>, player: Player) -> u64 {
@@ -18,13 +24,298 @@ fn compute_position_hash<const NW: usize>(board: &Board<NW>, player: Player) ->
 
 #[derive(Clone, Debug)]
 struct MoveHistoryEntry<const NW: usize> {
+    player: Player,
     move_: Move,
     captured_stones: Bitboard<NW>,
+    suicided_stones: Bitboard<NW>,
     previous_ko_point: Option<Position>,
+    /// Whether this pass handed the opponent a prisoner (AGA rules).
+    pass_stone: bool,
 }
 
 pub const DEFAULT_KOMI: f32 = 7.5;
 
+/// `points` in half points, the unit scores and margins are counted in so
+/// that every platform agrees on a result. Rounds to the nearest half point.
+#[hotpath::measure]
+pub fn to_half_points(points: f32) -> i32 {
+    (points * 2.0).round() as i32
+}
+
+/// `half_points` as points. Exact: every half point is an `f32`.
+#[hotpath::measure]
+pub fn from_half_points(half_points: i32) -> f32 {
+    half_points as f32 / 2.0
+}
+
+/// The move limit `Game::new` uses: three moves per point, capped at
+/// `u16::MAX`.
+#[hotpath::measure]
+pub fn default_max_moves(width: u8, height: u8) -> u16 {
+    (width as u32 * height as u32 * 3).min(u16::MAX as u32) as u16
+}
+
+/// The number of moves before passing is allowed that `Game::new` uses:
+/// half the points.
+#[hotpath::measure]
+pub fn default_min_moves_before_pass_possible(width: u8, height: u8) -> u16 {
+    (width as u32 * height as u32 / 2).min(u16::MAX as u32) as u16
+}
+
+/// Why a pair of move limits is rejected by `validate_move_limits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveLimitError {
+    /// `max_moves` is 0, so the game would end before the first move.
+    ZeroMaxMoves,
+    /// Passing would only become possible after the game has ended.
+    PassAfterMaxMoves {
+        min_moves_before_pass_possible: u16,
+        max_moves: u16,
+    },
+}
+
+impl std::fmt::Display for MoveLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveLimitError::ZeroMaxMoves => write!(f, "max_moves must be at least 1"),
+            MoveLimitError::PassAfterMaxMoves {
+                min_moves_before_pass_possible,
+                max_moves,
+            } => write!(
+                f,
+                "min_moves_before_pass_possible ({}) must not exceed max_moves ({})",
+                min_moves_before_pass_possible, max_moves
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveLimitError {}
+
+/// Check limits for `Game::with_options`, which itself accepts any values.
+#[hotpath::measure]
+pub fn validate_move_limits(
+    min_moves_before_pass_possible: u16,
+    max_moves: u16,
+) -> Result<(), MoveLimitError> {
+    if max_moves == 0 {
+        return Err(MoveLimitError::ZeroMaxMoves);
+    }
+    if min_moves_before_pass_possible > max_moves {
+        return Err(MoveLimitError::PassAfterMaxMoves {
+            min_moves_before_pass_possible,
+            max_moves,
+        });
+    }
+    Ok(())
+}
+
+/// Why `Game::is_legal_move` rejects a move, from
+/// `Game::illegal_move_reason`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalMoveReason {
+    GameOver,
+    /// Only resignation is possible while dead stones are being marked.
+    ScoringPhase,
+    /// A pass before `min_moves_before_pass_possible` while a placement is
+    /// still legal.
+    PassTooEarly {
+        min_moves_before_pass_possible: u16,
+    },
+    SwapNotAllowed,
+    OffBoard,
+    Occupied,
+    Ko,
+    Suicide,
+    Superko,
+}
+
+impl std::fmt::Display for IllegalMoveReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IllegalMoveReason::GameOver => write!(f, "the game is over"),
+            IllegalMoveReason::ScoringPhase => {
+                write!(f, "only resignation is allowed while marking dead stones")
+            }
+            IllegalMoveReason::PassTooEarly {
+                min_moves_before_pass_possible,
+            } => write!(
+                f,
+                "passing is not allowed before move {}",
+                min_moves_before_pass_possible
+            ),
+            IllegalMoveReason::SwapNotAllowed => write!(f, "swapping is not allowed"),
+            IllegalMoveReason::OffBoard => write!(f, "the point is off the board"),
+            IllegalMoveReason::Occupied => write!(f, "the point is occupied"),
+            IllegalMoveReason::Ko => write!(f, "the point is a ko"),
+            IllegalMoveReason::Suicide => write!(f, "the move is suicide"),
+            IllegalMoveReason::Superko => write!(f, "the move repeats an earlier position"),
+        }
+    }
+}
+
+impl std::error::Error for IllegalMoveReason {}
+
+/// Why `Game::set_position` rejects a position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetPositionError {
+    OffBoard(Position),
+    /// The point is listed for both Black and White.
+    BothColors(Position),
+    /// The group containing the stone has no liberties, so it would have
+    /// been captured.
+    NoLiberties(Position),
+    /// The ko point is off the board or not empty.
+    InvalidKo(Position),
+}
+
+impl std::fmt::Display for SetPositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetPositionError::OffBoard(pos) => {
+                write!(f, "({}, {}) is off the board", pos.col, pos.row)
+            }
+            SetPositionError::BothColors(pos) => {
+                write!(
+                    f,
+                    "({}, {}) holds both a black and a white stone",
+                    pos.col, pos.row
+                )
+            }
+            SetPositionError::NoLiberties(pos) => write!(
+                f,
+                "the group at ({}, {}) has no liberties",
+                pos.col, pos.row
+            ),
+            SetPositionError::InvalidKo(pos) => write!(
+                f,
+                "the ko point ({}, {}) must be an empty point on the board",
+                pos.col, pos.row
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SetPositionError {}
+
+/// An outstanding request by `player` to take back the last `plies` moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TakebackRequest {
+    pub player: Player,
+    pub plies: usize,
+}
+
+/// Where a game is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamePhase {
+    /// Moves are being played.
+    Playing,
+    /// Both players passed and dead stones are being marked (only when
+    /// dead-stone marking is enabled).
+    Scoring,
+    /// The game has finished and has an outcome.
+    Over,
+}
+
+/// Descriptive information about a game, as kept in SGF root properties.
+/// Komi, ruleset and outcome are part of the `Game` itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GameInfo {
+    pub black_player: Option<String>,
+    pub white_player: Option<String>,
+    pub black_rank: Option<String>,
+    pub white_rank: Option<String>,
+    /// Date as written in the record, e.g. `2024-05-01`.
+    pub date: Option<String>,
+    pub event: Option<String>,
+    /// The recorded result, e.g. `W+3.5`. Kept as written, since records
+    /// often stop before the moves that decide it.
+    pub result: Option<String>,
+}
+
+/// One player's share of the points counted by `Game::score_details()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScoreBreakdown {
+    /// Stones on the board, not counting stones marked dead.
+    pub stones: u32,
+    /// Empty points bordered only by this player's stones.
+    pub territory: u32,
+    /// Opponent stones captured, marked dead, or handed over as pass stones.
+    pub prisoners: u32,
+}
+
+/// The components of a score, for displaying a count or debugging one.
+/// Which components are added up depends on the ruleset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreDetails {
+    pub ruleset: Ruleset,
+    pub black: ScoreBreakdown,
+    pub white: ScoreBreakdown,
+    /// Komi plus any handicap compensation for the ruleset, in half points.
+    pub komi_half_points: i32,
+    /// Empty points that belong to neither player.
+    pub dame: u32,
+}
+
+#[hotpath::measure_all]
+impl ScoreDetails {
+    pub fn breakdown(&self, player: Player) -> &ScoreBreakdown {
+        match player {
+            Player::Black => &self.black,
+            Player::White => &self.white,
+        }
+    }
+
+    /// Komi plus any handicap compensation for the ruleset.
+    pub fn komi(&self) -> f32 {
+        from_half_points(self.komi_half_points)
+    }
+
+    /// `player`'s score in half points, with komi included for white.
+    pub fn total_half_points(&self, player: Player) -> i32 {
+        let b = self.breakdown(player);
+        let points = if self.ruleset.uses_area_scoring() {
+            b.stones + b.territory
+        } else {
+            b.territory + b.prisoners
+        };
+        match player {
+            Player::Black => points as i32 * 2,
+            Player::White => points as i32 * 2 + self.komi_half_points,
+        }
+    }
+
+    /// `player`'s score, with komi included for white.
+    pub fn total(&self, player: Player) -> f32 {
+        from_half_points(self.total_half_points(player))
+    }
+
+    /// Totals in half points as (black, white), as returned by
+    /// `Game::score_half_points()`.
+    pub fn totals_half_points(&self) -> (i32, i32) {
+        (
+            self.total_half_points(Player::Black),
+            self.total_half_points(Player::White),
+        )
+    }
+
+    /// Totals as (black, white), as returned by `Game::score()`.
+    pub fn totals(&self) -> (f32, f32) {
+        (self.total(Player::Black), self.total(Player::White))
+    }
+}
+
+/// The position before the first move, which `Game::reset_keep_config` and
+/// `Game::rewind` restore instead of undoing moves one by one.
+#[derive(Clone, Debug)]
+struct StartPosition<const NW: usize> {
+    board: Board<NW>,
+    current_player: Player,
+    ko_point: Option<Position>,
+    black_prisoners: u32,
+    white_prisoners: u32,
+}
+
 #[derive(Clone, Debug)]
 pub struct Game<const NW: usize> {
     board: Board<NW>,
@@ -33,27 +324,45 @@ pub struct Game<const NW: usize> {
     move_history: Vec<MoveHistoryEntry<NW>>,
     is_over: bool,
     outcome: Option<GameOutcome>,
-    consecutive_passes: u8,
+    consecutive_passes: u32,
+    black_passes: u32,
+    white_passes: u32,
     ko_point: Option<Position>,
-    komi: f32,
+    /// In half points.
+    komi: i32,
+    handicap: u8,
     min_moves_before_pass_possible: u16,
     max_moves: u16,
     superko: bool,
+    allow_suicide: bool,
+    swap_rule: bool,
+    dead_stone_marking: bool,
+    in_scoring_phase: bool,
+    dead_stones: Bitboard<NW>,
+    ruleset: Ruleset,
+    black_prisoners: u32,
+    white_prisoners: u32,
     position_hashes: Option<HashSet<u64>>,
+    log: Option<GameLog>,
+    pending_takeback: Option<TakebackRequest>,
+    info: GameInfo,
+    /// Moves played before the position the game was set up from.
+    moves_before_start: u32,
+    /// Saved when the first move is played.
+    start: Option<StartPosition<NW>>,
+    #[cfg(feature = "stream")]
+    subscribers: Subscribers,
 }
 
 #[hotpath::measure_all]
 impl<const NW: usize> Game<NW> {
     pub fn new(width: u8, height: u8) -> Self {
-        let board_size = width as u16 * height as u16;
-        let min_moves_before_pass_possible = board_size / 2;
-        let max_moves = board_size * 3;
         Self::with_options(
             width,
             height,
             DEFAULT_KOMI,
-            min_moves_before_pass_possible,
-            max_moves,
+            default_min_moves_before_pass_possible(width, height),
+            default_max_moves(width, height),
             true,
         )
     }
@@ -76,27 +385,199 @@ impl<const NW: usize> Game<NW> {
         };
         Game {
             board,
-            geo: BoardGeometry::new(width, height),
+            geo: BoardGeometry::cached(width, height),
             current_player: Player::Black,
             move_history: Vec::new(),
             is_over: false,
             outcome: None,
             consecutive_passes: 0,
+            black_passes: 0,
+            white_passes: 0,
             ko_point: None,
-            komi,
+            komi: to_half_points(komi),
+            handicap: 0,
             min_moves_before_pass_possible,
             max_moves,
             superko,
+            allow_suicide: false,
+            swap_rule: false,
+            dead_stone_marking: false,
+            in_scoring_phase: false,
+            dead_stones: Bitboard::empty(),
+            ruleset: Ruleset::default(),
+            black_prisoners: 0,
+            white_prisoners: 0,
             position_hashes: if superko { Some(position_hashes) } else { None },
+            log: None,
+            pending_takeback: None,
+            info: GameInfo::default(),
+            moves_before_start: 0,
+            start: None,
+            #[cfg(feature = "stream")]
+            subscribers: Subscribers::default(),
         }
     }
 
+    /// A game starting from `board` with `to_move` to play, no move history
+    /// and no minimum before passing. Used for playouts from arbitrary positions.
+    #[cfg(feature = "search")]
+    pub(crate) fn from_position(board: Board<NW>, to_move: Player, komi: f32) -> Self {
+        let max_moves = default_max_moves(board.width(), board.height());
+        let mut game = Self::with_options(board.width(), board.height(), komi, 0, max_moves, false);
+        game.board = board;
+        game.current_player = to_move;
+        game
+    }
+
+    /// This position as the start of a playout with `komi`: the board, the
+    /// player to move, the ko point, prisoners and rules carry over, the move
+    /// history does not.
+    #[cfg(feature = "search")]
+    pub(crate) fn playout_start(&self, komi: f32) -> Self {
+        let mut game = Self::from_position(self.board, self.current_player, komi);
+        game.ruleset = self.ruleset;
+        game.allow_suicide = self.allow_suicide;
+        game.ko_point = self.ko_point;
+        game.black_prisoners = self.black_prisoners;
+        game.white_prisoners = self.white_prisoners;
+        game
+    }
+
+    /// Komi, rounded to the nearest half point when set.
     pub fn komi(&self) -> f32 {
+        from_half_points(self.komi)
+    }
+
+    pub fn komi_half_points(&self) -> i32 {
         self.komi
     }
 
     pub fn set_komi(&mut self, komi: f32) {
-        self.komi = komi;
+        self.komi = to_half_points(komi);
+        self.record(GameEvent::SetKomi { komi: self.komi() });
+    }
+
+    pub fn handicap(&self) -> u8 {
+        self.handicap
+    }
+
+    /// Place `stones` fixed handicap stones (see `handicap::fixed_handicap_positions`)
+    /// and give White the move. Only possible on an empty board before any
+    /// moves; returns false otherwise or if the handicap is not available on
+    /// this board size.
+    pub fn place_handicap(&mut self, stones: u8) -> bool {
+        if !self.move_history.is_empty() || self.board.occupied().is_nonzero() {
+            return false;
+        }
+        let Some(positions) = fixed_handicap_positions(self.geo.width, self.geo.height, stones)
+        else {
+            return false;
+        };
+
+        self.set_up_position(&positions, &[], stones, Player::White);
+        true
+    }
+
+    /// Add setup stones before the first move, record `handicap` and give
+    /// `to_move` the move. Used for handicaps and for records with setup stones.
+    pub(crate) fn set_up_position(
+        &mut self,
+        black: &[Position],
+        white: &[Position],
+        handicap: u8,
+        to_move: Player,
+    ) {
+        for pos in black {
+            self.set_piece(pos, Some(Player::Black));
+        }
+        for pos in white {
+            self.set_piece(pos, Some(Player::White));
+        }
+        self.handicap = handicap;
+        self.current_player = to_move;
+        if let Some(ref mut hashes) = self.position_hashes {
+            hashes.clear();
+            hashes.insert(compute_position_hash(&self.board, self.current_player));
+        }
+    }
+
+    /// Restore the counters of a set-up position: the ko point, prisoners
+    /// and the number of moves played before it.
+    pub(crate) fn set_up_counters(
+        &mut self,
+        ko_point: Option<Position>,
+        black_prisoners: u32,
+        white_prisoners: u32,
+        moves_before_start: u32,
+    ) {
+        self.ko_point = ko_point;
+        self.black_prisoners = black_prisoners;
+        self.white_prisoners = white_prisoners;
+        self.moves_before_start = moves_before_start;
+    }
+
+    /// Start over from a set-up position instead of replaying moves, keeping
+    /// the rules: `black` and `white` stones, `to_move` to play, `ko_point`
+    /// forbidden to them, and `move_number` moves played before it. Clears
+    /// the moves, prisoners and any result. Rejects stones off the board or
+    /// on both lists, groups without liberties and an occupied ko point,
+    /// leaving the game unchanged.
+    pub fn set_position(
+        &mut self,
+        black: &[Position],
+        white: &[Position],
+        to_move: Player,
+        ko_point: Option<Position>,
+        move_number: u32,
+    ) -> Result<(), SetPositionError> {
+        let (width, height) = (self.board.width(), self.board.height());
+        let mut board = Board::<NW>::new(width, height);
+        for (stones, player) in [(black, Player::Black), (white, Player::White)] {
+            for pos in stones {
+                if !pos.is_valid(width, height) {
+                    return Err(SetPositionError::OffBoard(*pos));
+                }
+                if board.get_piece(pos).is_some_and(|p| p != player) {
+                    return Err(SetPositionError::BothColors(*pos));
+                }
+                board.set_piece(pos, Some(player));
+            }
+        }
+        let empty = board.empty_squares(self.geo.board_mask);
+        for pos in black.iter().chain(white) {
+            let player = board.get_piece(pos).expect("stones were just placed");
+            let seed = Bitboard::single(pos.to_index(width));
+            let group = self.geo.flood_fill(seed, board.stones_for(player));
+            if (self.geo.neighbors(&group) & empty).is_empty() {
+                return Err(SetPositionError::NoLiberties(*pos));
+            }
+        }
+        if let Some(ko) = ko_point {
+            if !ko.is_valid(width, height) || board.get_piece(&ko).is_some() {
+                return Err(SetPositionError::InvalidKo(ko));
+            }
+        }
+
+        self.reset_keep_config();
+        for idx in self.board.occupied().iter_ones() {
+            self.set_piece(&Position::from_index(idx, width), None);
+        }
+        self.set_up_position(black, white, 0, to_move);
+        self.set_up_counters(ko_point, 0, 0, move_number);
+        Ok(())
+    }
+
+    /// Komi plus the handicap compensation White receives under the ruleset.
+    pub fn effective_komi(&self) -> f32 {
+        from_half_points(self.effective_komi_half_points())
+    }
+
+    /// `effective_komi()` in half points.
+    pub fn effective_komi_half_points(&self) -> i32 {
+        self.komi
+            + self
+                .ruleset
+                .handicap_compensation_half_points(self.handicap)
     }
 
     pub fn min_moves_before_pass_possible(&self) -> u16 {
@@ -111,6 +592,21 @@ impl<const NW: usize> Game<NW> {
         self.move_history.len()
     }
 
+    /// Moves left before `max_moves` ends the game, or 0 once it is over.
+    /// A scoring phase can still outlast the limit.
+    pub fn moves_remaining(&self) -> u16 {
+        if self.is_over {
+            return 0;
+        }
+        (self.max_moves as usize).saturating_sub(self.move_history.len()) as u16
+    }
+
+    /// Moves played in the whole game, including any played before the
+    /// position it was set up from (see `from_position_string`).
+    pub fn move_number(&self) -> u32 {
+        self.moves_before_start + self.move_history.len() as u32
+    }
+
     pub fn width(&self) -> u8 {
         self.board.width()
     }
@@ -124,7 +620,11 @@ impl<const NW: usize> Game<NW> {
     }
 
     pub fn set_piece(&mut self, pos: &Position, player: Option<Player>) {
-        self.board.set_piece(pos, player)
+        self.board.set_piece(pos, player);
+        self.record(GameEvent::SetPiece {
+            position: *pos,
+            player,
+        });
     }
 
     pub fn board(&self) -> &Board<NW> {
@@ -139,6 +639,16 @@ impl<const NW: usize> Game<NW> {
         self.is_over
     }
 
+    pub fn phase(&self) -> GamePhase {
+        if self.is_over {
+            GamePhase::Over
+        } else if self.in_scoring_phase {
+            GamePhase::Scoring
+        } else {
+            GamePhase::Playing
+        }
+    }
+
     pub fn outcome(&self) -> Option<GameOutcome> {
         self.outcome
     }
@@ -147,102 +657,570 @@ impl<const NW: usize> Game<NW> {
         self.move_history.iter().map(|e| e.move_).collect()
     }
 
-    pub fn ko_point(&self) -> Option<Position> {
-        self.ko_point
+    /// The board and ko point before the first move: the setup stones and
+    /// any ko of a set-up position.
+    #[cfg(all(feature = "search", feature = "gtp"))]
+    pub(crate) fn start_position(&self) -> (Board<NW>, Option<Position>) {
+        match &self.start {
+            Some(start) => (start.board, start.ko_point),
+            None => (self.board, self.ko_point),
+        }
     }
 
-    pub fn superko(&self) -> bool {
-        self.superko
+    /// Each move played with the board it was played on, first move first.
+    /// Built from the recorded captures, without replaying the rules.
+    pub fn history(&self) -> Vec<(Move, Board<NW>)> {
+        let Some(start) = &self.start else {
+            return Vec::new();
+        };
+        let mut board = start.board;
+        let mut history = Vec::with_capacity(self.move_history.len());
+        for entry in &self.move_history {
+            history.push((entry.move_, board));
+            match entry.move_ {
+                Move::Place { col, row } => {
+                    let idx = Position::new(col, row).to_index(board.width());
+                    board.set_bit(idx, entry.player);
+                    board.remove_stones(entry.captured_stones | entry.suicided_stones);
+                }
+                Move::Swap => {
+                    let first = self.move_history[0]
+                        .move_
+                        .position()
+                        .expect("swap follows a placement");
+                    let idx = first.to_index(board.width());
+                    board.clear_bit(idx);
+                    board.set_bit(idx, Player::White);
+                }
+                Move::Pass | Move::Resign => {}
+            }
+        }
+        history
     }
 
-    /// Simulate placing a stone and performing captures, returning the resulting board.
-    fn simulate_placement(&self, idx: usize, player: Player) -> Board<NW> {
-        let mut board = self.board;
-        board.set_bit(idx, player);
-
-        let opponent = player.opposite();
-        let bit = Bitboard::single(idx);
-        let adj_opp = self.geo.neighbors(&bit) & board.stones_for(opponent);
-
-        let mut remaining = adj_opp;
-        while let Some(opp_idx) = remaining.lowest_bit_index() {
-            let opp_seed = Bitboard::single(opp_idx);
-            let opp_group = self.geo.flood_fill(opp_seed, board.stones_for(opponent));
-            remaining &= !opp_group;
+    /// The board changes made by the last move: its stone, the stones it
+    /// captured, and under the swap rule the recolored first stone. Empty
+    /// for a pass or resignation; `None` before the first move.
+    pub fn last_move_diff(&self) -> Option<BoardDiff> {
+        let entry = self.move_history.last()?;
+        let width = self.board.width();
+        let mut changed = entry.captured_stones | entry.suicided_stones;
+        let placed = match entry.move_ {
+            Move::Swap => self.move_history[0].move_.position(),
+            move_ => move_.position(),
+        };
+        if let Some(pos) = placed {
+            changed.set(pos.to_index(width));
+        }
+        let changes = changed
+            .iter_ones_sparse()
+            .map(|idx| {
+                let pos = Position::from_index(idx, width);
+                (pos, self.board.get_piece(&pos))
+            })
+            .collect();
+        Some(BoardDiff {
+            width,
+            height: self.board.height(),
+            changes,
+        })
+    }
 
-            let opp_neighbors = self.geo.neighbors(&opp_group);
-            if (opp_neighbors & board.empty_squares(self.geo.board_mask)).is_empty() {
-                board.remove_stones(opp_group);
+    /// How many times a stone has been played on each point this game,
+    /// counting points played again after a capture, as in a ko fight.
+    /// Layout: row-major, index = row * width + col.
+    pub fn play_frequency_map(&self) -> Vec<u32> {
+        let width = self.board.width();
+        let mut counts = vec![0u32; width as usize * self.board.height() as usize];
+        for entry in &self.move_history {
+            if let Some(pos) = entry.move_.position() {
+                counts[pos.to_index(width)] += 1;
             }
         }
-        board
+        counts
     }
 
-    fn is_illegal_placement(&self, idx: usize, player: Player) -> bool {
-        let bit = Bitboard::single(idx);
-        let own = self.board.stones_for(player) | bit;
-        let opponent = player.opposite();
-        let opp = self.board.stones_for(opponent);
-        let occupied = own | opp;
-        let empty = self.geo.board_mask.andnot(occupied);
-        let bit_neighbors = self.geo.neighbors(&bit);
+    /// The moves played so far with the player who made each one.
+    #[cfg(any(
+        feature = "sgf",
+        feature = "config",
+        all(feature = "search", feature = "gtp")
+    ))]
+    pub(crate) fn moves_by_player(&self) -> impl Iterator<Item = (Player, Move)> + '_ {
+        self.move_history.iter().map(|e| (e.player, e.move_))
+    }
 
-        // Fast path: placed stone has an empty neighbor -> not suicide
-        if (bit_neighbors & empty).is_nonzero() {
-            // Check superko only if captures occur
-            if self.superko {
-                let adj_opp = bit_neighbors & opp;
-                if adj_opp.is_nonzero() && self.adj_opp_has_captures(adj_opp, opp, empty) {
-                    return self.check_superko(idx, player);
+    /// Numbered move list such as `1. B D4  2. W Q16`, for logs and chat.
+    pub fn movelist_string(&self, style: MoveListStyle) -> String {
+        let height = self.board.height();
+        self.move_history
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                format!(
+                    "{}. {} {}",
+                    i + 1,
+                    e.player.to_char(),
+                    e.move_.notation(style, height)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    /// Boards walking backward through the move history: the current board
+    /// first, then the board before the last move, and so on back to the start.
+    pub(crate) fn board_history(&self) -> impl Iterator<Item = Board<NW>> + '_ {
+        let width = self.board.width();
+        let first_move = self.move_history.first().map(|e| e.move_);
+        let mut board = self.board;
+        std::iter::once(board).chain(self.move_history.iter().rev().map(move |entry| {
+            match entry.move_ {
+                Move::Place { col, row } => {
+                    let idx = Position::new(col, row).to_index(width);
+                    board.restore_stones(entry.suicided_stones, entry.player);
+                    board.clear_bit(idx);
+                    board.restore_stones(entry.captured_stones, entry.player.opposite());
+                }
+                Move::Swap => {
+                    if let Some(pos) = first_move.and_then(|m| m.position()) {
+                        let idx = pos.to_index(width);
+                        board.clear_bit(idx);
+                        board.set_bit(idx, Player::Black);
+                    }
                 }
+                Move::Pass | Move::Resign => {}
             }
-            return false;
+            board
+        }))
+    }
+
+    /// Start recording an append-only `GameLog` of moves, takebacks and edits.
+    /// Does nothing if logging is already enabled.
+    pub fn enable_log(&mut self) {
+        if self.log.is_none() {
+            self.log = Some(GameLog::new());
         }
+    }
 
-        // No immediate liberties. Flood-fill own group.
-        let group = self.geo.flood_fill(bit, own);
-        let group_neighbors = self.geo.neighbors(&group);
+    pub fn log(&self) -> Option<&GameLog> {
+        self.log.as_ref()
+    }
 
-        // Group has liberties through connected friendly stones -> not suicide
-        if (group_neighbors & empty).is_nonzero() {
-            if self.superko {
-                let adj_opp = bit_neighbors & opp;
-                if adj_opp.is_nonzero() && self.adj_opp_has_captures(adj_opp, opp, empty) {
-                    return self.check_superko(idx, player);
-                }
-            }
-            return false;
-        }
+    /// Record a clock update in the log (no-op when logging is disabled).
+    pub fn record_clock(&mut self, player: Player, remaining_ms: u64) {
+        self.record(GameEvent::Clock {
+            player,
+            remaining_ms,
+        });
+    }
 
-        // No liberties for our group. Check if we capture any opponent groups.
-        let adj_opp = group_neighbors & opp;
-        if adj_opp.is_empty() {
-            return true; // Suicide — no opponent neighbors to capture
-        }
+    pub fn pending_takeback(&self) -> Option<TakebackRequest> {
+        self.pending_takeback
+    }
 
-        let mut remaining = adj_opp;
-        let mut any_captures = false;
-        while let Some(opp_idx) = remaining.lowest_bit_index() {
-            let opp_seed = Bitboard::single(opp_idx);
-            let opp_group = self.geo.flood_fill(opp_seed, opp);
-            remaining = remaining.andnot(opp_group);
-            let opp_nbrs = self.geo.neighbors(&opp_group);
-            if (opp_nbrs & empty).is_empty() {
-                any_captures = true;
-                break;
-            }
+    /// Ask for the last `plies` moves to be taken back. The request stays
+    /// pending until accepted, declined, or another move is played. Returns
+    /// false if a request is already pending or `plies` is not in `1..=move_count()`.
+    pub fn request_takeback(&mut self, player: Player, plies: usize) -> bool {
+        if self.pending_takeback.is_some() || plies == 0 || plies > self.move_history.len() {
+            return false;
         }
+        self.pending_takeback = Some(TakebackRequest { player, plies });
+        self.record(GameEvent::TakebackRequested { player, plies });
+        true
+    }
 
-        if !any_captures {
-            return true; // Suicide
+    /// Accept the pending takeback request, undoing the requested moves.
+    pub fn accept_takeback(&mut self) -> bool {
+        let Some(request) = self.pending_takeback.take() else {
+            return false;
+        };
+        self.record(GameEvent::TakebackAccepted {
+            player: request.player,
+            plies: request.plies,
+        });
+        for _ in 0..request.plies {
+            self.unmake_move();
         }
+        true
+    }
 
-        // Not suicide (captures save us). Check superko only when captures occur.
-        if self.superko {
-            return self.check_superko(idx, player);
+    pub fn decline_takeback(&mut self) -> bool {
+        let Some(request) = self.pending_takeback.take() else {
+            return false;
+        };
+        self.record(GameEvent::TakebackDeclined {
+            player: request.player,
+            plies: request.plies,
+        });
+        true
+    }
+
+    fn record(&mut self, event: GameEvent) {
+        #[cfg(feature = "stream")]
+        self.publish(&event);
+        if let Some(ref mut log) = self.log {
+            log.push(event);
         }
+    }
 
-        false
+    /// An ordered stream of this game's moves with their captures,
+    /// takebacks, clock updates and result, for servers, loggers and GUIs.
+    /// Receivers may be dropped at any time. Clones of the game do not
+    /// publish to it.
+    #[cfg(feature = "stream")]
+    pub fn subscribe_stream(&mut self) -> std::sync::mpsc::Receiver<StreamEvent> {
+        self.subscribers.subscribe()
+    }
+
+    /// Pass `event` on to the stream subscribers, with the captures of a
+    /// move taken from the move history.
+    #[cfg(feature = "stream")]
+    fn publish(&mut self, event: &GameEvent) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        let event = match *event {
+            GameEvent::Move { player, move_ } => {
+                let width = self.board.width();
+                let captures = self.move_history.last().map_or(Vec::new(), |e| {
+                    (e.captured_stones | e.suicided_stones)
+                        .iter_ones_sparse()
+                        .map(|idx| Position::from_index(idx, width))
+                        .collect()
+                });
+                StreamEvent::Move {
+                    player,
+                    move_,
+                    captures,
+                }
+            }
+            GameEvent::Unmake { player, move_ } => StreamEvent::Unmake { player, move_ },
+            GameEvent::Clock {
+                player,
+                remaining_ms,
+            } => StreamEvent::Clock {
+                player,
+                remaining_ms,
+            },
+            _ => return,
+        };
+        self.subscribers.publish(event);
+    }
+
+    /// Tell the stream subscribers the result of a game that just ended.
+    fn publish_result(&mut self) {
+        #[cfg(feature = "stream")]
+        if let Some(outcome) = self.outcome.filter(|_| !self.subscribers.is_empty()) {
+            self.subscribers.publish(StreamEvent::Result(outcome));
+        }
+    }
+
+    pub fn ko_point(&self) -> Option<Position> {
+        self.ko_point
+    }
+
+    /// The Zobrist hash of the board, the player to move and the ko point.
+    /// See `zobrist::position_hash`.
+    pub fn position_hash(&self) -> u64 {
+        zobrist::position_hash(&self.board, self.current_player, self.ko_point)
+    }
+
+    pub fn superko(&self) -> bool {
+        self.superko
+    }
+
+    /// Whether suicide moves are legal (New Zealand / Tromp-Taylor rules).
+    /// A suicided group is removed from the board as if it had been captured.
+    pub fn allow_suicide(&self) -> bool {
+        self.allow_suicide
+    }
+
+    pub fn set_allow_suicide(&mut self, allow_suicide: bool) {
+        self.allow_suicide = allow_suicide;
+    }
+
+    /// Whether the swap (pie) rule is on: White may answer Black's first move
+    /// with `Move::Swap` instead of a stone.
+    ///
+    /// Swapping exchanges the players' colors. The board keeps the colors in
+    /// place instead: the first stone turns white, Black moves next, and the
+    /// komi changes sides (`komi()` becomes negative), which is the same game
+    /// with the players' roles exchanged.
+    pub fn swap_rule(&self) -> bool {
+        self.swap_rule
+    }
+
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        self.swap_rule = swap_rule;
+    }
+
+    /// Whether `Move::Swap` is legal now: the swap rule is on, the game has no
+    /// handicap and Black has placed exactly one stone.
+    fn can_swap(&self) -> bool {
+        self.swap_rule
+            && self.handicap == 0
+            && self.move_history.len() == 1
+            && matches!(self.move_history[0].move_, Move::Place { .. })
+    }
+
+    /// Whether two consecutive passes enter `GamePhase::Scoring` instead of
+    /// ending the game with every stone counted as alive.
+    pub fn dead_stone_marking(&self) -> bool {
+        self.dead_stone_marking
+    }
+
+    pub fn set_dead_stone_marking(&mut self, dead_stone_marking: bool) {
+        self.dead_stone_marking = dead_stone_marking;
+    }
+
+    /// Mark the groups containing `positions` as dead. Empty points are
+    /// ignored. Returns false outside the scoring phase.
+    pub fn mark_dead(&mut self, positions: &[Position]) -> bool {
+        if self.phase() != GamePhase::Scoring {
+            return false;
+        }
+        for pos in positions {
+            if let Some(group) = self.group_at(pos) {
+                self.dead_stones |= group;
+            }
+        }
+        true
+    }
+
+    /// Flip the dead/alive status of the group at `pos`. Returns false outside
+    /// the scoring phase or if `pos` is empty.
+    pub fn toggle_dead_group(&mut self, pos: &Position) -> bool {
+        if self.phase() != GamePhase::Scoring {
+            return false;
+        }
+        let Some(group) = self.group_at(pos) else {
+            return false;
+        };
+        if (self.dead_stones & group).is_nonzero() {
+            self.dead_stones &= !group;
+        } else {
+            self.dead_stones |= group;
+        }
+        true
+    }
+
+    /// The stones removed for scoring.
+    #[cfg(feature = "dataset")]
+    pub(crate) fn dead_stones(&self) -> Bitboard<NW> {
+        self.dead_stones
+    }
+
+    /// Replace the stones removed for scoring, regardless of phase.
+    #[cfg(feature = "search")]
+    pub(crate) fn set_dead_stones(&mut self, dead_stones: Bitboard<NW>) {
+        self.dead_stones = dead_stones & self.board.occupied();
+    }
+
+    pub fn is_dead(&self, pos: &Position) -> bool {
+        pos.is_valid(self.board.width(), self.board.height())
+            && self.dead_stones.get(pos.to_index(self.board.width()))
+    }
+
+    /// Both players agree on the marked dead stones: remove them for scoring
+    /// and end the game. Returns false outside the scoring phase.
+    pub fn accept_score(&mut self) -> bool {
+        if self.phase() != GamePhase::Scoring {
+            return false;
+        }
+        self.is_over = true;
+        self.outcome = Some(self.determine_outcome(EndReason::DoublePass));
+        self.publish_result();
+        true
+    }
+
+    /// The stones of the group containing `pos`, or None if `pos` is empty.
+    pub fn group_at(&self, pos: &Position) -> Option<Bitboard<NW>> {
+        let player = self.board.get_piece(pos)?;
+        let seed = Bitboard::single(pos.to_index(self.board.width()));
+        Some(self.geo.flood_fill(seed, self.board.stones_for(player)))
+    }
+
+    /// The number of liberties of the group containing `pos`, or None if
+    /// `pos` is empty.
+    pub fn liberties_at(&self, pos: &Position) -> Option<u32> {
+        let group = self.group_at(pos)?;
+        let empty = self.board.empty_squares(self.geo.board_mask);
+        Some((self.geo.neighbors(&group) & empty).count())
+    }
+
+    /// The board used for scoring: the current board minus stones marked dead.
+    fn scoring_board(&self) -> Board<NW> {
+        let mut board = self.board;
+        board.remove_stones(self.dead_stones);
+        board
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.ruleset
+    }
+
+    pub fn set_ruleset(&mut self, ruleset: Ruleset) {
+        self.ruleset = ruleset;
+    }
+
+    /// Player names, ranks, date and other record metadata.
+    pub fn info(&self) -> &GameInfo {
+        &self.info
+    }
+
+    pub fn info_mut(&mut self) -> &mut GameInfo {
+        &mut self.info
+    }
+
+    pub fn set_info(&mut self, info: GameInfo) {
+        self.info = info;
+    }
+
+    /// Number of opponent stones `player` has captured so far (suicided stones
+    /// count as captured by the opponent).
+    pub fn prisoners(&self, player: Player) -> u32 {
+        match player {
+            Player::Black => self.black_prisoners,
+            Player::White => self.white_prisoners,
+        }
+    }
+
+    /// Number of passes `player` has made this game.
+    pub fn passes(&self, player: Player) -> u32 {
+        match player {
+            Player::Black => self.black_passes,
+            Player::White => self.white_passes,
+        }
+    }
+
+    /// Number of passes in a row at the end of the move history.
+    pub fn consecutive_passes(&self) -> u32 {
+        self.consecutive_passes
+    }
+
+    fn add_prisoners(&mut self, player: Player, count: u32) {
+        match player {
+            Player::Black => self.black_prisoners += count,
+            Player::White => self.white_prisoners += count,
+        }
+    }
+
+    fn remove_prisoners(&mut self, player: Player, count: u32) {
+        match player {
+            Player::Black => self.black_prisoners -= count,
+            Player::White => self.white_prisoners -= count,
+        }
+    }
+
+    /// Simulate placing a stone and performing captures, returning the resulting board.
+    pub(crate) fn simulate_placement(&self, idx: usize, player: Player) -> Board<NW> {
+        let mut board = self.board;
+        board.set_bit(idx, player);
+
+        let opponent = player.opposite();
+        let bit = Bitboard::single(idx);
+        let adj_opp = self.geo.neighbors(&bit) & board.stones_for(opponent);
+
+        let mut remaining = adj_opp;
+        while let Some(opp_idx) = remaining.lowest_bit_index() {
+            let opp_seed = Bitboard::single(opp_idx);
+            let opp_group = self.geo.flood_fill(opp_seed, board.stones_for(opponent));
+            remaining &= !opp_group;
+
+            let opp_neighbors = self.geo.neighbors(&opp_group);
+            if (opp_neighbors & board.empty_squares(self.geo.board_mask)).is_empty() {
+                board.remove_stones(opp_group);
+            }
+        }
+
+        // With suicide allowed, the placed group may be left without liberties.
+        if self.allow_suicide {
+            let own_group = self.geo.flood_fill(bit, board.stones_for(player));
+            let own_neighbors = self.geo.neighbors(&own_group);
+            if (own_neighbors & board.empty_squares(self.geo.board_mask)).is_empty() {
+                board.remove_stones(own_group);
+            }
+        }
+        board
+    }
+
+    fn is_illegal_placement(&self, idx: usize, player: Player) -> bool {
+        let bit = Bitboard::single(idx);
+        let own = self.board.stones_for(player) | bit;
+        let opponent = player.opposite();
+        let opp = self.board.stones_for(opponent);
+        let occupied = own | opp;
+        let empty = self.geo.board_mask.andnot(occupied);
+        let bit_neighbors = self.geo.neighbors(&bit);
+
+        // Fast path: placed stone has an empty neighbor -> not suicide
+        if (bit_neighbors & empty).is_nonzero() {
+            // Check superko only if captures occur
+            if self.superko {
+                let adj_opp = bit_neighbors & opp;
+                if adj_opp.is_nonzero() && self.adj_opp_has_captures(adj_opp, opp, empty) {
+                    return self.check_superko(idx, player);
+                }
+            }
+            return false;
+        }
+
+        // No immediate liberties. Flood-fill own group.
+        let group = self.geo.flood_fill(bit, own);
+        let group_neighbors = self.geo.neighbors(&group);
+
+        // Group has liberties through connected friendly stones -> not suicide
+        if (group_neighbors & empty).is_nonzero() {
+            if self.superko {
+                let adj_opp = bit_neighbors & opp;
+                if adj_opp.is_nonzero() && self.adj_opp_has_captures(adj_opp, opp, empty) {
+                    return self.check_superko(idx, player);
+                }
+            }
+            return false;
+        }
+
+        // No liberties for our group. Check if we capture any opponent groups.
+        let adj_opp = group_neighbors & opp;
+        if adj_opp.is_empty() {
+            return self.is_illegal_suicide(idx, player); // No opponent neighbors to capture
+        }
+
+        let mut remaining = adj_opp;
+        let mut any_captures = false;
+        while let Some(opp_idx) = remaining.lowest_bit_index() {
+            let opp_seed = Bitboard::single(opp_idx);
+            let opp_group = self.geo.flood_fill(opp_seed, opp);
+            remaining = remaining.andnot(opp_group);
+            let opp_nbrs = self.geo.neighbors(&opp_group);
+            if (opp_nbrs & empty).is_empty() {
+                any_captures = true;
+                break;
+            }
+        }
+
+        if !any_captures {
+            return self.is_illegal_suicide(idx, player);
+        }
+
+        // Not suicide (captures save us). Check superko only when captures occur.
+        if self.superko {
+            return self.check_superko(idx, player);
+        }
+
+        false
+    }
+
+    /// A placement that captures nothing and leaves its group without liberties.
+    /// Illegal unless suicide is allowed, in which case superko still applies
+    /// to the position left behind once the group is removed.
+    fn is_illegal_suicide(&self, idx: usize, player: Player) -> bool {
+        if !self.allow_suicide {
+            return true;
+        }
+        self.superko && self.check_superko(idx, player)
     }
 
     /// Check if any adjacent opponent group has zero liberties (would be captured).
@@ -275,80 +1253,128 @@ impl<const NW: usize> Game<NW> {
         }
     }
 
+    /// Score as (black, white) under the selected ruleset, with komi added to white.
+    /// Area scoring counts stones plus territory; territory scoring counts
+    /// territory plus prisoners.
     pub fn score(&self) -> (f32, f32) {
-        let mut black_score: f32 = 0.0;
-        let mut white_score: f32 = self.komi;
+        self.score_details().totals()
+    }
 
-        black_score += self.board.black_stones().count() as f32;
-        white_score += self.board.white_stones().count() as f32;
+    /// `score()` in half points. Scoring is done in integers throughout, so
+    /// this is the same on every platform.
+    pub fn score_half_points(&self) -> (i32, i32) {
+        self.score_details().totals_half_points()
+    }
 
-        let occupied = self.board.occupied();
-        let mut remaining_empty = self.board.empty_squares(self.geo.board_mask);
+    /// The stones, territory and prisoners behind `score()`.
+    pub fn score_details(&self) -> ScoreDetails {
+        let board = self.scoring_board();
+        let (black_territory, white_territory) = self.territory(&board);
+        let empty = board.empty_squares(self.geo.board_mask).count();
+
+        // Stones marked dead are taken off the board as prisoners.
+        let dead_black = (self.dead_stones & self.board.black_stones()).count();
+        let dead_white = (self.dead_stones & self.board.white_stones()).count();
+
+        ScoreDetails {
+            ruleset: self.ruleset,
+            black: ScoreBreakdown {
+                stones: board.black_stones().count(),
+                territory: black_territory,
+                prisoners: self.black_prisoners + dead_white,
+            },
+            white: ScoreBreakdown {
+                stones: board.white_stones().count(),
+                territory: white_territory,
+                prisoners: self.white_prisoners + dead_black,
+            },
+            komi_half_points: self.effective_komi_half_points(),
+            dame: empty - black_territory - white_territory,
+        }
+    }
 
-        while let Some(idx) = remaining_empty.lowest_bit_index() {
-            let seed = Bitboard::single(idx);
-            let empty_mask = self.geo.board_mask & !occupied;
-            let region = self.geo.flood_fill(seed, empty_mask);
+    /// Area score as (black, white), with komi added to white, computed from
+    /// the bitboards alone: two flood fills find the empty points each color
+    /// reaches, whatever the number of empty regions. Ignores the ruleset and
+    /// any stones marked dead, so it equals `score()` under area scoring
+    /// with nothing marked. Used to score playout terminals.
+    pub fn score_fast(&self) -> (f32, f32) {
+        let (black, white) = self.score_fast_half_points();
+        (from_half_points(black), from_half_points(white))
+    }
+
+    /// `score_fast()` in half points.
+    pub fn score_fast_half_points(&self) -> (i32, i32) {
+        let mask = self.geo.board_mask;
+        let black = self.board.black_stones();
+        let white = self.board.white_stones();
+        let empty = self.board.empty_squares(mask);
+
+        let black_reach = self.geo.flood_fill(self.geo.neighbors(&black), empty);
+        let white_reach = self.geo.flood_fill(self.geo.neighbors(&white), empty);
+        let black_area = black.count() + black_reach.andnot(white_reach).count();
+        let white_area = white.count() + white_reach.andnot(black_reach).count();
+        (
+            black_area as i32 * 2,
+            white_area as i32 * 2 + self.effective_komi_half_points(),
+        )
+    }
+
+    /// Empty points of `board` bordered exclusively by each color, as (black, white).
+    fn territory(&self, board: &Board<NW>) -> (u32, u32) {
+        let (black_territory, white_territory) = self.territory_regions(board);
+        (black_territory.count(), white_territory.count())
+    }
+
+    /// Bitboards of the empty regions of `board` bordered exclusively by each
+    /// color, as (black, white). Each empty component is found with one flood
+    /// fill and classified with one `neighbors()` intersection per color.
+    fn territory_regions(&self, board: &Board<NW>) -> (Bitboard<NW>, Bitboard<NW>) {
+        let mut black_territory = Bitboard::empty();
+        let mut white_territory = Bitboard::empty();
+
+        let empty = board.empty_squares(self.geo.board_mask);
+        let mut remaining_empty = empty;
 
+        while let Some(idx) = remaining_empty.lowest_bit_index() {
+            let region = self.geo.flood_fill(Bitboard::single(idx), empty);
             remaining_empty &= !region;
 
             let region_neighbors = self.geo.neighbors(&region);
-            let black_adjacent = (region_neighbors & self.board.black_stones()).is_nonzero();
-            let white_adjacent = (region_neighbors & self.board.white_stones()).is_nonzero();
+            let black_adjacent = (region_neighbors & board.black_stones()).is_nonzero();
+            let white_adjacent = (region_neighbors & board.white_stones()).is_nonzero();
 
-            let territory = region.count() as f32;
             match (black_adjacent, white_adjacent) {
-                (true, false) => black_score += territory,
-                (false, true) => white_score += territory,
+                (true, false) => black_territory |= region,
+                (false, true) => white_territory |= region,
                 _ => {}
             }
         }
 
-        (black_score, white_score)
+        (black_territory, white_territory)
     }
 
     // Per-square ownership from black's (first player's) absolute perspective.
     // +1.0 = black owns, -1.0 = white owns, 0.0 = neutral/disputed.
     // Stones count as owned by their player; empty regions are assigned
     // based on which player's stones exclusively border them (area scoring).
+    // Stones marked dead are treated as empty points.
     // Layout: row-major, index = row * width + col.
     pub fn ownership_map_absolute(&self) -> Vec<f32> {
-        let w = self.board.width() as usize;
-        let h = self.board.height() as usize;
+        let board = self.scoring_board();
+        let w = board.width() as usize;
+        let h = board.height() as usize;
         let mut ownership = vec![0.0f32; h * w];
 
-        for idx in self.board.black_stones().iter_ones() {
+        let (black_territory, white_territory) = self.territory_regions(&board);
+
+        for idx in (board.black_stones() | black_territory).iter_ones() {
             ownership[idx] = 1.0;
         }
-        for idx in self.board.white_stones().iter_ones() {
+        for idx in (board.white_stones() | white_territory).iter_ones() {
             ownership[idx] = -1.0;
         }
 
-        let occupied = self.board.occupied();
-        let mut remaining_empty = self.board.empty_squares(self.geo.board_mask);
-
-        while let Some(idx) = remaining_empty.lowest_bit_index() {
-            let seed = Bitboard::single(idx);
-            let empty_mask = self.geo.board_mask & !occupied;
-            let region = self.geo.flood_fill(seed, empty_mask);
-
-            remaining_empty &= !region;
-
-            let region_neighbors = self.geo.neighbors(&region);
-            let black_adjacent = (region_neighbors & self.board.black_stones()).is_nonzero();
-            let white_adjacent = (region_neighbors & self.board.white_stones()).is_nonzero();
-
-            let owner = match (black_adjacent, white_adjacent) {
-                (true, false) => 1.0,
-                (false, true) => -1.0,
-                _ => 0.0,
-            };
-
-            for region_idx in region.iter_ones() {
-                ownership[region_idx] = owner;
-            }
-        }
-
         ownership
     }
 
@@ -365,8 +1391,8 @@ impl<const NW: usize> Game<NW> {
     // Score margin from black's absolute perspective (includes komi).
     // Positive means black is ahead.
     pub fn score_margin_absolute(&self) -> f32 {
-        let (black_score, white_score) = self.score();
-        black_score - white_score
+        let (black_score, white_score) = self.score_half_points();
+        from_half_points(black_score - white_score)
     }
 
     pub fn score_margin_from_perspective(&self, perspective: Player) -> f32 {
@@ -377,28 +1403,26 @@ impl<const NW: usize> Game<NW> {
         }
     }
 
-    fn determine_outcome(&self) -> GameOutcome {
-        let (black_score, white_score) = self.score();
-        if black_score > white_score {
-            GameOutcome::BlackWin
-        } else if white_score > black_score {
-            GameOutcome::WhiteWin
-        } else {
-            GameOutcome::Draw
-        }
+    fn determine_outcome(&self, reason: EndReason) -> GameOutcome {
+        let (black_score, white_score) =
+            if self.ruleset.uses_area_scoring() && self.dead_stones.is_empty() {
+                self.score_fast_half_points()
+            } else {
+                self.score_half_points()
+            };
+        GameOutcome::from_half_points(black_score, white_score, reason)
     }
 
     pub fn legal_moves(&self) -> Vec<Move> {
-        if self.is_over {
+        if self.is_over || self.in_scoring_phase {
             return Vec::new();
         }
 
         let mut moves = Vec::new();
-        let empty = self.board.empty_squares(self.geo.board_mask);
         let w = self.geo.width;
         let ko_idx = self.ko_point.map(|p| p.to_index(w));
 
-        for idx in empty.iter_ones() {
+        for idx in self.empty_points() {
             if let Some(ki) = ko_idx {
                 if ki == idx {
                     continue;
@@ -419,15 +1443,87 @@ impl<const NW: usize> Game<NW> {
             moves.push(Move::pass());
         }
 
+        if self.can_swap() {
+            moves.push(Move::swap());
+        }
+
         moves
     }
 
-    fn has_legal_board_moves(&self) -> bool {
+    /// Legal placements that capture at least one stone.
+    pub fn capturing_moves(&self) -> Vec<Move> {
+        self.capture_points()
+            .iter_ones()
+            .map(|idx| {
+                let pos = Position::from_index(idx, self.geo.width);
+                Move::place(pos.col, pos.row)
+            })
+            .collect()
+    }
+
+    /// Points where the player to move can legally capture: the last
+    /// liberty of each opponent group in atari, unless ko or superko
+    /// forbids it. A capture is never suicide.
+    pub(crate) fn capture_points(&self) -> Bitboard<NW> {
+        let mut points = Bitboard::empty();
+        if self.is_over || self.in_scoring_phase {
+            return points;
+        }
+        let player = self.current_player;
+        let opp = self.board.stones_for(player.opposite());
+        let empty = self.board.empty_squares(self.geo.board_mask);
+
+        let mut remaining = opp;
+        while let Some(idx) = remaining.lowest_bit_index() {
+            let group = self.geo.flood_fill(Bitboard::single(idx), opp);
+            remaining = remaining.andnot(group);
+            let liberties = self.geo.neighbors(&group) & empty;
+            if liberties.count() == 1 {
+                points |= liberties;
+            }
+        }
+
+        if let Some(ko) = self.ko_point {
+            points.clear(ko.to_index(self.geo.width));
+        }
+        if self.superko {
+            for idx in points.iter_ones() {
+                if self.check_superko(idx, player) {
+                    points.clear(idx);
+                }
+            }
+        }
+        points
+    }
+
+    /// Indices of empty points, iterated sparsely once the board fills up.
+    fn empty_points(&self) -> AdaptiveBitIterator<NW> {
+        self.board
+            .occupied()
+            .iter_zeros_adaptive(self.geo.board_mask)
+    }
+
+    /// Empty points that are legal for the side to move without any further
+    /// checks: the stone keeps an empty neighbor (so it is not suicide) and
+    /// touches no opponent stone (so it captures nothing and needs no superko
+    /// check). The ko point never qualifies, since it has no empty neighbor.
+    fn trivially_legal_points(&self) -> Bitboard<NW> {
         let empty = self.board.empty_squares(self.geo.board_mask);
+        let opp = self.board.stones_for(self.current_player.opposite());
+        (empty & self.geo.neighbors(&empty)).andnot(self.geo.neighbors(&opp))
+    }
+
+    fn has_legal_board_moves(&self) -> bool {
+        // Almost every position has a quiet placement available, which a few
+        // bitboard operations find without scanning the empty points.
+        if self.trivially_legal_points().is_nonzero() {
+            return true;
+        }
+
         let w = self.geo.width;
         let ko_idx = self.ko_point.map(|p| p.to_index(w));
 
-        for idx in empty.iter_ones() {
+        for idx in self.empty_points() {
             if let Some(ki) = ko_idx {
                 if ki == idx {
                     continue;
@@ -449,11 +1545,18 @@ impl<const NW: usize> Game<NW> {
             return false;
         }
 
+        // Only resignation is possible while dead stones are being marked.
+        if self.in_scoring_phase {
+            return move_.is_resign();
+        }
+
         match move_ {
             Move::Pass => {
                 self.move_history.len() >= self.min_moves_before_pass_possible as usize
                     || !self.has_legal_board_moves()
             }
+            Move::Resign => true,
+            Move::Swap => self.can_swap(),
             Move::Place { col, row } => {
                 let pos = Position::new(*col, *row);
 
@@ -478,24 +1581,102 @@ impl<const NW: usize> Game<NW> {
         }
     }
 
-    pub fn make_move(&mut self, move_: &Move) -> bool {
-        if !self.is_legal_move(move_) {
-            return false;
+    /// Why `move_` is illegal, or None if it is legal.
+    pub fn illegal_move_reason(&self, move_: &Move) -> Option<IllegalMoveReason> {
+        if self.is_legal_move(move_) {
+            return None;
         }
-
-        let previous_ko_point = self.ko_point;
-        let mut captured_stones = Bitboard::empty();
-        self.ko_point = None;
-
-        match move_ {
+        if self.is_over {
+            return Some(IllegalMoveReason::GameOver);
+        }
+        if self.in_scoring_phase {
+            return Some(IllegalMoveReason::ScoringPhase);
+        }
+        let reason = match move_ {
+            Move::Pass => IllegalMoveReason::PassTooEarly {
+                min_moves_before_pass_possible: self.min_moves_before_pass_possible,
+            },
+            Move::Swap => IllegalMoveReason::SwapNotAllowed,
+            Move::Resign => unreachable!("resignation is legal until the game is over"),
+            Move::Place { col, row } => {
+                let pos = Position::new(*col, *row);
+                if !pos.is_valid(self.board.width(), self.board.height()) {
+                    IllegalMoveReason::OffBoard
+                } else if self.board.get_piece(&pos).is_some() {
+                    IllegalMoveReason::Occupied
+                } else if self.ko_point == Some(pos) {
+                    IllegalMoveReason::Ko
+                } else if self.superko
+                    && self.check_superko(pos.to_index(self.board.width()), self.current_player)
+                {
+                    IllegalMoveReason::Superko
+                } else {
+                    IllegalMoveReason::Suicide
+                }
+            }
+        };
+        Some(reason)
+    }
+
+    pub fn make_move(&mut self, move_: &Move) -> bool {
+        if !self.is_legal_move(move_) {
+            return false;
+        }
+
+        if move_.is_resign() {
+            return self.resign(self.current_player);
+        }
+
+        self.pending_takeback = None;
+        self.save_start();
+
+        let previous_ko_point = self.ko_point;
+        let mut captured_stones = Bitboard::empty();
+        let mut suicided_stones = Bitboard::empty();
+        let mut pass_stone = false;
+        // A pass before `min_moves_before_pass_possible` is only legal when the
+        // player has no legal placement.
+        let forced_pass = move_.is_pass()
+            && self.move_history.len() < self.min_moves_before_pass_possible as usize;
+        self.ko_point = None;
+
+        match move_ {
             Move::Pass => {
-                self.consecutive_passes += 1;
+                self.consecutive_passes = self.consecutive_passes.saturating_add(1);
+                match self.current_player {
+                    Player::Black => self.black_passes += 1,
+                    Player::White => self.white_passes += 1,
+                }
+
+                let uses_pass_stones = self.ruleset.uses_pass_stones();
+                if uses_pass_stones {
+                    self.add_prisoners(self.current_player.opposite(), 1);
+                    pass_stone = true;
+                }
 
-                if self.consecutive_passes >= 2 {
-                    self.is_over = true;
-                    self.outcome = Some(self.determine_outcome());
+                // Under AGA rules White must make the last pass, so a second
+                // pass by Black leaves White to pass once more.
+                let last_pass_ok = !uses_pass_stones || self.current_player == Player::White;
+                if self.consecutive_passes >= 2 && last_pass_ok {
+                    if self.dead_stone_marking {
+                        self.in_scoring_phase = true;
+                    } else {
+                        self.is_over = true;
+                        self.outcome = Some(self.determine_outcome(EndReason::DoublePass));
+                    }
                 }
             }
+            Move::Resign => unreachable!("resignation is handled by Game::resign"),
+            Move::Swap => {
+                let first = self.move_history[0]
+                    .move_
+                    .position()
+                    .expect("swap follows a placement");
+                let idx = first.to_index(self.board.width());
+                self.board.clear_bit(idx);
+                self.board.set_bit(idx, Player::White);
+                self.komi = -self.komi;
+            }
             Move::Place { col, row } => {
                 self.consecutive_passes = 0;
 
@@ -535,6 +1716,22 @@ impl<const NW: usize> Game<NW> {
                     }
                 }
 
+                // Suicide (only legal when allowed): a move that captured nothing
+                // and left its own group without liberties removes that group.
+                if self.allow_suicide && total_captured == 0 {
+                    let own_group = self
+                        .geo
+                        .flood_fill(bit, self.board.stones_for(self.current_player));
+                    let own_neighbors = self.geo.neighbors(&own_group);
+                    if (own_neighbors & self.board.empty_squares(self.geo.board_mask)).is_empty() {
+                        suicided_stones = own_group;
+                        self.board.remove_stones(own_group);
+                    }
+                }
+
+                self.add_prisoners(self.current_player, total_captured);
+                self.add_prisoners(opponent, suicided_stones.count());
+
                 // Ko detection
                 if total_captured == 1 {
                     if let Some(cap_idx) = single_capture_idx {
@@ -556,9 +1753,17 @@ impl<const NW: usize> Game<NW> {
         }
 
         self.move_history.push(MoveHistoryEntry {
+            player: self.current_player,
             move_: *move_,
             captured_stones,
+            suicided_stones,
             previous_ko_point,
+            pass_stone,
+        });
+
+        self.record(GameEvent::Move {
+            player: self.current_player,
+            move_: *move_,
         });
 
         self.current_player = self.current_player.opposite();
@@ -567,49 +1772,285 @@ impl<const NW: usize> Game<NW> {
             hashes.insert(compute_position_hash(&self.board, self.current_player));
         }
 
+        // A forced pass answered by an opponent who has no legal placement either
+        // could only be followed by another pass, so score the board now.
+        if forced_pass && !self.is_over && !self.in_scoring_phase && !self.has_legal_board_moves() {
+            self.is_over = true;
+            self.outcome = Some(self.determine_outcome(EndReason::NoLegalMoves));
+        }
+
         // Check max moves limit
-        if !self.is_over && self.move_history.len() >= self.max_moves as usize {
+        if !self.is_over
+            && !self.in_scoring_phase
+            && self.move_history.len() >= self.max_moves as usize
+        {
             self.is_over = true;
-            self.outcome = Some(self.determine_outcome());
+            self.outcome = Some(self.determine_outcome(EndReason::MaxMoves));
         }
 
+        if self.is_over {
+            self.publish_result();
+        }
+        true
+    }
+
+    /// `player` resigns, ending the game with the opponent as the winner.
+    /// Either player may resign at any point; the resignation is recorded in the
+    /// move history (so `unmake_move` can undo it) but does not change the turn.
+    pub fn resign(&mut self, player: Player) -> bool {
+        if self.is_over {
+            return false;
+        }
+
+        self.pending_takeback = None;
+        self.save_start();
+
+        self.move_history.push(MoveHistoryEntry {
+            player,
+            move_: Move::Resign,
+            captured_stones: Bitboard::empty(),
+            suicided_stones: Bitboard::empty(),
+            previous_ko_point: self.ko_point,
+            pass_stone: false,
+        });
+
+        self.record(GameEvent::Move {
+            player,
+            move_: Move::Resign,
+        });
+
+        self.is_over = true;
+        self.outcome = Some(GameOutcome::resignation(player));
+        self.publish_result();
+
         true
     }
 
     pub fn unmake_move(&mut self) -> bool {
         if let Some(entry) = self.move_history.pop() {
-            if let Some(ref mut hashes) = self.position_hashes {
-                let hash = compute_position_hash(&self.board, self.current_player);
-                hashes.remove(&hash);
+            // Resigning changes neither the board nor the turn, so there is no
+            // position hash to remove.
+            if !entry.move_.is_resign() {
+                if let Some(ref mut hashes) = self.position_hashes {
+                    let hash = compute_position_hash(&self.board, self.current_player);
+                    hashes.remove(&hash);
+                }
             }
 
-            self.current_player = self.current_player.opposite();
+            if !entry.move_.is_resign() {
+                self.current_player = entry.player;
+            }
             self.ko_point = entry.previous_ko_point;
+            self.record(GameEvent::Unmake {
+                player: entry.player,
+                move_: entry.move_,
+            });
 
             match entry.move_ {
                 Move::Pass => {
-                    self.consecutive_passes = self.consecutive_passes.saturating_sub(1);
+                    match self.current_player {
+                        Player::Black => self.black_passes -= 1,
+                        Player::White => self.white_passes -= 1,
+                    }
+                    if entry.pass_stone {
+                        self.remove_prisoners(self.current_player.opposite(), 1);
+                    }
+                    self.is_over = false;
+                    self.outcome = None;
+                    self.in_scoring_phase = false;
+                    self.dead_stones = Bitboard::empty();
+                }
+                Move::Resign => {
+                    self.is_over = false;
+                    self.outcome = None;
+                }
+                Move::Swap => {
+                    let first = self.move_history[0]
+                        .move_
+                        .position()
+                        .expect("swap follows a placement");
+                    let idx = first.to_index(self.board.width());
+                    self.board.clear_bit(idx);
+                    self.board.set_bit(idx, Player::Black);
+                    self.komi = -self.komi;
                     self.is_over = false;
                     self.outcome = None;
                 }
                 Move::Place { col, row } => {
                     let pos = Position::new(col, row);
                     let idx = pos.to_index(self.board.width());
+                    self.board
+                        .restore_stones(entry.suicided_stones, self.current_player);
                     self.board.clear_bit(idx);
 
                     let opponent = self.current_player.opposite();
                     self.board.restore_stones(entry.captured_stones, opponent);
 
+                    self.remove_prisoners(self.current_player, entry.captured_stones.count());
+                    self.remove_prisoners(opponent, entry.suicided_stones.count());
+
                     self.is_over = false;
                     self.outcome = None;
                 }
             }
 
+            // Undoing a placement can expose passes made before it.
+            self.consecutive_passes = self
+                .move_history
+                .iter()
+                .rev()
+                .take_while(|e| e.move_.is_pass())
+                .count() as u32;
+
             true
         } else {
             false
         }
     }
+
+    /// Take back the last `n` moves, or all of them if there are fewer.
+    /// Returns the number taken back. Undoing more than half the game
+    /// restores the start position and replays the kept moves from their
+    /// recorded captures, without legality checks or flood fills.
+    pub fn rewind(&mut self, n: usize) -> usize {
+        let len = self.move_history.len();
+        let n = n.min(len);
+        if n == len {
+            self.reset_keep_config();
+            return n;
+        }
+        if n <= len - n {
+            for _ in 0..n {
+                self.unmake_move();
+            }
+            return n;
+        }
+
+        let mut history = std::mem::take(&mut self.move_history);
+        let removed = history.split_off(len - n);
+        self.record_unmakes(&removed);
+        if removed.iter().any(|e| e.move_ == Move::Swap) {
+            self.komi = -self.komi;
+        }
+        self.restore_start();
+        for entry in history {
+            self.replay_entry(entry);
+        }
+        self.ko_point = removed[0].previous_ko_point;
+        self.consecutive_passes = self
+            .move_history
+            .iter()
+            .rev()
+            .take_while(|e| e.move_.is_pass())
+            .count() as u32;
+        n
+    }
+
+    /// Take back every move, keeping the rules, komi, setup stones and
+    /// game info, so the game can be reused without reallocating.
+    pub fn reset_keep_config(&mut self) {
+        if self.move_history.is_empty() {
+            return;
+        }
+        let history = std::mem::take(&mut self.move_history);
+        self.record_unmakes(&history);
+        if history.iter().any(|e| e.move_ == Move::Swap) {
+            self.komi = -self.komi;
+        }
+        self.restore_start();
+        // Keep the allocation for the next game.
+        self.move_history = history;
+        self.move_history.clear();
+    }
+
+    fn save_start(&mut self) {
+        if self.move_history.is_empty() {
+            self.start = Some(StartPosition {
+                board: self.board,
+                current_player: self.current_player,
+                ko_point: self.ko_point,
+                black_prisoners: self.black_prisoners,
+                white_prisoners: self.white_prisoners,
+            });
+        }
+    }
+
+    /// Log `entries`, last first, as taken back.
+    fn record_unmakes(&mut self, entries: &[MoveHistoryEntry<NW>]) {
+        for entry in entries.iter().rev() {
+            self.record(GameEvent::Unmake {
+                player: entry.player,
+                move_: entry.move_,
+            });
+        }
+    }
+
+    /// Put back the position saved before the first move and clear the
+    /// state that moves build up. The move history must already be empty.
+    fn restore_start(&mut self) {
+        let start = self
+            .start
+            .clone()
+            .expect("the start is saved before the first move");
+        self.board = start.board;
+        self.current_player = start.current_player;
+        self.ko_point = start.ko_point;
+        self.black_prisoners = start.black_prisoners;
+        self.white_prisoners = start.white_prisoners;
+        self.black_passes = 0;
+        self.white_passes = 0;
+        self.consecutive_passes = 0;
+        self.is_over = false;
+        self.outcome = None;
+        self.in_scoring_phase = false;
+        self.dead_stones = Bitboard::empty();
+        self.pending_takeback = None;
+        if let Some(ref mut hashes) = self.position_hashes {
+            hashes.clear();
+            hashes.insert(compute_position_hash(&self.board, self.current_player));
+        }
+    }
+
+    /// Play `entry` again from what it recorded, as `make_move` played it.
+    fn replay_entry(&mut self, entry: MoveHistoryEntry<NW>) {
+        let opponent = entry.player.opposite();
+        match entry.move_ {
+            Move::Pass => {
+                match entry.player {
+                    Player::Black => self.black_passes += 1,
+                    Player::White => self.white_passes += 1,
+                }
+                if entry.pass_stone {
+                    self.add_prisoners(opponent, 1);
+                }
+            }
+            Move::Resign => {}
+            Move::Swap => {
+                let first = self.move_history[0]
+                    .move_
+                    .position()
+                    .expect("swap follows a placement");
+                let idx = first.to_index(self.board.width());
+                self.board.clear_bit(idx);
+                self.board.set_bit(idx, Player::White);
+            }
+            Move::Place { col, row } => {
+                let idx = Position::new(col, row).to_index(self.board.width());
+                self.board.set_bit(idx, entry.player);
+                self.board.remove_stones(entry.captured_stones);
+                self.board.remove_stones(entry.suicided_stones);
+                self.add_prisoners(entry.player, entry.captured_stones.count());
+                self.add_prisoners(opponent, entry.suicided_stones.count());
+            }
+        }
+        if !entry.move_.is_resign() {
+            self.current_player = opponent;
+            if let Some(ref mut hashes) = self.position_hashes {
+                hashes.insert(compute_position_hash(&self.board, self.current_player));
+            }
+        }
+        self.move_history.push(entry);
+    }
 }
 
 #[hotpath::measure_all]
@@ -690,117 +2131,675 @@ mod tests {
 
         game.make_move(&move_);
 
-        let same_pos = Move::place(0, 0);
-        assert!(!game.is_legal_move(&same_pos));
+        let same_pos = Move::place(0, 0);
+        assert!(!game.is_legal_move(&same_pos));
+    }
+
+    #[test]
+    fn test_unmake_move() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let move_ = Move::place(0, 0);
+
+        game.make_move(&move_);
+        assert_eq!(game.turn(), Player::White);
+
+        assert!(game.unmake_move());
+        assert_eq!(game.turn(), Player::Black);
+        assert_eq!(game.move_history().len(), 0);
+        assert!(game.board().get_piece(&Position::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_pass_move() {
+        let mut game =
+            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 0, 1000, false);
+
+        assert!(game.make_move(&Move::pass()));
+        assert_eq!(game.turn(), Player::White);
+        assert!(!game.is_over());
+
+        assert!(game.make_move(&Move::pass()));
+        assert!(game.is_over());
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::White));
+
+        let outcome = game.outcome().expect("game is over");
+        assert_eq!(outcome.reason(), EndReason::DoublePass);
+        assert_eq!(outcome.margin(), Some(-DEFAULT_KOMI));
+        assert_eq!(outcome.margin_half_points(), Some(-15));
+        assert_eq!(game.score_half_points(), (0, 15));
+        assert_eq!(
+            outcome.margin_from_perspective(Player::White),
+            Some(DEFAULT_KOMI)
+        );
+    }
+
+    #[test]
+    fn test_movelist_string() {
+        let mut game =
+            Game::<{ nw_for_board(19, 19) }>::with_options(19, 19, DEFAULT_KOMI, 0, 1000, true);
+        game.make_move(&Move::place(3, 3));
+        game.make_move(&Move::place(15, 15));
+        game.make_move(&Move::pass());
+        game.resign(Player::White);
+
+        assert_eq!(
+            game.movelist_string(MoveListStyle::Gtp),
+            "1. B D4  2. W Q16  3. B pass  4. W resign"
+        );
+        assert_eq!(
+            game.movelist_string(MoveListStyle::Sgf),
+            "1. B dp  2. W pd  3. B pass  4. W resign"
+        );
+    }
+
+    #[test]
+    fn test_pass_not_legal_before_min_moves() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        assert_eq!(game.min_moves_before_pass_possible(), 40u16);
+
+        // Pass should not be legal before min_moves_before_pass_possible
+        assert!(!game.is_legal_move(&Move::pass()));
+        assert!(!game.make_move(&Move::pass()));
+    }
+
+    #[test]
+    fn test_pass_legal_when_no_board_moves_remain() {
+        let mut game = Game::<{ nw_for_board(3, 3) }>::with_options(3, 3, 0.5, 1000, 1000, false);
+
+        // White fills the board apart from two single-point eyes
+        for idx in 1..8 {
+            game.set_piece(&Position::from_index(idx, 3), Some(Player::White));
+        }
+        assert!(!game.has_legal_board_moves());
+        assert_eq!(game.legal_moves(), vec![Move::pass()]);
+        assert!(game.is_legal_move(&Move::pass()));
+
+        // With one eye left, Black can capture everything, which only the full
+        // check finds
+        game.set_piece(&Position::new(2, 2), Some(Player::White));
+        assert!(game.trivially_legal_points().is_empty());
+        assert!(game.has_legal_board_moves());
+        assert!(!game.is_legal_move(&Move::pass()));
+    }
+
+    #[test]
+    fn test_forced_passes_end_game_when_neither_side_can_place() {
+        let mut game = Game::<{ nw_for_board(2, 2) }>::with_options(2, 2, 0.5, 1000, 1000, true);
+
+        // Superko leaves both players without a legal placement after Black's
+        // forced pass on the last move.
+        let moves = [
+            Move::place(1, 0),
+            Move::place(1, 1),
+            Move::place(0, 0),
+            Move::place(0, 1),
+            Move::place(0, 0),
+            Move::place(1, 0),
+            Move::place(0, 0),
+            Move::place(0, 1),
+            Move::place(1, 1),
+            Move::pass(),
+            Move::place(1, 0),
+            Move::place(0, 1),
+            Move::place(0, 0),
+            Move::place(1, 1),
+            Move::place(1, 0),
+            Move::place(0, 1),
+        ];
+        for move_ in &moves {
+            assert!(game.make_move(move_), "{} should be legal", move_);
+        }
+        assert_eq!(game.legal_moves(), vec![Move::pass()]);
+
+        assert!(game.make_move(&Move::pass()));
+        assert!(game.is_over());
+        assert_eq!(
+            game.outcome().map(|o| o.reason()),
+            Some(EndReason::NoLegalMoves)
+        );
+
+        game.unmake_move();
+        assert!(!game.is_over());
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn test_pass_ends_game_after_min_moves() {
+        let mut game =
+            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 4, 1000, false);
+
+        // Pass not legal before 4 moves
+        assert!(!game.is_legal_move(&Move::pass()));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(2, 0));
+        game.make_move(&Move::place(3, 0));
+        // Now at 4 moves, pass is legal
+        assert!(game.is_legal_move(&Move::pass()));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::pass());
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn test_max_moves_ends_game() {
+        let mut game =
+            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 100, 5, false);
+
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(2, 0));
+        game.make_move(&Move::place(3, 0));
+        assert!(!game.is_over());
+
+        game.make_move(&Move::place(4, 0));
+        assert!(game.is_over());
+        assert!(game.outcome().is_some());
+        assert_eq!(
+            game.outcome().map(|o| o.reason()),
+            Some(EndReason::MaxMoves)
+        );
+        assert_eq!(game.moves_remaining(), 0);
+    }
+
+    #[test]
+    fn test_move_limits() {
+        let game = Game::<{ nw_for_board(32, 32) }>::new(32, 32);
+        assert_eq!(game.max_moves(), 3072);
+        assert_eq!(game.min_moves_before_pass_possible(), 512);
+        assert_eq!(game.moves_remaining(), 3072);
+        assert_eq!(default_max_moves(255, 255), u16::MAX);
+
+        let mut game = Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, 7.5, 0, 10, false);
+        game.make_move(&Move::place(4, 4));
+        assert_eq!(game.moves_remaining(), 9);
+
+        assert_eq!(validate_move_limits(0, 1), Ok(()));
+        assert_eq!(
+            validate_move_limits(0, 0),
+            Err(MoveLimitError::ZeroMaxMoves)
+        );
+        assert!(validate_move_limits(100, 5)
+            .expect_err("passing after the limit")
+            .to_string()
+            .contains("(100) must not exceed max_moves (5)"));
+    }
+
+    #[test]
+    fn test_scoring_black_wins() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(0, 1));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(1, 1));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::pass());
+
+        assert!(game.is_over());
+        let (black_score, white_score) = game.score();
+        assert!(black_score > white_score);
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::Black));
+    }
+
+    #[test]
+    fn test_scoring_with_territory() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.0, 0, 1000, false);
+
+        game.make_move(&Move::place(0, 2));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(0, 3));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(1, 2));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::pass());
+
+        let (black_score, white_score) = game.score();
+        assert!(black_score > white_score);
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::Black));
+    }
+
+    #[test]
+    fn test_prisoners_tracked_through_unmake() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(0, 1));
+        assert_eq!(game.prisoners(Player::Black), 1);
+        assert_eq!(game.prisoners(Player::White), 0);
+
+        game.unmake_move();
+        assert_eq!(game.prisoners(Player::Black), 0);
+    }
+
+    #[test]
+    fn test_passes_tracked_per_player_through_unmake() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(2, 2));
+        game.make_move(&Move::pass());
+        assert_eq!(game.passes(Player::Black), 2);
+        assert_eq!(game.passes(Player::White), 0);
+        assert_eq!(game.consecutive_passes(), 1);
+
+        // Taking back White's stone exposes Black's first pass again
+        game.unmake_move();
+        game.unmake_move();
+        assert_eq!(game.passes(Player::Black), 1);
+        assert_eq!(game.consecutive_passes(), 1);
+
+        game.make_move(&Move::pass());
+        assert!(game.is_over());
+        assert_eq!(game.passes(Player::White), 1);
+        assert_eq!(game.consecutive_passes(), 2);
+    }
+
+    #[test]
+    fn test_handicap_stones_and_compensation() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, 0.5, 0, 1000, true);
+        assert!(!game.place_handicap(1));
+        assert!(game.place_handicap(4));
+        assert!(!game.place_handicap(2));
+
+        assert_eq!(game.handicap(), 4);
+        assert_eq!(game.turn(), Player::White);
+        assert_eq!(
+            game.get_piece(&Position::new(6, 2)),
+            Some(Player::Black as i8)
+        );
+
+        // Chinese rules: 4 stones owning the whole board, White gets 4 points
+        assert_eq!(game.effective_komi(), 4.5);
+        assert_eq!(game.score(), (81.0, 4.5));
+
+        game.set_ruleset(Ruleset::Japanese);
+        assert_eq!(game.score(), (77.0, 0.5));
+    }
+
+    #[test]
+    fn test_swap_rule_turns_first_stone_and_komi() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 7.5, 0, 1000, true);
+        game.make_move(&Move::place(2, 2));
+        assert!(!game.legal_moves().contains(&Move::swap()));
+
+        game.unmake_move();
+        game.set_swap_rule(true);
+        assert!(!game.is_legal_move(&Move::swap()));
+        game.make_move(&Move::place(2, 2));
+        assert!(game.legal_moves().contains(&Move::swap()));
+
+        assert!(game.make_move(&Move::swap()));
+        assert_eq!(game.turn(), Player::Black);
+        assert_eq!(
+            game.get_piece(&Position::new(2, 2)),
+            Some(Player::White as i8)
+        );
+        assert_eq!(game.komi(), -7.5);
+        assert!(!game.is_legal_move(&Move::swap()));
+
+        // The board before the swap still has Black's stone.
+        let before = game.board_history().nth(1).expect("board before swap");
+        assert_eq!(before.get_piece(&Position::new(2, 2)), Some(Player::Black));
+
+        assert!(game.unmake_move());
+        assert_eq!(game.turn(), Player::White);
+        assert_eq!(
+            game.get_piece(&Position::new(2, 2)),
+            Some(Player::Black as i8)
+        );
+        assert_eq!(game.komi(), 7.5);
+    }
+
+    #[test]
+    fn test_japanese_scoring_counts_territory_and_prisoners() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_ruleset(Ruleset::Japanese);
+
+        // Black wall on column 1, white wall on column 3, white stone captured in the corner
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(0, 1)); // captures (0, 0)
+        game.make_move(&Move::place(3, 0));
+        game.make_move(&Move::place(1, 1));
+        game.make_move(&Move::place(3, 1));
+        game.make_move(&Move::place(1, 2));
+        game.make_move(&Move::place(3, 2));
+        game.make_move(&Move::place(1, 3));
+        game.make_move(&Move::place(3, 3));
+        game.make_move(&Move::place(1, 4));
+        game.make_move(&Move::place(3, 4));
+
+        // Black territory: (0,0), (0,2), (0,3), (0,4) = 4, plus 1 prisoner
+        // White territory: column 4 = 5, plus komi. Column 2 is dame.
+        let (black_score, white_score) = game.score();
+        assert_eq!(black_score, 5.0);
+        assert_eq!(white_score, 5.5);
+
+        game.set_ruleset(Ruleset::Chinese);
+        let (black_score, white_score) = game.score();
+        assert_eq!(black_score, 10.0);
+        assert_eq!(white_score, 10.5);
+
+        let details = game.score_details();
+        assert_eq!(
+            details.black,
+            ScoreBreakdown {
+                stones: 6,
+                territory: 4,
+                prisoners: 1,
+            }
+        );
+        assert_eq!(
+            details.white,
+            ScoreBreakdown {
+                stones: 5,
+                territory: 5,
+                prisoners: 0,
+            }
+        );
+        assert_eq!(details.dame, 5);
+        assert_eq!(details.totals(), game.score());
+    }
+
+    #[test]
+    fn test_score_fast_matches_area_score() {
+        use rand::prelude::IndexedRandom;
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for _ in 0..20 {
+            let mut game = Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, 6.5, 0, 200, true);
+            game.set_ruleset(Ruleset::Chinese);
+            while !game.is_over() {
+                let moves = game.legal_moves();
+                let m = *moves.choose(&mut rng).expect("a legal move");
+                game.make_move(&m);
+                assert_eq!(game.score_fast(), game.score());
+            }
+        }
+
+        // Stones marked dead and territory rules are left to `score()`.
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_ruleset(Ruleset::Japanese);
+        game.make_move(&Move::place(2, 2));
+        assert_eq!(game.score_fast(), (25.0, 0.5));
+        assert_eq!(game.score(), (24.0, 0.5));
+    }
+
+    #[test]
+    fn test_rewind_matches_unmaking_one_by_one() {
+        use rand::prelude::IndexedRandom;
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        for rewind_by in [1, 30, 55, 200] {
+            let mut game = Game::<{ nw_for_board(7, 7) }>::with_options(7, 7, 6.5, 0, 60, true);
+            game.set_ruleset(Ruleset::Aga);
+            game.set_swap_rule(true);
+            while !game.is_over() {
+                let moves = game.legal_moves();
+                game.make_move(moves.choose(&mut rng).expect("a legal move"));
+            }
+
+            let mut expected = game.clone();
+            let undone = (0..rewind_by)
+                .take_while(|_| expected.unmake_move())
+                .count();
+            assert_eq!(game.rewind(rewind_by), undone);
+
+            assert_eq!(game.board(), expected.board());
+            assert_eq!(game.turn(), expected.turn());
+            assert_eq!(game.ko_point(), expected.ko_point());
+            assert_eq!(game.komi(), expected.komi());
+            assert_eq!(game.move_history(), expected.move_history());
+            assert_eq!(game.consecutive_passes(), expected.consecutive_passes());
+            for player in [Player::Black, Player::White] {
+                assert_eq!(game.prisoners(player), expected.prisoners(player));
+                assert_eq!(game.passes(player), expected.passes(player));
+            }
+            assert_eq!(game.position_hashes, expected.position_hashes);
+            assert_eq!(game.legal_moves(), expected.legal_moves());
+            assert!(!game.is_over());
+        }
+    }
+
+    #[test]
+    fn test_play_frequency_map_counts_ko_recaptures() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        // A ko at (1, 0) and (2, 0), taken back and forth with threats
+        // elsewhere in between.
+        for m in [
+            Move::place(3, 0),
+            Move::place(0, 0),
+            Move::place(2, 1),
+            Move::place(1, 1),
+            Move::place(4, 4),
+            Move::place(2, 0),
+            Move::place(1, 0),
+            Move::place(0, 4),
+            Move::place(4, 3),
+            Move::place(2, 0),
+            Move::place(4, 2),
+            Move::place(3, 4),
+            Move::place(1, 0),
+        ] {
+            assert!(game.make_move(&m), "{:?}", m);
+        }
+        let map = game.play_frequency_map();
+        assert_eq!(map.len(), 25);
+        assert_eq!((map[1], map[2], map[3]), (2, 2, 1));
+        assert_eq!(map.iter().sum::<u32>(), 13);
+
+        assert!(game.unmake_move());
+        assert_eq!(game.play_frequency_map()[1], 1);
+    }
+
+    #[test]
+    fn test_last_move_diff_replays_the_board() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_swap_rule(true);
+        assert_eq!(game.last_move_diff(), None);
+        // Black's first stone turns white on the swap; then (1, 0) captures
+        // at (0, 0), and a pass changes nothing.
+        for m in [
+            Move::place(0, 0),
+            Move::Swap,
+            Move::place(0, 1),
+            Move::pass(),
+            Move::place(1, 0),
+            Move::pass(),
+        ] {
+            let mut board = *game.board();
+            assert!(game.make_move(&m), "{:?}", m);
+            let diff = game.last_move_diff().expect("a move was made");
+            diff.apply(&mut board).expect("same size");
+            assert_eq!(board, *game.board(), "{:?}", m);
+            assert_eq!(diff.is_empty(), m.is_pass());
+        }
+        let capture = {
+            game.unmake_move();
+            game.last_move_diff().expect("a move was made")
+        };
+        assert_eq!(capture.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_history_matches_the_boards_played_on() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_swap_rule(true);
+        game.set_allow_suicide(true);
+        assert!(game.history().is_empty());
+        // A swap, a capture at (0, 0), a pass and a suicide at (0, 0).
+        let mut expected = Vec::new();
+        for m in [
+            Move::place(0, 0),
+            Move::Swap,
+            Move::place(0, 1),
+            Move::pass(),
+            Move::place(1, 0),
+            Move::place(0, 0),
+        ] {
+            expected.push((m, *game.board()));
+            assert!(game.make_move(&m), "{:?}", m);
+        }
+        assert_eq!(game.board().get_piece(&Position::new(0, 0)), None);
+        assert_eq!(game.history(), expected);
     }
 
     #[test]
-    fn test_unmake_move() {
-        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
-        let move_ = Move::place(0, 0);
+    fn test_reset_keep_config_keeps_setup() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, 0.5, 0, 1000, true);
+        game.set_ruleset(Ruleset::Japanese);
+        assert!(game.place_handicap(2));
+        let start = *game.board();
+        for m in [Move::place(4, 4), Move::place(3, 3), Move::pass()] {
+            assert!(game.make_move(&m));
+        }
 
-        game.make_move(&move_);
+        game.reset_keep_config();
+        assert_eq!(*game.board(), start);
         assert_eq!(game.turn(), Player::White);
-
-        assert!(game.unmake_move());
-        assert_eq!(game.turn(), Player::Black);
-        assert_eq!(game.move_history().len(), 0);
-        assert!(game.board().get_piece(&Position::new(0, 0)).is_none());
+        assert_eq!(game.move_count(), 0);
+        assert_eq!((game.handicap(), game.ruleset()), (2, Ruleset::Japanese));
+        assert!(game.make_move(&Move::place(4, 4)));
     }
 
     #[test]
-    fn test_pass_move() {
-        let mut game =
-            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 0, 1000, false);
+    fn test_aga_pass_stones_and_white_passes_last() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_ruleset(Ruleset::Aga);
 
-        assert!(game.make_move(&Move::pass()));
-        assert_eq!(game.turn(), Player::White);
+        game.make_move(&Move::place(2, 2));
+        game.make_move(&Move::pass());
+        assert_eq!(game.prisoners(Player::Black), 1);
+
+        // Black's pass would end the game, but White must pass last
+        game.make_move(&Move::pass());
+        assert_eq!(game.prisoners(Player::White), 1);
         assert!(!game.is_over());
 
-        assert!(game.make_move(&Move::pass()));
+        game.make_move(&Move::pass());
         assert!(game.is_over());
-        assert_eq!(game.outcome(), Some(GameOutcome::WhiteWin));
+        assert_eq!(game.prisoners(Player::Black), 2);
+
+        game.unmake_move();
+        assert!(!game.is_over());
+        assert_eq!(game.prisoners(Player::Black), 1);
     }
 
     #[test]
-    fn test_pass_not_legal_before_min_moves() {
-        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
-        assert_eq!(game.min_moves_before_pass_possible(), 40u16);
-
-        // Pass should not be legal before min_moves_before_pass_possible
-        assert!(!game.is_legal_move(&Move::pass()));
-        assert!(!game.make_move(&Move::pass()));
+    fn test_aga_territory_count_matches_area_count() {
+        let mut aga = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        aga.set_ruleset(Ruleset::Aga);
+
+        // Black wall on column 1, white wall on column 3, an extra black stone
+        // in the dame, then W pass, B pass, W pass.
+        for row in 0..5 {
+            aga.make_move(&Move::place(1, row));
+            aga.make_move(&Move::place(3, row));
+        }
+        aga.make_move(&Move::place(2, 2));
+        aga.make_move(&Move::pass());
+        aga.make_move(&Move::pass());
+        aga.make_move(&Move::pass());
+        assert!(aga.is_over());
+
+        let mut chinese = aga.clone();
+        chinese.set_ruleset(Ruleset::Chinese);
+
+        let (aga_black, aga_white) = aga.score();
+        let (area_black, area_white) = chinese.score();
+        assert_eq!(aga_black, 7.0);
+        assert_eq!(aga_white, 6.5);
+        assert_eq!(aga_black - aga_white, area_black - area_white);
     }
 
     #[test]
-    fn test_pass_ends_game_after_min_moves() {
-        let mut game =
-            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 4, 1000, false);
+    fn test_dead_stone_marking_phase() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_dead_stone_marking(true);
 
-        // Pass not legal before 4 moves
-        assert!(!game.is_legal_move(&Move::pass()));
-        game.make_move(&Move::place(0, 0));
-        game.make_move(&Move::place(1, 0));
-        game.make_move(&Move::place(2, 0));
-        game.make_move(&Move::place(3, 0));
-        // Now at 4 moves, pass is legal
-        assert!(game.is_legal_move(&Move::pass()));
+        // Black wall on column 2, white wall on column 3, white invader at (0, 2)
+        for row in 0..5 {
+            game.make_move(&Move::place(2, row));
+            game.make_move(&Move::place(3, row));
+        }
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(0, 2));
         game.make_move(&Move::pass());
         game.make_move(&Move::pass());
-        assert!(game.is_over());
-    }
-
-    #[test]
-    fn test_max_moves_ends_game() {
-        let mut game =
-            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 100, 5, false);
 
-        game.make_move(&Move::place(0, 0));
-        game.make_move(&Move::place(1, 0));
-        game.make_move(&Move::place(2, 0));
-        game.make_move(&Move::place(3, 0));
+        assert_eq!(game.phase(), GamePhase::Scoring);
         assert!(!game.is_over());
+        assert!(game.outcome().is_none());
+        assert!(game.legal_moves().is_empty());
+        assert!(!game.is_legal_move(&Move::pass()));
 
-        game.make_move(&Move::place(4, 0));
-        assert!(game.is_over());
-        assert!(game.outcome().is_some());
+        // The invader spoils black's territory until it is marked dead
+        assert_eq!(game.score(), (5.0, 11.5));
+
+        let wall = Position::new(2, 2);
+        assert!(game.toggle_dead_group(&wall));
+        assert!(game.is_dead(&Position::new(2, 0)));
+        assert!(game.toggle_dead_group(&wall));
+        assert!(!game.is_dead(&Position::new(2, 0)));
+
+        assert!(game.mark_dead(&[Position::new(0, 2), Position::new(1, 1)]));
+        assert!(game.is_dead(&Position::new(0, 2)));
+        assert_eq!(game.score(), (15.0, 10.5));
+
+        assert!(game.accept_score());
+        assert_eq!(game.phase(), GamePhase::Over);
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::Black));
+        assert!(!game.mark_dead(&[wall]));
+
+        // Taking back the second pass resumes play and clears the marks
+        assert!(game.unmake_move());
+        assert_eq!(game.phase(), GamePhase::Playing);
+        assert!(!game.is_dead(&Position::new(0, 2)));
+        assert_eq!(game.score(), (5.0, 11.5));
     }
 
     #[test]
-    fn test_scoring_black_wins() {
+    fn test_dead_stones_count_as_prisoners_under_japanese_rules() {
         let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_dead_stone_marking(true);
+        game.set_ruleset(Ruleset::Japanese);
 
-        game.make_move(&Move::place(0, 0));
-        game.make_move(&Move::pass());
-        game.make_move(&Move::place(1, 0));
-        game.make_move(&Move::pass());
-        game.make_move(&Move::place(0, 1));
+        for row in 0..5 {
+            game.make_move(&Move::place(2, row));
+            game.make_move(&Move::place(3, row));
+        }
         game.make_move(&Move::pass());
-        game.make_move(&Move::place(1, 1));
+        game.make_move(&Move::place(0, 2));
         game.make_move(&Move::pass());
         game.make_move(&Move::pass());
 
-        assert!(game.is_over());
-        let (black_score, white_score) = game.score();
-        assert!(black_score > white_score);
-        assert_eq!(game.outcome(), Some(GameOutcome::BlackWin));
+        assert!(game.mark_dead(&[Position::new(0, 2)]));
+        // Black: 10 territory + 1 dead stone; White: 5 territory + komi
+        assert_eq!(game.score(), (11.0, 5.5));
     }
 
     #[test]
-    fn test_scoring_with_territory() {
-        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.0, 0, 1000, false);
+    fn test_ownership_agrees_with_area_score() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+        for row in 0..5 {
+            game.make_move(&Move::place(1, row));
+            game.make_move(&Move::place(3, row));
+        }
 
-        game.make_move(&Move::place(0, 2));
-        game.make_move(&Move::pass());
-        game.make_move(&Move::place(0, 3));
-        game.make_move(&Move::pass());
-        game.make_move(&Move::place(1, 2));
-        game.make_move(&Move::pass());
-        game.make_move(&Move::pass());
+        let ownership = game.ownership_map_absolute();
+        let black_owned = ownership.iter().filter(|&&v| v == 1.0).count();
+        let white_owned = ownership.iter().filter(|&&v| v == -1.0).count();
+        let neutral = ownership.iter().filter(|&&v| v == 0.0).count();
 
-        let (black_score, white_score) = game.score();
-        assert!(black_score > white_score);
-        assert_eq!(game.outcome(), Some(GameOutcome::BlackWin));
+        assert_eq!(game.score(), (black_owned as f32, white_owned as f32 + 0.5));
+        assert_eq!((black_owned, white_owned, neutral), (10, 10, 5));
     }
 
     #[test]
@@ -840,6 +2839,98 @@ mod tests {
         assert!(game.board().get_piece(&Position::new(1, 1)).is_some());
     }
 
+    #[test]
+    fn test_set_position() {
+        let mut game =
+            Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 0, 1000, true);
+        game.make_move(&Move::place(2, 2));
+        game.make_move(&Move::place(3, 3));
+
+        // White has just captured at (1, 1), so Black may not retake at (2, 1).
+        let black = [
+            Position::new(1, 0),
+            Position::new(0, 1),
+            Position::new(1, 2),
+        ];
+        let white = [
+            Position::new(2, 0),
+            Position::new(3, 1),
+            Position::new(2, 2),
+            Position::new(1, 1),
+        ];
+        let ko = Position::new(2, 1);
+        assert_eq!(
+            game.set_position(&black, &white, Player::Black, Some(Position::new(1, 1)), 40),
+            Err(SetPositionError::InvalidKo(Position::new(1, 1)))
+        );
+        assert_eq!(game.move_count(), 2);
+
+        game.set_position(&black, &white, Player::Black, Some(ko), 40)
+            .expect("the position is valid");
+        assert_eq!(game.move_count(), 0);
+        assert_eq!(game.move_number(), 40);
+        assert_eq!(game.turn(), Player::Black);
+        assert_eq!(game.get_piece(&Position::new(3, 3)), None);
+        assert_eq!(
+            game.get_piece(&Position::new(1, 1)),
+            Some(Player::White as i8)
+        );
+        assert_eq!(
+            game.illegal_move_reason(&Move::place(2, 1)),
+            Some(IllegalMoveReason::Ko)
+        );
+        assert!(game.make_move(&Move::place(4, 4)));
+        assert!(game.unmake_move());
+        assert_eq!(
+            game.get_piece(&Position::new(1, 1)),
+            Some(Player::White as i8)
+        );
+
+        assert_eq!(
+            game.set_position(
+                &[Position::new(0, 0)],
+                &[Position::new(0, 0)],
+                Player::Black,
+                None,
+                0
+            ),
+            Err(SetPositionError::BothColors(Position::new(0, 0)))
+        );
+        assert_eq!(
+            game.set_position(
+                &[Position::new(0, 0)],
+                &[Position::new(1, 0), Position::new(0, 1)],
+                Player::Black,
+                None,
+                0
+            ),
+            Err(SetPositionError::NoLiberties(Position::new(0, 0)))
+        );
+        assert_eq!(
+            game.set_position(&[Position::new(5, 0)], &[], Player::Black, None, 0),
+            Err(SetPositionError::OffBoard(Position::new(5, 0)))
+        );
+    }
+
+    #[test]
+    fn test_group_and_liberties_at() {
+        let mut game =
+            Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 0, 1000, true);
+        for (col, row) in [(0, 0), (4, 4), (1, 0), (2, 0)] {
+            game.make_move(&Move::place(col, row));
+        }
+
+        let group = game
+            .group_at(&Position::new(0, 0))
+            .expect("(0, 0) holds a stone");
+        let stones: Vec<usize> = group.iter_ones().collect();
+        assert_eq!(stones, vec![0, 1]);
+        assert_eq!(game.liberties_at(&Position::new(1, 0)), Some(2));
+        assert_eq!(game.liberties_at(&Position::new(2, 0)), Some(2));
+        assert_eq!(game.group_at(&Position::new(3, 3)), None);
+        assert_eq!(game.liberties_at(&Position::new(3, 3)), None);
+    }
+
     #[test]
     fn test_suicide_prevention() {
         let mut game =
@@ -871,6 +2962,101 @@ mod tests {
         assert!(!game.is_legal_move(&suicide_move));
     }
 
+    #[test]
+    fn test_suicide_allowed_removes_group() {
+        let mut game =
+            Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 0, 1000, true);
+        game.set_allow_suicide(true);
+
+        // White pair at (0,0),(0,1) surrounded by black at (1,0),(1,1),(0,2)
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 1));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(0, 2));
+
+        let suicide_move = Move::place(0, 1);
+        assert!(game.is_legal_move(&suicide_move));
+        assert!(game.legal_moves().contains(&suicide_move));
+        assert!(game.make_move(&suicide_move));
+        assert!(game.board().get_piece(&Position::new(0, 0)).is_none());
+        assert!(game.board().get_piece(&Position::new(0, 1)).is_none());
+        assert_eq!(game.ko_point(), None);
+
+        assert!(game.unmake_move());
+        assert_eq!(
+            game.board().get_piece(&Position::new(0, 0)),
+            Some(Player::White)
+        );
+        assert!(game.board().get_piece(&Position::new(0, 1)).is_none());
+        assert_eq!(game.turn(), Player::White);
+    }
+
+    #[test]
+    fn test_suicide_disallowed_by_default() {
+        let mut game =
+            Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 0, 1000, true);
+        assert!(!game.allow_suicide());
+
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 1));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(0, 2));
+
+        assert!(!game.is_legal_move(&Move::place(0, 1)));
+    }
+
+    #[test]
+    fn test_illegal_move_reason() {
+        let mut game =
+            Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 0, 1000, true);
+        for (col, row) in [
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (2, 0),
+            (1, 2),
+            (3, 1),
+            (4, 4),
+            (2, 2),
+        ] {
+            assert_eq!(game.illegal_move_reason(&Move::place(col, row)), None);
+            game.make_move(&Move::place(col, row));
+        }
+        assert_eq!(
+            game.illegal_move_reason(&Move::place(1, 0)),
+            Some(IllegalMoveReason::Occupied)
+        );
+        assert_eq!(
+            game.illegal_move_reason(&Move::place(5, 0)),
+            Some(IllegalMoveReason::OffBoard)
+        );
+        // Black takes the ko at (1,1); White cannot retake at once.
+        game.make_move(&Move::place(2, 1));
+        assert_eq!(
+            game.illegal_move_reason(&Move::place(1, 1)),
+            Some(IllegalMoveReason::Ko)
+        );
+        assert_eq!(
+            game.illegal_move_reason(&Move::place(0, 0)),
+            Some(IllegalMoveReason::Suicide)
+        );
+        game.make_move(&Move::resign());
+        assert_eq!(
+            game.illegal_move_reason(&Move::pass()),
+            Some(IllegalMoveReason::GameOver)
+        );
+
+        let game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, DEFAULT_KOMI, 4, 1000, true);
+        assert_eq!(
+            game.illegal_move_reason(&Move::pass()),
+            Some(IllegalMoveReason::PassTooEarly {
+                min_moves_before_pass_possible: 4
+            })
+        );
+    }
+
     #[test]
     fn test_ko_rule() {
         let mut game =
@@ -947,6 +3133,176 @@ mod tests {
         assert_eq!(game.legal_moves().len(), 0);
     }
 
+    #[test]
+    fn test_resign_ends_game() {
+        let mut game =
+            Game::<{ nw_for_board(9, 9) }>::with_options(9, 9, DEFAULT_KOMI, 0, 1000, true);
+        game.make_move(&Move::place(4, 4));
+
+        // Black resigns out of turn; the turn does not change.
+        assert!(game.resign(Player::Black));
+        assert!(game.is_over());
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::White));
+        assert_eq!(game.turn(), Player::White);
+        assert_eq!(game.move_history(), vec![Move::place(4, 4), Move::resign()]);
+        assert!(game.legal_moves().is_empty());
+        assert!(!game.resign(Player::Black));
+
+        assert!(game.unmake_move());
+        assert!(!game.is_over());
+        assert!(game.outcome().is_none());
+        assert_eq!(game.turn(), Player::White);
+        assert!(game.make_move(&Move::place(2, 2)));
+    }
+
+    #[test]
+    fn test_resign_outcome_has_no_margin() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.resign(Player::White);
+
+        let outcome = game.outcome().expect("game is over");
+        assert_eq!(outcome.reason(), EndReason::Resignation);
+        assert_eq!(outcome.margin(), None);
+        assert!(!outcome.is_draw());
+        assert_eq!(outcome.to_string(), "Black wins by resignation");
+    }
+
+    #[test]
+    fn test_resign_move_resigns_current_player() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        assert!(game.is_legal_move(&Move::resign()));
+        assert!(!game.legal_moves().contains(&Move::resign()));
+        assert!(game.make_move(&Move::resign()));
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::White));
+        assert_eq!(game.board_history().count(), 2);
+    }
+
+    #[test]
+    fn test_log_disabled_by_default() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(0, 0));
+        assert!(game.log().is_none());
+    }
+
+    #[test]
+    fn test_log_survives_unmake() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.enable_log();
+
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 0));
+        game.unmake_move();
+        game.set_piece(&Position::new(4, 4), Some(Player::White));
+        game.record_clock(Player::White, 30_000);
+        // Illegal moves are not logged
+        game.make_move(&Move::place(0, 0));
+
+        assert_eq!(game.move_history().len(), 1);
+        let log = game.log().expect("log enabled");
+        assert_eq!(
+            log.events(),
+            &[
+                GameEvent::Move {
+                    player: Player::Black,
+                    move_: Move::place(0, 0)
+                },
+                GameEvent::Move {
+                    player: Player::White,
+                    move_: Move::place(1, 0)
+                },
+                GameEvent::Unmake {
+                    player: Player::White,
+                    move_: Move::place(1, 0)
+                },
+                GameEvent::SetPiece {
+                    position: Position::new(4, 4),
+                    player: Some(Player::White)
+                },
+                GameEvent::Clock {
+                    player: Player::White,
+                    remaining_ms: 30_000
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_takeback_accepted() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.enable_log();
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(2, 0));
+
+        assert!(!game.request_takeback(Player::Black, 4));
+        assert!(game.request_takeback(Player::Black, 2));
+        assert!(!game.request_takeback(Player::White, 1));
+        assert_eq!(
+            game.pending_takeback(),
+            Some(TakebackRequest {
+                player: Player::Black,
+                plies: 2
+            })
+        );
+
+        assert!(game.accept_takeback());
+        assert_eq!(game.move_count(), 1);
+        assert_eq!(game.turn(), Player::White);
+        assert!(game.pending_takeback().is_none());
+        assert!(!game.accept_takeback());
+
+        let events = game.log().expect("log enabled").events();
+        assert!(events.contains(&GameEvent::TakebackRequested {
+            player: Player::Black,
+            plies: 2
+        }));
+        assert!(events.contains(&GameEvent::TakebackAccepted {
+            player: Player::Black,
+            plies: 2
+        }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, GameEvent::Unmake { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_takeback_declined_or_superseded() {
+        let mut game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        game.make_move(&Move::place(0, 0));
+
+        assert!(game.request_takeback(Player::Black, 1));
+        assert!(game.decline_takeback());
+        assert_eq!(game.move_count(), 1);
+        assert!(!game.decline_takeback());
+
+        // Playing on cancels a pending request
+        assert!(game.request_takeback(Player::Black, 1));
+        game.make_move(&Move::place(1, 0));
+        assert!(game.pending_takeback().is_none());
+        assert!(!game.accept_takeback());
+        assert_eq!(game.move_count(), 2);
+    }
+
+    #[test]
+    fn test_board_history_matches_unmake() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.make_move(&Move::place(1, 0));
+        game.make_move(&Move::place(0, 0));
+        game.make_move(&Move::place(0, 1)); // captures (0, 0)
+        game.make_move(&Move::place(3, 3));
+
+        let boards: Vec<_> = game.board_history().collect();
+        assert_eq!(boards.len(), 5);
+        for expected in &boards {
+            assert_eq!(game.board(), expected);
+            game.unmake_move();
+        }
+    }
+
     #[test]
     fn test_superko_unmake_restores() {
         let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
@@ -0,0 +1,310 @@
+use crate::game::Game;
+use crate::player::Player;
+use crate::r#move::Move;
+#[cfg(feature = "sgf")]
+use crate::replay::{ReplayError, ReplayIssue};
+#[cfg(feature = "sgf")]
+use crate::sgf::{parse_sgf_tree, SgfError};
+
+#[derive(Clone, Debug)]
+struct TreeNode {
+    parent: Option<usize>,
+    /// The move leading here from the parent; `None` for the root.
+    move_: Option<(Player, Move)>,
+    /// Moves from the root.
+    depth: usize,
+    /// Variations from this node; the first one is the main line.
+    children: Vec<usize>,
+}
+
+/// A game with branching variations, as found in SGF records.
+///
+/// The tree starts from a root position and holds a cursor on one node.
+/// Moving the cursor plays or takes back moves on the underlying `Game`, so
+/// `game()` is always the full state at the current node.
+#[derive(Clone, Debug)]
+pub struct GameTree<const NW: usize> {
+    game: Game<NW>,
+    nodes: Vec<TreeNode>,
+    current: usize,
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> GameTree<NW> {
+    /// A tree whose root is the current position of `game`.
+    pub fn new(game: Game<NW>) -> Self {
+        GameTree {
+            game,
+            nodes: vec![TreeNode {
+                parent: None,
+                move_: None,
+                depth: 0,
+                children: Vec::new(),
+            }],
+            current: 0,
+        }
+    }
+
+    /// Read a game with all its variations from SGF, leaving the cursor at
+    /// the root. `NW` must be `nw_for_board(width, height)` for the record's
+    /// board size.
+    #[cfg(feature = "sgf")]
+    pub fn from_sgf(text: &str) -> Result<Self, SgfError> {
+        let (record, sgf_nodes) = parse_sgf_tree(text)?;
+        let mut tree = GameTree::new(record.start_game()?);
+        // The tree node reached after each SGF node's moves.
+        let mut reached = Vec::with_capacity(sgf_nodes.len());
+        for sgf_node in &sgf_nodes {
+            tree.go_to(sgf_node.parent.map_or(0, |p| reached[p]));
+            for &(player, move_) in &sgf_node.moves {
+                if !tree.play_as(player, move_) {
+                    let issue = if player != tree.game.turn() && !move_.is_resign() {
+                        ReplayIssue::OutOfTurn
+                    } else {
+                        ReplayIssue::Illegal
+                    };
+                    return Err(SgfError::Replay(ReplayError {
+                        index: tree.depth(),
+                        player,
+                        move_,
+                        issue,
+                    }));
+                }
+            }
+            reached.push(tree.current);
+        }
+        tree.to_root();
+        Ok(tree)
+    }
+
+    /// The game at the current node.
+    pub fn game(&self) -> &Game<NW> {
+        &self.game
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Moves from the root to the current node.
+    pub fn depth(&self) -> usize {
+        self.nodes[self.current].depth
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.current == 0
+    }
+
+    /// The move that led to the current node; `None` at the root.
+    pub fn current_move(&self) -> Option<Move> {
+        self.nodes[self.current].move_.map(|(_, m)| m)
+    }
+
+    /// The moves of the variations from the current node, main line first.
+    pub fn variations(&self) -> Vec<Move> {
+        self.nodes[self.current]
+            .children
+            .iter()
+            .filter_map(|&child| self.nodes[child].move_.map(|(_, m)| m))
+            .collect()
+    }
+
+    /// The moves of the main line from the root: the first variation at
+    /// every node.
+    pub fn main_line(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let mut node = 0;
+        while let Some(&child) = self.nodes[node].children.first() {
+            moves.extend(self.nodes[child].move_.map(|(_, m)| m));
+            node = child;
+        }
+        moves
+    }
+
+    /// Play `move_` from the current node and move the cursor there. An
+    /// existing variation with this move is entered; otherwise a new one is
+    /// added after the others. Returns false if the move is illegal.
+    pub fn play(&mut self, move_: &Move) -> bool {
+        self.play_as(self.game.turn(), *move_)
+    }
+
+    /// Follow the main line one move. Returns false at a leaf.
+    #[allow(clippy::should_implement_trait)] // a cursor step, not an iterator
+    pub fn next(&mut self) -> bool {
+        self.enter_variation(0)
+    }
+
+    /// Step back to the parent node. Returns false at the root.
+    pub fn prev(&mut self) -> bool {
+        match self.nodes[self.current].parent {
+            Some(parent) => {
+                self.game.unmake_move();
+                self.current = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enter variation `index` of the current node (0 is the main line).
+    /// Returns false if there is no such variation.
+    pub fn enter_variation(&mut self, index: usize) -> bool {
+        match self.nodes[self.current].children.get(index) {
+            Some(&child) => {
+                self.enter(child);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the cursor back to the root.
+    pub fn to_root(&mut self) {
+        while self.prev() {}
+    }
+
+    fn enter(&mut self, child: usize) {
+        let (player, move_) = self.nodes[child].move_.expect("only the root has no move");
+        let played = if move_.is_resign() {
+            self.game.resign(player)
+        } else {
+            self.game.make_move(&move_)
+        };
+        debug_assert!(played, "a tree move was legal when it was added");
+        self.current = child;
+    }
+
+    /// Move the cursor to `target`, taking back moves up to the closest
+    /// common ancestor and playing forward from there.
+    #[cfg(feature = "sgf")]
+    fn go_to(&mut self, target: usize) {
+        let mut path = Vec::new();
+        let mut node = target;
+        while self.nodes[node].depth > self.depth() {
+            path.push(node);
+            node = self.nodes[node].parent.expect("a deeper node has a parent");
+        }
+        while self.depth() > self.nodes[node].depth {
+            self.prev();
+        }
+        while self.current != node {
+            self.prev();
+            path.push(node);
+            node = self.nodes[node]
+                .parent
+                .expect("a non-root node has a parent");
+        }
+        for &node in path.iter().rev() {
+            self.enter(node);
+        }
+    }
+
+    /// Play `move_` for `player`, entering or adding the matching variation.
+    /// Either player may resign; other moves must be played in turn.
+    fn play_as(&mut self, player: Player, move_: Move) -> bool {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].move_ == Some((player, move_)));
+        if let Some(child) = existing {
+            self.enter(child);
+            return true;
+        }
+
+        let played = if move_.is_resign() {
+            self.game.resign(player)
+        } else {
+            player == self.game.turn() && self.game.make_move(&move_)
+        };
+        if !played {
+            return false;
+        }
+
+        let child = self.nodes.len();
+        self.nodes.push(TreeNode {
+            parent: Some(self.current),
+            move_: Some((player, move_)),
+            depth: self.depth() + 1,
+            children: Vec::new(),
+        });
+        self.nodes[self.current].children.push(child);
+        self.current = child;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::position::Position;
+
+    type Game9 = Game<{ nw_for_board(9, 9) }>;
+
+    #[test]
+    fn test_play_and_navigate_variations() {
+        let mut tree = GameTree::new(Game9::new(9, 9));
+        assert!(tree.play(&Move::place(4, 4)));
+        assert!(tree.play(&Move::place(2, 2)));
+        assert!(tree.prev());
+        assert!(tree.play(&Move::place(6, 6)));
+        assert_eq!(tree.depth(), 2);
+        assert_eq!(tree.num_nodes(), 4);
+
+        tree.to_root();
+        assert!(tree.is_root());
+        assert!(!tree.prev());
+        assert!(tree.next());
+        assert_eq!(
+            tree.variations(),
+            vec![Move::place(2, 2), Move::place(6, 6)]
+        );
+        assert!(tree.enter_variation(1));
+        assert_eq!(tree.current_move(), Some(Move::place(6, 6)));
+        assert_eq!(
+            tree.game().get_piece(&Position::new(6, 6)),
+            Some(Player::White as i8)
+        );
+        assert_eq!(tree.game().get_piece(&Position::new(2, 2)), None);
+        assert!(!tree.enter_variation(0));
+
+        // Replaying an existing move enters its variation.
+        assert!(tree.prev());
+        assert!(tree.play(&Move::place(2, 2)));
+        assert_eq!(tree.num_nodes(), 4);
+        assert_eq!(tree.main_line(), vec![Move::place(4, 4), Move::place(2, 2)]);
+
+        assert!(!tree.play(&Move::place(4, 4)));
+    }
+
+    #[cfg(feature = "sgf")]
+    #[test]
+    fn test_from_sgf_reads_variations() {
+        let sgf = "(;SZ[9]KM[6.5];B[ee](;W[cc];B[gc])(;W[gg]C[alt](;B[cg])(;B[gc])))";
+        let mut tree = GameTree::<{ nw_for_board(9, 9) }>::from_sgf(sgf).expect("reads");
+
+        assert!(tree.is_root());
+        assert_eq!(tree.game().komi(), 6.5);
+        assert_eq!(tree.num_nodes(), 7);
+        assert_eq!(
+            tree.main_line(),
+            vec![Move::place(4, 4), Move::place(2, 6), Move::place(6, 6)]
+        );
+
+        assert!(tree.next());
+        assert!(tree.enter_variation(1));
+        assert_eq!(tree.current_move(), Some(Move::place(6, 2)));
+        assert_eq!(
+            tree.variations(),
+            vec![Move::place(2, 2), Move::place(6, 6)]
+        );
+        assert!(tree.enter_variation(1));
+        assert_eq!(tree.game().move_count(), 3);
+
+        assert!(matches!(
+            GameTree::<{ nw_for_board(9, 9) }>::from_sgf("(;SZ[9];B[ee](;W[ee])(;W[cc]))"),
+            Err(SgfError::Replay(_))
+        ));
+    }
+}
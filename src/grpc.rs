@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use tonic::{Request, Response, Status};
+
+use crate::dispatch::{make_game_inner_with_options, GameInner};
+use crate::encode;
+use crate::game::{
+    default_max_moves, default_min_moves_before_pass_possible, validate_move_limits, DEFAULT_KOMI,
+};
+use crate::policy::{playout_counts, CycleDetection};
+use crate::position_string::parse_position_string;
+
+/// Messages and service stubs generated from `proto/spooky_go.proto`.
+pub mod proto {
+    tonic::include_proto!("spooky_go");
+}
+
+use proto::self_play_server::{SelfPlay, SelfPlayServer};
+use proto::{
+    ApplyActionRequest, BatchPlayoutReply, BatchPlayoutRequest, CreateGameRequest, DropGameReply,
+    GameRef, GameState, Planes, PlayoutCounts,
+};
+
+/// Most playouts a single `BatchPlayout` call may ask for per game.
+pub const MAX_PLAYOUTS_PER_GAME: u32 = 100_000;
+
+/// The `SelfPlay` service: games held in memory by id, for workers that
+/// drive the engine remotely.
+#[derive(Default)]
+pub struct SelfPlayService {
+    games: Mutex<HashMap<u64, GameInner>>,
+    next_id: AtomicU64,
+}
+
+#[hotpath::measure]
+fn game_state(game_id: u64, inner: &GameInner) -> GameState {
+    dispatch_game!(inner, g => GameState {
+        game_id,
+        width: g.width() as u32,
+        height: g.height() as u32,
+        turn: g.turn() as i32,
+        move_count: g.move_count() as u32,
+        is_over: g.is_over(),
+        winner: g.outcome().and_then(|o| o.winner()).map_or(0, |p| p as i32),
+        legal_actions: g
+            .legal_moves()
+            .iter()
+            .map(|m| encode::encode_move(m, g.width(), g.height()) as u32)
+            .collect(),
+        total_actions: encode::total_actions_for(g) as u32,
+    })
+}
+
+#[hotpath::measure]
+fn to_u16(value: u32, name: &str) -> Result<u16, Status> {
+    u16::try_from(value)
+        .map_err(|_| Status::invalid_argument(format!("{} must be at most {}", name, u16::MAX)))
+}
+
+#[hotpath::measure]
+fn new_game(request: &CreateGameRequest) -> Result<GameInner, Status> {
+    let record = match &request.position {
+        Some(text) => {
+            Some(parse_position_string(text).map_err(|e| Status::invalid_argument(e.to_string()))?)
+        }
+        None => None,
+    };
+    let (width, height) = match &record {
+        Some(record) => (record.width, record.height),
+        None => {
+            let size = |v: u32| u8::try_from(v).ok().filter(|v| (2..=32).contains(v));
+            match (size(request.width), size(request.height)) {
+                (Some(w), Some(h)) => (w, h),
+                _ => {
+                    return Err(Status::invalid_argument(
+                        "Board width and height must be between 2 and 32",
+                    ))
+                }
+            }
+        }
+    };
+
+    let min_moves = match request.min_moves_before_pass_possible {
+        Some(v) => to_u16(v, "min_moves_before_pass_possible")?,
+        None => default_min_moves_before_pass_possible(width, height),
+    };
+    let max_moves = match request.max_moves {
+        Some(v) => to_u16(v, "max_moves")?,
+        None => default_max_moves(width, height),
+    };
+    validate_move_limits(min_moves, max_moves)
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+    let mut inner = make_game_inner_with_options(
+        width,
+        height,
+        request.komi.unwrap_or(DEFAULT_KOMI),
+        min_moves,
+        max_moves,
+        request.superko.unwrap_or(true),
+    );
+    if let Some(record) = record {
+        dispatch_game_mut!(&mut inner, g => record.set_up(g));
+    }
+    Ok(inner)
+}
+
+#[hotpath::measure_all]
+impl SelfPlayService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of games currently held.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, GameInner>> {
+        // A panic while holding the lock leaves no game half-updated, as
+        // every change is a single call on one game.
+        self.games.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn with_game<T>(
+        &self,
+        game_id: u64,
+        f: impl FnOnce(&mut GameInner) -> Result<T, Status>,
+    ) -> Result<T, Status> {
+        let mut games = self.lock();
+        let game = games
+            .get_mut(&game_id)
+            .ok_or_else(|| Status::not_found(format!("no game {}", game_id)))?;
+        f(game)
+    }
+}
+
+#[tonic::async_trait]
+impl SelfPlay for SelfPlayService {
+    async fn create_game(
+        &self,
+        request: Request<CreateGameRequest>,
+    ) -> Result<Response<GameState>, Status> {
+        let inner = new_game(request.get_ref())?;
+        let game_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let state = game_state(game_id, &inner);
+        self.lock().insert(game_id, inner);
+        Ok(Response::new(state))
+    }
+
+    async fn apply_action(
+        &self,
+        request: Request<ApplyActionRequest>,
+    ) -> Result<Response<GameState>, Status> {
+        let ApplyActionRequest { game_id, action } = request.into_inner();
+        self.with_game(game_id, |inner| {
+            let played = dispatch_game_mut!(inner, g => {
+                match encode::decode_move_for(action as usize, g) {
+                    Some(move_) => g.make_move(&move_),
+                    None => false,
+                }
+            });
+            if !played {
+                return Err(Status::invalid_argument(format!(
+                    "action {} is not legal in game {}",
+                    action, game_id
+                )));
+            }
+            Ok(Response::new(game_state(game_id, inner)))
+        })
+    }
+
+    async fn get_state(&self, request: Request<GameRef>) -> Result<Response<GameState>, Status> {
+        let game_id = request.get_ref().game_id;
+        self.with_game(game_id, |inner| {
+            Ok(Response::new(game_state(game_id, inner)))
+        })
+    }
+
+    async fn encode_planes(&self, request: Request<GameRef>) -> Result<Response<Planes>, Status> {
+        self.with_game(request.get_ref().game_id, |inner| {
+            let (values, num_planes, height, width) =
+                dispatch_game!(inner, g => encode::encode_game_planes(g));
+            Ok(Response::new(Planes {
+                num_planes: num_planes as u32,
+                height: height as u32,
+                width: width as u32,
+                values,
+            }))
+        })
+    }
+
+    async fn batch_playout(
+        &self,
+        request: Request<BatchPlayoutRequest>,
+    ) -> Result<Response<BatchPlayoutReply>, Status> {
+        let BatchPlayoutRequest {
+            game_ids,
+            playouts,
+            seed,
+        } = request.into_inner();
+        if playouts > MAX_PLAYOUTS_PER_GAME {
+            return Err(Status::invalid_argument(format!(
+                "at most {} playouts per game",
+                MAX_PLAYOUTS_PER_GAME
+            )));
+        }
+        let games = {
+            let games = self.lock();
+            game_ids
+                .iter()
+                .map(|id| {
+                    games
+                        .get(id)
+                        .cloned()
+                        .map(|g| (*id, g))
+                        .ok_or_else(|| Status::not_found(format!("no game {}", id)))
+                })
+                .collect::<Result<Vec<_>, Status>>()?
+        };
+
+        // Playouts are CPU-bound; keep them off the async workers.
+        let results = tokio::task::spawn_blocking(move || {
+            games
+                .iter()
+                .map(|(game_id, inner)| {
+                    let mut rng = StdRng::seed_from_u64(seed ^ game_id);
+                    let counts = dispatch_game!(inner, g => {
+                        playout_counts(g, playouts, &mut rng, &CycleDetection::default())
+                    });
+                    PlayoutCounts {
+                        game_id: *game_id,
+                        black_wins: counts.black_wins,
+                        white_wins: counts.white_wins,
+                        draws: counts.draws,
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(BatchPlayoutReply { results }))
+    }
+
+    async fn drop_game(
+        &self,
+        request: Request<GameRef>,
+    ) -> Result<Response<DropGameReply>, Status> {
+        let existed = self.lock().remove(&request.get_ref().game_id).is_some();
+        Ok(Response::new(DropGameReply { existed }))
+    }
+}
+
+/// Serve `SelfPlayService` on `addr` until the process ends.
+#[hotpath::measure]
+pub async fn serve(addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(SelfPlayServer::new(SelfPlayService::new()))
+        .serve(addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_self_play_service() {
+        let service = SelfPlayService::new();
+        let state = service
+            .create_game(Request::new(CreateGameRequest {
+                width: 5,
+                height: 5,
+                min_moves_before_pass_possible: Some(0),
+                ..CreateGameRequest::default()
+            }))
+            .await
+            .expect("creates")
+            .into_inner();
+        assert_eq!((state.width, state.turn, state.total_actions), (5, 1, 26));
+        assert_eq!(state.legal_actions.len(), 26);
+        let id = state.game_id;
+
+        let state = service
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: id,
+                action: 12,
+            }))
+            .await
+            .expect("plays")
+            .into_inner();
+        assert_eq!((state.turn, state.move_count), (-1, 1));
+        let illegal = service
+            .apply_action(Request::new(ApplyActionRequest {
+                game_id: id,
+                action: 12,
+            }))
+            .await
+            .expect_err("occupied");
+        assert_eq!(illegal.code(), tonic::Code::InvalidArgument);
+
+        let planes = service
+            .encode_planes(Request::new(GameRef { game_id: id }))
+            .await
+            .expect("encodes")
+            .into_inner();
+        assert_eq!(planes.values.len(), planes.num_planes as usize * 25);
+
+        let reply = service
+            .batch_playout(Request::new(BatchPlayoutRequest {
+                game_ids: vec![id],
+                playouts: 8,
+                seed: 1,
+            }))
+            .await
+            .expect("plays out")
+            .into_inner();
+        let counts = &reply.results[0];
+        assert_eq!(counts.black_wins + counts.white_wins + counts.draws, 8);
+
+        let bad_size = service
+            .create_game(Request::new(CreateGameRequest {
+                width: 40,
+                height: 9,
+                ..CreateGameRequest::default()
+            }))
+            .await
+            .expect_err("too wide");
+        assert_eq!(bad_size.code(), tonic::Code::InvalidArgument);
+
+        let dropped = service
+            .drop_game(Request::new(GameRef { game_id: id }))
+            .await
+            .expect("drops")
+            .into_inner();
+        assert!(dropped.existed);
+        assert!(service.is_empty());
+        let missing = service
+            .get_state(Request::new(GameRef { game_id: id }))
+            .await
+            .expect_err("dropped");
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+    }
+}
@@ -1,18 +1,22 @@
-use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Child, Command, Stdio};
 
 use crate::player::Player;
+use crate::position::Position;
 use crate::r#move::Move;
 
-use super::error::{GenmoveResult, GtpError};
+use super::error::{FinalStatus, GenmoveResult, GtpError};
 use super::protocol::{format_command, parse_response};
-use super::vertex::{move_to_gtp, player_to_gtp};
+use super::vertex::{move_to_gtp, player_to_gtp, vertex_to_position};
 
-/// A raw GTP client that communicates with an engine subprocess.
+/// A raw GTP client that communicates with an engine subprocess, or with an
+/// engine listening on a TCP port (e.g. `gnugo --mode gtp --gtp-listen 5000`).
 pub struct GtpClient {
-    child: Child,
-    stdin: BufWriter<ChildStdin>,
-    stdout: BufReader<ChildStdout>,
+    /// The engine process, when this client spawned it.
+    child: Option<Child>,
+    stdin: BufWriter<Box<dyn Write + Send + Sync>>,
+    stdout: BufReader<Box<dyn Read + Send + Sync>>,
     next_id: u32,
 }
 
@@ -35,12 +39,31 @@ impl GtpClient {
             .take()
             .ok_or_else(|| GtpError::Protocol("failed to open stdout".to_string()))?;
 
-        Ok(GtpClient {
-            child,
-            stdin: BufWriter::new(stdin),
-            stdout: BufReader::new(stdout),
+        let mut client = GtpClient::from_streams(stdout, stdin);
+        client.child = Some(child);
+        Ok(client)
+    }
+
+    /// Connect to an engine serving GTP on a TCP socket.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, GtpError> {
+        let stream = TcpStream::connect(addr)?;
+        let reader = stream.try_clone()?;
+        Ok(GtpClient::from_streams(reader, stream))
+    }
+
+    /// Talk GTP over any pair of streams: responses are read from `reader`
+    /// and commands written to `writer`.
+    pub fn from_streams<R, W>(reader: R, writer: W) -> Self
+    where
+        R: Read + Send + Sync + 'static,
+        W: Write + Send + Sync + 'static,
+    {
+        GtpClient {
+            child: None,
+            stdin: BufWriter::new(Box::new(writer)),
+            stdout: BufReader::new(Box::new(reader)),
             next_id: 1,
-        })
+        }
     }
 
     /// Send a raw GTP command and return the response content.
@@ -134,15 +157,32 @@ impl GtpClient {
     pub fn genmove(&mut self, player: Player, board_height: u8) -> Result<GenmoveResult, GtpError> {
         let color = player_to_gtp(player);
         let resp = self.send_command("genmove", &[color])?;
-        let lower = resp.trim().to_lowercase();
-        if lower == "resign" {
-            Ok(GenmoveResult::Resign)
-        } else if lower == "pass" {
-            Ok(GenmoveResult::Move(Move::pass()))
-        } else {
-            let pos = super::vertex::vertex_to_position(&resp, board_height)?;
-            Ok(GenmoveResult::Move(Move::place(pos.col, pos.row)))
-        }
+        parse_genmove(&resp, board_height)
+    }
+
+    /// Like `genmove`, but for the cleanup phase after a dead-stone
+    /// disagreement: the engine keeps playing until every dead stone has been
+    /// captured instead of passing (`kgs-genmove_cleanup`).
+    pub fn genmove_cleanup(
+        &mut self,
+        player: Player,
+        board_height: u8,
+    ) -> Result<GenmoveResult, GtpError> {
+        let color = player_to_gtp(player);
+        let resp = self.send_command("kgs-genmove_cleanup", &[color])?;
+        parse_genmove(&resp, board_height)
+    }
+
+    /// The stones the engine gives `status` in the current position.
+    pub fn final_status_list(
+        &mut self,
+        status: FinalStatus,
+        board_height: u8,
+    ) -> Result<Vec<Position>, GtpError> {
+        let resp = self.send_command("final_status_list", &[status.as_str()])?;
+        resp.split_whitespace()
+            .map(|vertex| vertex_to_position(vertex, board_height))
+            .collect()
     }
 
     pub fn undo(&mut self) -> Result<(), GtpError> {
@@ -165,9 +205,24 @@ impl GtpClient {
     }
 }
 
+/// The move in a `genmove` response.
+fn parse_genmove(resp: &str, board_height: u8) -> Result<GenmoveResult, GtpError> {
+    let lower = resp.trim().to_lowercase();
+    if lower == "resign" {
+        Ok(GenmoveResult::Resign)
+    } else if lower == "pass" {
+        Ok(GenmoveResult::Move(Move::pass()))
+    } else {
+        let pos = vertex_to_position(resp, board_height)?;
+        Ok(GenmoveResult::Move(Move::place(pos.col, pos.row)))
+    }
+}
+
 impl Drop for GtpClient {
     fn drop(&mut self) {
         let _ = self.send_command("quit", &[]);
-        let _ = self.child.wait();
+        if let Some(child) = &mut self.child {
+            let _ = child.wait();
+        }
     }
 }
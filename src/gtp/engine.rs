@@ -1,9 +1,13 @@
+use std::net::ToSocketAddrs;
+
 use crate::dispatch::{make_game_inner_with_options, GameInner};
 use crate::player::Player;
+use crate::position::Position;
 use crate::r#move::Move;
+use crate::render::BoardRenderer;
 
 use super::client::GtpClient;
-use super::error::{GenmoveResult, GtpError};
+use super::error::{FinalStatus, GenmoveResult, GtpError};
 
 /// A synchronized GTP engine that pairs a `GtpClient` with a local `Game`.
 pub struct GtpEngine {
@@ -19,8 +23,22 @@ impl GtpEngine {
         if !(2..=25).contains(&size) {
             return Err(GtpError::UnsupportedBoardSize(size));
         }
+        Self::with_client(GtpClient::new(program, args)?, size, komi)
+    }
 
-        let mut client = GtpClient::new(program, args)?;
+    /// Like `new`, but with an engine serving GTP on a TCP socket.
+    pub fn connect<A: ToSocketAddrs>(addr: A, size: u8, komi: f32) -> Result<Self, GtpError> {
+        if !(2..=25).contains(&size) {
+            return Err(GtpError::UnsupportedBoardSize(size));
+        }
+        Self::with_client(GtpClient::connect(addr)?, size, komi)
+    }
+
+    /// Set up the engine behind an existing `client` as in `new`.
+    pub fn with_client(mut client: GtpClient, size: u8, komi: f32) -> Result<Self, GtpError> {
+        if !(2..=25).contains(&size) {
+            return Err(GtpError::UnsupportedBoardSize(size));
+        }
         client.boardsize(size)?;
         client.clear_board()?;
         client.komi(komi)?;
@@ -65,7 +83,13 @@ impl GtpEngine {
     /// Ask the engine to generate a move as a specific player.
     pub fn genmove_as(&mut self, player: Player) -> Result<GenmoveResult, GtpError> {
         let result = self.client.genmove(player, self.size)?;
-        match &result {
+        self.apply_generated(player, &result)?;
+        Ok(result)
+    }
+
+    /// Play a move the engine generated for `player` on the local game.
+    fn apply_generated(&mut self, player: Player, result: &GenmoveResult) -> Result<(), GtpError> {
+        match result {
             GenmoveResult::Move(m) => {
                 let success = dispatch_game_mut!(&mut self.game, g => g.make_move(m));
                 if !success {
@@ -75,11 +99,27 @@ impl GtpEngine {
                     )));
                 }
             }
-            GenmoveResult::Resign => {}
+            GenmoveResult::Resign => {
+                dispatch_game_mut!(&mut self.game, g => g.resign(player));
+            }
         }
+        Ok(())
+    }
+
+    /// Like `genmove`, but with `kgs-genmove_cleanup`: the engine captures
+    /// the stones it thinks are dead before passing.
+    pub fn genmove_cleanup(&mut self) -> Result<GenmoveResult, GtpError> {
+        let player = self.turn();
+        let result = self.client.genmove_cleanup(player, self.size)?;
+        self.apply_generated(player, &result)?;
         Ok(result)
     }
 
+    /// The stones the engine gives `status` in the current position.
+    pub fn final_status_list(&mut self, status: FinalStatus) -> Result<Vec<Position>, GtpError> {
+        self.client.final_status_list(status, self.size)
+    }
+
     /// Undo the last move on both the engine and local game.
     pub fn undo(&mut self) -> Result<(), GtpError> {
         self.client.undo()?;
@@ -122,6 +162,12 @@ impl GtpEngine {
         dispatch_game!(&self.game, g => g.score())
     }
 
+    /// Draw the local game's board with `renderer` (see
+    /// `render::renderer_for_theme`).
+    pub fn render(&self, renderer: &dyn BoardRenderer) -> String {
+        dispatch_game!(&self.game, g => g.board().render(renderer))
+    }
+
     /// Get the komi from the local game.
     pub fn komi(&self) -> f32 {
         dispatch_game!(&self.game, g => g.komi())
@@ -11,8 +11,11 @@ pub enum GtpError {
     InvalidVertex(String),
     InvalidColor(String),
     InvalidMove(String),
+    InvalidBoard(String),
     ProcessNotRunning,
     UnsupportedBoardSize(u8),
+    /// A position the engine cannot be given with `play` commands.
+    UnsupportedPosition(String),
 }
 
 /// Result of a `genmove` command — the engine can play a move, pass, or resign.
@@ -22,6 +25,34 @@ pub enum GenmoveResult {
     Resign,
 }
 
+/// Status classes of the `final_status_list` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinalStatus {
+    Alive,
+    Dead,
+    Seki,
+}
+
+impl FinalStatus {
+    /// The command argument for this status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinalStatus::Alive => "alive",
+            FinalStatus::Dead => "dead",
+            FinalStatus::Seki => "seki",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<FinalStatus> {
+        match name.trim().to_lowercase().as_str() {
+            "alive" => Some(FinalStatus::Alive),
+            "dead" => Some(FinalStatus::Dead),
+            "seki" => Some(FinalStatus::Seki),
+            _ => None,
+        }
+    }
+}
+
 impl From<std::io::Error> for GtpError {
     fn from(e: std::io::Error) -> Self {
         GtpError::Io(e)
@@ -37,8 +68,10 @@ impl fmt::Display for GtpError {
             GtpError::InvalidVertex(v) => write!(f, "invalid GTP vertex: {}", v),
             GtpError::InvalidColor(c) => write!(f, "invalid GTP color: {}", c),
             GtpError::InvalidMove(m) => write!(f, "invalid GTP move: {}", m),
+            GtpError::InvalidBoard(msg) => write!(f, "invalid showboard output: {}", msg),
             GtpError::ProcessNotRunning => write!(f, "GTP engine process is not running"),
             GtpError::UnsupportedBoardSize(s) => write!(f, "unsupported board size: {}", s),
+            GtpError::UnsupportedPosition(msg) => write!(f, "unsupported position: {}", msg),
         }
     }
 }
@@ -1,16 +1,26 @@
+#[cfg(feature = "gtp")]
 mod client;
+#[cfg(feature = "gtp")]
 mod engine;
 mod error;
+#[cfg(feature = "gtp")]
 mod protocol;
+#[cfg(feature = "gtp")]
+mod showboard;
 mod vertex;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "gtp"))]
 mod test;
 
+#[cfg(feature = "gtp")]
 pub use client::GtpClient;
+#[cfg(feature = "gtp")]
 pub use engine::GtpEngine;
-pub use error::{GenmoveResult, GtpError};
+pub use error::{FinalStatus, GenmoveResult, GtpError};
+#[cfg(feature = "gtp")]
 pub use protocol::{format_command, parse_response, GtpResponse};
+#[cfg(feature = "gtp")]
+pub use showboard::{parse_showboard, Showboard};
 pub use vertex::{
     col_to_letter, gtp_to_move, gtp_to_player, letter_to_col, move_to_gtp, player_to_gtp,
     position_to_vertex, vertex_to_position,
@@ -0,0 +1,162 @@
+use crate::board::Board;
+use crate::player::Player;
+use crate::position::Position;
+
+use super::error::GtpError;
+
+/// A position read from an engine's `showboard` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Showboard {
+    pub width: u8,
+    pub height: u8,
+    pub stones: Vec<(Position, Player)>,
+}
+
+#[hotpath::measure_all]
+impl Showboard {
+    /// Build a board holding these stones. `NW` must be `nw_for_board(width, height)`.
+    pub fn to_board<const NW: usize>(&self) -> Board<NW> {
+        let mut board = Board::new(self.width, self.height);
+        for (pos, player) in &self.stones {
+            board.set_piece(pos, Some(*player));
+        }
+        board
+    }
+}
+
+/// What a character inside a board row stands for.
+enum Cell {
+    Empty,
+    Stone(Player),
+    /// Borders and last-move markers, e.g. `|` or `(X)`.
+    Decoration,
+    /// Anything else ends the row (a trailing row number, captured-stone notes).
+    End,
+}
+
+#[hotpath::measure]
+fn classify(ch: char) -> Cell {
+    match ch {
+        '.' | '+' | ',' => Cell::Empty,
+        'X' | 'x' | '#' => Cell::Stone(Player::Black),
+        'O' | 'o' | '@' => Cell::Stone(Player::White),
+        ' ' | '|' | '(' | ')' | '[' | ']' | '<' | '>' => Cell::Decoration,
+        _ => Cell::End,
+    }
+}
+
+/// Strip a GTP response prefix (`=` or `=<id>`) from a line.
+#[hotpath::measure]
+fn strip_response_prefix(line: &str) -> &str {
+    match line.trim_start().strip_prefix('=') {
+        Some(rest) => rest.trim_start_matches(|c: char| c.is_ascii_digit()),
+        None => line,
+    }
+}
+
+/// A column header such as `A B C D E F G H J`, returning the number of columns.
+#[hotpath::measure]
+fn header_width(line: &str) -> Option<usize> {
+    let mut count = 0;
+    for token in line.split_whitespace() {
+        let mut chars = token.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_uppercase() && c != 'I' => count += 1,
+            _ => return None,
+        }
+    }
+    (count >= 2).then_some(count)
+}
+
+/// A board row such as ` 9 . . X O + . . . . 9`, returning its label and cells.
+/// Stops after `width` cells when the width is already known.
+#[hotpath::measure]
+fn parse_row(line: &str, width: Option<usize>) -> Option<(u8, Vec<Option<Player>>)> {
+    let line = line.trim_start();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let label: u8 = line[..digits].parse().ok()?;
+
+    let mut cells = Vec::new();
+    for ch in line[digits..].chars() {
+        if width.is_some_and(|w| cells.len() == w) {
+            break;
+        }
+        match classify(ch) {
+            Cell::Empty => cells.push(None),
+            Cell::Stone(player) => cells.push(Some(player)),
+            Cell::Decoration => {}
+            Cell::End => break,
+        }
+    }
+
+    (!cells.is_empty()).then_some((label, cells))
+}
+
+/// Parse a `showboard` dump as printed by GNU Go, Leela Zero, KataGo or Pachi.
+///
+/// Rows are recognized by their leading row number and columns by the letter
+/// header (when present). `X`/`#` are black, `O`/`@` are white, and `.`, `+`
+/// (hoshi) and `,` are empty; borders, last-move markers and any text after the
+/// trailing row number are ignored.
+#[hotpath::measure]
+pub fn parse_showboard(text: &str) -> Result<Showboard, GtpError> {
+    let mut width: Option<usize> = None;
+    let mut rows: Vec<(u8, Vec<Option<Player>>)> = Vec::new();
+
+    for line in text.lines() {
+        let line = strip_response_prefix(line);
+        if width.is_none() && rows.is_empty() {
+            if let Some(w) = header_width(line) {
+                width = Some(w);
+                continue;
+            }
+        }
+        if let Some(row) = parse_row(line, width) {
+            width.get_or_insert(row.1.len());
+            rows.push(row);
+        }
+    }
+
+    let width = width.ok_or_else(|| GtpError::InvalidBoard("no board rows found".to_string()))?;
+    let height = rows.len();
+    if !(2..=25).contains(&width) {
+        return Err(GtpError::UnsupportedBoardSize(width as u8));
+    }
+    if !(2..=25).contains(&height) {
+        return Err(GtpError::UnsupportedBoardSize(height as u8));
+    }
+
+    let mut seen = vec![false; height];
+    let mut stones = Vec::new();
+    for (label, cells) in &rows {
+        let label = *label as usize;
+        if label == 0 || label > height || seen[label - 1] {
+            return Err(GtpError::InvalidBoard(format!("unexpected row {}", label)));
+        }
+        seen[label - 1] = true;
+
+        if cells.len() != width {
+            return Err(GtpError::InvalidBoard(format!(
+                "row {} has {} points, expected {}",
+                label,
+                cells.len(),
+                width
+            )));
+        }
+
+        for (col, cell) in cells.iter().enumerate() {
+            if let Some(player) = cell {
+                stones.push((Position::new(col as u8, (label - 1) as u8), *player));
+            }
+        }
+    }
+
+    Ok(Showboard {
+        width: width as u8,
+        height: height as u8,
+        stones,
+    })
+}
@@ -1,6 +1,8 @@
 use super::error::GtpError;
-use crate::gtp::{GenmoveResult, GtpEngine};
+use crate::bitboard::nw_for_board;
+use crate::gtp::{parse_showboard, FinalStatus, GenmoveResult, GtpEngine};
 use crate::player::Player;
+use crate::position::Position;
 use crate::r#move::Move;
 
 fn gnugo_available() -> bool {
@@ -101,7 +103,7 @@ fn test_gtp_engine_multiple_moves() {
         GtpEngine::new("gnugo", &["--mode", "gtp"], 9, 7.5).expect("failed to start gnugo");
 
     // Play several moves
-    let moves = vec![
+    let moves = [
         Move::place(2, 2),
         Move::place(6, 6),
         Move::place(2, 6),
@@ -263,6 +265,91 @@ fn test_gtp_engine_clear_board_allows_replay() {
     assert_eq!(engine.turn(), Player::White);
 }
 
+/// Serve one connection on a local port, answering `genmove` and
+/// `kgs-genmove_cleanup` with C3, `final_status_list` with E5 and G7, and
+/// everything else with an empty success. Returns the port and a handle
+/// yielding the commands received.
+fn fake_tcp_engine() -> (u16, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let port = listener.local_addr().expect("local address").port();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().expect("accept");
+        let mut writer = stream.try_clone().expect("clone stream");
+        let mut commands = Vec::new();
+        for line in BufReader::new(stream).lines() {
+            let line = line.expect("read command");
+            let (id, command) = line.split_once(' ').expect("numbered command");
+            let reply = if command.contains("genmove") {
+                "C3"
+            } else if command.starts_with("final_status_list") {
+                "E5 G7"
+            } else {
+                ""
+            };
+            write!(writer, "={} {}\n\n", id, reply).expect("write reply");
+            commands.push(command.to_string());
+            if command == "quit" {
+                break;
+            }
+        }
+        commands
+    });
+    (port, handle)
+}
+
+#[test]
+fn test_gtp_engine_over_tcp() {
+    let (port, server) = fake_tcp_engine();
+    let mut engine = GtpEngine::connect(("127.0.0.1", port), 9, 6.5).expect("connects");
+    engine.play(Move::place(4, 4)).expect("play failed");
+    assert_eq!(
+        engine.genmove().expect("genmove failed"),
+        GenmoveResult::Move(Move::place(2, 2))
+    );
+    drop(engine);
+
+    let commands = server.join().expect("server thread");
+    assert_eq!(
+        commands,
+        [
+            "boardsize 9",
+            "clear_board",
+            "komi 6.5",
+            "play black E5",
+            "genmove white",
+            "quit"
+        ]
+    );
+}
+
+#[test]
+fn test_final_status_and_cleanup_over_tcp() {
+    let (port, server) = fake_tcp_engine();
+    let mut engine = GtpEngine::connect(("127.0.0.1", port), 9, 6.5).expect("connects");
+    assert_eq!(
+        engine.final_status_list(FinalStatus::Dead).expect("status"),
+        vec![Position::new(4, 4), Position::new(6, 6)]
+    );
+    assert_eq!(
+        engine.genmove_cleanup().expect("cleanup move"),
+        GenmoveResult::Move(Move::place(2, 2))
+    );
+    assert_eq!(engine.turn(), Player::White);
+    drop(engine);
+
+    let commands = server.join().expect("server thread");
+    assert_eq!(
+        &commands[3..],
+        [
+            "final_status_list dead",
+            "kgs-genmove_cleanup black",
+            "quit"
+        ]
+    );
+}
+
 #[test]
 fn test_gtp_client_protocol_version() {
     if !gnugo_available() {
@@ -329,6 +416,76 @@ fn test_gtp_client_showboard() {
     assert!(!board.is_empty());
 }
 
+#[test]
+fn test_gtp_client_showboard_parses() {
+    if !gnugo_available() {
+        eprintln!("gnugo not found, skipping");
+        return;
+    }
+
+    let mut engine =
+        GtpEngine::new("gnugo", &["--mode", "gtp"], 9, 7.5).expect("failed to start gnugo");
+    engine.play(Move::place(2, 3)).expect("play failed");
+    let text = engine.client().showboard().expect("showboard failed");
+    let parsed = parse_showboard(&text).expect("parse failed");
+    assert_eq!((parsed.width, parsed.height), (9, 9));
+    assert_eq!(parsed.stones, vec![(Position::new(2, 3), Player::Black)]);
+}
+
+#[test]
+fn test_parse_showboard_gnugo_style() {
+    let text = "= \n   A B C D E \n 5 . . . . . 5\n 4 . O . . . 4     WHITE (O) has captured 0 stones\n 3 . . + X . 3     BLACK (X) has captured 1 stones\n 2 . . . . . 2\n 1 X . . . O 1\n   A B C D E \n";
+    let parsed = parse_showboard(text).expect("parse failed");
+
+    assert_eq!((parsed.width, parsed.height), (5, 5));
+    let board = parsed.to_board::<{ nw_for_board(5, 5) }>();
+    assert_eq!(board.get_piece(&Position::new(1, 3)), Some(Player::White));
+    assert_eq!(board.get_piece(&Position::new(3, 2)), Some(Player::Black));
+    assert_eq!(board.get_piece(&Position::new(2, 2)), None);
+    assert_eq!(board.get_piece(&Position::new(0, 0)), Some(Player::Black));
+    assert_eq!(board.get_piece(&Position::new(4, 0)), Some(Player::White));
+    assert_eq!(parsed.stones.len(), 4);
+}
+
+#[test]
+fn test_parse_showboard_bordered_with_last_move_marker() {
+    let text = "\
+      A B C D
+    +---------+
+  4 | . . . . |
+  3 | . X(O). |
+  2 | . . . . |
+  1 | . . . . |
+    +---------+
+";
+    let parsed = parse_showboard(text).expect("parse failed");
+
+    assert_eq!((parsed.width, parsed.height), (4, 4));
+    assert_eq!(
+        parsed.stones,
+        vec![
+            (Position::new(1, 2), Player::Black),
+            (Position::new(2, 2), Player::White)
+        ]
+    );
+}
+
+#[test]
+fn test_parse_showboard_rejects_ragged_or_missing_rows() {
+    assert!(matches!(
+        parse_showboard("no board here"),
+        Err(GtpError::InvalidBoard(_))
+    ));
+    assert!(matches!(
+        parse_showboard(" 3 . . .\n 2 . .\n 1 . . .\n"),
+        Err(GtpError::InvalidBoard(_))
+    ));
+    assert!(matches!(
+        parse_showboard(" 3 . . .\n 3 . . .\n 1 . . .\n"),
+        Err(GtpError::InvalidBoard(_))
+    ));
+}
+
 #[test]
 fn test_gtp_client_final_score() {
     if !gnugo_available() {
@@ -59,10 +59,13 @@ pub fn vertex_to_position(s: &str, _height: u8) -> Result<Position, GtpError> {
     Ok(Position::new(col, row_num - 1))
 }
 
-/// Convert a Move to GTP move string ("C4" or "pass").
+/// Convert a Move to GTP move string ("C4", "pass" or "resign"). GTP has no
+/// swap move; it is written as "swap", which engines will reject.
 pub fn move_to_gtp(m: &Move, height: u8) -> String {
     match m {
         Move::Pass => "pass".to_string(),
+        Move::Resign => "resign".to_string(),
+        Move::Swap => "swap".to_string(),
         Move::Place { col, row } => {
             let pos = Position::new(*col, *row);
             position_to_vertex(&pos, height)
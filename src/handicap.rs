@@ -0,0 +1,103 @@
+use crate::position::Position;
+
+/// Distance of the handicap star points from the edge, or None if the board
+/// is too small for handicap stones.
+#[hotpath::measure]
+fn star_line(size: u8) -> Option<u8> {
+    match size {
+        13.. => Some(3),
+        7..=12 => Some(2),
+        _ => None,
+    }
+}
+
+/// Largest fixed handicap for a board: 9 on odd boards from 9x9 up, 4 on
+/// other boards from 7x7 up, and 0 below that.
+#[hotpath::measure]
+pub fn max_fixed_handicap(width: u8, height: u8) -> u8 {
+    if star_line(width).is_none() || star_line(height).is_none() {
+        0
+    } else if width % 2 == 1 && height % 2 == 1 && width.min(height) >= 9 {
+        9
+    } else {
+        4
+    }
+}
+
+/// Positions of `stones` fixed handicap stones, in the order of the GTP
+/// `fixed_handicap` command (D4 Q16 Q4 D16, then sides and center on 19x19).
+/// Returns None unless `stones` is in `2..=max_fixed_handicap(width, height)`.
+#[hotpath::measure]
+pub fn fixed_handicap_positions(width: u8, height: u8, stones: u8) -> Option<Vec<Position>> {
+    if stones < 2 || stones > max_fixed_handicap(width, height) {
+        return None;
+    }
+    let (ex, ey) = (star_line(width)?, star_line(height)?);
+    let (fx, fy) = (width - 1 - ex, height - 1 - ey);
+    let (mx, my) = (width / 2, height / 2);
+
+    let corners = [
+        Position::new(ex, ey),
+        Position::new(fx, fy),
+        Position::new(fx, ey),
+        Position::new(ex, fy),
+    ];
+    let sides = [Position::new(ex, my), Position::new(fx, my)];
+    let ends = [Position::new(mx, ey), Position::new(mx, fy)];
+    let center = Position::new(mx, my);
+
+    let mut positions: Vec<Position> = corners
+        .iter()
+        .take(stones.min(4) as usize)
+        .copied()
+        .collect();
+    if stones >= 6 {
+        positions.extend(sides);
+    }
+    if stones >= 8 {
+        positions.extend(ends);
+    }
+    if stones % 2 == 1 && stones >= 5 {
+        positions.push(center);
+    }
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtp::position_to_vertex;
+
+    fn vertices(stones: u8) -> Vec<String> {
+        fixed_handicap_positions(19, 19, stones)
+            .expect("valid handicap")
+            .iter()
+            .map(|p| position_to_vertex(p, 19))
+            .collect()
+    }
+
+    #[test]
+    fn test_gtp_fixed_handicap_order() {
+        assert_eq!(vertices(2), ["D4", "Q16"]);
+        assert_eq!(vertices(3), ["D4", "Q16", "Q4"]);
+        assert_eq!(vertices(5), ["D4", "Q16", "Q4", "D16", "K10"]);
+        assert_eq!(
+            vertices(8),
+            ["D4", "Q16", "Q4", "D16", "D10", "Q10", "K4", "K16"]
+        );
+        assert_eq!(vertices(9).len(), 9);
+    }
+
+    #[test]
+    fn test_limits_by_board_size() {
+        assert_eq!(max_fixed_handicap(9, 9), 9);
+        assert_eq!(max_fixed_handicap(10, 10), 4);
+        assert_eq!(max_fixed_handicap(5, 5), 0);
+        assert!(fixed_handicap_positions(19, 19, 1).is_none());
+        assert!(fixed_handicap_positions(10, 10, 5).is_none());
+        assert_eq!(
+            fixed_handicap_positions(9, 9, 2),
+            Some(vec![Position::new(2, 2), Position::new(6, 6)])
+        );
+    }
+}
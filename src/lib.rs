@@ -1,16 +1,63 @@
+#[cfg(feature = "analysis")]
+pub mod analysis;
+pub mod benson;
 pub mod bitboard;
 pub mod board;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod encode;
 pub mod game;
+pub mod game_tree;
+pub mod handicap;
+pub mod log;
 pub mod r#move;
+#[cfg(all(feature = "config", feature = "sgf"))]
+pub mod ogs;
 pub mod outcome;
 pub mod player;
+#[cfg(feature = "search")]
+pub mod policy;
 pub mod position;
+pub mod position_string;
+pub mod render;
+pub mod replay;
+#[cfg(feature = "search")]
+pub mod rollout;
+pub mod rules;
+pub mod scoring;
+#[cfg(feature = "selfplay")]
+pub mod selfplay;
+#[cfg(feature = "config")]
+pub mod session;
+#[cfg(feature = "sgf")]
+pub mod sgf;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "search")]
+pub mod terminal;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "selfplay")]
+pub mod tournament;
+#[cfg(feature = "dataset")]
+pub mod training;
+pub mod transcript;
+#[cfg(feature = "dataset")]
+pub mod wire;
+pub mod zobrist;
 
 #[allow(unused_macros)]
 #[macro_use]
 mod dispatch;
 
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "dataset")]
+pub mod dataset;
+#[cfg(feature = "selfplay")]
+pub mod eval;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod gtp;
 
 #[cfg(feature = "python")]
@@ -22,6 +69,15 @@ use pyo3::prelude::*;
 #[cfg(feature = "python")]
 mod python;
 
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "node")]
+mod node;
+
 #[cfg(feature = "python")]
 #[pymodule(gil_used = false)]
 #[hotpath::measure]
@@ -30,11 +86,40 @@ fn spooky_go(m: &Bound<'_, PyModule>) -> PyResult<()> {
     use python::*;
     m.add_class::<PyBoard>()?;
     m.add_class::<PyGame>()?;
+    m.add_class::<PyBatchGame>()?;
+    m.add_class::<PyGameInfo>()?;
     m.add_class::<PyMove>()?;
+    m.add_class::<PyPosition>()?;
     m.add_class::<PyGameOutcome>()?;
     m.add_class::<PyGtpEngine>()?;
+    m.add_class::<PyScoreDetails>()?;
+    m.add_class::<PyMoveAnalysis>()?;
+    m.add_class::<PyEncodingCache>()?;
+    m.add_class::<PyPolicy>()?;
+    m.add_class::<PyUniformPolicy>()?;
+    m.add_class::<PyNoEyeFillPolicy>()?;
+    m.add_class::<PyHeuristicPolicy>()?;
+    m.add_class::<PyMctsPolicy>()?;
+    m.add_class::<PyGameRecord>()?;
+    m.add_class::<PyMatchResult>()?;
+    m.add_class::<PyTrainingSample>()?;
+    m.add("IllegalMoveError", m.py().get_type::<IllegalMoveError>())?;
+    m.add("GameOverError", m.py().get_type::<GameOverError>())?;
+    m.add(
+        "InvalidPlayerError",
+        m.py().get_type::<InvalidPlayerError>(),
+    )?;
     m.add("BLACK", Player::Black as i8)?;
     m.add("WHITE", Player::White as i8)?;
     m.add("TOTAL_INPUT_PLANES", encode::TOTAL_INPUT_PLANES)?;
+    m.add("HISTORY_LENGTH", encode::HISTORY_LENGTH)?;
+    m.add_function(wrap_pyfunction!(total_actions, m)?)?;
+    m.add_function(wrap_pyfunction!(encode_move, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_move, m)?)?;
+    m.add_function(wrap_pyfunction!(nw_for_board, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_wire_messages, m)?)?;
+    m.add_function(wrap_pyfunction!(random_playouts_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(encode_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(play_interactive, m)?)?;
     Ok(())
 }
@@ -0,0 +1,63 @@
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+
+/// A single entry in a `GameLog`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// `player` played `move_`.
+    Move { player: Player, move_: Move },
+    /// The last move (`move_`, played by `player`) was taken back.
+    Unmake { player: Player, move_: Move },
+    /// The board was edited directly, outside of move play.
+    SetPiece {
+        position: Position,
+        player: Option<Player>,
+    },
+    /// `player` asked for the last `plies` moves to be taken back.
+    TakebackRequested { player: Player, plies: usize },
+    /// The pending takeback request was accepted and the moves were undone.
+    TakebackAccepted { player: Player, plies: usize },
+    /// The pending takeback request was declined.
+    TakebackDeclined { player: Player, plies: usize },
+    /// Komi was changed.
+    SetKomi { komi: f32 },
+    /// A clock update reported by the caller.
+    Clock { player: Player, remaining_ms: u64 },
+}
+
+/// Append-only record of everything that happened to a `Game`.
+///
+/// Unlike the move history, which `unmake_move` truncates, the log only ever
+/// grows, so takebacks and board edits remain visible for auditing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameLog {
+    events: Vec<GameEvent>,
+}
+
+#[hotpath::measure_all]
+impl GameLog {
+    pub fn new() -> Self {
+        GameLog { events: Vec::new() }
+    }
+
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GameEvent> {
+        self.events.iter()
+    }
+
+    pub(crate) fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+}
@@ -2,8 +2,14 @@ use crate::position::Position;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Move {
-    Place { col: u8, row: u8 },
+    Place {
+        col: u8,
+        row: u8,
+    },
     Pass,
+    Resign,
+    /// White's reply to Black's first move under the swap (pie) rule.
+    Swap,
 }
 
 #[hotpath::measure_all]
@@ -16,28 +22,116 @@ impl Move {
         Move::Pass
     }
 
+    pub fn resign() -> Self {
+        Move::Resign
+    }
+
+    pub fn swap() -> Self {
+        Move::Swap
+    }
+
     pub fn is_pass(&self) -> bool {
         matches!(self, Move::Pass)
     }
 
+    pub fn is_resign(&self) -> bool {
+        matches!(self, Move::Resign)
+    }
+
+    pub fn is_swap(&self) -> bool {
+        matches!(self, Move::Swap)
+    }
+
     pub fn position(&self) -> Option<Position> {
         match self {
             Move::Place { col, row } => Some(Position::new(*col, *row)),
-            Move::Pass => None,
+            Move::Pass | Move::Resign | Move::Swap => None,
         }
     }
 
     pub fn col(&self) -> Option<u8> {
         match self {
             Move::Place { col, .. } => Some(*col),
-            Move::Pass => None,
+            Move::Pass | Move::Resign | Move::Swap => None,
         }
     }
 
     pub fn row(&self) -> Option<u8> {
         match self {
             Move::Place { row, .. } => Some(*row),
-            Move::Pass => None,
+            Move::Pass | Move::Resign | Move::Swap => None,
+        }
+    }
+}
+
+/// Coordinate notation used when listing moves for people to read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MoveListStyle {
+    /// Column letter (skipping I) and row number from the bottom, e.g. `D4`.
+    #[default]
+    Gtp,
+    /// Two letters, column then row from the top, e.g. `dp`.
+    Sgf,
+    /// Zero-based column and row, e.g. `(3, 3)`.
+    Index,
+}
+
+#[hotpath::measure_all]
+impl MoveListStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MoveListStyle::Gtp => "gtp",
+            MoveListStyle::Sgf => "sgf",
+            MoveListStyle::Index => "index",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "gtp" => Some(MoveListStyle::Gtp),
+            "sgf" => Some(MoveListStyle::Sgf),
+            "index" => Some(MoveListStyle::Index),
+            _ => None,
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl Move {
+    /// The move in `style` notation on a board `height` rows tall. Passes,
+    /// resignations and swaps are written as `pass`, `resign` and `swap` in
+    /// every style.
+    pub fn notation(&self, style: MoveListStyle, height: u8) -> String {
+        let (col, row) = match *self {
+            Move::Place { col, row } => (col, row),
+            Move::Pass => return "pass".to_string(),
+            Move::Resign => return "resign".to_string(),
+            Move::Swap => return "swap".to_string(),
+        };
+        match style {
+            MoveListStyle::Gtp => crate::gtp::move_to_gtp(self, height),
+            MoveListStyle::Sgf => {
+                let letter = |i: u8| (b'a' + i) as char;
+                format!("{}{}", letter(col), letter(height - 1 - row))
+            }
+            MoveListStyle::Index => format!("({}, {})", col, row),
+        }
+    }
+
+    /// The move in GTP notation on a board `height` rows tall: a vertex such
+    /// as `D4` or `Q16`, or `pass`, `resign` or `swap`.
+    pub fn to_gtp(&self, height: u8) -> String {
+        crate::gtp::move_to_gtp(self, height)
+    }
+
+    /// Parse a move written by `to_gtp`, case-insensitively. Returns None if
+    /// it is malformed or off a `width` x `height` board.
+    pub fn from_gtp(s: &str, width: u8, height: u8) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pass" => Some(Move::Pass),
+            "resign" => Some(Move::Resign),
+            "swap" => Some(Move::Swap),
+            _ => Position::from_gtp(s, width, height).map(|pos| Move::place(pos.col, pos.row)),
         }
     }
 }
@@ -48,6 +142,46 @@ impl std::fmt::Display for Move {
         match self {
             Move::Place { col, row } => write!(f, "Place({}, {})", col, row),
             Move::Pass => write!(f, "Pass"),
+            Move::Resign => write!(f, "Resign"),
+            Move::Swap => write!(f, "Swap"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notation_styles() {
+        let m = Move::place(3, 3);
+        assert_eq!(m.notation(MoveListStyle::Gtp, 19), "D4");
+        assert_eq!(m.notation(MoveListStyle::Sgf, 19), "dp");
+        assert_eq!(m.notation(MoveListStyle::Index, 19), "(3, 3)");
+        assert_eq!(Move::place(8, 0).notation(MoveListStyle::Gtp, 9), "J1");
+        assert_eq!(Move::pass().notation(MoveListStyle::Sgf, 9), "pass");
+        assert_eq!(MoveListStyle::from_name("SGF"), Some(MoveListStyle::Sgf));
+    }
+
+    #[test]
+    fn test_gtp_coordinates() {
+        assert_eq!(Move::place(3, 3).to_gtp(19), "D4");
+        assert_eq!(Move::place(15, 15).to_gtp(19), "Q16");
+        assert_eq!(Move::pass().to_gtp(19), "pass");
+        assert_eq!(Position::new(8, 0).to_gtp(9), "J1");
+
+        assert_eq!(Move::from_gtp("q16", 19, 19), Some(Move::place(15, 15)));
+        assert_eq!(Move::from_gtp(" J1 ", 9, 9), Some(Move::place(8, 0)));
+        assert_eq!(Move::from_gtp("PASS", 9, 9), Some(Move::pass()));
+        assert_eq!(Move::from_gtp("resign", 9, 9), Some(Move::resign()));
+        assert_eq!(
+            Position::from_gtp("T19", 19, 19),
+            Some(Position::new(18, 18))
+        );
+
+        assert_eq!(Move::from_gtp("I5", 19, 19), None);
+        assert_eq!(Move::from_gtp("Q16", 9, 9), None);
+        assert_eq!(Move::from_gtp("A10", 9, 9), None);
+        assert_eq!(Position::from_gtp("", 9, 9), None);
+    }
+}
@@ -0,0 +1,5 @@
+#[macro_use]
+mod dispatch;
+mod node_board;
+mod node_game;
+mod node_move;
@@ -0,0 +1,82 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use super::dispatch::*;
+use crate::player::Player;
+use crate::position::Position;
+
+#[napi(js_name = "Board")]
+#[derive(Clone)]
+pub struct NodeBoard {
+    inner: BoardInner,
+}
+
+#[hotpath::measure_all]
+impl NodeBoard {
+    pub(super) fn from_inner(inner: BoardInner) -> Self {
+        NodeBoard { inner }
+    }
+}
+
+#[hotpath::measure_all]
+#[napi]
+impl NodeBoard {
+    #[napi(constructor)]
+    pub fn new(width: u8, height: u8) -> Result<Self> {
+        if !(2..=32).contains(&width) || !(2..=32).contains(&height) {
+            return Err(Error::from_reason(
+                "Board width and height must be between 2 and 32",
+            ));
+        }
+        Ok(NodeBoard {
+            inner: make_board_inner(width, height),
+        })
+    }
+
+    #[napi(factory)]
+    pub fn standard() -> Self {
+        NodeBoard {
+            inner: make_board_inner(19, 19),
+        }
+    }
+
+    #[napi]
+    pub fn width(&self) -> u8 {
+        dispatch_board!(&self.inner, b => b.width())
+    }
+
+    #[napi]
+    pub fn height(&self) -> u8 {
+        dispatch_board!(&self.inner, b => b.height())
+    }
+
+    /// 1 for Black, -1 for White, null for an empty point.
+    #[napi]
+    pub fn get_piece(&self, col: u8, row: u8) -> Option<i8> {
+        let pos = Position::new(col, row);
+        dispatch_board!(&self.inner, b => b.get_piece(&pos).map(|p| p as i8))
+    }
+
+    #[napi]
+    pub fn set_piece(&mut self, col: u8, row: u8, piece: Option<i8>) -> Result<()> {
+        let pos = Position::new(col, row);
+        let player = match piece {
+            Some(p) => Some(
+                Player::from_int(p).ok_or_else(|| Error::from_reason("Invalid player value"))?,
+            ),
+            None => None,
+        };
+        dispatch_board_mut!(&mut self.inner, b => b.set_piece(&pos, player));
+        Ok(())
+    }
+
+    #[napi]
+    pub fn clear(&mut self) {
+        dispatch_board_mut!(&mut self.inner, b => b.clear())
+    }
+
+    #[napi(js_name = "toString")]
+    pub fn to_string_js(&self) -> String {
+        dispatch_board!(&self.inner, b => b.to_string())
+    }
+}
@@ -0,0 +1,399 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use super::dispatch::*;
+use super::node_board::NodeBoard;
+use super::node_move::NodeMove;
+use crate::encode;
+use crate::game::{validate_move_limits, GamePhase};
+use crate::player::Player;
+use crate::position::Position;
+use crate::position_string;
+use crate::replay::IllegalMovePolicy;
+use crate::rules::Ruleset;
+use crate::sgf;
+
+#[hotpath::measure]
+fn check_size(width: u8, height: u8) -> Result<()> {
+    if (2..=32).contains(&width) && (2..=32).contains(&height) {
+        Ok(())
+    } else {
+        Err(Error::from_reason(
+            "Board width and height must be between 2 and 32",
+        ))
+    }
+}
+
+#[hotpath::measure]
+fn player_from_int(player: i8) -> Result<Player> {
+    Player::from_int(player).ok_or_else(|| Error::from_reason("Invalid player value"))
+}
+
+#[napi(js_name = "Game")]
+#[derive(Clone)]
+pub struct NodeGame {
+    inner: GameInner,
+}
+
+#[hotpath::measure_all]
+#[napi]
+impl NodeGame {
+    #[napi(constructor)]
+    pub fn new(width: u8, height: u8) -> Result<Self> {
+        check_size(width, height)?;
+        Ok(NodeGame {
+            inner: make_game_inner(width, height),
+        })
+    }
+
+    #[napi(factory)]
+    pub fn with_options(
+        width: u8,
+        height: u8,
+        komi: f64,
+        min_moves_before_pass_possible: u16,
+        max_moves: u16,
+        superko: bool,
+    ) -> Result<Self> {
+        check_size(width, height)?;
+        validate_move_limits(min_moves_before_pass_possible, max_moves)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        Ok(NodeGame {
+            inner: make_game_inner_with_options(
+                width,
+                height,
+                komi as f32,
+                min_moves_before_pass_possible,
+                max_moves,
+                superko,
+            ),
+        })
+    }
+
+    /// Replay an SGF game record, failing on the first illegal move.
+    #[napi(factory)]
+    pub fn from_sgf(text: String) -> Result<Self> {
+        let to_node_err = |e: sgf::SgfError| Error::from_reason(e.to_string());
+        let record = sgf::parse_sgf(&text).map_err(to_node_err)?;
+
+        let mut inner = make_game_inner_with_options(
+            record.width,
+            record.height,
+            record.komi,
+            0,
+            u16::MAX,
+            true,
+        );
+        dispatch_game_mut!(&mut inner, g => {
+            record.set_up(g);
+            record
+                .replay_into(g, IllegalMovePolicy::Fail)
+                .map_err(to_node_err)?;
+        });
+        Ok(NodeGame { inner })
+    }
+
+    /// Start a game with the default rules from a position string.
+    #[napi(factory)]
+    pub fn from_position_string(text: String) -> Result<Self> {
+        let record = position_string::parse_position_string(&text)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        let mut inner = make_game_inner(record.width, record.height);
+        dispatch_game_mut!(&mut inner, g => record.set_up(g));
+        Ok(NodeGame { inner })
+    }
+
+    #[napi(factory)]
+    pub fn standard() -> Self {
+        NodeGame {
+            inner: make_game_inner(19, 19),
+        }
+    }
+
+    #[napi]
+    pub fn clone_game(&self) -> Self {
+        self.clone()
+    }
+
+    #[napi]
+    pub fn to_position_string(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_position_string())
+    }
+
+    #[napi]
+    pub fn to_sgf(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_sgf())
+    }
+
+    #[napi]
+    pub fn width(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.width())
+    }
+
+    #[napi]
+    pub fn height(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.height())
+    }
+
+    #[napi]
+    pub fn komi(&self) -> f32 {
+        dispatch_game!(&self.inner, g => g.komi())
+    }
+
+    /// Komi plus the handicap compensation for the ruleset.
+    #[napi]
+    pub fn effective_komi(&self) -> f32 {
+        dispatch_game!(&self.inner, g => g.effective_komi())
+    }
+
+    #[napi]
+    pub fn ruleset(&self) -> String {
+        dispatch_game!(&self.inner, g => g.ruleset().name().to_string())
+    }
+
+    #[napi]
+    pub fn set_ruleset(&mut self, ruleset: String) -> Result<()> {
+        let parsed = Ruleset::from_name(&ruleset)
+            .ok_or_else(|| Error::from_reason(format!("Unknown ruleset: {}", ruleset)))?;
+        dispatch_game_mut!(&mut self.inner, g => g.set_ruleset(parsed));
+        Ok(())
+    }
+
+    #[napi]
+    pub fn superko(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.superko())
+    }
+
+    #[napi]
+    pub fn allow_suicide(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.allow_suicide())
+    }
+
+    #[napi]
+    pub fn set_allow_suicide(&mut self, allow_suicide: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_allow_suicide(allow_suicide))
+    }
+
+    #[napi]
+    pub fn swap_rule(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.swap_rule())
+    }
+
+    #[napi]
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_swap_rule(swap_rule))
+    }
+
+    #[napi]
+    pub fn handicap(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.handicap())
+    }
+
+    /// Place fixed handicap stones on an empty board and give White the move.
+    #[napi]
+    pub fn place_handicap(&mut self, stones: u8) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.place_handicap(stones))
+    }
+
+    /// The player to move: 1 for Black, -1 for White.
+    #[napi]
+    pub fn turn(&self) -> i8 {
+        dispatch_game!(&self.inner, g => g.turn() as i8)
+    }
+
+    #[napi]
+    pub fn move_count(&self) -> u32 {
+        dispatch_game!(&self.inner, g => g.move_count() as u32)
+    }
+
+    /// Moves left before the move limit ends the game, or 0 once it is over.
+    #[napi]
+    pub fn moves_remaining(&self) -> u16 {
+        dispatch_game!(&self.inner, g => g.moves_remaining())
+    }
+
+    /// Stones captured by `player`.
+    #[napi]
+    pub fn prisoners(&self, player: i8) -> Result<u32> {
+        let player = player_from_int(player)?;
+        Ok(dispatch_game!(&self.inner, g => g.prisoners(player)))
+    }
+
+    #[napi]
+    pub fn ko_point(&self) -> Option<Vec<u8>> {
+        dispatch_game!(&self.inner, g => g.ko_point().map(|p| vec![p.col, p.row]))
+    }
+
+    #[napi]
+    pub fn board(&self) -> NodeBoard {
+        NodeBoard::from_inner(game_to_board_inner!(&self.inner))
+    }
+
+    #[napi]
+    pub fn get_piece(&self, col: u8, row: u8) -> Option<i8> {
+        let pos = Position::new(col, row);
+        dispatch_game!(&self.inner, g => g.board().get_piece(&pos).map(|p| p as i8))
+    }
+
+    #[napi]
+    pub fn legal_moves(&self) -> Vec<NodeMove> {
+        dispatch_game!(&self.inner, g => g.legal_moves())
+            .into_iter()
+            .map(NodeMove::from_move)
+            .collect()
+    }
+
+    #[napi]
+    pub fn is_legal_move(&self, move_: &NodeMove) -> bool {
+        dispatch_game!(&self.inner, g => g.is_legal_move(move_.as_inner()))
+    }
+
+    /// Play `move_`. Returns false, leaving the game unchanged, if it is
+    /// illegal.
+    #[napi]
+    pub fn make_move(&mut self, move_: &NodeMove) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.make_move(move_.as_inner()))
+    }
+
+    #[napi]
+    pub fn unmake_move(&mut self) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.unmake_move())
+    }
+
+    #[napi]
+    pub fn resign(&mut self, player: i8) -> Result<bool> {
+        let player = player_from_int(player)?;
+        Ok(dispatch_game_mut!(&mut self.inner, g => g.resign(player)))
+    }
+
+    #[napi]
+    pub fn is_over(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.is_over())
+    }
+
+    /// One of "playing", "scoring" or "over".
+    #[napi]
+    pub fn phase(&self) -> &'static str {
+        match dispatch_game!(&self.inner, g => g.phase()) {
+            GamePhase::Playing => "playing",
+            GamePhase::Scoring => "scoring",
+            GamePhase::Over => "over",
+        }
+    }
+
+    #[napi]
+    pub fn dead_stone_marking(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.dead_stone_marking())
+    }
+
+    #[napi]
+    pub fn set_dead_stone_marking(&mut self, dead_stone_marking: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_dead_stone_marking(dead_stone_marking))
+    }
+
+    /// Mark the groups at the given `[col, row]` points dead.
+    #[napi]
+    pub fn mark_dead(&mut self, positions: Vec<Vec<u8>>) -> Result<bool> {
+        let positions = positions
+            .into_iter()
+            .map(|p| match p[..] {
+                [col, row] => Ok(Position::new(col, row)),
+                _ => Err(Error::from_reason("Positions must be [col, row] pairs")),
+            })
+            .collect::<Result<Vec<Position>>>()?;
+        Ok(dispatch_game_mut!(&mut self.inner, g => g.mark_dead(&positions)))
+    }
+
+    #[napi]
+    pub fn toggle_dead_group(&mut self, col: u8, row: u8) -> bool {
+        let pos = Position::new(col, row);
+        dispatch_game_mut!(&mut self.inner, g => g.toggle_dead_group(&pos))
+    }
+
+    #[napi]
+    pub fn is_dead(&self, col: u8, row: u8) -> bool {
+        let pos = Position::new(col, row);
+        dispatch_game!(&self.inner, g => g.is_dead(&pos))
+    }
+
+    #[napi]
+    pub fn accept_score(&mut self) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.accept_score())
+    }
+
+    /// Score as [black, white], with komi added to white.
+    #[napi]
+    pub fn score(&self) -> Vec<f32> {
+        let (black, white) = dispatch_game!(&self.inner, g => g.score());
+        vec![black, white]
+    }
+
+    /// The winner once the game is over (1 for Black, -1 for White), or null
+    /// for a draw or a game still in progress.
+    #[napi]
+    pub fn winner(&self) -> Option<i8> {
+        dispatch_game!(&self.inner, g => g.outcome())
+            .and_then(|o| o.winner())
+            .map(|p| p as i8)
+    }
+
+    /// The result as in SGF, such as `B+3.5` or `W+R`, once the game is over.
+    #[napi]
+    pub fn result_string(&self) -> Option<String> {
+        dispatch_game!(&self.inner, g => g.outcome()).map(|o| o.to_result_string())
+    }
+
+    // ---------------------------------------------------------------------
+    // Encoding/decoding
+    // ---------------------------------------------------------------------
+
+    /// The input planes of `encode_game_planes`, plane-major then row-major:
+    /// `inputPlaneCount()` planes of `height() * width()` values.
+    #[napi]
+    pub fn encode_game_planes(&self) -> Vec<f32> {
+        dispatch_game!(&self.inner, g => encode::encode_game_planes(g)).0
+    }
+
+    #[napi]
+    pub fn input_plane_count(&self) -> u32 {
+        encode::TOTAL_INPUT_PLANES as u32
+    }
+
+    #[napi]
+    pub fn total_actions(&self) -> u32 {
+        dispatch_game!(&self.inner, g => encode::total_actions_for(g) as u32)
+    }
+
+    #[napi]
+    pub fn legal_action_indices(&self) -> Vec<u32> {
+        dispatch_game!(&self.inner, g => {
+            g.legal_moves()
+                .iter()
+                .map(|m| encode::encode_move(m, g.width(), g.height()) as u32)
+                .collect()
+        })
+    }
+
+    /// Play the move of action index `action`. Returns false if the index is
+    /// out of range or the move is illegal.
+    #[napi]
+    pub fn apply_action(&mut self, action: u32) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => {
+            match encode::decode_move_for(action as usize, g) {
+                Some(move_) => g.make_move(&move_),
+                None => false,
+            }
+        })
+    }
+
+    #[napi]
+    pub fn decode_action(&self, action: u32) -> Option<NodeMove> {
+        dispatch_game!(&self.inner, g => encode::decode_move_for(action as usize, g))
+            .map(NodeMove::from_move)
+    }
+
+    #[napi(js_name = "toString")]
+    pub fn to_string_js(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_string())
+    }
+}
@@ -0,0 +1,114 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use crate::encode;
+use crate::r#move::Move;
+
+#[napi(js_name = "Move")]
+#[derive(Clone, Debug)]
+pub struct NodeMove {
+    move_: Move,
+}
+
+#[hotpath::measure_all]
+impl NodeMove {
+    pub(super) fn from_move(move_: Move) -> Self {
+        NodeMove { move_ }
+    }
+
+    pub(super) fn as_inner(&self) -> &Move {
+        &self.move_
+    }
+}
+
+#[hotpath::measure_all]
+#[napi]
+impl NodeMove {
+    #[napi(factory)]
+    pub fn place(col: u8, row: u8) -> Self {
+        NodeMove {
+            move_: Move::place(col, row),
+        }
+    }
+
+    #[napi(factory, js_name = "pass")]
+    pub fn pass_move() -> Self {
+        NodeMove {
+            move_: Move::pass(),
+        }
+    }
+
+    #[napi(factory)]
+    pub fn resign() -> Self {
+        NodeMove {
+            move_: Move::resign(),
+        }
+    }
+
+    #[napi(factory)]
+    pub fn swap() -> Self {
+        NodeMove {
+            move_: Move::swap(),
+        }
+    }
+
+    #[napi]
+    pub fn is_pass(&self) -> bool {
+        self.move_.is_pass()
+    }
+
+    #[napi]
+    pub fn is_resign(&self) -> bool {
+        self.move_.is_resign()
+    }
+
+    #[napi]
+    pub fn is_swap(&self) -> bool {
+        self.move_.is_swap()
+    }
+
+    #[napi]
+    pub fn col(&self) -> Option<u8> {
+        self.move_.col()
+    }
+
+    #[napi]
+    pub fn row(&self) -> Option<u8> {
+        self.move_.row()
+    }
+
+    #[napi]
+    pub fn encode(&self, board_width: u8, board_height: u8) -> u32 {
+        encode::encode_move(&self.move_, board_width, board_height) as u32
+    }
+
+    #[napi(factory)]
+    pub fn decode(action: u32, board_width: u8, board_height: u8) -> Result<Self> {
+        encode::decode_move(action as usize, board_width, board_height)
+            .map(NodeMove::from_move)
+            .ok_or_else(|| Error::from_reason(format!("Invalid action index: {}", action)))
+    }
+
+    #[napi]
+    pub fn to_gtp(&self, board_height: u8) -> String {
+        self.move_.to_gtp(board_height)
+    }
+
+    /// Parse a GTP move such as `D4` or `pass`.
+    #[napi(factory)]
+    pub fn from_gtp(text: String, board_width: u8, board_height: u8) -> Result<Self> {
+        Move::from_gtp(&text, board_width, board_height)
+            .map(NodeMove::from_move)
+            .ok_or_else(|| Error::from_reason(format!("Invalid GTP move: {}", text)))
+    }
+
+    #[napi]
+    pub fn equals(&self, other: &NodeMove) -> bool {
+        self.move_ == other.move_
+    }
+
+    #[napi(js_name = "toString")]
+    pub fn to_string_js(&self) -> String {
+        self.move_.to_string()
+    }
+}
@@ -0,0 +1,484 @@
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::game::{Game, GameInfo};
+use crate::game_tree::GameTree;
+use crate::handicap::fixed_handicap_positions;
+use crate::outcome::GameOutcome;
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+use crate::replay::{IllegalMovePolicy, ReplayError, ReplayIssue};
+use crate::rules::Ruleset;
+use crate::sgf::{SgfError, SgfGame};
+
+/// Errors from reading an OGS game record.
+#[derive(Debug)]
+pub enum OgsError {
+    Json(serde_json::Error),
+    InvalidBoardSize(String),
+    InvalidField {
+        field: &'static str,
+        value: String,
+    },
+    /// The record could not be set up or replayed.
+    Sgf(SgfError),
+}
+
+impl From<serde_json::Error> for OgsError {
+    fn from(e: serde_json::Error) -> Self {
+        OgsError::Json(e)
+    }
+}
+
+impl From<SgfError> for OgsError {
+    fn from(e: SgfError) -> Self {
+        OgsError::Sgf(e)
+    }
+}
+
+impl fmt::Display for OgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OgsError::Json(e) => write!(f, "invalid OGS JSON: {}", e),
+            OgsError::InvalidBoardSize(msg) => write!(f, "invalid board size: {}", msg),
+            OgsError::InvalidField { field, value } => {
+                write!(f, "invalid {} in OGS record: {}", field, value)
+            }
+            OgsError::Sgf(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for OgsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OgsError::Json(e) => Some(e),
+            OgsError::Sgf(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct OgsPlayer {
+    id: Option<u64>,
+    username: Option<String>,
+    /// A number on the OGS scale or already a label such as `"3d"`.
+    rank: Option<Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct OgsPlayers {
+    #[serde(default)]
+    black: OgsPlayer,
+    #[serde(default)]
+    white: OgsPlayer,
+}
+
+/// Stones on the board before the first move, as runs of SGF points.
+#[derive(Deserialize, Default)]
+struct OgsInitialState {
+    #[serde(default)]
+    black: String,
+    #[serde(default)]
+    white: String,
+}
+
+/// The fields of an OGS game record that are read; others are ignored.
+#[derive(Deserialize)]
+struct OgsJson {
+    width: u8,
+    height: u8,
+    komi: Option<f32>,
+    #[serde(default)]
+    handicap: u8,
+    #[serde(default)]
+    free_handicap_placement: bool,
+    initial_player: Option<String>,
+    #[serde(default)]
+    initial_state: OgsInitialState,
+    /// `[x, y, milliseconds, ...]` per move, from the top left; `x` of -1
+    /// is a pass.
+    #[serde(default)]
+    moves: Vec<Vec<Value>>,
+    rules: Option<String>,
+    #[serde(default)]
+    players: OgsPlayers,
+    black_player_id: Option<u64>,
+    white_player_id: Option<u64>,
+    game_name: Option<String>,
+    /// Unix time in seconds.
+    start_time: Option<i64>,
+    /// The winner's player id.
+    winner: Option<u64>,
+    /// How the game ended: `Resignation`, `Timeout`, `3.5 points`...
+    outcome: Option<String>,
+}
+
+/// A game record read from OGS JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OgsGame {
+    /// The board, rules, setup stones, moves, result and player information.
+    pub record: SgfGame,
+    /// Time taken for each move of `record.moves`, in milliseconds, if
+    /// recorded.
+    pub move_times_ms: Vec<Option<u64>>,
+}
+
+#[hotpath::measure]
+fn invalid(field: &'static str, value: impl fmt::Display) -> OgsError {
+    OgsError::InvalidField {
+        field,
+        value: value.to_string(),
+    }
+}
+
+/// The points of an `initial_state` string of two-letter SGF points.
+#[hotpath::measure]
+fn parse_points(text: &str, width: u8, height: u8) -> Result<Vec<Position>, OgsError> {
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(invalid("initial state", text));
+    }
+    bytes
+        .chunks(2)
+        .map(|pair| {
+            let (x, y) = (pair[0].wrapping_sub(b'a'), pair[1].wrapping_sub(b'a'));
+            if x >= width || y >= height {
+                return Err(invalid("initial state", text));
+            }
+            Ok(Position::new(x, height - 1 - y))
+        })
+        .collect()
+}
+
+/// One entry of `moves` with its time, if any.
+#[hotpath::measure]
+fn parse_move(entry: &[Value], width: u8, height: u8) -> Result<(Move, Option<u64>), OgsError> {
+    let bad = || invalid("move", Value::from(entry.to_vec()));
+    let (x, y) = match entry {
+        [x, y, ..] => (x.as_i64().ok_or_else(bad)?, y.as_i64().ok_or_else(bad)?),
+        _ => return Err(bad()),
+    };
+    let time = entry
+        .get(2)
+        .and_then(Value::as_f64)
+        .filter(|ms| *ms >= 0.0)
+        .map(|ms| ms as u64);
+    if x == -1 {
+        return Ok((Move::pass(), time));
+    }
+    if !(0..i64::from(width)).contains(&x) || !(0..i64::from(height)).contains(&y) {
+        return Err(bad());
+    }
+    Ok((Move::place(x as u8, height - 1 - y as u8), time))
+}
+
+/// A rank on the OGS scale, where 30 is 1 dan, as a label like `5k`.
+#[hotpath::measure]
+fn rank_label(rank: &Value) -> Option<String> {
+    match rank {
+        Value::Number(n) => {
+            let rank = n.as_f64()?.floor() as i64;
+            Some(if rank < 30 {
+                format!("{}k", 30 - rank)
+            } else {
+                format!("{}d", rank - 29)
+            })
+        }
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// The `YYYY-MM-DD` date of a Unix time in UTC.
+#[hotpath::measure]
+fn utc_date(seconds: i64) -> String {
+    // Days since 1970-01-01 to a civil date, in 400-year eras from 0000-03-01.
+    let z = seconds.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The result from the winner's id and the outcome text.
+#[hotpath::measure]
+fn read_outcome(json: &OgsJson) -> Option<GameOutcome> {
+    let winner = json.winner?;
+    let black_id = json.players.black.id.or(json.black_player_id);
+    let white_id = json.players.white.id.or(json.white_player_id);
+    let winner = if Some(winner) == black_id {
+        Player::Black
+    } else if Some(winner) == white_id {
+        Player::White
+    } else {
+        return None;
+    };
+    let outcome = json.outcome.as_deref().unwrap_or("").trim();
+    let how = match outcome.to_ascii_lowercase().as_str() {
+        "resignation" => "R".to_string(),
+        "timeout" => "T".to_string(),
+        other => other.strip_suffix(" points")?.to_string(),
+    };
+    GameOutcome::from_result_string(&format!("{}+{}", winner.to_char(), how)).ok()
+}
+
+/// Parse a game record in the JSON format of the OGS (online-go.com) API.
+///
+/// Reads the board size, komi, handicap, rules, starting stones, moves with
+/// their times, player names and ranks, game name, start date and result.
+/// With free handicap placement Black's first `handicap` moves are the
+/// handicap stones; otherwise the stones are on the fixed points. A handicap
+/// of 1 only means no komi: Black moves first and places no stones.
+#[hotpath::measure]
+pub fn parse_ogs_json(text: &str) -> Result<OgsGame, OgsError> {
+    let json: OgsJson = serde_json::from_str(text)?;
+    let (width, height) = (json.width, json.height);
+    if !(2..=25).contains(&width) || !(2..=25).contains(&height) {
+        return Err(OgsError::InvalidBoardSize(format!("{}x{}", width, height)));
+    }
+
+    let mut black_setup = parse_points(&json.initial_state.black, width, height)?;
+    let white_setup = parse_points(&json.initial_state.white, width, height)?;
+    let mut entries = json
+        .moves
+        .iter()
+        .map(|entry| parse_move(entry, width, height))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let handicap = json.handicap;
+    if handicap > 1 {
+        if json.free_handicap_placement {
+            if entries.len() < handicap as usize {
+                return Err(invalid("handicap", handicap));
+            }
+            for (move_, _) in entries.drain(..handicap as usize) {
+                black_setup.push(
+                    move_
+                        .position()
+                        .ok_or_else(|| invalid("handicap", handicap))?,
+                );
+            }
+        } else {
+            let stones = fixed_handicap_positions(width, height, handicap)
+                .ok_or_else(|| invalid("handicap", handicap))?;
+            black_setup.extend(stones);
+        }
+    }
+
+    let first = if handicap > 1 {
+        Player::White
+    } else {
+        match json.initial_player.as_deref() {
+            None | Some("black") => Player::Black,
+            Some("white") => Player::White,
+            Some(other) => return Err(invalid("initial player", other)),
+        }
+    };
+    let mut player = first;
+    let mut moves = Vec::with_capacity(entries.len());
+    let mut move_times_ms = Vec::with_capacity(entries.len());
+    for (move_, time) in entries {
+        moves.push((player, move_));
+        move_times_ms.push(time);
+        player = player.opposite();
+    }
+
+    let result = read_outcome(&json);
+    let info = GameInfo {
+        black_player: json.players.black.username.clone(),
+        white_player: json.players.white.username.clone(),
+        black_rank: json.players.black.rank.as_ref().and_then(rank_label),
+        white_rank: json.players.white.rank.as_ref().and_then(rank_label),
+        date: json.start_time.map(utc_date),
+        event: json
+            .game_name
+            .clone()
+            .filter(|name| !name.trim().is_empty()),
+        result: result.map(|outcome| outcome.to_result_string()),
+    };
+
+    Ok(OgsGame {
+        record: SgfGame {
+            width,
+            height,
+            komi: json.komi.unwrap_or(0.0),
+            handicap,
+            ruleset: json.rules.as_deref().and_then(Ruleset::from_name),
+            black_setup,
+            white_setup,
+            to_move: Some(first),
+            moves,
+            result,
+            info,
+        },
+        move_times_ms,
+    })
+}
+
+#[hotpath::measure_all]
+impl OgsGame {
+    /// Build a game with `SgfGame::to_game`, failing on any move that
+    /// cannot be played.
+    pub fn to_game<const NW: usize>(&self) -> Result<Game<NW>, OgsError> {
+        Ok(self.record.to_game(IllegalMovePolicy::Fail)?)
+    }
+
+    /// A tree holding the moves as its main line, with the cursor at the
+    /// root.
+    pub fn to_game_tree<const NW: usize>(&self) -> Result<GameTree<NW>, OgsError> {
+        let mut tree = GameTree::new(self.record.start_game()?);
+        for (index, &(player, move_)) in self.record.moves.iter().enumerate() {
+            if !tree.play(&move_) {
+                return Err(OgsError::Sgf(SgfError::Replay(ReplayError {
+                    index,
+                    player,
+                    move_,
+                    issue: ReplayIssue::Illegal,
+                })));
+            }
+        }
+        tree.to_root();
+        Ok(tree)
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Game<NW> {
+    /// Read a game from OGS JSON (see `ogs::parse_ogs_json`). `NW` must be
+    /// `nw_for_board(width, height)` for the record's board size.
+    pub fn from_ogs_json(text: &str) -> Result<Self, OgsError> {
+        parse_ogs_json(text)?.to_game()
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> GameTree<NW> {
+    /// Read a game from OGS JSON as a tree with a single line.
+    pub fn from_ogs_json(text: &str) -> Result<Self, OgsError> {
+        parse_ogs_json(text)?.to_game_tree()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+
+    type Game9 = Game<{ nw_for_board(9, 9) }>;
+
+    const RECORD: &str = r#"{
+        "width": 9, "height": 9, "komi": 0.5, "rules": "japanese",
+        "handicap": 2, "free_handicap_placement": true,
+        "initial_player": "black",
+        "initial_state": {"black": "", "white": "aa"},
+        "moves": [[2, 2, 1000], [6, 6, 1500], [4, 4, 8000], [-1, -1, 2100.5, {"blur": 5}]],
+        "players": {
+            "black": {"id": 11, "username": "alice", "rank": 31.7},
+            "white": {"id": 22, "username": "bob", "rank": 24.2}
+        },
+        "game_name": "Friendly Match", "start_time": 1585699200,
+        "winner": 11, "outcome": "Resignation",
+        "time_control": {"system": "fischer"}
+    }"#;
+
+    #[test]
+    fn test_reads_setup_moves_and_metadata() {
+        let ogs = parse_ogs_json(RECORD).expect("parses");
+        let record = &ogs.record;
+        assert_eq!(
+            record.black_setup,
+            vec![Position::new(2, 6), Position::new(6, 2)]
+        );
+        assert_eq!(record.white_setup, vec![Position::new(0, 8)]);
+        assert_eq!(
+            record.moves,
+            vec![
+                (Player::White, Move::place(4, 4)),
+                (Player::Black, Move::pass())
+            ]
+        );
+        assert_eq!(ogs.move_times_ms, vec![Some(8000), Some(2100)]);
+        assert_eq!(record.ruleset, Some(Ruleset::Japanese));
+
+        let info = &record.info;
+        assert_eq!(info.black_player.as_deref(), Some("alice"));
+        assert_eq!(info.black_rank.as_deref(), Some("2d"));
+        assert_eq!(info.white_rank.as_deref(), Some("6k"));
+        assert_eq!(info.date.as_deref(), Some("2020-04-01"));
+        assert_eq!(info.event.as_deref(), Some("Friendly Match"));
+        assert_eq!(info.result.as_deref(), Some("B+R"));
+
+        let game = Game9::from_ogs_json(RECORD).expect("replays");
+        assert_eq!(game.handicap(), 2);
+        assert_eq!(game.move_count(), 3);
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::resignation(Player::White))
+        );
+
+        let mut tree = GameTree::<{ nw_for_board(9, 9) }>::from_ogs_json(RECORD).expect("replays");
+        assert_eq!(tree.main_line(), vec![Move::place(4, 4), Move::pass()]);
+        assert!(tree.next());
+        assert_eq!(
+            tree.game().get_piece(&Position::new(4, 4)),
+            Some(Player::White as i8)
+        );
+    }
+
+    #[test]
+    fn test_fixed_handicap_and_errors() {
+        let fixed = r#"{"width": 9, "height": 9, "handicap": 2, "moves": [[4, 4, 0]]}"#;
+        let ogs = parse_ogs_json(fixed).expect("parses");
+        assert_eq!(ogs.record.black_setup.len(), 2);
+        assert_eq!(ogs.record.moves, vec![(Player::White, Move::place(4, 4))]);
+        assert_eq!(utc_date(-86_400), "1969-12-31");
+
+        // One handicap stone is just Black moving first without komi.
+        for placement in ["false", "true"] {
+            let text = format!(
+                r#"{{"width": 9, "height": 9, "komi": 0.5, "handicap": 1,
+                    "free_handicap_placement": {}, "moves": [[4, 4, 0], [2, 2, 0]]}}"#,
+                placement
+            );
+            let ogs = parse_ogs_json(&text).expect("parses");
+            assert!(ogs.record.black_setup.is_empty());
+            assert_eq!(
+                ogs.record.moves,
+                vec![
+                    (Player::Black, Move::place(4, 4)),
+                    (Player::White, Move::place(2, 6))
+                ]
+            );
+            assert_eq!(
+                Game9::from_ogs_json(&text).expect("replays").move_count(),
+                2
+            );
+        }
+
+        assert!(matches!(
+            parse_ogs_json(r#"{"width": 30, "height": 9}"#),
+            Err(OgsError::InvalidBoardSize(_))
+        ));
+        assert!(matches!(
+            parse_ogs_json(r#"{"width": 9, "height": 9, "moves": [[9, 0]]}"#),
+            Err(OgsError::InvalidField { field: "move", .. })
+        ));
+        assert!(matches!(parse_ogs_json("{"), Err(OgsError::Json(_))));
+        let illegal = r#"{"width": 9, "height": 9, "moves": [[4, 4], [4, 4]]}"#;
+        assert!(matches!(
+            Game9::from_ogs_json(illegal),
+            Err(OgsError::Sgf(SgfError::Replay(_)))
+        ));
+    }
+}
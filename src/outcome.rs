@@ -1,57 +1,334 @@
+use crate::game::{from_half_points, to_half_points};
 use crate::player::Player;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum GameOutcome {
-    BlackWin,
-    WhiteWin,
-    Draw,
+/// Why a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EndReason {
+    /// Both players passed in a row and the board was scored.
+    DoublePass,
+    /// The move limit was reached and the board was scored.
+    MaxMoves,
+    /// A player was forced to pass and the opponent had no legal placement
+    /// either, so the board was scored.
+    NoLegalMoves,
+    /// A player resigned.
+    Resignation,
+    /// A player ran out of time.
+    Timeout,
+    /// The game ended without a result (abandoned, void).
+    NoResult,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GameOutcome {
+    winner: Option<Player>,
+    /// In half points.
+    margin: Option<i32>,
+    reason: EndReason,
+}
+
+/// `half_points` as an SGF margin: `3.5`, `7`.
+#[hotpath::measure]
+fn format_half_points(half_points: i32) -> String {
+    if half_points % 2 == 0 {
+        (half_points / 2).to_string()
+    } else {
+        format!("{}.5", half_points / 2)
+    }
 }
 
 #[hotpath::measure_all]
 impl GameOutcome {
-    pub fn winner(&self) -> Option<Player> {
-        match self {
-            GameOutcome::BlackWin => Some(Player::Black),
-            GameOutcome::WhiteWin => Some(Player::White),
-            GameOutcome::Draw => None,
+    /// Outcome of a scored game. The margin is black's score minus white's.
+    /// Scores are rounded to the nearest half point.
+    pub fn from_score(black_score: f32, white_score: f32, reason: EndReason) -> Self {
+        GameOutcome::from_half_points(
+            to_half_points(black_score),
+            to_half_points(white_score),
+            reason,
+        )
+    }
+
+    /// Outcome of a game scored in half points, as `Game` scores it.
+    pub fn from_half_points(black_score: i32, white_score: i32, reason: EndReason) -> Self {
+        let winner = if black_score > white_score {
+            Some(Player::Black)
+        } else if white_score > black_score {
+            Some(Player::White)
+        } else {
+            None
+        };
+        GameOutcome {
+            winner,
+            margin: Some(black_score - white_score),
+            reason,
+        }
+    }
+
+    /// `loser` resigned.
+    pub fn resignation(loser: Player) -> Self {
+        GameOutcome {
+            winner: Some(loser.opposite()),
+            margin: None,
+            reason: EndReason::Resignation,
+        }
+    }
+
+    /// `loser` ran out of time.
+    pub fn timeout(loser: Player) -> Self {
+        GameOutcome {
+            winner: Some(loser.opposite()),
+            margin: None,
+            reason: EndReason::Timeout,
+        }
+    }
+
+    /// An outcome from its fields, as read back from a serialized record.
+    #[cfg(feature = "dataset")]
+    pub(crate) fn from_parts(
+        winner: Option<Player>,
+        margin: Option<i32>,
+        reason: EndReason,
+    ) -> Self {
+        GameOutcome {
+            winner,
+            margin,
+            reason,
         }
     }
 
+    pub fn no_result() -> Self {
+        GameOutcome {
+            winner: None,
+            margin: None,
+            reason: EndReason::NoResult,
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        self.winner
+    }
+
+    /// Black's score minus white's (komi included), or None if the game was
+    /// not decided by counting.
+    pub fn margin(&self) -> Option<f32> {
+        self.margin.map(from_half_points)
+    }
+
+    /// `margin()` in half points.
+    pub fn margin_half_points(&self) -> Option<i32> {
+        self.margin
+    }
+
+    pub fn margin_from_perspective(&self, perspective: Player) -> Option<f32> {
+        self.margin().map(|m| match perspective {
+            Player::Black => m,
+            Player::White => -m,
+        })
+    }
+
+    pub fn reason(&self) -> EndReason {
+        self.reason
+    }
+
     pub fn encode_winner_absolute(&self) -> f32 {
-        match self {
-            GameOutcome::BlackWin => 1.0,
-            GameOutcome::WhiteWin => -1.0,
-            GameOutcome::Draw => 0.0,
+        match self.winner {
+            Some(Player::Black) => 1.0,
+            Some(Player::White) => -1.0,
+            None => 0.0,
         }
     }
 
     pub fn encode_winner_from_perspective(&self, perspective: Player) -> f32 {
-        match perspective {
-            Player::Black => match self {
-                GameOutcome::BlackWin => 1.0,
-                GameOutcome::WhiteWin => -1.0,
-                GameOutcome::Draw => 0.0,
-            },
-            Player::White => match self {
-                GameOutcome::BlackWin => -1.0,
-                GameOutcome::WhiteWin => 1.0,
-                GameOutcome::Draw => 0.0,
-            },
+        match self.winner {
+            Some(winner) if winner == perspective => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
         }
     }
 
     pub fn is_draw(&self) -> bool {
-        matches!(self, GameOutcome::Draw)
+        self.winner.is_none() && self.reason != EndReason::NoResult
+    }
+
+    /// The result in SGF `RE` notation: `B+3.5`, `W+R` (resignation), `B+T`
+    /// (timeout), `0` (draw) or `Void` (no result).
+    pub fn to_result_string(&self) -> String {
+        let Some(winner) = self.winner else {
+            return match self.reason {
+                EndReason::NoResult => "Void".to_string(),
+                _ => "0".to_string(),
+            };
+        };
+        let how = match (self.reason, self.margin) {
+            (EndReason::Resignation, _) => "R".to_string(),
+            (EndReason::Timeout, _) => "T".to_string(),
+            (_, Some(margin)) => format_half_points(margin.abs()),
+            (_, None) => String::new(),
+        };
+        format!("{}+{}", winner.to_char(), how)
+    }
+
+    /// Parse SGF `RE` notation as written by `to_result_string`. Also accepts
+    /// the long forms `Resign`/`Time`, `Draw`, `?` for an unknown result, and
+    /// `B+` for a counted win without a margin.
+    pub fn from_result_string(s: &str) -> Result<Self, ParseResultError> {
+        let err = || ParseResultError(s.to_string());
+        let trimmed = s.trim();
+
+        match trimmed.to_ascii_lowercase().as_str() {
+            "0" | "draw" | "jigo" => {
+                return Ok(GameOutcome::from_half_points(0, 0, EndReason::DoublePass))
+            }
+            "void" | "?" => return Ok(GameOutcome::no_result()),
+            _ => {}
+        }
+
+        let (color, how) = trimmed.split_once('+').ok_or_else(err)?;
+        let mut chars = color.chars();
+        let winner = match (chars.next(), chars.next()) {
+            (Some(c), None) => Player::from_char(c.to_ascii_uppercase()).ok_or_else(err)?,
+            _ => return Err(err()),
+        };
+        let loser = winner.opposite();
+
+        match how.to_ascii_lowercase().as_str() {
+            "r" | "resign" => Ok(GameOutcome::resignation(loser)),
+            "t" | "time" => Ok(GameOutcome::timeout(loser)),
+            "" => Ok(GameOutcome {
+                winner: Some(winner),
+                margin: None,
+                reason: EndReason::DoublePass,
+            }),
+            margin => {
+                let margin: f32 = margin.parse().map_err(|_| err())?;
+                if !margin.is_finite() || margin <= 0.0 {
+                    return Err(err());
+                }
+                let margin = to_half_points(margin);
+                if margin == 0 {
+                    return Err(err());
+                }
+                let margin = match winner {
+                    Player::Black => margin,
+                    Player::White => -margin,
+                };
+                Ok(GameOutcome::from_half_points(
+                    margin.max(0),
+                    (-margin).max(0),
+                    EndReason::DoublePass,
+                ))
+            }
+        }
     }
 }
 
+/// A result string that `GameOutcome::from_result_string` could not parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseResultError(pub String);
+
+impl std::fmt::Display for ParseResultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid result string: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseResultError {}
+
 #[hotpath::measure_all]
-impl std::fmt::Display for GameOutcome {
+impl std::fmt::Display for EndReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            GameOutcome::BlackWin => write!(f, "Black wins"),
-            GameOutcome::WhiteWin => write!(f, "White wins"),
-            GameOutcome::Draw => write!(f, "Draw"),
+            EndReason::DoublePass => write!(f, "double pass"),
+            EndReason::MaxMoves => write!(f, "max moves"),
+            EndReason::NoLegalMoves => write!(f, "no legal moves"),
+            EndReason::Resignation => write!(f, "resignation"),
+            EndReason::Timeout => write!(f, "timeout"),
+            EndReason::NoResult => write!(f, "no result"),
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl std::fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.winner, self.margin) {
+            (Some(Player::Black), Some(margin)) => {
+                write!(f, "Black wins by {}", format_half_points(margin))
+            }
+            (Some(Player::White), Some(margin)) => {
+                write!(f, "White wins by {}", format_half_points(-margin))
+            }
+            (Some(Player::Black), None) => write!(f, "Black wins by {}", self.reason),
+            (Some(Player::White), None) => write!(f, "White wins by {}", self.reason),
+            (None, _) if self.reason == EndReason::NoResult => write!(f, "No result"),
+            (None, _) => write!(f, "Draw"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_string_round_trip() {
+        let cases = [
+            (
+                GameOutcome::from_score(10.0, 6.5, EndReason::DoublePass),
+                "B+3.5",
+            ),
+            (
+                GameOutcome::from_score(3.0, 10.0, EndReason::MaxMoves),
+                "W+7",
+            ),
+            (GameOutcome::resignation(Player::Black), "W+R"),
+            (GameOutcome::timeout(Player::White), "B+T"),
+            (
+                GameOutcome::from_score(5.0, 5.0, EndReason::DoublePass),
+                "0",
+            ),
+            (GameOutcome::no_result(), "Void"),
+        ];
+        for (outcome, expected) in cases {
+            assert_eq!(outcome.to_result_string(), expected);
+            let parsed = GameOutcome::from_result_string(expected).expect("parses");
+            assert_eq!(parsed.winner(), outcome.winner());
+            assert_eq!(parsed.margin(), outcome.margin());
+            assert_eq!(parsed.to_result_string(), expected);
         }
     }
+
+    #[test]
+    fn test_parse_long_forms_and_errors() {
+        let parsed = GameOutcome::from_result_string("b+resign").expect("parses");
+        assert_eq!(parsed.reason(), EndReason::Resignation);
+        assert_eq!(parsed.winner(), Some(Player::Black));
+
+        let parsed = GameOutcome::from_result_string("W+").expect("parses");
+        assert_eq!(parsed.winner(), Some(Player::White));
+        assert_eq!(parsed.margin(), None);
+
+        for bad in ["", "B", "X+3", "B+abc", "B+-2", "B+0", "BW+R"] {
+            assert!(GameOutcome::from_result_string(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_margins_are_counted_in_half_points() {
+        let outcome = GameOutcome::from_half_points(20, 33, EndReason::DoublePass);
+        assert_eq!(outcome.winner(), Some(Player::White));
+        assert_eq!(outcome.margin_half_points(), Some(-13));
+        assert_eq!(outcome.margin(), Some(-6.5));
+        assert_eq!(outcome.to_string(), "White wins by 6.5");
+        assert_eq!(
+            outcome,
+            GameOutcome::from_score(10.0, 16.5, EndReason::DoublePass)
+        );
+
+        // Off-grid margins are rounded to the nearest half point.
+        let parsed = GameOutcome::from_result_string("B+2.4").expect("parses");
+        assert_eq!(parsed.margin_half_points(), Some(5));
+        assert!(GameOutcome::from_result_string("B+0.1").is_err());
+    }
 }
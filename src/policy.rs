@@ -0,0 +1,1311 @@
+use std::collections::VecDeque;
+
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::RngExt;
+
+use crate::bitboard::{Bitboard, BoardGeometry};
+#[cfg(feature = "gtp")]
+use crate::board::Board;
+pub use crate::dispatch::AnySizePolicy;
+use crate::encode;
+use crate::game::Game;
+#[cfg(feature = "gtp")]
+use crate::gtp::{FinalStatus, GenmoveResult, GtpClient, GtpError};
+use crate::outcome::{EndReason, GameOutcome};
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+use crate::rollout::{estimate_dead_stones, RolloutConfig};
+
+/// Picks the next move for the side to play in `game`.
+///
+/// The match runner, self-play and the GTP engine all drive games through
+/// this trait, so any policy can play against any other. Policies should
+/// return a legal move; the caller stops the game when `make_move` rejects it.
+pub trait MovePolicy<const NW: usize> {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move;
+
+    /// The stones this policy considers dead once `game` reaches the
+    /// scoring phase. By default, the groups random playouts judge dead.
+    fn dead_stones(&mut self, game: &Game<NW>) -> Vec<Position> {
+        playout_dead_stones(game)
+    }
+
+    /// Play resumes after the players disagreed about dead stones: until the
+    /// next two passes, capture the stones this policy considers dead rather
+    /// than passing. `game` is the position play resumes from.
+    fn begin_cleanup(&mut self, _game: &Game<NW>) {}
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize, P: MovePolicy<NW> + ?Sized> MovePolicy<NW> for Box<P> {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        (**self).choose(game, rng)
+    }
+
+    fn dead_stones(&mut self, game: &Game<NW>) -> Vec<Position> {
+        (**self).dead_stones(game)
+    }
+
+    fn begin_cleanup(&mut self, game: &Game<NW>) {
+        (**self).begin_cleanup(game)
+    }
+}
+
+/// Stones of the groups random playouts judge dead (see
+/// `rollout::estimate_dead_stones`).
+#[hotpath::measure]
+fn playout_dead_stones<const NW: usize>(game: &Game<NW>) -> Vec<Position> {
+    estimate_dead_stones(game, &RolloutConfig::default())
+        .iter_ones()
+        .map(|idx| Position::from_index(idx, game.width()))
+        .collect()
+}
+
+/// Empty points whose neighbors are all stones of the side to move.
+#[hotpath::measure]
+fn own_eyes<const NW: usize>(game: &Game<NW>, geo: &BoardGeometry<NW>) -> Bitboard<NW> {
+    let board = game.board();
+    let empty = board.empty_squares(geo.board_mask);
+    let opponent = board.stones_for(game.turn().opposite());
+    empty.andnot(geo.neighbors(&(empty | opponent)))
+}
+
+/// Legal placements that do not fill one of the mover's own single-point eyes.
+#[hotpath::measure]
+fn non_eye_placements<const NW: usize>(game: &Game<NW>) -> Vec<Move> {
+    let geo = BoardGeometry::cached(game.width(), game.height());
+    let eyes = own_eyes(game, &geo);
+    game.legal_moves()
+        .into_iter()
+        .filter(|m| match m.position() {
+            Some(pos) => !eyes.get(pos.to_index(geo.width)),
+            None => false,
+        })
+        .collect()
+}
+
+/// Pass when legal, otherwise any legal move (pass may be forbidden before
+/// the game's minimum move count).
+#[hotpath::measure]
+fn pass_or_any<const NW: usize>(game: &Game<NW>, rng: &mut StdRng) -> Move {
+    if game.is_legal_move(&Move::pass()) {
+        Move::pass()
+    } else {
+        game.legal_moves()
+            .choose(rng)
+            .copied()
+            .unwrap_or(Move::pass())
+    }
+}
+
+/// Uniformly random legal move, passes included.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformPolicy;
+
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for UniformPolicy {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        game.legal_moves()
+            .choose(rng)
+            .copied()
+            .unwrap_or(Move::pass())
+    }
+}
+
+/// Random placement that never fills the mover's own single-point eyes, so
+/// living groups stay alive; passes once no such placement is left.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoEyeFillPolicy;
+
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for NoEyeFillPolicy {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        match non_eye_placements(game).choose(rng) {
+            Some(m) => *m,
+            None => pass_or_any(game, rng),
+        }
+    }
+}
+
+/// `NoEyeFillPolicy` with simple tactics: capture when possible, otherwise
+/// avoid moves that leave the placed group in atari.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeuristicPolicy;
+
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for HeuristicPolicy {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        let geo = BoardGeometry::cached(game.width(), game.height());
+        let player = game.turn();
+        let opponent_stones = game.board().stones_for(player.opposite()).count();
+
+        let mut captures = Vec::new();
+        let mut safe = Vec::new();
+        for m in non_eye_placements(game) {
+            let Some(pos) = m.position() else {
+                continue;
+            };
+            let idx = pos.to_index(geo.width);
+            let after = game.simulate_placement(idx, player);
+
+            if after.stones_for(player.opposite()).count() < opponent_stones {
+                captures.push(m);
+                continue;
+            }
+
+            let group = geo.flood_fill(Bitboard::single(idx), after.stones_for(player));
+            let liberties = geo.neighbors(&group) & after.empty_squares(geo.board_mask);
+            if liberties.count() > 1 {
+                safe.push(m);
+            }
+        }
+
+        if let Some(m) = captures.choose(rng).or_else(|| safe.choose(rng)) {
+            *m
+        } else {
+            pass_or_any(game, rng)
+        }
+    }
+}
+
+/// A node of the `MctsPolicy` search tree.
+struct Node {
+    move_: Move,
+    /// The player who made `move_`; wins are counted for them.
+    player: Player,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    wins: f32,
+}
+
+/// Moves searched from a position: legal moves except own-eye fills, falling
+/// back to all legal moves when nothing else is left.
+#[hotpath::measure]
+fn tree_moves<const NW: usize>(game: &Game<NW>) -> Vec<Move> {
+    let mut moves = non_eye_placements(game);
+    if game.is_legal_move(&Move::pass()) {
+        moves.push(Move::pass());
+    }
+    if moves.is_empty() {
+        moves = game.legal_moves();
+    }
+    moves
+}
+
+/// How a playout that comes back to a recent position ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "lowercase"))]
+pub enum CycleOutcome {
+    /// Score the board as it stands when the position repeats.
+    #[default]
+    Score,
+    /// No winner; the playout counts as half a win for both sides and adds
+    /// nothing to ownership estimates.
+    Draw,
+}
+
+#[hotpath::measure_all]
+impl CycleOutcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CycleOutcome::Score => "score",
+            CycleOutcome::Draw => "draw",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CycleOutcome> {
+        match name.trim().to_lowercase().as_str() {
+            "score" => Some(CycleOutcome::Score),
+            "draw" => Some(CycleOutcome::Draw),
+            _ => None,
+        }
+    }
+}
+
+/// Stops playouts that loop. Playouts run without superko, so a triple ko or
+/// other long cycle would otherwise go on until the move limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CycleDetection {
+    /// Number of recent positions a new position is compared with. 0 turns
+    /// detection off.
+    pub window: usize,
+    pub outcome: CycleOutcome,
+}
+
+#[hotpath::measure_all]
+impl Default for CycleDetection {
+    fn default() -> Self {
+        CycleDetection {
+            window: 32,
+            outcome: CycleOutcome::default(),
+        }
+    }
+}
+
+/// Play `NoEyeFillPolicy` moves from `game`'s position, with `komi`, until
+/// the game ends or a position of the last `cycle.window` moves comes back.
+/// The ko point and rules carry over (see `Game::playout_start`). Returns
+/// the final game and whether a repeated position stopped it.
+#[hotpath::measure]
+pub(crate) fn playout<const NW: usize>(
+    game: &Game<NW>,
+    komi: f32,
+    rng: &mut StdRng,
+    cycle: &CycleDetection,
+) -> (Game<NW>, bool) {
+    let mut playout = game.playout_start(komi);
+    let mut recent = VecDeque::with_capacity(cycle.window);
+
+    while !playout.is_over() {
+        let move_ = NoEyeFillPolicy.choose(&playout, rng);
+        if !playout.make_move(&move_) {
+            break;
+        }
+        if cycle.window == 0 || playout.is_over() {
+            continue;
+        }
+        let hash = playout.position_hash();
+        if recent.contains(&hash) {
+            return (playout, true);
+        }
+        if recent.len() == cycle.window {
+            recent.pop_front();
+        }
+        recent.push_back(hash);
+    }
+    (playout, false)
+}
+
+/// Play `game` out with `NoEyeFillPolicy` and return the winner.
+#[hotpath::measure]
+fn playout_winner<const NW: usize>(
+    game: &Game<NW>,
+    rng: &mut StdRng,
+    cycle: &CycleDetection,
+) -> Option<Player> {
+    if game.is_over() {
+        return game.outcome().and_then(|o| o.winner());
+    }
+    finish_playout(game, rng, cycle).1
+}
+
+/// Play an unfinished `game` out with `NoEyeFillPolicy`. Returns the final
+/// game and its winner, judging a playout cut short by `cycle` as
+/// `cycle.outcome` says.
+#[hotpath::measure]
+pub(crate) fn finish_playout<const NW: usize>(
+    game: &Game<NW>,
+    rng: &mut StdRng,
+    cycle: &CycleDetection,
+) -> (Game<NW>, Option<Player>) {
+    let (playout, cycled) = playout(game, game.effective_komi(), rng, cycle);
+    let winner = if !cycled {
+        playout.outcome().and_then(|o| o.winner())
+    } else {
+        match cycle.outcome {
+            CycleOutcome::Score => {
+                let (black, white) = playout.score_fast_half_points();
+                GameOutcome::from_half_points(black, white, EndReason::MaxMoves).winner()
+            }
+            CycleOutcome::Draw => None,
+        }
+    };
+    (playout, winner)
+}
+
+/// Seed for game `index` of a run started with `base_seed`.
+///
+/// Each game gets its own RNG stream derived from the run seed and its index
+/// (a SplitMix64 step), so results do not depend on how many threads play
+/// the games or in which order they are scheduled. Playout batches seed
+/// each playout the same way.
+#[hotpath::measure]
+pub fn derive_game_seed(base_seed: u64, index: u64) -> u64 {
+    let mut z = base_seed ^ index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Results of a batch of playouts from one position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayoutCounts {
+    pub black_wins: u32,
+    pub white_wins: u32,
+    /// Playouts with no winner, e.g. cut short by `CycleOutcome::Draw`.
+    pub draws: u32,
+}
+
+#[hotpath::measure_all]
+impl PlayoutCounts {
+    pub fn total(&self) -> u32 {
+        self.black_wins + self.white_wins + self.draws
+    }
+
+    pub(crate) fn record(&mut self, winner: Option<Player>) {
+        match winner {
+            Some(Player::Black) => self.black_wins += 1,
+            Some(Player::White) => self.white_wins += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: &PlayoutCounts) {
+        self.black_wins += other.black_wins;
+        self.white_wins += other.white_wins;
+        self.draws += other.draws;
+    }
+}
+
+/// Play `game` out `playouts` times with `NoEyeFillPolicy` and count the
+/// winners. A finished game counts its own result every time.
+#[hotpath::measure]
+pub fn playout_counts<const NW: usize>(
+    game: &Game<NW>,
+    playouts: u32,
+    rng: &mut StdRng,
+    cycle: &CycleDetection,
+) -> PlayoutCounts {
+    let mut counts = PlayoutCounts::default();
+    for _ in 0..playouts {
+        counts.record(playout_winner(game, rng, cycle));
+    }
+    counts
+}
+
+/// Upper confidence bound tree search with `NoEyeFillPolicy` playouts. Plays
+/// the most visited move after `iterations` playouts.
+#[derive(Clone, Copy, Debug)]
+pub struct MctsPolicy {
+    pub iterations: u32,
+    /// UCT exploration constant.
+    pub exploration: f32,
+    /// How looping playouts are cut short.
+    pub cycle: CycleDetection,
+}
+
+#[hotpath::measure_all]
+impl Default for MctsPolicy {
+    fn default() -> Self {
+        MctsPolicy {
+            iterations: 1000,
+            exploration: std::f32::consts::SQRT_2,
+            cycle: CycleDetection::default(),
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl MctsPolicy {
+    pub fn new(iterations: u32) -> Self {
+        MctsPolicy {
+            iterations,
+            ..Self::default()
+        }
+    }
+
+    fn select_child(&self, nodes: &[Node], node: usize) -> usize {
+        let log_visits = (nodes[node].visits.max(1) as f32).ln();
+        let uct = |child: usize| {
+            let n = &nodes[child];
+            let visits = n.visits.max(1) as f32;
+            n.wins / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+        nodes[node]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| uct(a).total_cmp(&uct(b)))
+            .expect("select_child called on a leaf")
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for MctsPolicy {
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        let root_moves = tree_moves(game);
+        if root_moves.len() <= 1 {
+            return root_moves.first().copied().unwrap_or(Move::pass());
+        }
+
+        let mut nodes = vec![Node {
+            move_: Move::pass(),
+            player: game.turn().opposite(),
+            parent: None,
+            children: Vec::new(),
+            untried: root_moves,
+            visits: 0,
+            wins: 0.0,
+        }];
+
+        for _ in 0..self.iterations {
+            let mut sim = game.clone();
+            let mut node = 0;
+
+            while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+                node = self.select_child(&nodes, node);
+                sim.make_move(&nodes[node].move_);
+            }
+
+            if !nodes[node].untried.is_empty() {
+                let i = rng.random_range(0..nodes[node].untried.len());
+                let move_ = nodes[node].untried.swap_remove(i);
+                let player = sim.turn();
+                sim.make_move(&move_);
+
+                let child = nodes.len();
+                nodes.push(Node {
+                    move_,
+                    player,
+                    parent: Some(node),
+                    children: Vec::new(),
+                    untried: if sim.is_over() {
+                        Vec::new()
+                    } else {
+                        tree_moves(&sim)
+                    },
+                    visits: 0,
+                    wins: 0.0,
+                });
+                nodes[node].children.push(child);
+                node = child;
+            }
+
+            let winner = playout_winner(&sim, rng, &self.cycle);
+            let mut current = Some(node);
+            while let Some(idx) = current {
+                let n = &mut nodes[idx];
+                n.visits += 1;
+                n.wins += match winner {
+                    Some(p) if p == n.player => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+                current = n.parent;
+            }
+        }
+
+        nodes[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| nodes[child].visits)
+            .map_or(Move::pass(), |&child| nodes[child].move_)
+    }
+}
+
+/// An evaluator's judgement of one position: a prior for each action in the
+/// layout of `encode::encode_move_with` under the searching policy's
+/// `encoder`, and the expected result for the player to move, from -1.0 for
+/// a loss to 1.0 for a win.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Evaluation {
+    /// Need not sum to 1; priors are normalized over the legal moves. An
+    /// empty or all-zero policy gives every legal move the same prior.
+    pub policy: Vec<f32>,
+    pub value: f32,
+}
+
+/// Judges positions for `PuctPolicy`, e.g. a neural network.
+pub trait Evaluator<const NW: usize> {
+    /// `None` stops the search, e.g. when inference fails; the move is then
+    /// chosen from the visits so far.
+    fn evaluate(&mut self, game: &Game<NW>) -> Option<Evaluation>;
+}
+
+struct PuctNode {
+    move_: Move,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    prior: f32,
+    visits: u32,
+    /// Sum of the values backed up through this node, for the player who
+    /// made `move_`.
+    value_sum: f32,
+}
+
+/// Tree search guided by an `Evaluator`, as in AlphaZero: priors steer the
+/// exploration and leaf values replace playouts. Plays the most visited move
+/// after `iterations` evaluations.
+pub struct PuctPolicy<E> {
+    pub evaluator: E,
+    pub iterations: u32,
+    /// Weight of the prior against the value in child selection.
+    pub exploration: f32,
+    /// The action layout the evaluator's policy is indexed by, as the
+    /// network was trained with.
+    pub encoder: encode::EncoderConfig,
+}
+
+#[hotpath::measure_all]
+impl<E> PuctPolicy<E> {
+    pub fn new(evaluator: E, iterations: u32) -> Self {
+        PuctPolicy {
+            evaluator,
+            iterations,
+            exploration: 1.5,
+            encoder: encode::EncoderConfig::default(),
+        }
+    }
+
+    fn select_child(&self, nodes: &[PuctNode], node: usize) -> usize {
+        let scale = self.exploration * (nodes[node].visits.max(1) as f32).sqrt();
+        let score = |child: usize| {
+            let n = &nodes[child];
+            let q = if n.visits == 0 {
+                0.0
+            } else {
+                n.value_sum / n.visits as f32
+            };
+            q + scale * n.prior / (1 + n.visits) as f32
+        };
+        nodes[node]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&a, &b| score(a).total_cmp(&score(b)))
+            .expect("select_child called on a leaf")
+    }
+
+    /// Search from `game` and return the visit count of each root move, in
+    /// legal move order. Empty when the game is over or the evaluator stops
+    /// on the root.
+    pub fn search<const NW: usize>(&mut self, game: &Game<NW>) -> Vec<(Move, u32)>
+    where
+        E: Evaluator<NW>,
+    {
+        if game.is_over() {
+            return Vec::new();
+        }
+        let mut nodes = vec![PuctNode {
+            move_: Move::pass(),
+            parent: None,
+            children: Vec::new(),
+            prior: 1.0,
+            visits: 0,
+            value_sum: 0.0,
+        }];
+
+        for _ in 0..self.iterations.max(1) {
+            let mut sim = game.clone();
+            let mut node = 0;
+            while !nodes[node].children.is_empty() {
+                node = self.select_child(&nodes, node);
+                sim.make_move(&nodes[node].move_);
+            }
+
+            // The value of the leaf for the player to move there.
+            let value = match sim.outcome() {
+                Some(outcome) => match outcome.winner() {
+                    Some(winner) if winner == sim.turn() => 1.0,
+                    Some(_) => -1.0,
+                    None => 0.0,
+                },
+                None => {
+                    let Some(evaluation) = self.evaluator.evaluate(&sim) else {
+                        break;
+                    };
+                    expand(&mut nodes, node, &sim, &evaluation.policy, &self.encoder);
+                    evaluation.value
+                }
+            };
+
+            let mut value = -value;
+            let mut current = Some(node);
+            while let Some(idx) = current {
+                let n = &mut nodes[idx];
+                n.visits += 1;
+                n.value_sum += value;
+                value = -value;
+                current = n.parent;
+            }
+        }
+
+        nodes[0]
+            .children
+            .iter()
+            .map(|&child| (nodes[child].move_, nodes[child].visits))
+            .collect()
+    }
+}
+
+/// Add a child of `node` for every legal move of `game`, with its prior from
+/// `policy`, laid out as `config` encodes actions.
+#[hotpath::measure]
+fn expand<const NW: usize>(
+    nodes: &mut Vec<PuctNode>,
+    node: usize,
+    game: &Game<NW>,
+    policy: &[f32],
+    config: &encode::EncoderConfig,
+) {
+    let moves = game.legal_moves();
+    let priors: Vec<f32> = moves
+        .iter()
+        .map(|m| {
+            let action = encode::encode_move_with(m, game, config);
+            policy.get(action).copied().unwrap_or(0.0).max(0.0)
+        })
+        .collect();
+    let total: f32 = priors.iter().sum();
+    let uniform = 1.0 / moves.len() as f32;
+    for (move_, prior) in moves.into_iter().zip(priors) {
+        let child = nodes.len();
+        nodes.push(PuctNode {
+            move_,
+            parent: Some(node),
+            children: Vec::new(),
+            prior: if total > 0.0 { prior / total } else { uniform },
+            visits: 0,
+            value_sum: 0.0,
+        });
+        nodes[node].children.push(child);
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize, E: Evaluator<NW>> MovePolicy<NW> for PuctPolicy<E> {
+    fn choose(&mut self, game: &Game<NW>, _rng: &mut StdRng) -> Move {
+        self.search(game)
+            .into_iter()
+            .max_by_key(|&(_, visits)| visits)
+            .map_or(Move::pass(), |(move_, _)| move_)
+    }
+}
+
+/// Delegates to a callback, e.g. a neural network evaluator or a Python
+/// function held by the bindings.
+pub struct ExternalPolicy<F> {
+    callback: F,
+}
+
+#[hotpath::measure_all]
+impl<F> ExternalPolicy<F> {
+    pub fn new(callback: F) -> Self {
+        ExternalPolicy { callback }
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize, F> MovePolicy<NW> for ExternalPolicy<F>
+where
+    F: FnMut(&Game<NW>) -> Move,
+{
+    fn choose(&mut self, game: &Game<NW>, _rng: &mut StdRng) -> Move {
+        (self.callback)(game)
+    }
+}
+
+/// Playing strength of the policies from `preset`, weakest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    /// Random placements that leave the mover's own eyes alone.
+    Random,
+    /// Random play that captures when it can and avoids self-atari.
+    Weak,
+    /// `MctsPolicy` with its default 1000 playouts.
+    Medium,
+    /// `MctsPolicy` with 10000 playouts, or `PuctPolicy` with an evaluator.
+    Strong,
+}
+
+#[hotpath::measure_all]
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Random,
+        Difficulty::Weak,
+        Difficulty::Medium,
+        Difficulty::Strong,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Random => "random",
+            Difficulty::Weak => "weak",
+            Difficulty::Medium => "medium",
+            Difficulty::Strong => "strong",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name.trim().to_lowercase().as_str() {
+            "random" => Some(Difficulty::Random),
+            "weak" => Some(Difficulty::Weak),
+            "medium" => Some(Difficulty::Medium),
+            "strong" => Some(Difficulty::Strong),
+            _ => None,
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Playouts of the strong preset without an evaluator.
+const STRONG_MCTS_ITERATIONS: u32 = 10_000;
+
+/// Evaluations per move of the strong preset with an evaluator.
+const STRONG_PUCT_ITERATIONS: u32 = 800;
+
+/// A ready-made opponent of the given strength, for embedding the engine
+/// without tuning search parameters.
+#[hotpath::measure]
+pub fn preset<const NW: usize>(difficulty: Difficulty) -> Box<dyn MovePolicy<NW>> {
+    match difficulty {
+        Difficulty::Random => Box::new(NoEyeFillPolicy),
+        Difficulty::Weak => Box::new(HeuristicPolicy),
+        Difficulty::Medium => Box::new(MctsPolicy::default()),
+        Difficulty::Strong => Box::new(MctsPolicy::new(STRONG_MCTS_ITERATIONS)),
+    }
+}
+
+/// `preset`, with `Difficulty::Strong` searching with `evaluator`, e.g. a
+/// neural network, instead of playouts.
+#[hotpath::measure]
+pub fn preset_with_evaluator<const NW: usize, E: Evaluator<NW> + 'static>(
+    difficulty: Difficulty,
+    evaluator: E,
+) -> Box<dyn MovePolicy<NW>> {
+    match difficulty {
+        Difficulty::Strong => Box::new(PuctPolicy::new(evaluator, STRONG_PUCT_ITERATIONS)),
+        _ => preset(difficulty),
+    }
+}
+
+/// Asks a GTP engine for its move, replaying the game to it first.
+///
+/// Moves already sent are remembered, so only the new ones are played before
+/// each `genmove`; after an undo or a new game the engine is cleared and the
+/// whole game is sent again, setup stones first. If the engine fails, or the
+/// game cannot be given to it with `play` (a swap, or a setup with a ko or
+/// captures), the policy resigns and the error is kept in `last_error`.
+#[cfg(feature = "gtp")]
+pub struct GtpPolicy {
+    client: GtpClient,
+    synced: Vec<Move>,
+    synced_size: Option<(u8, u8)>,
+    synced_setup: Vec<(Player, Position)>,
+    synced_komi: f32,
+    /// The moves of the game when cleanup began; later positions of the same
+    /// game use `kgs-genmove_cleanup`.
+    cleanup_from: Option<Vec<Move>>,
+    last_error: Option<GtpError>,
+}
+
+/// The stones `game` started with, Black's then White's, in the order they
+/// are played to the engine. Errors if the start has a ko point, which
+/// `play` cannot set, or if playing a stone would leave it or a neighboring
+/// chain without liberties, so the engine would capture or refuse it.
+#[cfg(feature = "gtp")]
+#[hotpath::measure]
+fn setup_stones<const NW: usize>(game: &Game<NW>) -> Result<Vec<(Player, Position)>, GtpError> {
+    let (start, ko_point) = game.start_position();
+    if ko_point.is_some() {
+        return Err(GtpError::UnsupportedPosition(
+            "the starting position has a ko point".to_string(),
+        ));
+    }
+    let width = start.width();
+    let geo = BoardGeometry::<NW>::cached(width, start.height());
+    let mut board = Board::<NW>::new(width, start.height());
+    let mut stones = Vec::new();
+    for player in [Player::Black, Player::White] {
+        for idx in start.stones_for(player).iter_ones() {
+            board.set_bit(idx, player);
+            let empty = board.empty_squares(geo.board_mask);
+            let has_liberties = |seed: Bitboard<NW>, owner: Player| {
+                let chain = geo.flood_fill(seed, board.stones_for(owner));
+                (geo.neighbors(&chain) & empty).is_nonzero()
+            };
+            let placed = Bitboard::single(idx);
+            let opponents = geo.neighbors(&placed) & board.stones_for(player.opposite());
+            let captures = !has_liberties(placed, player)
+                || opponents
+                    .iter_ones()
+                    .any(|n| !has_liberties(Bitboard::single(n), player.opposite()));
+            let pos = Position::from_index(idx, width);
+            if captures {
+                return Err(GtpError::UnsupportedPosition(format!(
+                    "setup stone at {:?} has no liberties when played",
+                    pos
+                )));
+            }
+            stones.push((player, pos));
+        }
+    }
+    Ok(stones)
+}
+
+#[cfg(feature = "gtp")]
+#[hotpath::measure_all]
+impl GtpPolicy {
+    pub fn new(client: GtpClient) -> Self {
+        GtpPolicy {
+            client,
+            synced: Vec::new(),
+            synced_size: None,
+            synced_setup: Vec::new(),
+            synced_komi: 0.0,
+            cleanup_from: None,
+            last_error: None,
+        }
+    }
+
+    /// The error that made the last `choose` resign, if any.
+    pub fn last_error(&self) -> Option<&GtpError> {
+        self.last_error.as_ref()
+    }
+
+    fn sync<const NW: usize>(&mut self, game: &Game<NW>) -> Result<(), GtpError> {
+        let size = (game.width(), game.height());
+        let history = game.move_history();
+        let setup = setup_stones(game)?;
+        let komi = game.effective_komi();
+
+        if self.synced_size != Some(size)
+            || self.synced_setup != setup
+            || self.synced_komi != komi
+            || !history.starts_with(&self.synced)
+        {
+            if size.0 != size.1 {
+                return Err(GtpError::UnsupportedBoardSize(size.0.max(size.1)));
+            }
+            self.client.boardsize(size.0)?;
+            self.client.clear_board()?;
+            self.client.komi(komi)?;
+            for (player, pos) in &setup {
+                self.client
+                    .play(*player, &Move::place(pos.col, pos.row), size.1)?;
+            }
+            self.synced.clear();
+            self.synced_size = Some(size);
+            self.synced_setup = setup;
+            self.synced_komi = komi;
+        }
+
+        for (player, move_) in game.moves_by_player().skip(self.synced.len()) {
+            match move_ {
+                Move::Swap => {
+                    return Err(GtpError::UnsupportedPosition(
+                        "the swap rule cannot be played over GTP".to_string(),
+                    ));
+                }
+                Move::Resign => {}
+                _ => self.client.play(player, &move_, size.1)?,
+            }
+            self.synced.push(move_);
+        }
+        Ok(())
+    }
+
+    fn genmove<const NW: usize>(&mut self, game: &Game<NW>) -> Result<Move, GtpError> {
+        self.sync(game)?;
+        let cleanup = self
+            .cleanup_from
+            .as_ref()
+            .is_some_and(|from| game.move_history().starts_with(from));
+        let generated = if cleanup {
+            self.client.genmove_cleanup(game.turn(), game.height())?
+        } else {
+            self.client.genmove(game.turn(), game.height())?
+        };
+        let move_ = match generated {
+            GenmoveResult::Move(m) => m,
+            GenmoveResult::Resign => Move::resign(),
+        };
+        // The engine has played the move on its board; the caller will too.
+        if !move_.is_resign() {
+            self.synced.push(move_);
+        }
+        Ok(move_)
+    }
+}
+
+#[cfg(feature = "gtp")]
+#[hotpath::measure_all]
+impl<const NW: usize> MovePolicy<NW> for GtpPolicy {
+    fn choose(&mut self, game: &Game<NW>, _rng: &mut StdRng) -> Move {
+        match self.genmove(game) {
+            Ok(m) => {
+                self.last_error = None;
+                m
+            }
+            Err(e) => {
+                self.synced_size = None;
+                self.last_error = Some(e);
+                Move::resign()
+            }
+        }
+    }
+
+    /// The engine's `final_status_list dead`, or the playout estimate if the
+    /// engine fails.
+    fn dead_stones(&mut self, game: &Game<NW>) -> Vec<Position> {
+        let listed = self.sync(game).and_then(|()| {
+            self.client
+                .final_status_list(FinalStatus::Dead, game.height())
+        });
+        match listed {
+            Ok(dead) => dead,
+            Err(e) => {
+                self.synced_size = None;
+                self.last_error = Some(e);
+                playout_dead_stones(game)
+            }
+        }
+    }
+
+    fn begin_cleanup(&mut self, game: &Game<NW>) {
+        self.cleanup_from = Some(game.move_history());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::position::Position;
+    use crate::rules::Ruleset;
+    use rand::SeedableRng;
+
+    type Game7 = Game<{ nw_for_board(7, 7) }>;
+
+    #[test]
+    fn test_random_policies_play_legal_moves() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for policy in [
+            &mut UniformPolicy as &mut dyn MovePolicy<{ nw_for_board(7, 7) }>,
+            &mut NoEyeFillPolicy,
+            &mut HeuristicPolicy,
+        ] {
+            let mut game = Game7::with_options(7, 7, 7.5, 10, 200, true);
+            while !game.is_over() {
+                let move_ = policy.choose(&game, &mut rng);
+                assert!(game.make_move(&move_), "illegal move {}", move_);
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_eye_fill_keeps_own_eye() {
+        // Black surrounds the corner point A1; it is Black's only empty point.
+        let mut board = crate::board::Board::new(2, 2);
+        board.set_piece(&Position::new(1, 0), Some(Player::Black));
+        board.set_piece(&Position::new(0, 1), Some(Player::Black));
+        board.set_piece(&Position::new(1, 1), Some(Player::Black));
+        let game = Game::<{ nw_for_board(2, 2) }>::from_position(board, Player::Black, 0.0);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(NoEyeFillPolicy.choose(&game, &mut rng), Move::pass());
+        assert_eq!(HeuristicPolicy.choose(&game, &mut rng), Move::pass());
+    }
+
+    #[test]
+    fn test_heuristic_and_mcts_take_capture() {
+        // A white 2x2 block in atari; Black C4 captures it.
+        let mut board = crate::board::Board::new(5, 5);
+        for (col, row) in [(1, 1), (2, 1), (1, 2), (2, 2), (4, 4), (4, 3)] {
+            board.set_piece(&Position::new(col, row), Some(Player::White));
+        }
+        for (col, row) in [(1, 0), (2, 0), (0, 1), (3, 1), (0, 2), (3, 2), (1, 3)] {
+            board.set_piece(&Position::new(col, row), Some(Player::Black));
+        }
+        let game = Game::<{ nw_for_board(5, 5) }>::from_position(board, Player::Black, 0.5);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(HeuristicPolicy.choose(&game, &mut rng), Move::place(2, 3));
+        assert_eq!(
+            MctsPolicy::new(500).choose(&game, &mut rng),
+            Move::place(2, 3)
+        );
+    }
+
+    #[test]
+    fn test_playouts_stop_on_repeated_position() {
+        // Several short-of-liberties groups; without superko, random play
+        // here often recaptures back into an earlier position.
+        let game =
+            Game::<{ nw_for_board(4, 4) }>::from_position_string("W1WW/B1B1/WW1W/1W1B b - 0 0 0")
+                .expect("parses");
+        let mut rng = StdRng::seed_from_u64(0);
+        let off = CycleDetection {
+            window: 0,
+            ..CycleDetection::default()
+        };
+        let on = CycleDetection::default();
+
+        let mut cycles = 0;
+        for _ in 0..20 {
+            let (full, cycled) = playout(&game, 0.5, &mut rng, &off);
+            assert!(!cycled);
+            assert!(full.is_over());
+
+            let (short, cycled) = playout(&game, 0.5, &mut rng, &on);
+            if cycled {
+                cycles += 1;
+                assert!(!short.is_over());
+                assert!(short.move_number() < short.max_moves() as u32);
+            }
+        }
+        assert!(cycles > 0);
+
+        let mut draws = MctsPolicy {
+            cycle: CycleDetection {
+                outcome: CycleOutcome::Draw,
+                ..on
+            },
+            ..MctsPolicy::new(100)
+        };
+        let move_ = draws.choose(&game, &mut rng);
+        assert!(game.is_legal_move(&move_));
+    }
+
+    #[test]
+    fn test_playouts_respect_the_ko() {
+        // Black has just taken the ko at (1, 1) with (2, 1); White may not
+        // take back at once, in the playout either.
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.set_position(
+            &[
+                Position::new(1, 0),
+                Position::new(0, 1),
+                Position::new(1, 2),
+                Position::new(2, 1),
+            ],
+            &[
+                Position::new(2, 0),
+                Position::new(3, 1),
+                Position::new(2, 2),
+            ],
+            Player::White,
+            Some(Position::new(1, 1)),
+            0,
+        )
+        .expect("a legal position");
+        game.set_ruleset(Ruleset::Japanese);
+        game.set_allow_suicide(true);
+
+        let start = game.playout_start(0.5);
+        assert_eq!(start.ko_point(), Some(Position::new(1, 1)));
+        assert_eq!(start.ruleset(), Ruleset::Japanese);
+        assert!(start.allow_suicide());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let (playout, _) = playout(&game, 0.5, &mut rng, &CycleDetection::default());
+            assert_ne!(playout.move_history()[0], Move::place(1, 1));
+        }
+    }
+
+    #[test]
+    fn test_external_policy_calls_back() {
+        let mut calls = 0;
+        let mut policy = ExternalPolicy::new(|game: &Game7| {
+            calls += 1;
+            game.legal_moves()[0]
+        });
+        let mut game = Game7::new(7, 7);
+        let mut rng = StdRng::seed_from_u64(0);
+        let move_ = policy.choose(&game, &mut rng);
+        assert!(game.make_move(&move_));
+        assert_eq!(calls, 1);
+    }
+
+    /// Puts all its prior on one action, with a neutral value, for a
+    /// limited number of positions.
+    struct FixedEvaluator {
+        action: usize,
+        remaining: u32,
+    }
+
+    impl<const NW: usize> Evaluator<NW> for FixedEvaluator {
+        fn evaluate(&mut self, game: &Game<NW>) -> Option<Evaluation> {
+            self.remaining = self.remaining.checked_sub(1)?;
+            let mut policy = vec![0.0; encode::total_actions_for(game)];
+            policy[self.action] = 1.0;
+            Some(Evaluation { policy, value: 0.0 })
+        }
+    }
+
+    #[test]
+    fn test_puct_follows_priors_and_stops_with_the_evaluator() {
+        let game = Game7::new(7, 7);
+        let mut rng = StdRng::seed_from_u64(0);
+        let evaluator = FixedEvaluator {
+            action: 24,
+            remaining: u32::MAX,
+        };
+        let mut policy = PuctPolicy::new(evaluator, 50);
+        assert_eq!(policy.choose(&game, &mut rng), Move::place(3, 3));
+
+        // The first evaluation is the root's; each later one is a visit.
+        policy.evaluator.remaining = 10;
+        let visits: u32 = policy.search(&game).iter().map(|&(_, v)| v).sum();
+        assert_eq!(visits, 9);
+        policy.evaluator.remaining = 0;
+        assert!(policy.search(&game).is_empty());
+    }
+
+    #[test]
+    fn test_puct_values_outweigh_a_bad_prior() {
+        // One move left: any stone wins Black the board, passing loses on
+        // komi. The evaluator only likes passing.
+        let game = Game::<{ nw_for_board(3, 3) }>::with_options(3, 3, 0.5, 0, 1, true);
+        let evaluator = FixedEvaluator {
+            action: 9,
+            remaining: u32::MAX,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let move_ = PuctPolicy::new(evaluator, 200).choose(&game, &mut rng);
+        assert!(!move_.is_pass(), "{}", move_);
+    }
+
+    #[test]
+    fn test_presets_play_legal_moves() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for difficulty in Difficulty::ALL {
+            assert_eq!(Difficulty::from_name(difficulty.name()), Some(difficulty));
+            let mut policy = preset::<{ nw_for_board(5, 5) }>(difficulty);
+            let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 50, true);
+            let move_ = policy.choose(&game, &mut rng);
+            assert!(game.make_move(&move_), "{} played {}", difficulty, move_);
+        }
+    }
+
+    #[test]
+    fn test_strong_preset_uses_the_evaluator() {
+        let evaluator = FixedEvaluator {
+            action: 24,
+            remaining: u32::MAX,
+        };
+        let mut policy = preset_with_evaluator(Difficulty::Strong, evaluator);
+        let game = Game7::new(7, 7);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(policy.choose(&game, &mut rng), Move::place(3, 3));
+    }
+
+    /// A `GtpPolicy` whose engine answers `= pass` to everything, and the
+    /// commands sent to it.
+    #[cfg(feature = "gtp")]
+    fn recording_gtp_policy() -> (GtpPolicy, std::sync::Arc<std::sync::Mutex<Vec<u8>>>) {
+        struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl std::io::Write for Recorder {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().expect("not poisoned").extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let responses = std::io::Cursor::new("= pass\n\n".repeat(100).into_bytes());
+        let client = GtpClient::from_streams(responses, Recorder(sent.clone()));
+        (GtpPolicy::new(client), sent)
+    }
+
+    #[cfg(feature = "gtp")]
+    fn sent_commands(sent: &std::sync::Mutex<Vec<u8>>) -> Vec<String> {
+        let sent = sent.lock().expect("not poisoned");
+        String::from_utf8_lossy(&sent)
+            .lines()
+            .map(|line| line.split_once(' ').expect("has an id").1.to_string())
+            .collect()
+    }
+
+    #[cfg(feature = "gtp")]
+    #[test]
+    fn test_gtp_policy_sends_setup_players_and_effective_komi() {
+        let (mut policy, sent) = recording_gtp_policy();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = Game7::new(7, 7);
+        game.set_komi(0.5);
+        game.set_ruleset(Ruleset::Chinese);
+        game.set_position(
+            &[Position::new(0, 0), Position::new(1, 0)],
+            &[Position::new(6, 6)],
+            Player::White,
+            None,
+            0,
+        )
+        .expect("valid position");
+        assert!(game.make_move(&Move::place(3, 3)));
+        assert!(game.make_move(&Move::place(2, 2)));
+
+        assert_eq!(policy.choose(&game, &mut rng), Move::pass());
+        assert!(policy.last_error().is_none());
+        assert_eq!(
+            sent_commands(&sent),
+            [
+                "boardsize 7",
+                "clear_board",
+                "komi 0.5",
+                "play black A1",
+                "play black B1",
+                "play white G7",
+                "play white D4",
+                "play black C3",
+                "genmove white",
+            ]
+        );
+
+        // A handicap game: the stones, then White first, with the
+        // compensation in the komi.
+        let (mut policy, sent) = recording_gtp_policy();
+        let mut game = Game7::new(7, 7);
+        game.set_komi(0.5);
+        game.set_ruleset(Ruleset::Chinese);
+        assert!(game.place_handicap(2));
+        assert!(game.make_move(&Move::place(3, 3)));
+        policy.choose(&game, &mut rng);
+        let commands = sent_commands(&sent);
+        assert_eq!(commands[2], "komi 2.5");
+        assert_eq!(commands[5], "play white D4");
+        assert_eq!(commands[6], "genmove black");
+    }
+
+    #[cfg(feature = "gtp")]
+    #[test]
+    fn test_gtp_policy_rejects_setups_it_cannot_play() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.set_position(
+            &[(1, 0), (0, 1), (1, 2), (2, 1)].map(|(c, r)| Position::new(c, r)),
+            &[(2, 0), (3, 1), (2, 2)].map(|(c, r)| Position::new(c, r)),
+            Player::White,
+            Some(Position::new(1, 1)),
+            0,
+        )
+        .expect("valid position");
+        let (mut policy, sent) = recording_gtp_policy();
+        assert_eq!(policy.choose(&game, &mut rng), Move::resign());
+        assert!(matches!(
+            policy.last_error(),
+            Some(GtpError::UnsupportedPosition(_))
+        ));
+        assert!(sent_commands(&sent).is_empty());
+
+        // Setup stones from a record may leave a stone without liberties,
+        // which the engine would capture.
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.set_up_position(
+            &[Position::new(0, 0)],
+            &[Position::new(1, 0), Position::new(0, 1)],
+            0,
+            Player::Black,
+        );
+        assert_eq!(policy.choose(&game, &mut rng), Move::resign());
+        assert!(matches!(
+            policy.last_error(),
+            Some(GtpError::UnsupportedPosition(_))
+        ));
+    }
+}
@@ -25,4 +25,41 @@ impl Position {
     pub fn is_valid(&self, width: u8, height: u8) -> bool {
         self.col < width && self.row < height
     }
+
+    /// This point under one of the symmetries of a `width` x `height` board:
+    /// 0-3 flip the columns and/or rows, 4-7 (square boards only) also
+    /// transpose.
+    pub fn transform(&self, symmetry: usize, width: u8, height: u8) -> Position {
+        let (col, row) = if symmetry & 4 != 0 {
+            (self.row, self.col)
+        } else {
+            (self.col, self.row)
+        };
+        let col = if symmetry & 1 != 0 {
+            width - 1 - col
+        } else {
+            col
+        };
+        let row = if symmetry & 2 != 0 {
+            height - 1 - row
+        } else {
+            row
+        };
+        Position { col, row }
+    }
+
+    /// The GTP vertex of this point on a board `height` rows tall, e.g.
+    /// `D4`: a column letter (skipping `I`) and the row number counted from
+    /// the bottom.
+    pub fn to_gtp(&self, height: u8) -> String {
+        crate::gtp::position_to_vertex(self, height)
+    }
+
+    /// Parse a GTP vertex, case-insensitively. Returns None if it is malformed
+    /// or off a `width` x `height` board.
+    pub fn from_gtp(s: &str, width: u8, height: u8) -> Option<Self> {
+        crate::gtp::vertex_to_position(s, height)
+            .ok()
+            .filter(|pos| pos.is_valid(width, height))
+    }
 }
@@ -0,0 +1,310 @@
+use std::fmt;
+use std::fmt::Write;
+
+use crate::bitboard::nw_for_board;
+use crate::game::Game;
+use crate::gtp::{position_to_vertex, vertex_to_position};
+use crate::player::Player;
+use crate::position::Position;
+
+/// Errors from reading a position string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionStringError {
+    /// Not six space-separated fields.
+    FieldCount(usize),
+    InvalidBoardSize(String),
+    /// A field, or a row of the board field, cannot be read.
+    InvalidField {
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for PositionStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionStringError::FieldCount(n) => {
+                write!(f, "expected 6 fields in position string, found {}", n)
+            }
+            PositionStringError::InvalidBoardSize(msg) => write!(f, "invalid board size: {}", msg),
+            PositionStringError::InvalidField { field, value } => {
+                write!(f, "invalid {} in position string: {}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PositionStringError {}
+
+/// A position read from a position string, independent of the `Game` board type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionRecord {
+    pub width: u8,
+    pub height: u8,
+    pub black: Vec<Position>,
+    pub white: Vec<Position>,
+    pub to_move: Player,
+    pub ko_point: Option<Position>,
+    pub black_prisoners: u32,
+    pub white_prisoners: u32,
+    pub move_number: u32,
+}
+
+#[hotpath::measure]
+fn invalid(field: &'static str, value: &str) -> PositionStringError {
+    PositionStringError::InvalidField {
+        field,
+        value: value.to_string(),
+    }
+}
+
+/// Parse a position string, a single-line position format in the spirit of
+/// chess FEN, such as `4B4/9/2W6/9/9/9/9/9/9 b - 0 0 2`. The fields are:
+///
+/// 1. The board, top row first, rows separated by `/`. `B` and `W` are
+///    stones; a number is a run of empty points.
+/// 2. The player to move, `b` or `w`.
+/// 3. The ko point as a GTP vertex, or `-`.
+/// 4. Stones captured by Black, then by White.
+/// 5. The number of moves played so far.
+#[hotpath::measure]
+pub fn parse_position_string(text: &str) -> Result<PositionRecord, PositionStringError> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let &[board, to_move, ko, black_prisoners, white_prisoners, move_number] = fields.as_slice()
+    else {
+        return Err(PositionStringError::FieldCount(fields.len()));
+    };
+
+    let rows: Vec<&str> = board.split('/').collect();
+    let height = u8::try_from(rows.len())
+        .ok()
+        .filter(|h| (2..=25).contains(h))
+        .ok_or_else(|| PositionStringError::InvalidBoardSize(format!("{} rows", rows.len())))?;
+
+    let mut black = Vec::new();
+    let mut white = Vec::new();
+    let mut width = None;
+    for (i, row_text) in rows.iter().enumerate() {
+        let row = height - 1 - i as u8;
+        let mut col: u32 = 0;
+        let mut run: u32 = 0;
+        for c in row_text.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                run = run * 10 + digit;
+                if run > 25 {
+                    return Err(invalid("row", row_text));
+                }
+                continue;
+            }
+            col += run;
+            run = 0;
+            let stones = match c {
+                'B' => &mut black,
+                'W' => &mut white,
+                _ => return Err(invalid("row", row_text)),
+            };
+            if col >= 25 {
+                return Err(invalid("row", row_text));
+            }
+            stones.push(Position::new(col as u8, row));
+            col += 1;
+        }
+        col += run;
+        match width {
+            None => width = Some(col),
+            Some(w) if w == col => {}
+            Some(_) => return Err(invalid("row", row_text)),
+        }
+    }
+    let width = width
+        .and_then(|w| u8::try_from(w).ok())
+        .filter(|w| (2..=25).contains(w))
+        .ok_or_else(|| PositionStringError::InvalidBoardSize(board.to_string()))?;
+
+    let to_move = match to_move {
+        "b" => Player::Black,
+        "w" => Player::White,
+        _ => return Err(invalid("player to move", to_move)),
+    };
+    let ko_point = match ko {
+        "-" => None,
+        _ => {
+            let pos = vertex_to_position(ko, height).map_err(|_| invalid("ko point", ko))?;
+            let occupied = black.contains(&pos) || white.contains(&pos);
+            if !pos.is_valid(width, height) || occupied {
+                return Err(invalid("ko point", ko));
+            }
+            Some(pos)
+        }
+    };
+    let count =
+        |field: &'static str, value: &str| value.parse::<u32>().map_err(|_| invalid(field, value));
+
+    Ok(PositionRecord {
+        width,
+        height,
+        black,
+        white,
+        to_move,
+        ko_point,
+        black_prisoners: count("prisoners", black_prisoners)?,
+        white_prisoners: count("prisoners", white_prisoners)?,
+        move_number: count("move number", move_number)?,
+    })
+}
+
+#[hotpath::measure_all]
+impl PositionRecord {
+    /// Put this position on a fresh `game` of the same size.
+    pub fn set_up<const NW: usize>(&self, game: &mut Game<NW>) {
+        game.set_up_position(&self.black, &self.white, 0, self.to_move);
+        game.set_up_counters(
+            self.ko_point,
+            self.black_prisoners,
+            self.white_prisoners,
+            self.move_number,
+        );
+    }
+
+    /// A game with the default rules for this size, starting from this
+    /// position. `NW` must be `nw_for_board(width, height)`.
+    pub fn to_game<const NW: usize>(&self) -> Result<Game<NW>, PositionStringError> {
+        if nw_for_board(self.width, self.height) != NW {
+            return Err(PositionStringError::InvalidBoardSize(format!(
+                "{}x{} does not fit this game type",
+                self.width, self.height
+            )));
+        }
+        let mut game = Game::new(self.width, self.height);
+        self.set_up(&mut game);
+        Ok(game)
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Game<NW> {
+    /// The current position as a single-line string (see
+    /// `parse_position_string`). Rules, komi and history are not included.
+    pub fn to_position_string(&self) -> String {
+        let (width, height) = (self.width(), self.height());
+        let mut text = String::new();
+        for row in (0..height).rev() {
+            let mut empty = 0;
+            for col in 0..width {
+                match self.board().get_piece(&Position::new(col, row)) {
+                    Some(player) => {
+                        if empty > 0 {
+                            let _ = write!(text, "{}", empty);
+                            empty = 0;
+                        }
+                        text.push(player.to_char());
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                let _ = write!(text, "{}", empty);
+            }
+            if row > 0 {
+                text.push('/');
+            }
+        }
+
+        let to_move = match self.turn() {
+            Player::Black => 'b',
+            Player::White => 'w',
+        };
+        let ko = self
+            .ko_point()
+            .map_or("-".to_string(), |pos| position_to_vertex(&pos, height));
+        let _ = write!(
+            text,
+            " {} {} {} {} {}",
+            to_move,
+            ko,
+            self.prisoners(Player::Black),
+            self.prisoners(Player::White),
+            self.move_number()
+        );
+        text
+    }
+
+    /// A game with the default rules for its size, starting from a position
+    /// string. `NW` must be `nw_for_board(width, height)` for its board.
+    pub fn from_position_string(text: &str) -> Result<Self, PositionStringError> {
+        parse_position_string(text)?.to_game()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#move::Move;
+
+    type Game9 = Game<{ nw_for_board(9, 9) }>;
+
+    #[test]
+    fn test_round_trip_with_ko_and_prisoners() {
+        let mut game = Game9::with_options(9, 9, 7.5, 0, 500, true);
+        // Black captures at the ko.
+        for m in [
+            Move::place(1, 0),
+            Move::place(2, 0),
+            Move::place(0, 1),
+            Move::place(3, 1),
+            Move::place(1, 2),
+            Move::place(2, 2),
+            Move::pass(),
+            Move::place(1, 1),
+            Move::place(2, 1),
+        ] {
+            assert!(game.make_move(&m));
+        }
+        assert!(game.ko_point().is_some());
+
+        let text = game.to_position_string();
+        assert_eq!(text, "9/9/9/9/9/9/1BW6/B1BW5/1BW6 w B2 1 0 9");
+
+        let read = Game9::from_position_string(&text).expect("parses");
+        assert_eq!(read.board(), game.board());
+        assert_eq!(read.turn(), Player::White);
+        assert_eq!(read.ko_point(), game.ko_point());
+        assert_eq!(read.prisoners(Player::Black), 1);
+        assert_eq!(read.move_number(), 9);
+        assert!(!read.is_legal_move(&Move::place(1, 1)));
+        assert_eq!(read.to_position_string(), text);
+    }
+
+    #[test]
+    fn test_rejects_malformed_strings() {
+        let error = |text: &str| Game9::from_position_string(text).err();
+
+        assert!(matches!(
+            error("9/9/9 b - 0 0"),
+            Some(PositionStringError::FieldCount(5))
+        ));
+        assert!(matches!(
+            error("9/9/9/9/9/9/9/9/8 b - 0 0 0"),
+            Some(PositionStringError::InvalidField { field: "row", .. })
+        ));
+        assert!(matches!(
+            error("9/9/9/9/9/9/9/9/4X4 b - 0 0 0"),
+            Some(PositionStringError::InvalidField { field: "row", .. })
+        ));
+        assert!(matches!(
+            error("9/9/9/9/9/9/9/9/B8 x - 0 0 0"),
+            Some(PositionStringError::InvalidField { .. })
+        ));
+        assert!(matches!(
+            error("9/9/9/9/9/9/9/9/B8 b A1 0 0 0"),
+            Some(PositionStringError::InvalidField {
+                field: "ko point",
+                ..
+            })
+        ));
+        assert!(matches!(
+            error("5/5/5/5/5 b - 0 0 0"),
+            Some(PositionStringError::InvalidBoardSize(_))
+        ));
+    }
+}
@@ -1,13 +1,40 @@
 #[macro_use]
 mod dispatch;
+mod py_batch_game;
 mod py_board;
+mod py_encode;
+mod py_encoding_cache;
+mod py_errors;
 mod py_game;
+mod py_game_info;
 mod py_game_outcome;
 mod py_gtp;
 mod py_move;
+mod py_move_analysis;
+mod py_playouts;
+mod py_policy;
+mod py_position;
+mod py_score_details;
+mod py_terminal;
+mod py_wire;
 
+pub use py_batch_game::{encode_batch, PyBatchGame};
 pub use py_board::PyBoard;
+pub use py_encode::{decode_move, encode_move, nw_for_board, total_actions};
+pub use py_encoding_cache::PyEncodingCache;
+pub use py_errors::{GameOverError, IllegalMoveError, InvalidPlayerError};
 pub use py_game::PyGame;
+pub use py_game_info::PyGameInfo;
 pub use py_game_outcome::PyGameOutcome;
 pub use py_gtp::PyGtpEngine;
 pub use py_move::PyMove;
+pub use py_move_analysis::PyMoveAnalysis;
+pub use py_playouts::random_playouts_batch;
+pub use py_policy::{
+    PyGameRecord, PyHeuristicPolicy, PyMatchResult, PyMctsPolicy, PyNoEyeFillPolicy, PyPolicy,
+    PyUniformPolicy,
+};
+pub use py_position::PyPosition;
+pub use py_score_details::PyScoreDetails;
+pub use py_terminal::play_interactive;
+pub use py_wire::{decode_wire_messages, PyTrainingSample};
@@ -0,0 +1,354 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+
+use super::dispatch::*;
+use super::py_encode::writable_slice;
+use super::py_errors::player_from_int;
+use super::py_game::PyGame;
+use super::py_game_outcome::PyGameOutcome;
+use crate::encode;
+use crate::r#move::Move;
+
+/// Split `games` and `data` (`per_game` items each) into one chunk per
+/// thread and run `f` on every game with its items.
+#[hotpath::measure]
+fn for_each_chunk<T: Send>(
+    games: &mut [GameInner],
+    data: &mut [T],
+    per_game: usize,
+    threads: usize,
+    f: impl Fn(&mut GameInner, &mut [T]) + Sync,
+) {
+    let games_per_thread = games.len().div_ceil(threads.max(1)).max(1);
+    let run = |games: &mut [GameInner], data: &mut [T]| {
+        for (game, items) in games.iter_mut().zip(data.chunks_mut(per_game.max(1))) {
+            f(game, items);
+        }
+    };
+    if threads <= 1 || games.len() <= games_per_thread {
+        run(games, data);
+        return;
+    }
+    std::thread::scope(|scope| {
+        for (games, data) in games
+            .chunks_mut(games_per_thread)
+            .zip(data.chunks_mut(games_per_thread * per_game.max(1)))
+        {
+            scope.spawn(|| run(games, data));
+        }
+    });
+}
+
+/// Copies of the games in `games`, which must share a board size; `caller`
+/// names the function for the error when they do not.
+#[hotpath::measure]
+pub(super) fn same_size_games(
+    games: &[PyRef<'_, PyGame>],
+    caller: &str,
+) -> PyResult<Vec<GameInner>> {
+    let Some(first) = games.first() else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "{} needs at least one game",
+            caller
+        )));
+    };
+    let size = dispatch_game!(&first.inner, g => (g.width(), g.height()));
+    let mut inners = Vec::with_capacity(games.len());
+    for (index, game) in games.iter().enumerate() {
+        let game_size = dispatch_game!(&game.inner, g => (g.width(), g.height()));
+        if game_size != size {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "game {} is {}x{}, expected {}x{}",
+                index, game_size.0, game_size.1, size.0, size.1
+            )));
+        }
+        inners.push(game.inner.clone());
+    }
+    Ok(inners)
+}
+
+/// Encode `games`, which must share a board size, as
+/// `Game.encode_game_planes` does into a new numpy float32 array of shape
+/// (len(games), num_planes, height, width). With `threads` above 1 the
+/// games are split across that many threads. The GIL is released while
+/// encoding. Needs numpy.
+#[pyfunction]
+#[pyo3(signature = (games, threads=1))]
+#[hotpath::measure]
+pub fn encode_batch<'py>(
+    py: Python<'py>,
+    games: Vec<PyRef<'py, PyGame>>,
+    threads: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut inners = same_size_games(&games, "encode_batch")?;
+    let config = encode::EncoderConfig::default();
+    let (per_game, width, height) = dispatch_game!(&inners[0], g => {
+        (encode::encoded_len(g, &config), g.width() as usize, g.height() as usize)
+    });
+    let array = py.import("numpy")?.call_method1(
+        "empty",
+        (
+            (inners.len(), config.num_planes(), height, width),
+            "float32",
+        ),
+    )?;
+    let buffer = PyBuffer::<f32>::get(&array)?;
+    // SAFETY: the array was just created here and no Python code has seen
+    // it, so releasing the GIL while the slice is alive is fine.
+    let data = unsafe { writable_slice(&buffer, py, inners.len() * per_game, "float32") }?;
+    py.detach(|| {
+        for_each_chunk(&mut inners, data, per_game, threads, |inner, planes| {
+            dispatch_game!(inner, g => encode::encode_game_planes_into(g, &config, planes));
+        })
+    });
+    Ok(array)
+}
+
+/// Games of one size and rule set stepped together, for vectorized
+/// environments. Actions use the layout of `Game.legal_action_indices()`;
+/// the array methods write into caller-provided buffers such as numpy
+/// arrays, one row per game.
+#[pyclass(name = "BatchGame")]
+pub struct PyBatchGame {
+    games: Vec<GameInner>,
+    threads: usize,
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyBatchGame {
+    /// `num_games` copies of `game`. With `threads` above 1, stepping and
+    /// encoding are split across that many threads.
+    #[new]
+    #[pyo3(signature = (game, num_games, threads=1))]
+    pub fn new(game: &PyGame, num_games: usize, threads: usize) -> PyResult<Self> {
+        if num_games == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "A batch needs at least one game",
+            ));
+        }
+        Ok(PyBatchGame {
+            games: vec![game.inner.clone(); num_games],
+            threads,
+        })
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Actions per game, the row length of `legal_action_masks`.
+    pub fn total_actions(&self) -> usize {
+        dispatch_game!(&self.games[0], g => encode::total_actions_for(g))
+    }
+
+    /// A copy of game `index`.
+    pub fn game(&self, index: usize) -> PyResult<PyGame> {
+        self.games
+            .get(index)
+            .map(|inner| PyGame::from_inner(inner.clone()))
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!(
+                    "game index {} out of range",
+                    index
+                ))
+            })
+    }
+
+    /// Write 1 for each legal action and 0 elsewhere into `out`, a uint8
+    /// buffer of len(self) * total_actions() values. Finished games have
+    /// no legal actions. The masks are built with the GIL released and
+    /// copied into `out` once it is held again.
+    pub fn legal_action_masks(&mut self, out: &Bound<'_, PyAny>) -> PyResult<()> {
+        let actions = self.total_actions();
+        let buffer = PyBuffer::<u8>::get(out)?;
+        let mut masks = vec![0; self.games.len() * actions];
+        let (games, threads) = (&mut self.games, self.threads);
+        out.py().detach(|| {
+            for_each_chunk(games, &mut masks, actions, threads, |inner, mask| {
+                let config = encode::EncoderConfig::default();
+                dispatch_game!(inner, g => encode::legal_action_mask_into(g, &config, mask));
+            })
+        });
+        // SAFETY: the GIL is held again and no Python code runs while the
+        // slice is alive.
+        unsafe { writable_slice(&buffer, out.py(), masks.len(), "uint8") }?.copy_from_slice(&masks);
+        Ok(())
+    }
+
+    /// Play one action per game from `actions`, an int64 buffer of
+    /// len(self) values. Finished games ignore theirs. Raises ValueError,
+    /// playing nothing, if an action is illegal in an unfinished game.
+    pub fn apply_actions(&mut self, actions: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = actions.py();
+        let actions = PyBuffer::<i64>::get(actions)?.to_vec(py)?;
+        if actions.len() != self.games.len() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Expected {} actions, got {}",
+                self.games.len(),
+                actions.len()
+            )));
+        }
+        let mut moves: Vec<Option<Move>> = Vec::with_capacity(actions.len());
+        for (index, (inner, &action)) in self.games.iter().zip(&actions).enumerate() {
+            let move_ = dispatch_game!(inner, g => {
+                if g.is_over() {
+                    None
+                } else {
+                    let move_ = usize::try_from(action)
+                        .ok()
+                        .and_then(|a| encode::decode_move_for(a, g))
+                        .filter(|m| g.is_legal_move(m));
+                    if move_.is_none() {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                            "action {} is not legal in game {}",
+                            action, index
+                        )));
+                    }
+                    move_
+                }
+            });
+            moves.push(move_);
+        }
+        let (games, threads) = (&mut self.games, self.threads);
+        py.detach(|| {
+            for_each_chunk(games, &mut moves, 1, threads, |inner, move_| {
+                if let Some(move_) = move_[0] {
+                    dispatch_game_mut!(inner, g => g.make_move(&move_));
+                }
+            })
+        });
+        Ok(())
+    }
+
+    /// Encode every game as `Game.encode_game_planes` does into `out`, a
+    /// float32 buffer of len(self) * num_planes * height * width values.
+    /// Returns (len(self), num_planes, height, width). The planes are
+    /// encoded with the GIL released and copied into `out` once it is held
+    /// again.
+    pub fn encode_all(&mut self, out: &Bound<'_, PyAny>) -> PyResult<(usize, usize, usize, usize)> {
+        let config = encode::EncoderConfig::default();
+        let per_game = dispatch_game!(&self.games[0], g => encode::encoded_len(g, &config));
+        let buffer = PyBuffer::<f32>::get(out)?;
+        let mut planes = vec![0.0; self.games.len() * per_game];
+        let (games, threads) = (&mut self.games, self.threads);
+        out.py().detach(|| {
+            for_each_chunk(games, &mut planes, per_game, threads, |inner, planes| {
+                dispatch_game!(inner, g => encode::encode_game_planes_into(g, &config, planes));
+            })
+        });
+        // SAFETY: the GIL is held again and no Python code runs while the
+        // slice is alive.
+        unsafe { writable_slice(&buffer, out.py(), planes.len(), "float32") }?
+            .copy_from_slice(&planes);
+        let (width, height) =
+            dispatch_game!(&self.games[0], g => (g.width() as usize, g.height() as usize));
+        Ok((self.games.len(), config.num_planes(), height, width))
+    }
+
+    /// Whether each game is over.
+    pub fn dones(&self) -> Vec<bool> {
+        self.games
+            .iter()
+            .map(|inner| dispatch_game!(inner, g => g.is_over()))
+            .collect()
+    }
+
+    /// Write each game's result from Black's perspective into `out`, a
+    /// float32 buffer of len(self) values: 1.0 for a Black win, -1.0 for a
+    /// White win, 0.0 for a draw or a game still in progress.
+    pub fn rewards(&self, out: &Bound<'_, PyAny>) -> PyResult<()> {
+        let buffer = PyBuffer::<f32>::get(out)?;
+        // SAFETY: the GIL stays held and no Python code runs while the slice
+        // is alive.
+        let data = unsafe { writable_slice(&buffer, out.py(), self.games.len(), "float32") }?;
+        for (reward, inner) in data.iter_mut().zip(&self.games) {
+            *reward = dispatch_game!(inner, g => {
+                g.outcome().map_or(0.0, |o| o.encode_winner_absolute())
+            });
+        }
+        Ok(())
+    }
+
+    /// Write each game's result from the perspective of the matching entry
+    /// of `perspectives`, an int8 buffer of len(self) values of 1 (Black)
+    /// or -1 (White), into `out`, a float32 buffer of len(self) values:
+    /// 1.0 for a win, -1.0 for a loss, 0.0 for a draw or a game still in
+    /// progress.
+    pub fn rewards_from_perspective(
+        &self,
+        perspectives: &Bound<'_, PyAny>,
+        out: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let perspectives = PyBuffer::<i8>::get(perspectives)?.to_vec(perspectives.py())?;
+        if perspectives.len() != self.games.len() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Expected {} perspectives, got {}",
+                self.games.len(),
+                perspectives.len()
+            )));
+        }
+        let perspectives = perspectives
+            .into_iter()
+            .map(player_from_int)
+            .collect::<PyResult<Vec<_>>>()?;
+        let buffer = PyBuffer::<f32>::get(out)?;
+        // SAFETY: the GIL stays held and no Python code runs while the slice
+        // is alive.
+        let data = unsafe { writable_slice(&buffer, out.py(), self.games.len(), "float32") }?;
+        for ((reward, inner), perspective) in data.iter_mut().zip(&self.games).zip(perspectives) {
+            *reward = dispatch_game!(inner, g => {
+                g.outcome()
+                    .map_or(0.0, |o| o.encode_winner_from_perspective(perspective))
+            });
+        }
+        Ok(())
+    }
+
+    /// Each game's outcome, or None for a game still in progress.
+    pub fn outcomes(&self) -> Vec<Option<PyGameOutcome>> {
+        self.games
+            .iter()
+            .map(|inner| dispatch_game!(inner, g => g.outcome().map(PyGameOutcome::from_outcome)))
+            .collect()
+    }
+
+    /// Write the player to move in each game into `out`, an int8 buffer of
+    /// len(self) values: 1 for Black, -1 for White.
+    pub fn turns(&self, out: &Bound<'_, PyAny>) -> PyResult<()> {
+        let buffer = PyBuffer::<i8>::get(out)?;
+        // SAFETY: the GIL stays held and no Python code runs while the slice
+        // is alive.
+        let data = unsafe { writable_slice(&buffer, out.py(), self.games.len(), "int8") }?;
+        for (turn, inner) in data.iter_mut().zip(&self.games) {
+            *turn = dispatch_game!(inner, g => g.turn() as i8);
+        }
+        Ok(())
+    }
+
+    /// Start every finished game again with `Game.reset_keep_config()`.
+    /// Returns the indices of the games reset.
+    pub fn reset_finished(&mut self) -> Vec<usize> {
+        let mut reset = Vec::new();
+        for (index, inner) in self.games.iter_mut().enumerate() {
+            dispatch_game_mut!(inner, g => {
+                if g.is_over() {
+                    g.reset_keep_config();
+                    reset.push(index);
+                }
+            });
+        }
+        reset
+    }
+
+    pub fn __repr__(&self) -> String {
+        let (width, height) = dispatch_game!(&self.games[0], g => (g.width(), g.height()));
+        format!(
+            "BatchGame(len={}, size={}x{}, threads={})",
+            self.games.len(),
+            width,
+            height,
+            self.threads
+        )
+    }
+}
@@ -1,8 +1,61 @@
+use pyo3::buffer::PyBuffer;
 use pyo3::prelude::*;
+use pyo3::types::PyTuple;
 
 use super::dispatch::*;
+use super::py_encode::writable_slice;
+use super::py_errors::player_from_int;
+use super::py_position::{index_point, point_only, split_point};
+use crate::analysis;
+use crate::gtp::parse_showboard;
 use crate::player::Player;
 use crate::position::Position;
+use crate::render;
+
+/// The stones of an (height, width) array of 1 (Black), -1 (White) and 0
+/// (empty), as `Board.to_numpy` makes.
+pub(super) struct StonesArray {
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) black: Vec<Position>,
+    pub(super) white: Vec<Position>,
+}
+
+#[hotpath::measure_all]
+impl StonesArray {
+    /// Read `array`, anything numpy can convert to int8. Needs numpy.
+    pub(super) fn extract(array: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = array.py();
+        let array = py
+            .import("numpy")?
+            .call_method1("ascontiguousarray", (array, "int8"))?;
+        let shape: Vec<usize> = array.getattr("shape")?.extract()?;
+        let [height, width] = shape[..] else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Board array must have 2 dimensions, got {}",
+                shape.len()
+            )));
+        };
+        let mut stones = StonesArray {
+            width,
+            height,
+            black: Vec::new(),
+            white: Vec::new(),
+        };
+        let cells = PyBuffer::<i8>::get(&array)?.to_vec(py)?;
+        for (index, &cell) in cells.iter().enumerate() {
+            if cell == 0 {
+                continue;
+            }
+            let pos = Position::new((index % width) as u8, (index / width) as u8);
+            match player_from_int(cell)? {
+                Player::Black => stones.black.push(pos),
+                Player::White => stones.white.push(pos),
+            }
+        }
+        Ok(stones)
+    }
+}
 
 #[pyclass(name = "Board")]
 #[derive(Clone)]
@@ -44,6 +97,57 @@ impl PyBoard {
         }
     }
 
+    /// Read a board from an engine's GTP `showboard` output.
+    #[staticmethod]
+    pub fn from_showboard(text: &str) -> PyResult<Self> {
+        let parsed = parse_showboard(text)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let mut inner = make_board_inner(parsed.width, parsed.height);
+        for (pos, player) in &parsed.stones {
+            dispatch_board_mut!(&mut inner, b => b.set_piece(pos, Some(*player)));
+        }
+        Ok(PyBoard { inner })
+    }
+
+    /// A board from an (height, width) array of 1 (Black), -1 (White) and
+    /// 0 (empty), indexed `[row, col]`, as made by `to_numpy`. Needs numpy.
+    #[staticmethod]
+    pub fn from_numpy(array: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let stones = StonesArray::extract(array)?;
+        let mut board = PyBoard::new(stones.width, stones.height)?;
+        for (stones, player) in [
+            (&stones.black, Player::Black),
+            (&stones.white, Player::White),
+        ] {
+            for pos in stones {
+                dispatch_board_mut!(&mut board.inner, b => b.set_piece(pos, Some(player)));
+            }
+        }
+        Ok(board)
+    }
+
+    /// The board as a new numpy int8 array of shape (height, width) holding
+    /// 1 (Black), -1 (White) and 0 (empty), indexed `[row, col]`. Needs numpy.
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let (width, height) = (self.width(), self.height());
+        let array = py
+            .import("numpy")?
+            .call_method1("zeros", ((height, width), "int8"))?;
+        let buffer = PyBuffer::<i8>::get(&array)?;
+        // SAFETY: the array was just created here and no Python code runs while
+        // the slice is alive.
+        let cells = unsafe { writable_slice(&buffer, py, width * height, "int8") }?;
+        dispatch_board!(&self.inner, b => {
+            for (index, cell) in cells.iter_mut().enumerate() {
+                let pos = Position::new((index % width) as u8, (index / width) as u8);
+                if let Some(player) = b.get_piece(&pos) {
+                    *cell = player as i8;
+                }
+            }
+        });
+        Ok(array)
+    }
+
     pub fn width(&self) -> usize {
         dispatch_board!(&self.inner, b => b.width() as usize)
     }
@@ -52,21 +156,68 @@ impl PyBoard {
         dispatch_board!(&self.inner, b => b.height() as usize)
     }
 
-    pub fn get_piece(&self, col: usize, row: usize) -> Option<i8> {
-        let pos = Position::new(col as u8, row as u8);
-        dispatch_board!(&self.inner, b => b.get_piece(&pos).map(|p| p as i8))
+    /// The stone at a point given as col, row, a Position or a (col, row)
+    /// tuple.
+    #[pyo3(signature = (*point))]
+    pub fn get_piece(&self, point: &Bound<'_, PyTuple>) -> PyResult<Option<i8>> {
+        let pos = point_only(point)?;
+        Ok(dispatch_board!(&self.inner, b => b.get_piece(&pos).map(|p| p as i8)))
     }
 
-    pub fn set_piece(&mut self, col: usize, row: usize, piece: Option<i8>) {
-        let pos = Position::new(col as u8, row as u8);
-        let player = piece.map(|p| Player::from_int(p).expect("Invalid player value"));
-        dispatch_board_mut!(&mut self.inner, b => b.set_piece(&pos, player))
+    /// Put `piece` (1, -1 or None) on a point given as col, row, a Position
+    /// or a (col, row) tuple.
+    #[pyo3(signature = (*args))]
+    pub fn set_piece(&mut self, args: &Bound<'_, PyTuple>) -> PyResult<()> {
+        let (pos, rest) = split_point(args)?;
+        let [piece] = &rest[..] else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "set_piece takes a point and a piece",
+            ));
+        };
+        let player = piece
+            .extract::<Option<i8>>()?
+            .map(player_from_int)
+            .transpose()?;
+        dispatch_board_mut!(&mut self.inner, b => b.set_piece(&pos, player));
+        Ok(())
     }
 
     pub fn clear(&mut self) {
         dispatch_board_mut!(&mut self.inner, b => b.clear())
     }
 
+    /// `board[point]`: the stone at a Position or (col, row) tuple.
+    pub fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<Option<i8>> {
+        dispatch_board!(&self.inner, b => {
+            let pos = index_point(key, b.width(), b.height())?;
+            Ok(b.get_piece(&pos).map(|p| p as i8))
+        })
+    }
+
+    /// `board[point] = piece`, with `piece` 1, -1 or None.
+    pub fn __setitem__(&mut self, key: &Bound<'_, PyAny>, piece: Option<i8>) -> PyResult<()> {
+        let player = piece.map(player_from_int).transpose()?;
+        dispatch_board_mut!(&mut self.inner, b => {
+            let pos = index_point(key, b.width(), b.height())?;
+            b.set_piece(&pos, player);
+        });
+        Ok(())
+    }
+
+    /// The board as a standalone SVG image.
+    pub fn to_svg(&self) -> String {
+        dispatch_board!(&self.inner, b => analysis::board_to_svg(b))
+    }
+
+    /// The board drawn in a theme of `render::THEMES`.
+    #[pyo3(signature = (theme="text"))]
+    pub fn render(&self, theme: &str) -> PyResult<String> {
+        let renderer = render::renderer_for_theme(theme).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown theme: {}", theme))
+        })?;
+        Ok(dispatch_board!(&self.inner, b => b.render(renderer.as_ref())))
+    }
+
     pub fn __str__(&self) -> String {
         dispatch_board!(&self.inner, b => b.to_string())
     }
@@ -0,0 +1,111 @@
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+use super::py_move::PyMove;
+use super::py_position::split_point;
+use crate::bitboard;
+use crate::encode;
+use crate::r#move::Move;
+
+#[hotpath::measure]
+fn check_size(width: u8, height: u8) -> PyResult<()> {
+    if (2..=32).contains(&width) && (2..=32).contains(&height) {
+        Ok(())
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Board size must be between 2 and 32, got {}x{}",
+            width, height
+        )))
+    }
+}
+
+/// `buffer` as a slice to write into. It must be writable, C-contiguous and
+/// hold exactly `len` items; `kind` names the item type for errors.
+///
+/// # Safety
+///
+/// Nothing else may read or write the buffer while the returned slice is
+/// alive. Python code can reach the buffer whenever it runs, so the caller
+/// must not release the GIL (`Python::detach`) or call into Python until it
+/// is done with the slice, unless it allocated the buffer itself and has not
+/// handed it to any Python code yet.
+#[hotpath::measure]
+pub(super) unsafe fn writable_slice<'a, T: Element>(
+    buffer: &'a PyBuffer<T>,
+    py: Python<'a>,
+    len: usize,
+    kind: &str,
+) -> PyResult<&'a mut [T]> {
+    let cells = buffer.as_mut_slice(py).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "out must be a writable C-contiguous {} buffer",
+            kind
+        ))
+    })?;
+    if cells.len() != len {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "out holds {} values, {} are needed",
+            cells.len(),
+            len
+        )));
+    }
+    // SAFETY: `Cell<T>` has the layout of `T`; the caller guarantees the
+    // slice is the only access to the buffer while it lives.
+    Ok(unsafe { std::slice::from_raw_parts_mut(cells.as_ptr() as *mut T, len) })
+}
+
+/// Size of the action space for a board: every point plus pass, swap when
+/// `swap` is set, and resignation last when `resign` is set.
+#[pyfunction]
+#[pyo3(signature = (width, height, swap=false, resign=false))]
+#[hotpath::measure]
+pub fn total_actions(width: u8, height: u8, swap: bool, resign: bool) -> PyResult<usize> {
+    check_size(width, height)?;
+    let actions = if swap {
+        encode::total_actions_with_swap(width, height)
+    } else {
+        encode::total_actions(width, height)
+    };
+    Ok(actions + resign as usize)
+}
+
+/// The action index of a move on a `width` x `height` board, called as
+/// `encode_move(move_, width, height)` or with a placement's point as col,
+/// row, a Position or a (col, row) tuple in place of `move_`.
+#[pyfunction]
+#[pyo3(signature = (*args))]
+#[hotpath::measure]
+pub fn encode_move(args: &Bound<'_, PyTuple>) -> PyResult<usize> {
+    let (move_, rest) = match args.get_item(0).map(|first| first.cast_into::<PyMove>()) {
+        Ok(Ok(move_)) => (*move_.borrow().as_inner(), args.iter().skip(1).collect()),
+        _ => {
+            let (pos, rest) = split_point(args)?;
+            (Move::place(pos.col, pos.row), rest)
+        }
+    };
+    let [width, height] = &rest[..] else {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "encode_move takes a move or a point, then width and height",
+        ));
+    };
+    let (width, height) = (width.extract()?, height.extract()?);
+    check_size(width, height)?;
+    Ok(encode::encode_move(&move_, width, height))
+}
+
+/// The move for action index `action`, or None if it is out of range.
+#[pyfunction]
+#[hotpath::measure]
+pub fn decode_move(action: usize, width: u8, height: u8) -> PyResult<Option<PyMove>> {
+    check_size(width, height)?;
+    Ok(encode::decode_move(action, width, height).map(PyMove::from_move))
+}
+
+/// Number of 64-bit words in a bitboard for a `width` x `height` board.
+#[pyfunction]
+#[hotpath::measure]
+pub fn nw_for_board(width: u8, height: u8) -> PyResult<usize> {
+    check_size(width, height)?;
+    Ok(bitboard::nw_for_board(width, height))
+}
@@ -0,0 +1,61 @@
+use pyo3::prelude::*;
+
+use crate::encode::{EncoderConfig, EncodingCache, HISTORY_LENGTH};
+
+/// LRU cache of encoded planes, shared by any number of games through
+/// `Game.encode_game_planes(cache)`.
+#[pyclass(name = "EncodingCache")]
+pub struct PyEncodingCache {
+    pub(super) cache: EncodingCache,
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyEncodingCache {
+    /// With `capture_plane`, encodings end with a plane of the legal
+    /// capturing moves.
+    #[new]
+    #[pyo3(signature = (capacity=65536, history_length=HISTORY_LENGTH, capture_plane=false))]
+    pub fn new(capacity: usize, history_length: usize, capture_plane: bool) -> Self {
+        PyEncodingCache {
+            cache: EncodingCache::new(
+                capacity,
+                EncoderConfig {
+                    history_length,
+                    capture_plane,
+                    ..EncoderConfig::default()
+                },
+            ),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cache.capacity()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.cache.hits()
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.cache.misses()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear()
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "EncodingCache(len={}, capacity={}, hits={}, misses={})",
+            self.cache.len(),
+            self.cache.capacity(),
+            self.cache.hits(),
+            self.cache.misses()
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::game::{Game, IllegalMoveReason};
+use crate::player::Player;
+use crate::r#move::Move;
+
+create_exception!(
+    spooky_go,
+    IllegalMoveError,
+    PyValueError,
+    "A move or action that cannot be played in the current position."
+);
+create_exception!(
+    spooky_go,
+    GameOverError,
+    IllegalMoveError,
+    "A move or action played after the game has ended."
+);
+create_exception!(
+    spooky_go,
+    InvalidPlayerError,
+    PyValueError,
+    "A player value other than 1 (Black) or -1 (White)."
+);
+
+/// `value` as a player, raising InvalidPlayerError for anything but 1 or -1.
+#[hotpath::measure]
+pub(super) fn player_from_int(value: i8) -> PyResult<Player> {
+    Player::from_int(value).ok_or_else(|| {
+        InvalidPlayerError::new_err(format!(
+            "Invalid player value {}: expected 1 (Black) or -1 (White)",
+            value
+        ))
+    })
+}
+
+#[hotpath::measure]
+fn error_for(reason: Option<IllegalMoveReason>, message: String) -> PyErr {
+    match reason {
+        Some(IllegalMoveReason::GameOver) => GameOverError::new_err(message),
+        _ => IllegalMoveError::new_err(message),
+    }
+}
+
+#[hotpath::measure]
+fn describe<const NW: usize>(game: &Game<NW>, move_: &Move) -> (Option<IllegalMoveReason>, String) {
+    let reason = game.illegal_move_reason(move_);
+    let text = format!(
+        "{} {} is illegal: {}",
+        game.turn(),
+        move_.to_gtp(game.height()),
+        reason.map_or_else(|| "it was rejected".to_string(), |r| r.to_string())
+    );
+    (reason, text)
+}
+
+/// The error for playing `move_` in `game`, naming the move, the player and
+/// why it is illegal. GameOverError once the game has ended.
+#[hotpath::measure]
+pub(super) fn illegal_move_error<const NW: usize>(game: &Game<NW>, move_: &Move) -> PyErr {
+    let (reason, text) = describe(game, move_);
+    error_for(reason, text)
+}
+
+/// The error for playing `action`, which decodes to `move_` in `game` or to
+/// nothing when it is out of range.
+#[hotpath::measure]
+pub(super) fn illegal_action_error<const NW: usize>(
+    game: &Game<NW>,
+    action: usize,
+    move_: Option<Move>,
+    total_actions: usize,
+) -> PyErr {
+    match move_ {
+        Some(move_) => {
+            let (reason, text) = describe(game, &move_);
+            error_for(reason, format!("action {}: {}", action, text))
+        }
+        None if game.is_over() => {
+            GameOverError::new_err(format!("action {} is illegal: the game is over", action))
+        }
+        None => IllegalMoveError::new_err(format!(
+            "action {} is out of range: there are {} actions",
+            action, total_actions
+        )),
+    }
+}
+
+/// `error` with `context` put in front of its message, keeping its type.
+#[hotpath::measure]
+pub(super) fn with_context(py: Python<'_>, error: PyErr, context: &str) -> PyErr {
+    let message = format!("{}: {}", context, error.value(py));
+    PyErr::from_type(error.get_type(py), message)
+}
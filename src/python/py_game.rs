@@ -1,23 +1,106 @@
+use std::io::Write;
+
+use pyo3::buffer::PyBuffer;
 use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use rand::RngExt;
 
 use super::dispatch::*;
-use super::py_board::PyBoard;
+use super::py_board::{PyBoard, StonesArray};
+use super::py_encode::writable_slice;
+use super::py_encoding_cache::PyEncodingCache;
+use super::py_errors::{
+    illegal_action_error, illegal_move_error, player_from_int, with_context, GameOverError,
+};
+use super::py_game_info::PyGameInfo;
 use super::py_game_outcome::PyGameOutcome;
 use super::py_move::PyMove;
+use super::py_move_analysis::PyMoveAnalysis;
+use super::py_playouts::stats_to_numpy;
+use super::py_policy::{cycle_detection, PolicyAdapter, PyGameRecord, PyMatchResult};
+use super::py_position::{extract_point, index_point, point_only, split_point};
+use super::py_score_details::PyScoreDetails;
+use crate::analysis;
+use crate::benson;
+use crate::config::{self, Config, RulesConfig};
 use crate::encode;
+use crate::game::{validate_move_limits, GamePhase, IllegalMoveReason};
+use crate::handicap::max_fixed_handicap;
 use crate::player::Player;
 use crate::position::Position;
+use crate::position_string;
+use crate::r#move::{Move, MoveListStyle};
+use crate::replay::IllegalMovePolicy;
+use crate::rollout::{self, RolloutConfig};
+use crate::rules::Ruleset;
+use crate::sgf;
+use crate::{selfplay, tournament, training};
+
+/// The action layout of the `resign` argument of the action methods.
+#[hotpath::measure]
+fn action_config(resign: bool) -> encode::EncoderConfig {
+    encode::EncoderConfig {
+        resign_action: resign,
+        ..encode::EncoderConfig::default()
+    }
+}
+
+/// A TOML or JSON config file, with every section validated.
+#[hotpath::measure]
+pub(super) fn load_config(path: &str) -> PyResult<Config> {
+    let to_py_err =
+        |e: config::ConfigError| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string());
+    let config: Config = config::load(path).map_err(to_py_err)?;
+    config.validate().map_err(to_py_err)?;
+    Ok(config)
+}
+
+/// A game with `rules` and nothing played.
+#[hotpath::measure]
+fn game_from_rules(rules: &RulesConfig) -> GameInner {
+    let mut inner = make_game_inner_with_options(
+        rules.width,
+        rules.height,
+        rules.komi,
+        rules.min_moves_before_pass_possible(),
+        rules.max_moves(),
+        rules.superko,
+    );
+    dispatch_game_mut!(&mut inner, g => rules.apply(g));
+    inner
+}
+
+/// An entry of `Game.play_sequence`.
+enum SequenceItem {
+    Move(Move),
+    Action(usize),
+}
 
 #[pyclass(name = "Game")]
 pub struct PyGame {
-    inner: GameInner,
+    pub(super) inner: GameInner,
+    /// Reject `set_piece` once a move has been played, since editing the
+    /// board mid-game bypasses the superko history and hashes.
+    strict: bool,
+}
+
+#[hotpath::measure_all]
+impl PyGame {
+    pub(super) fn from_inner(inner: GameInner) -> Self {
+        PyGame {
+            inner,
+            strict: false,
+        }
+    }
 }
 
 #[hotpath::measure_all]
 #[pymethods]
 impl PyGame {
+    /// With `strict`, `set_piece` raises once the first move is played.
     #[new]
-    pub fn new(width: usize, height: usize) -> PyResult<Self> {
+    #[pyo3(signature = (width, height, strict=false))]
+    pub fn new(width: usize, height: usize, strict: bool) -> PyResult<Self> {
         if !(2..=32).contains(&width) {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 "Board width must be between 2 and 32",
@@ -30,18 +113,40 @@ impl PyGame {
         }
         Ok(PyGame {
             inner: make_game_inner(width as u8, height as u8),
+            strict,
         })
     }
 
+    /// `ruleset` is one of the `Ruleset` names. `scoring` is "all_alive",
+    /// where two passes end the game with every stone counted, or "marking",
+    /// where they enter the scoring phase to mark dead stones. Handicap
+    /// stones are placed on their fixed points and White moves first.
+    /// `strict` is as for the constructor.
     #[staticmethod]
-    #[pyo3(signature = (width, height, komi, min_moves_before_pass_possible, max_moves, superko))]
+    #[pyo3(signature = (
+        width,
+        height,
+        komi,
+        min_moves_before_pass_possible,
+        max_moves,
+        superko,
+        handicap=0,
+        ruleset="chinese",
+        scoring="all_alive",
+        strict=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn with_options(
         width: usize,
         height: usize,
         komi: f32,
-        min_moves_before_pass_possible: usize,
-        max_moves: usize,
+        min_moves_before_pass_possible: u16,
+        max_moves: u16,
         superko: bool,
+        handicap: u8,
+        ruleset: &str,
+        scoring: &str,
+        strict: bool,
     ) -> PyResult<Self> {
         if !(2..=32).contains(&width) {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
@@ -53,35 +158,152 @@ impl PyGame {
                 "Board height must be between 2 and 32",
             ));
         }
-        Ok(PyGame {
-            inner: make_game_inner_with_options(
-                width as u8,
-                height as u8,
-                komi,
-                min_moves_before_pass_possible as u16,
-                max_moves as u16,
-                superko,
-            ),
+        validate_move_limits(min_moves_before_pass_possible, max_moves)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let max_handicap = max_fixed_handicap(width as u8, height as u8);
+        if handicap != 0 && !(2..=max_handicap).contains(&handicap) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Handicap must be 0 or between 2 and {} on a {}x{} board",
+                max_handicap, width, height
+            )));
+        }
+        let ruleset = Ruleset::from_name(ruleset).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown ruleset: {}", ruleset))
+        })?;
+        let dead_stone_marking = match scoring {
+            "all_alive" => false,
+            "marking" => true,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown scoring rule: {}",
+                    scoring
+                )))
+            }
+        };
+
+        let mut inner = make_game_inner_with_options(
+            width as u8,
+            height as u8,
+            komi,
+            min_moves_before_pass_possible,
+            max_moves,
+            superko,
+        );
+        dispatch_game_mut!(&mut inner, g => {
+            g.set_ruleset(ruleset);
+            g.set_dead_stone_marking(dead_stone_marking);
+            if handicap > 0 {
+                g.place_handicap(handicap);
+            }
+        });
+        Ok(PyGame { inner, strict })
+    }
+
+    /// A name for the configuration, for experiment tracking: size, handicap,
+    /// ruleset and scoring rule, e.g. "go_9x9_h2_chinese". "_marking" is
+    /// appended when dead stones are marked.
+    pub fn name(&self) -> String {
+        dispatch_game!(&self.inner, g => {
+            let mut name = format!("go_{}x{}", g.width(), g.height());
+            if g.handicap() > 0 {
+                name.push_str(&format!("_h{}", g.handicap()));
+            }
+            name.push('_');
+            name.push_str(g.ruleset().name());
+            if g.dead_stone_marking() {
+                name.push_str("_marking");
+            }
+            name
         })
     }
 
+    /// Create a game from the `[rules]` section of a TOML or JSON config file.
+    #[staticmethod]
+    pub fn from_config(path: &str) -> PyResult<Self> {
+        let config = load_config(path)?;
+        Ok(PyGame::from_inner(game_from_rules(&config.rules)))
+    }
+
+    /// Play the `[selfplay]` games of a TOML or JSON config: games with the
+    /// `[rules]` section, both sides searching with the `[search]` settings,
+    /// on the configured number of threads.
+    #[staticmethod]
+    pub fn self_play_from_config(py: Python<'_>, path: &str) -> PyResult<Vec<PyGameRecord>> {
+        let config = load_config(path)?;
+        let inner = game_from_rules(&config.rules);
+        let records = py.detach(|| {
+            dispatch_game!(&inner, g => {
+                selfplay::run_self_play(
+                    || g.clone(),
+                    || config.search.mcts_policy(),
+                    config.selfplay.games,
+                    config.selfplay.seed,
+                    config.selfplay.threads,
+                )
+            })
+        });
+        Ok(records.into_iter().map(PyGameRecord::from_record).collect())
+    }
+
+    /// Read a game from an SGF record, failing on moves that cannot be played.
+    #[staticmethod]
+    pub fn from_sgf(text: &str) -> PyResult<Self> {
+        let to_py_err =
+            |e: sgf::SgfError| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string());
+        let record = sgf::parse_sgf(text).map_err(to_py_err)?;
+
+        let mut inner = make_game_inner_with_options(
+            record.width,
+            record.height,
+            record.komi,
+            0,
+            u16::MAX,
+            true,
+        );
+        dispatch_game_mut!(&mut inner, g => {
+            record.set_up(g);
+            record
+                .replay_into(g, IllegalMovePolicy::Fail)
+                .map_err(to_py_err)?;
+        });
+        Ok(PyGame::from_inner(inner))
+    }
+
+    /// Start a game with the default rules from a position string.
+    #[staticmethod]
+    pub fn from_position_string(text: &str) -> PyResult<Self> {
+        let record = position_string::parse_position_string(text)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let mut inner = make_game_inner(record.width, record.height);
+        dispatch_game_mut!(&mut inner, g => record.set_up(g));
+        Ok(PyGame::from_inner(inner))
+    }
+
     #[staticmethod]
     pub fn standard() -> Self {
-        PyGame {
-            inner: make_game_inner(19, 19),
-        }
+        PyGame::from_inner(make_game_inner(19, 19))
+    }
+
+    /// Whether `set_piece` is rejected after the first move.
+    pub fn strict(&self) -> bool {
+        self.strict
     }
 
     pub fn komi(&self) -> f32 {
         dispatch_game!(&self.inner, g => g.komi())
     }
 
-    pub fn min_moves_before_pass_possible(&self) -> usize {
-        dispatch_game!(&self.inner, g => g.min_moves_before_pass_possible() as usize)
+    pub fn min_moves_before_pass_possible(&self) -> u16 {
+        dispatch_game!(&self.inner, g => g.min_moves_before_pass_possible())
     }
 
-    pub fn max_moves(&self) -> usize {
-        dispatch_game!(&self.inner, g => g.max_moves() as usize)
+    pub fn max_moves(&self) -> u16 {
+        dispatch_game!(&self.inner, g => g.max_moves())
+    }
+
+    /// Moves left before `max_moves` ends the game, or 0 once it is over.
+    pub fn moves_remaining(&self) -> u16 {
+        dispatch_game!(&self.inner, g => g.moves_remaining())
     }
 
     pub fn move_count(&self) -> usize {
@@ -92,6 +314,20 @@ impl PyGame {
         dispatch_game!(&self.inner, g => g.score())
     }
 
+    /// Area score as (black, white), ignoring the ruleset and dead stones.
+    pub fn score_fast(&self) -> (f32, f32) {
+        dispatch_game!(&self.inner, g => g.score_fast())
+    }
+
+    /// `score()` in half points, exact on every platform.
+    pub fn score_half_points(&self) -> (i32, i32) {
+        dispatch_game!(&self.inner, g => g.score_half_points())
+    }
+
+    pub fn score_details(&self) -> PyScoreDetails {
+        PyScoreDetails::from_details(dispatch_game!(&self.inner, g => g.score_details()))
+    }
+
     pub fn width(&self) -> usize {
         dispatch_game!(&self.inner, g => g.width() as usize)
     }
@@ -100,15 +336,76 @@ impl PyGame {
         dispatch_game!(&self.inner, g => g.height() as usize)
     }
 
-    pub fn get_piece(&self, col: usize, row: usize) -> Option<i8> {
-        let pos = Position::new(col as u8, row as u8);
-        dispatch_game!(&self.inner, g => g.get_piece(&pos).map(|p| p as i8))
+    /// Start over from the position in `board_array`, an (height, width)
+    /// array of 1 (Black), -1 (White) and 0 (empty) the size of the game,
+    /// with `to_play` to move, `ko` (a Position or (col, row) tuple)
+    /// forbidden to them and `move_number` moves played before it. The rules
+    /// are kept; moves, prisoners and any result are cleared. Raises
+    /// ValueError for stones without liberties or an occupied ko point.
+    /// Needs numpy.
+    #[pyo3(signature = (board_array, to_play, ko=None, move_number=0))]
+    pub fn set_position(
+        &mut self,
+        board_array: &Bound<'_, PyAny>,
+        to_play: i8,
+        ko: Option<&Bound<'_, PyAny>>,
+        move_number: u32,
+    ) -> PyResult<()> {
+        let to_play = player_from_int(to_play)?;
+        let ko = ko.map(extract_point).transpose()?;
+        let stones = StonesArray::extract(board_array)?;
+        let (height, width) = self.board_shape();
+        if (stones.height, stones.width) != (height, width) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Board array has shape ({}, {}), the game needs ({}, {})",
+                stones.height, stones.width, height, width
+            )));
+        }
+        dispatch_game_mut!(&mut self.inner, g => {
+            g.set_position(&stones.black, &stones.white, to_play, ko, move_number)
+        })
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
     }
 
-    pub fn set_piece(&mut self, col: usize, row: usize, piece: Option<i8>) {
-        let pos = Position::new(col as u8, row as u8);
-        let player = piece.map(|p| Player::from_int(p).expect("Invalid player value"));
-        dispatch_game_mut!(&mut self.inner, g => g.set_piece(&pos, player))
+    /// The stone at a point given as col, row, a Position or a (col, row)
+    /// tuple.
+    #[pyo3(signature = (*point))]
+    pub fn get_piece(&self, point: &Bound<'_, PyTuple>) -> PyResult<Option<i8>> {
+        let pos = point_only(point)?;
+        Ok(dispatch_game!(&self.inner, g => g.get_piece(&pos).map(|p| p as i8)))
+    }
+
+    /// `game[point]`: the stone at a Position or (col, row) tuple. Games are
+    /// changed through moves, so there is no `game[point] = piece`.
+    pub fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<Option<i8>> {
+        dispatch_game!(&self.inner, g => {
+            let pos = index_point(key, g.width(), g.height())?;
+            Ok(g.get_piece(&pos))
+        })
+    }
+
+    /// Put `piece` (1, -1 or None) on a point given as col, row, a Position
+    /// or a (col, row) tuple. Raises RuntimeError in a strict game once a
+    /// move has been played.
+    #[pyo3(signature = (*args))]
+    pub fn set_piece(&mut self, args: &Bound<'_, PyTuple>) -> PyResult<()> {
+        if self.strict && self.move_count() > 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "set_piece cannot be used after the first move of a strict game",
+            ));
+        }
+        let (pos, rest) = split_point(args)?;
+        let [piece] = &rest[..] else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "set_piece takes a point and a piece",
+            ));
+        };
+        let player = piece
+            .extract::<Option<i8>>()?
+            .map(player_from_int)
+            .transpose()?;
+        dispatch_game_mut!(&mut self.inner, g => g.set_piece(&pos, player));
+        Ok(())
     }
 
     pub fn turn(&self) -> i8 {
@@ -123,47 +420,182 @@ impl PyGame {
     // Unified Game Protocol Methods
     // ---------------------------------------------------------------------
 
-    pub fn legal_action_indices(&self) -> Vec<usize> {
+    /// Action indices of the legal moves. With `resign`, the action space
+    /// ends with resignation (see `total_actions`).
+    #[pyo3(signature = (resign=false))]
+    pub fn legal_action_indices(&self, resign: bool) -> Vec<usize> {
+        let config = action_config(resign);
         dispatch_game!(&self.inner, g => {
-            let w = g.width();
-            let h = g.height();
-            g.legal_moves()
+            let mut actions: Vec<usize> = g
+                .legal_moves()
                 .into_iter()
-                .map(|m| encode::encode_move(&m, w, h))
-                .collect()
+                .map(|m| encode::encode_move_with(&m, g, &config))
+                .collect();
+            if resign && g.is_legal_move(&Move::resign()) {
+                actions.push(encode::encode_move_with(&Move::resign(), g, &config));
+            }
+            actions
         })
     }
 
-    pub fn apply_action(&mut self, action: usize) -> bool {
+    /// The legal actions as a numpy bool array of `total_actions(resign)`
+    /// values, built in one pass. Needs numpy.
+    #[pyo3(signature = (resign=false))]
+    pub fn legal_action_mask<'py>(
+        &self,
+        py: Python<'py>,
+        resign: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let array = py
+            .import("numpy")?
+            .call_method1("empty", (self.total_actions(resign), "uint8"))?;
+        self.legal_action_mask_into(&array, resign)?;
+        array.call_method1("view", ("bool",))
+    }
+
+    /// `legal_action_mask` written into `out`, a writable C-contiguous
+    /// uint8 buffer of `total_actions(resign)` values.
+    #[pyo3(signature = (out, resign=false))]
+    pub fn legal_action_mask_into(&self, out: &Bound<'_, PyAny>, resign: bool) -> PyResult<()> {
+        let config = action_config(resign);
+        let buffer = PyBuffer::<u8>::get(out)?;
+        // SAFETY: the GIL stays held and no Python code runs while the slice
+        // is alive.
+        let mask =
+            unsafe { writable_slice(&buffer, out.py(), self.total_actions(resign), "uint8") }?;
+        dispatch_game!(&self.inner, g => encode::legal_action_mask_into(g, &config, mask));
+        Ok(())
+    }
+
+    #[pyo3(signature = (action, resign=false))]
+    pub fn apply_action(&mut self, action: usize, resign: bool) -> PyResult<()> {
+        let config = action_config(resign);
         dispatch_game_mut!(&mut self.inner, g => {
-            let w = g.width();
-            let h = g.height();
-            if let Some(move_) = encode::decode_move(action, w, h) {
-                g.make_move(&move_)
-            } else {
-                false
+            let move_ = encode::decode_move_with(action, g, &config);
+            if !move_.is_some_and(|m| g.make_move(&m)) {
+                let total = encode::total_actions_with(g, &config);
+                return Err(illegal_action_error(g, action, move_, total));
             }
-        })
+        });
+        Ok(())
     }
 
     // ---------------------------------------------------------------------
     // Encoding/decoding
     // ---------------------------------------------------------------------
 
-    pub fn encode_game_planes(&mut self) -> (Vec<f32>, usize, usize, usize) {
-        dispatch_game_mut!(&mut self.inner, g => encode::encode_game_planes(g))
+    /// Encode the position as input planes, through `cache` when given.
+    #[pyo3(signature = (cache=None))]
+    pub fn encode_game_planes(
+        &self,
+        cache: Option<PyRefMut<'_, PyEncodingCache>>,
+    ) -> (Vec<f32>, usize, usize, usize) {
+        match cache {
+            Some(mut cache) => dispatch_game!(&self.inner, g => cache.cache.encode(g)),
+            None => dispatch_game!(&self.inner, g => encode::encode_game_planes(g)),
+        }
+    }
+
+    /// `encode_game_planes` written into `out` instead of a new list: any
+    /// writable C-contiguous float32 buffer holding exactly
+    /// num_planes * height * width values, such as a numpy array or one row
+    /// of a batch. Returns (num_planes, height, width).
+    #[pyo3(signature = (out, cache=None))]
+    pub fn encode_game_planes_into(
+        &self,
+        out: &Bound<'_, PyAny>,
+        cache: Option<PyRefMut<'_, PyEncodingCache>>,
+    ) -> PyResult<(usize, usize, usize)> {
+        let config = cache
+            .as_ref()
+            .map_or_else(encode::EncoderConfig::default, |c| *c.cache.config());
+        let len = dispatch_game!(&self.inner, g => encode::encoded_len(g, &config));
+        let buffer = PyBuffer::<f32>::get(out)?;
+        // SAFETY: the GIL stays held and no Python code runs while the slice
+        // is alive.
+        let data = unsafe { writable_slice(&buffer, out.py(), len, "float32") }?;
+        Ok(match cache {
+            Some(mut cache) => dispatch_game!(&self.inner, g => cache.cache.encode_into(g, data)),
+            None => {
+                dispatch_game!(&self.inner, g => encode::encode_game_planes_into(g, &config, data))
+            }
+        })
+    }
+
+    /// `encode_game_planes` as a new numpy float32 array of shape
+    /// (num_planes, height, width). Needs numpy.
+    #[pyo3(signature = (cache=None))]
+    pub fn encode_game_planes_numpy<'py>(
+        &self,
+        py: Python<'py>,
+        cache: Option<PyRefMut<'_, PyEncodingCache>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let config = cache
+            .as_ref()
+            .map_or_else(encode::EncoderConfig::default, |c| *c.cache.config());
+        let (width, height) =
+            dispatch_game!(&self.inner, g => (g.width() as usize, g.height() as usize));
+        let array = py
+            .import("numpy")?
+            .call_method1("empty", ((config.num_planes(), height, width), "float32"))?;
+        self.encode_game_planes_into(&array, cache)?;
+        Ok(array)
     }
 
-    pub fn decode_action(&self, action: usize) -> Option<PyMove> {
+    /// The position under each symmetry of the board, for training
+    /// augmentation: 8 on square boards, the 4 flips otherwise. Each is a
+    /// pair of the `encode_game_planes_numpy` planes and an int64 array
+    /// permuting a policy over `total_actions(resign)` actions to match,
+    /// as `policy[permutation]`. The first is the untransformed position.
+    /// Needs numpy.
+    #[pyo3(signature = (resign=false))]
+    pub fn symmetries<'py>(
+        &self,
+        py: Python<'py>,
+        resign: bool,
+    ) -> PyResult<Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>> {
+        let config = action_config(resign);
+        let symmetries = dispatch_game!(&self.inner, g => encode::symmetries(g, &config));
+        let (height, width) = self.board_shape();
+        let numpy = py.import("numpy")?;
+        symmetries
+            .into_iter()
+            .map(|(planes, permutation)| {
+                let shape = (config.num_planes(), height, width);
+                let planes_array = numpy.call_method1("empty", (shape, "float32"))?;
+                let buffer = PyBuffer::<f32>::get(&planes_array)?;
+                // SAFETY: the array was just created here and no Python code
+                // runs while the slice is alive.
+                unsafe { writable_slice(&buffer, py, planes.len(), "float32") }?
+                    .copy_from_slice(&planes);
+
+                let permutation_array =
+                    numpy.call_method1("empty", (permutation.len(), "int64"))?;
+                let buffer = PyBuffer::<i64>::get(&permutation_array)?;
+                // SAFETY: as above.
+                let data = unsafe { writable_slice(&buffer, py, permutation.len(), "int64") }?;
+                for (value, &action) in data.iter_mut().zip(&permutation) {
+                    *value = action as i64;
+                }
+                Ok((planes_array, permutation_array))
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (action, resign=false))]
+    pub fn decode_action(&self, action: usize, resign: bool) -> Option<PyMove> {
+        let config = action_config(resign);
         dispatch_game!(&self.inner, g => {
-            let w = g.width();
-            let h = g.height();
-            encode::decode_move(action, w, h).map(|move_| PyMove::from_move(move_))
+            encode::decode_move_with(action, g, &config).map(|move_| PyMove::from_move(move_))
         })
     }
 
-    pub fn total_actions(&self) -> usize {
-        dispatch_game!(&self.inner, g => encode::total_actions(g.width(), g.height()))
+    /// Size of the action space: every point, pass, swap under the swap
+    /// rule, and resignation last with `resign`.
+    #[pyo3(signature = (resign=false))]
+    pub fn total_actions(&self, resign: bool) -> usize {
+        let config = action_config(resign);
+        dispatch_game!(&self.inner, g => encode::total_actions_with(g, &config))
     }
 
     pub fn board_shape(&self) -> (usize, usize) {
@@ -182,16 +614,13 @@ impl PyGame {
         })
     }
 
-    pub fn reward_from_perspective(&self, perspective: i8) -> f32 {
-        dispatch_game!(&self.inner, g => {
+    pub fn reward_from_perspective(&self, perspective: i8) -> PyResult<f32> {
+        let perspective = player_from_int(perspective)?;
+        Ok(dispatch_game!(&self.inner, g => {
             g.outcome()
-                .map(|o| {
-                    o.encode_winner_from_perspective(
-                        Player::from_int(perspective).expect("Invalid perspective"),
-                    )
-                })
+                .map(|o| o.encode_winner_from_perspective(perspective))
                 .unwrap_or(0.0)
-        })
+        }))
     }
 
     pub fn outcome(&self) -> Option<PyGameOutcome> {
@@ -207,35 +636,617 @@ impl PyGame {
         })
     }
 
+    /// Legal placements that capture at least one stone.
+    pub fn capturing_moves(&self) -> Vec<PyMove> {
+        dispatch_game!(&self.inner, g => {
+            g.capturing_moves()
+                .into_iter()
+                .map(PyMove::from_move)
+                .collect()
+        })
+    }
+
     pub fn is_legal_move(&self, move_: &PyMove) -> bool {
         dispatch_game!(&self.inner, g => g.is_legal_move(move_.as_inner()))
     }
 
-    pub fn make_move(&mut self, move_: &PyMove) -> bool {
-        dispatch_game_mut!(&mut self.inner, g => g.make_move(move_.as_inner()))
+    /// Why `move_` is illegal: "game_over", "scoring_phase",
+    /// "pass_too_early", "swap_not_allowed", "off_board", "occupied", "ko",
+    /// "suicide" or "superko". None if it is legal.
+    pub fn why_illegal(&self, move_: &PyMove) -> Option<&'static str> {
+        let reason = dispatch_game!(&self.inner, g => g.illegal_move_reason(move_.as_inner()))?;
+        Some(match reason {
+            IllegalMoveReason::GameOver => "game_over",
+            IllegalMoveReason::ScoringPhase => "scoring_phase",
+            IllegalMoveReason::PassTooEarly { .. } => "pass_too_early",
+            IllegalMoveReason::SwapNotAllowed => "swap_not_allowed",
+            IllegalMoveReason::OffBoard => "off_board",
+            IllegalMoveReason::Occupied => "occupied",
+            IllegalMoveReason::Ko => "ko",
+            IllegalMoveReason::Suicide => "suicide",
+            IllegalMoveReason::Superko => "superko",
+        })
+    }
+
+    /// Raises IllegalMoveError saying why if `move_` cannot be played, or
+    /// GameOverError once the game has ended.
+    pub fn make_move(&mut self, move_: &PyMove) -> PyResult<()> {
+        dispatch_game_mut!(&mut self.inner, g => {
+            if !g.make_move(move_.as_inner()) {
+                return Err(illegal_move_error(g, move_.as_inner()));
+            }
+        });
+        Ok(())
     }
 
     pub fn unmake_move(&mut self) -> bool {
         dispatch_game_mut!(&mut self.inner, g => g.unmake_move())
     }
 
+    /// Play `moves`, each a `Move` or an action index (with resignation
+    /// last when `resign` is set), in one call. Returns the number played.
+    /// Raises IllegalMoveError at the first one that cannot be played,
+    /// leaving the game as it was.
+    #[pyo3(signature = (moves, resign=false))]
+    pub fn play_sequence(
+        &mut self,
+        py: Python<'_>,
+        moves: Vec<Bound<'_, PyAny>>,
+        resign: bool,
+    ) -> PyResult<usize> {
+        let mut items = Vec::with_capacity(moves.len());
+        for item in &moves {
+            if let Ok(move_) = item.cast::<PyMove>() {
+                items.push(SequenceItem::Move(*move_.borrow().as_inner()));
+            } else {
+                items.push(SequenceItem::Action(item.extract().map_err(|_| {
+                    PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                        "play_sequence takes Moves and action indices",
+                    )
+                })?));
+            }
+        }
+
+        let config = action_config(resign);
+        dispatch_game_mut!(&mut self.inner, g => {
+            for (index, item) in items.iter().enumerate() {
+                let move_ = match *item {
+                    SequenceItem::Move(move_) => Some(move_),
+                    SequenceItem::Action(action) => encode::decode_move_with(action, g, &config),
+                };
+                if !move_.is_some_and(|m| g.make_move(&m)) {
+                    let error = match *item {
+                        SequenceItem::Move(move_) => illegal_move_error(g, &move_),
+                        SequenceItem::Action(action) => {
+                            let total = encode::total_actions_with(g, &config);
+                            illegal_action_error(g, action, move_, total)
+                        }
+                    };
+                    g.rewind(index);
+                    return Err(with_context(py, error, &format!("move {}", index + 1)));
+                }
+            }
+        });
+        Ok(items.len())
+    }
+
+    /// Take back the last `n` moves, or all of them if there are fewer.
+    /// Returns the number taken back.
+    pub fn rewind(&mut self, n: usize) -> usize {
+        dispatch_game_mut!(&mut self.inner, g => g.rewind(n))
+    }
+
+    /// Take back every move, keeping the rules and setup stones, to reuse
+    /// the game for another episode.
+    pub fn reset_keep_config(&mut self) {
+        dispatch_game_mut!(&mut self.inner, g => g.reset_keep_config())
+    }
+
+    /// How many times a stone has been played on each point, counting
+    /// replays after captures. Row-major: index = row * width + col.
+    pub fn play_frequency_map(&self) -> Vec<u32> {
+        dispatch_game!(&self.inner, g => g.play_frequency_map())
+    }
+
+    /// Stones captured so far as (by Black, by White), suicided stones
+    /// counting for the opponent.
+    pub fn captures(&self) -> (u32, u32) {
+        dispatch_game!(&self.inner, g => {
+            (g.prisoners(Player::Black), g.prisoners(Player::White))
+        })
+    }
+
+    pub fn passes(&self, player: i8) -> PyResult<u32> {
+        let player = player_from_int(player)?;
+        Ok(dispatch_game!(&self.inner, g => g.passes(player)))
+    }
+
+    pub fn consecutive_passes(&self) -> u32 {
+        dispatch_game!(&self.inner, g => g.consecutive_passes())
+    }
+
+    /// Numbered move list, e.g. "1. B D4  2. W Q16". `style` is "gtp",
+    /// "sgf" or "index".
+    #[pyo3(signature = (style="gtp"))]
+    pub fn movelist_string(&self, style: &str) -> PyResult<String> {
+        let style = MoveListStyle::from_name(style).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown move list style: {}",
+                style
+            ))
+        })?;
+        Ok(dispatch_game!(&self.inner, g => g.movelist_string(style)))
+    }
+
+    pub fn info(&self) -> PyGameInfo {
+        PyGameInfo {
+            info: dispatch_game!(&self.inner, g => g.info().clone()),
+        }
+    }
+
+    pub fn set_info(&mut self, info: &PyGameInfo) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_info(info.info.clone()))
+    }
+
+    /// The position as a single line: board, player to move, ko point,
+    /// prisoners and move number.
+    pub fn to_position_string(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_position_string())
+    }
+
+    pub fn move_number(&self) -> u32 {
+        dispatch_game!(&self.inner, g => g.move_number())
+    }
+
+    /// The game as an SGF record, for opening in other Go tools.
+    pub fn to_sgf(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_sgf())
+    }
+
+    /// Raises GameOverError if the game has already ended.
+    pub fn resign(&mut self, player: i8) -> PyResult<()> {
+        let player = player_from_int(player)?;
+        dispatch_game_mut!(&mut self.inner, g => {
+            if !g.resign(player) {
+                return Err(GameOverError::new_err(format!(
+                    "{} cannot resign: the game is over",
+                    player
+                )));
+            }
+        });
+        Ok(())
+    }
+
     pub fn board(&self) -> PyBoard {
         PyBoard::from_inner(game_to_board_inner!(&self.inner))
     }
 
+    /// Each move played, first move first, paired with the board it was
+    /// played on. Nothing is replayed, so the game itself is left alone.
+    pub fn history(&self) -> Vec<(PyMove, PyBoard)> {
+        dispatch_game!(&self.inner, g => {
+            g.history()
+                .into_iter()
+                .map(|(m, b)| (PyMove::from_move(m), PyBoard::from_inner(b.into())))
+                .collect()
+        })
+    }
+
     pub fn superko(&self) -> bool {
         dispatch_game!(&self.inner, g => g.superko())
     }
 
+    pub fn allow_suicide(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.allow_suicide())
+    }
+
+    pub fn set_allow_suicide(&mut self, allow_suicide: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_allow_suicide(allow_suicide))
+    }
+
+    pub fn swap_rule(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.swap_rule())
+    }
+
+    pub fn set_swap_rule(&mut self, swap_rule: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_swap_rule(swap_rule))
+    }
+
+    pub fn dead_stone_marking(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.dead_stone_marking())
+    }
+
+    pub fn set_dead_stone_marking(&mut self, dead_stone_marking: bool) {
+        dispatch_game_mut!(&mut self.inner, g => g.set_dead_stone_marking(dead_stone_marking))
+    }
+
+    /// One of "playing", "scoring" or "over".
+    pub fn phase(&self) -> &'static str {
+        match dispatch_game!(&self.inner, g => g.phase()) {
+            GamePhase::Playing => "playing",
+            GamePhase::Scoring => "scoring",
+            GamePhase::Over => "over",
+        }
+    }
+
+    /// `positions` holds Positions or (col, row) tuples.
+    pub fn mark_dead(&mut self, positions: Vec<Bound<'_, PyAny>>) -> PyResult<bool> {
+        let positions = positions
+            .iter()
+            .map(extract_point)
+            .collect::<PyResult<Vec<Position>>>()?;
+        Ok(dispatch_game_mut!(&mut self.inner, g => g.mark_dead(&positions)))
+    }
+
+    /// Groups judged dead by random playouts, as (col, row) stone positions.
+    #[pyo3(signature = (
+        rollouts=64,
+        dead_threshold=0.5,
+        seed=0,
+        cycle_window=32,
+        cycle_outcome="score"
+    ))]
+    pub fn estimate_dead_stones(
+        &self,
+        rollouts: u32,
+        dead_threshold: f32,
+        seed: u64,
+        cycle_window: usize,
+        cycle_outcome: &str,
+    ) -> PyResult<Vec<(usize, usize)>> {
+        let config = RolloutConfig {
+            rollouts,
+            dead_threshold,
+            seed,
+            cycle: cycle_detection(cycle_window, cycle_outcome)?,
+        };
+        Ok(dispatch_game!(&self.inner, g => {
+            let w = g.width();
+            rollout::estimate_dead_stones(g, &config)
+                .iter_ones()
+                .map(|idx| {
+                    let pos = Position::from_index(idx, w);
+                    (pos.col as usize, pos.row as usize)
+                })
+                .collect()
+        }))
+    }
+
+    /// Score details after removing the groups judged dead by random playouts.
+    #[pyo3(signature = (
+        rollouts=64,
+        dead_threshold=0.5,
+        seed=0,
+        cycle_window=32,
+        cycle_outcome="score"
+    ))]
+    pub fn score_with_rollouts(
+        &self,
+        rollouts: u32,
+        dead_threshold: f32,
+        seed: u64,
+        cycle_window: usize,
+        cycle_outcome: &str,
+    ) -> PyResult<PyScoreDetails> {
+        let config = RolloutConfig {
+            rollouts,
+            dead_threshold,
+            seed,
+            cycle: cycle_detection(cycle_window, cycle_outcome)?,
+        };
+        Ok(PyScoreDetails::from_details(
+            dispatch_game!(&self.inner, g => rollout::score_with_rollouts(g, &config)),
+        ))
+    }
+
+    /// Play this position out `playouts` times with the no-eye-fill policy,
+    /// split across `threads` threads with the GIL released. Returns numpy
+    /// arrays: outcomes as (black_wins, white_wins, draws) and the mean
+    /// final ownership from Black's side, shape (height, width). Playout
+    /// `i` uses the seed `derive_game_seed(seed, i)`, whatever `threads` is.
+    #[pyo3(signature = (
+        playouts,
+        seed=0,
+        threads=1,
+        cycle_window=32,
+        cycle_outcome="score"
+    ))]
+    pub fn random_playouts<'py>(
+        &self,
+        py: Python<'py>,
+        playouts: u32,
+        seed: u64,
+        threads: usize,
+        cycle_window: usize,
+        cycle_outcome: &str,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let cycle = cycle_detection(cycle_window, cycle_outcome)?;
+        let inner = &self.inner;
+        let stats = py.detach(|| {
+            dispatch_game!(inner, g => {
+                rollout::playout_stats(g, playouts, seed, threads, &cycle)
+            })
+        });
+        let (height, width) = self.board_shape();
+        stats_to_numpy(py, &[stats], &[], height, width)
+    }
+
+    /// Play the game out once with the random playout policy, in Rust with
+    /// the GIL released. Returns the winner (1, -1 or None) and the final
+    /// ownership as a float32 array of shape (height, width), 1.0 for Black
+    /// and -1.0 for White. `seed=None` draws a fresh seed. Needs numpy.
+    #[pyo3(signature = (seed=None, cycle_window=32, cycle_outcome="score"))]
+    pub fn random_playout<'py>(
+        &self,
+        py: Python<'py>,
+        seed: Option<u64>,
+        cycle_window: usize,
+        cycle_outcome: &str,
+    ) -> PyResult<(Option<i8>, Bound<'py, PyAny>)> {
+        let cycle = cycle_detection(cycle_window, cycle_outcome)?;
+        let seed = seed.unwrap_or_else(|| rand::rng().random());
+        let inner = &self.inner;
+        let (winner, ownership) =
+            py.detach(|| dispatch_game!(inner, g => rollout::random_playout(g, seed, &cycle)));
+        let (height, width) = self.board_shape();
+        let array = py
+            .import("numpy")?
+            .call_method1("empty", ((height, width), "float32"))?;
+        let buffer = PyBuffer::<f32>::get(&array)?;
+        // SAFETY: the array was just created here and no Python code runs
+        // while the slice is alive.
+        unsafe { writable_slice(&buffer, py, ownership.len(), "float32") }?
+            .copy_from_slice(&ownership);
+        Ok((winner.map(|p| p as i8), array))
+    }
+
+    /// Who owns each point under area scoring, as a float32 array of shape
+    /// (height, width): 1.0 for Black, -1.0 for White and 0.0 for neutral
+    /// points, or 1.0 for `perspective`'s points if given. Stones count for
+    /// their owner unless marked dead. Needs numpy.
+    #[pyo3(signature = (perspective=None))]
+    pub fn ownership<'py>(
+        &self,
+        py: Python<'py>,
+        perspective: Option<i8>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ownership = match perspective.map(player_from_int).transpose()? {
+            Some(player) => {
+                dispatch_game!(&self.inner, g => g.ownership_map_from_perspective(player))
+            }
+            None => dispatch_game!(&self.inner, g => g.ownership_map_absolute()),
+        };
+        let (height, width) = self.board_shape();
+        let array = py
+            .import("numpy")?
+            .call_method1("empty", ((height, width), "float32"))?;
+        let buffer = PyBuffer::<f32>::get(&array)?;
+        // SAFETY: the array was just created here and no Python code runs
+        // while the slice is alive.
+        unsafe { writable_slice(&buffer, py, ownership.len(), "float32") }?
+            .copy_from_slice(&ownership);
+        Ok(array)
+    }
+
+    #[pyo3(signature = (*point))]
+    pub fn toggle_dead_group(&mut self, point: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        let pos = point_only(point)?;
+        Ok(dispatch_game_mut!(&mut self.inner, g => g.toggle_dead_group(&pos)))
+    }
+
+    #[pyo3(signature = (*point))]
+    pub fn is_dead(&self, point: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        let pos = point_only(point)?;
+        Ok(dispatch_game!(&self.inner, g => g.is_dead(&pos)))
+    }
+
+    /// The stones of the group at a point, as (col, row) tuples in index
+    /// order. Empty if the point is empty.
+    #[pyo3(signature = (*point))]
+    pub fn group_at(&self, point: &Bound<'_, PyTuple>) -> PyResult<Vec<(usize, usize)>> {
+        let pos = point_only(point)?;
+        Ok(dispatch_game!(&self.inner, g => {
+            g.group_at(&pos).map_or_else(Vec::new, |group| {
+                group
+                    .iter_ones()
+                    .map(|idx| {
+                        let p = Position::from_index(idx, g.width());
+                        (p.col as usize, p.row as usize)
+                    })
+                    .collect()
+            })
+        }))
+    }
+
+    /// The liberties of the group at a point, or None if the point is empty.
+    #[pyo3(signature = (*point))]
+    pub fn liberties_at(&self, point: &Bound<'_, PyTuple>) -> PyResult<Option<u32>> {
+        let pos = point_only(point)?;
+        Ok(dispatch_game!(&self.inner, g => g.liberties_at(&pos)))
+    }
+
+    /// A bool array of shape (height, width) marking `player`'s pass-alive
+    /// stones and the points they enclose for certain, by Benson's
+    /// algorithm. Needs numpy.
+    pub fn pass_alive<'py>(&self, py: Python<'py>, player: i8) -> PyResult<Bound<'py, PyAny>> {
+        let player = player_from_int(player)?;
+        let (height, width) = self.board_shape();
+        let array = py
+            .import("numpy")?
+            .call_method1("zeros", ((height, width), "uint8"))?;
+        let buffer = PyBuffer::<u8>::get(&array)?;
+        // SAFETY: the array was just created here and no Python code runs
+        // while the slice is alive.
+        let cells = unsafe { writable_slice(&buffer, py, height * width, "uint8") }?;
+        dispatch_game!(&self.inner, g => {
+            for idx in benson::pass_alive(g.board(), player).iter_ones() {
+                cells[idx] = 1;
+            }
+        });
+        array.call_method1("view", ("bool",))
+    }
+
+    pub fn accept_score(&mut self) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.accept_score())
+    }
+
+    /// SVG of the board with the `top_k` most likely moves of `policy`
+    /// (laid out like `encode_move`) overlaid.
+    #[pyo3(signature = (policy, top_k=analysis::DEFAULT_TOP_K))]
+    pub fn policy_to_svg(&self, policy: Vec<f32>, top_k: usize) -> PyResult<String> {
+        let (w, h) = (self.width(), self.height());
+        if policy.len() < w * h {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Policy has {} entries, expected at least {}",
+                policy.len(),
+                w * h
+            )));
+        }
+        Ok(dispatch_game!(&self.inner, g => analysis::policy_to_svg_top_k(&policy, g, top_k)))
+    }
+
+    pub fn handicap(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.handicap())
+    }
+
+    /// Place fixed handicap stones on an empty board and give White the move.
+    pub fn place_handicap(&mut self, stones: u8) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.place_handicap(stones))
+    }
+
+    /// Komi plus the handicap compensation for the ruleset.
+    pub fn effective_komi(&self) -> f32 {
+        dispatch_game!(&self.inner, g => g.effective_komi())
+    }
+
+    pub fn ruleset(&self) -> String {
+        dispatch_game!(&self.inner, g => g.ruleset().name().to_string())
+    }
+
+    pub fn set_ruleset(&mut self, ruleset: &str) -> PyResult<()> {
+        let ruleset = Ruleset::from_name(ruleset).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown ruleset: {}", ruleset))
+        })?;
+        dispatch_game_mut!(&mut self.inner, g => g.set_ruleset(ruleset));
+        Ok(())
+    }
+
     pub fn ko_point(&self) -> Option<(usize, usize)> {
         dispatch_game!(&self.inner, g => {
             g.ko_point().map(|p| (p.col as usize, p.row as usize))
         })
     }
 
+    /// The 64-bit Zobrist hash of the board, the player to move and the ko
+    /// point, the same in every process: a key for transposition tables.
+    pub fn position_hash(&self) -> u64 {
+        dispatch_game!(&self.inner, g => g.position_hash())
+    }
+
+    /// What `move_` would do if played now, with a `depth`-ply search of the
+    /// replies. `None` if the move is not legal.
+    #[pyo3(signature = (move_, depth=1))]
+    pub fn analyze_move(&self, move_: &PyMove, depth: u32) -> Option<PyMoveAnalysis> {
+        dispatch_game!(&self.inner, g => {
+            g.analyze_move(move_.as_inner(), depth)
+                .map(PyMoveAnalysis::from_analysis)
+        })
+    }
+
+    /// Estimated ko threats for `player`: opponent groups they can put in
+    /// atari without being left in atari themselves.
+    pub fn count_ko_threats(&self, player: i8) -> PyResult<u32> {
+        let player = player_from_int(player)?;
+        Ok(dispatch_game!(&self.inner, g => analysis::count_ko_threats(g, player)))
+    }
+
+    /// The distinct positions reachable in at most `depth` plies, each as
+    /// the first line of moves found to it, breadth-first from the empty
+    /// line. With `symmetry_reduce`, rotations and reflections count once.
+    #[pyo3(signature = (depth, symmetry_reduce=true))]
+    pub fn opening_tree(&self, depth: u32, symmetry_reduce: bool) -> Vec<Vec<PyMove>> {
+        dispatch_game!(&self.inner, g => {
+            analysis::opening_tree(g, depth, symmetry_reduce)
+                .into_iter()
+                .map(|position| position.moves.into_iter().map(PyMove::from_move).collect())
+                .collect()
+        })
+    }
+
+    /// Play `num_games` games between `first` and `second` from copies of this
+    /// game, alternating colors (`first` is Black in even-numbered games).
+    /// Policies are built-in policies or `Policy` subclasses.
+    #[pyo3(signature = (first, second, num_games=2, seed=0))]
+    pub fn play_match(
+        &self,
+        first: &Bound<'_, PyAny>,
+        second: &Bound<'_, PyAny>,
+        num_games: u64,
+        seed: u64,
+    ) -> PyResult<PyMatchResult> {
+        let mut first = PolicyAdapter::new(first)?;
+        let mut second = PolicyAdapter::new(second)?;
+        let result = dispatch_game!(&self.inner, g => {
+            tournament::run_match(|| g.clone(), &mut first, &mut second, num_games, seed)
+        });
+        if let Some(e) = first.take_error().or_else(|| second.take_error()) {
+            return Err(e);
+        }
+        Ok(PyMatchResult::from_result(result))
+    }
+
+    /// Play `num_games` games from copies of this game with `policy` choosing
+    /// the moves of both sides. Game `i` uses the seed
+    /// `derive_game_seed(seed, i)`, as in the Rust self-play runner.
+    #[pyo3(signature = (policy, num_games=1, seed=0))]
+    pub fn self_play(
+        &self,
+        policy: &Bound<'_, PyAny>,
+        num_games: u64,
+        seed: u64,
+    ) -> PyResult<Vec<PyGameRecord>> {
+        let mut policy = PolicyAdapter::new(policy)?;
+        let mut records = Vec::with_capacity(num_games as usize);
+        for index in 0..num_games {
+            let game_seed = selfplay::derive_game_seed(seed, index);
+            let record = dispatch_game!(&self.inner, g => {
+                selfplay::play_game(g.clone(), &mut policy, index, game_seed)
+            });
+            if let Some(e) = policy.take_error() {
+                return Err(e);
+            }
+            records.push(PyGameRecord::from_record(record));
+        }
+        Ok(records)
+    }
+
+    /// Write `records` of games played from this position to `path` in the
+    /// Leela Zero training format. Returns the number of positions.
+    pub fn write_leela_zero(
+        &self,
+        records: Vec<PyRef<'_, PyGameRecord>>,
+        path: &str,
+    ) -> PyResult<usize> {
+        let to_py_err = |e: training::TrainingDataError| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+        };
+        let file = std::fs::File::create(path).map_err(|e| to_py_err(e.into()))?;
+        let mut out = std::io::BufWriter::new(file);
+        let mut positions = 0;
+        for record in &records {
+            positions += dispatch_game!(&self.inner, g => {
+                training::write_leela_zero(g, record.record(), &mut out)
+            })
+            .map_err(to_py_err)?;
+        }
+        out.flush().map_err(|e| to_py_err(e.into()))?;
+        Ok(positions)
+    }
+
     pub fn clone(&self) -> PyGame {
         PyGame {
             inner: self.inner.clone(),
+            strict: self.strict,
         }
     }
 
@@ -257,12 +1268,14 @@ impl PyGame {
     pub fn __repr__(&self) -> String {
         dispatch_game!(&self.inner, g => {
             format!(
-                "Game(width={}, height={}, turn={:?}, over={}, superko={})",
+                "Game(width={}, height={}, turn={:?}, over={}, superko={}, captures=({}, {}))",
                 g.width(),
                 g.height(),
                 g.turn(),
                 g.is_over(),
-                g.superko()
+                g.superko(),
+                g.prisoners(Player::Black),
+                g.prisoners(Player::White)
             )
         })
     }
@@ -0,0 +1,85 @@
+use pyo3::prelude::*;
+
+use crate::game::GameInfo;
+
+/// Player names, ranks, date, event and recorded result of a game.
+#[pyclass(name = "GameInfo", skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyGameInfo {
+    pub(super) info: GameInfo,
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyGameInfo {
+    #[new]
+    #[pyo3(signature = (
+        black_player=None,
+        white_player=None,
+        black_rank=None,
+        white_rank=None,
+        date=None,
+        event=None,
+        result=None,
+    ))]
+    pub fn new(
+        black_player: Option<String>,
+        white_player: Option<String>,
+        black_rank: Option<String>,
+        white_rank: Option<String>,
+        date: Option<String>,
+        event: Option<String>,
+        result: Option<String>,
+    ) -> Self {
+        PyGameInfo {
+            info: GameInfo {
+                black_player,
+                white_player,
+                black_rank,
+                white_rank,
+                date,
+                event,
+                result,
+            },
+        }
+    }
+
+    pub fn black_player(&self) -> Option<String> {
+        self.info.black_player.clone()
+    }
+
+    pub fn white_player(&self) -> Option<String> {
+        self.info.white_player.clone()
+    }
+
+    pub fn black_rank(&self) -> Option<String> {
+        self.info.black_rank.clone()
+    }
+
+    pub fn white_rank(&self) -> Option<String> {
+        self.info.white_rank.clone()
+    }
+
+    pub fn date(&self) -> Option<String> {
+        self.info.date.clone()
+    }
+
+    pub fn event(&self) -> Option<String> {
+        self.info.event.clone()
+    }
+
+    pub fn result(&self) -> Option<String> {
+        self.info.result.clone()
+    }
+
+    pub fn __eq__(&self, other: &PyGameInfo) -> bool {
+        self.info == other.info
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "GameInfo(black_player={:?}, white_player={:?}, date={:?})",
+            self.info.black_player, self.info.white_player, self.info.date
+        )
+    }
+}
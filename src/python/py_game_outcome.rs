@@ -1,7 +1,7 @@
 use pyo3::prelude::*;
 
-use crate::outcome::GameOutcome;
-use crate::player::Player;
+use super::py_errors::player_from_int;
+use crate::outcome::{EndReason, GameOutcome};
 
 #[pyclass(name = "GameOutcome")]
 #[derive(Clone, Copy, Debug)]
@@ -27,16 +27,58 @@ impl PyGameOutcome {
         self.outcome.encode_winner_absolute()
     }
 
-    pub fn encode_winner_from_perspective(&self, perspective: i8) -> f32 {
-        self.outcome.encode_winner_from_perspective(
-            Player::from_int(perspective).expect("Unrecognized perspective"),
-        )
+    pub fn encode_winner_from_perspective(&self, perspective: i8) -> PyResult<f32> {
+        Ok(self
+            .outcome
+            .encode_winner_from_perspective(player_from_int(perspective)?))
     }
 
     pub fn is_draw(&self) -> bool {
         self.outcome.is_draw()
     }
 
+    /// Black's score minus white's, or None if the game was not decided by counting.
+    pub fn margin(&self) -> Option<f32> {
+        self.outcome.margin()
+    }
+
+    /// `margin()` in half points, exact on every platform.
+    pub fn margin_half_points(&self) -> Option<i32> {
+        self.outcome.margin_half_points()
+    }
+
+    pub fn margin_from_perspective(&self, perspective: i8) -> PyResult<Option<f32>> {
+        Ok(self
+            .outcome
+            .margin_from_perspective(player_from_int(perspective)?))
+    }
+
+    /// One of "double_pass", "max_moves", "no_legal_moves", "resignation",
+    /// "timeout" or "no_result".
+    pub fn reason(&self) -> &'static str {
+        match self.outcome.reason() {
+            EndReason::DoublePass => "double_pass",
+            EndReason::MaxMoves => "max_moves",
+            EndReason::NoLegalMoves => "no_legal_moves",
+            EndReason::Resignation => "resignation",
+            EndReason::Timeout => "timeout",
+            EndReason::NoResult => "no_result",
+        }
+    }
+
+    /// SGF-style result such as "B+3.5", "W+R" or "0".
+    #[allow(clippy::wrong_self_convention)] // pymethods cannot take self by value
+    pub fn to_result_string(&self) -> String {
+        self.outcome.to_result_string()
+    }
+
+    #[staticmethod]
+    pub fn from_result_string(s: &str) -> PyResult<Self> {
+        GameOutcome::from_result_string(s)
+            .map(PyGameOutcome::from_outcome)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
     pub fn __str__(&self) -> String {
         self.outcome.to_string()
     }
@@ -1,9 +1,10 @@
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 
+use super::py_errors::player_from_int;
 use super::py_move::PyMove;
-use crate::gtp::{GenmoveResult, GtpEngine};
-use crate::player::Player;
+use crate::gtp::{FinalStatus, GenmoveResult, GtpEngine};
+use crate::render;
 
 #[pyclass(name = "GtpEngine")]
 pub struct PyGtpEngine {
@@ -42,6 +43,16 @@ impl PyGtpEngine {
         })
     }
 
+    /// Connect to an engine serving GTP on `host`:`port`.
+    #[staticmethod]
+    #[pyo3(signature = (host, port, size=19, komi=7.5))]
+    pub fn connect(host: &str, port: u16, size: u8, komi: f32) -> PyResult<Self> {
+        let engine = GtpEngine::connect((host, port), size, komi).map_err(gtp_err_to_py)?;
+        Ok(PyGtpEngine {
+            inner: Some(engine),
+        })
+    }
+
     /// Play a move for the current turn's player.
     pub fn play(&mut self, m: &PyMove) -> PyResult<()> {
         self.engine_mut()?
@@ -51,8 +62,7 @@ impl PyGtpEngine {
 
     /// Play a move as a specific player (1=Black, -1=White).
     pub fn play_as(&mut self, player: i8, m: &PyMove) -> PyResult<()> {
-        let p = Player::from_int(player)
-            .ok_or_else(|| PyRuntimeError::new_err("Invalid player value"))?;
+        let p = player_from_int(player)?;
         self.engine_mut()?
             .play_as(p, *m.as_inner())
             .map_err(gtp_err_to_py)
@@ -68,14 +78,42 @@ impl PyGtpEngine {
 
     /// Ask the engine to generate a move as a specific player.
     pub fn genmove_as(&mut self, player: i8) -> PyResult<Option<PyMove>> {
-        let p = Player::from_int(player)
-            .ok_or_else(|| PyRuntimeError::new_err("Invalid player value"))?;
+        let p = player_from_int(player)?;
         match self.engine_mut()?.genmove_as(p).map_err(gtp_err_to_py)? {
             GenmoveResult::Move(m) => Ok(Some(PyMove::from_move(m))),
             GenmoveResult::Resign => Ok(None),
         }
     }
 
+    /// Ask the engine for a move with `kgs-genmove_cleanup`, which captures
+    /// dead stones before passing. Returns None if the engine resigns.
+    pub fn genmove_cleanup(&mut self) -> PyResult<Option<PyMove>> {
+        match self
+            .engine_mut()?
+            .genmove_cleanup()
+            .map_err(gtp_err_to_py)?
+        {
+            GenmoveResult::Move(m) => Ok(Some(PyMove::from_move(m))),
+            GenmoveResult::Resign => Ok(None),
+        }
+    }
+
+    /// The (col, row) of the stones the engine gives `status`: "alive",
+    /// "dead" or "seki".
+    pub fn final_status_list(&mut self, status: &str) -> PyResult<Vec<(u8, u8)>> {
+        let status = FinalStatus::from_name(status).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "unknown final status: {}",
+                status
+            ))
+        })?;
+        let stones = self
+            .engine_mut()?
+            .final_status_list(status)
+            .map_err(gtp_err_to_py)?;
+        Ok(stones.iter().map(|p| (p.col, p.row)).collect())
+    }
+
     /// Undo the last move.
     pub fn undo(&mut self) -> PyResult<()> {
         self.engine_mut()?.undo().map_err(gtp_err_to_py)
@@ -116,6 +154,15 @@ impl PyGtpEngine {
         Ok(self.engine()?.score())
     }
 
+    /// The local game's board drawn in a theme of `render::THEMES`.
+    #[pyo3(signature = (theme="text"))]
+    pub fn render(&self, theme: &str) -> PyResult<String> {
+        let renderer = render::renderer_for_theme(theme).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown theme: {}", theme))
+        })?;
+        Ok(self.engine()?.render(renderer.as_ref()))
+    }
+
     /// Get the board size.
     pub fn size(&self) -> PyResult<u8> {
         Ok(self.engine()?.size())
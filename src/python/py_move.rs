@@ -1,6 +1,9 @@
 use pyo3::prelude::*;
+use pyo3::types::PyTuple;
 
+use super::py_position::{point_only, PyPosition};
 use crate::encode;
+use crate::position::Position;
 use crate::r#move::Move;
 
 #[pyclass(name = "Move")]
@@ -23,11 +26,15 @@ impl PyMove {
 #[hotpath::measure_all]
 #[pymethods]
 impl PyMove {
+    /// A stone on a point given as col, row, a Position or a (col, row)
+    /// tuple.
     #[staticmethod]
-    pub fn place(col: usize, row: usize) -> Self {
-        PyMove {
-            move_: Move::place(col as u8, row as u8),
-        }
+    #[pyo3(signature = (*point))]
+    pub fn place(point: &Bound<'_, PyTuple>) -> PyResult<Self> {
+        let pos = point_only(point)?;
+        Ok(PyMove {
+            move_: Move::place(pos.col, pos.row),
+        })
     }
 
     #[staticmethod]
@@ -37,10 +44,32 @@ impl PyMove {
         }
     }
 
+    #[staticmethod]
+    pub fn resign() -> Self {
+        PyMove {
+            move_: Move::resign(),
+        }
+    }
+
+    #[staticmethod]
+    pub fn swap() -> Self {
+        PyMove {
+            move_: Move::swap(),
+        }
+    }
+
     pub fn is_pass(&self) -> bool {
         self.move_.is_pass()
     }
 
+    pub fn is_resign(&self) -> bool {
+        self.move_.is_resign()
+    }
+
+    pub fn is_swap(&self) -> bool {
+        self.move_.is_swap()
+    }
+
     pub fn col(&self) -> Option<usize> {
         self.move_.col().map(|c| c as usize)
     }
@@ -49,6 +78,14 @@ impl PyMove {
         self.move_.row().map(|r| r as usize)
     }
 
+    /// The point of a placement, or None for any other move.
+    pub fn position(&self) -> Option<PyPosition> {
+        match self.move_ {
+            Move::Place { col, row } => Some(PyPosition::from_position(Position::new(col, row))),
+            _ => None,
+        }
+    }
+
     pub fn encode(&self, board_width: usize, board_height: usize) -> usize {
         encode::encode_move(&self.move_, board_width as u8, board_height as u8)
     }
@@ -63,6 +100,24 @@ impl PyMove {
         }
     }
 
+    /// The move in GTP notation, such as "D4", "pass", "resign" or "swap".
+    pub fn to_gtp(&self, board_height: usize) -> String {
+        self.move_.to_gtp(board_height as u8)
+    }
+
+    /// Parse a move in GTP notation, case-insensitively. Raises ValueError
+    /// if it is malformed or off the board.
+    #[staticmethod]
+    pub fn from_gtp(s: &str, board_width: usize, board_height: usize) -> PyResult<Self> {
+        match Move::from_gtp(s, board_width as u8, board_height as u8) {
+            Some(mv) => Ok(PyMove { move_: mv }),
+            _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "invalid GTP move: {}",
+                s
+            ))),
+        }
+    }
+
     pub fn __str__(&self) -> String {
         self.move_.to_string()
     }
@@ -71,6 +126,8 @@ impl PyMove {
         match &self.move_ {
             Move::Place { col, row } => format!("Move.place({}, {})", col, row),
             Move::Pass => "Move.pass_move()".to_string(),
+            Move::Resign => "Move.resign()".to_string(),
+            Move::Swap => "Move.swap()".to_string(),
         }
     }
 
@@ -0,0 +1,53 @@
+use pyo3::prelude::*;
+
+use crate::analysis::MoveAnalysis;
+
+#[pyclass(name = "MoveAnalysis", skip_from_py_object)]
+#[derive(Clone, Copy, Debug)]
+pub struct PyMoveAnalysis {
+    analysis: MoveAnalysis,
+}
+
+#[hotpath::measure_all]
+impl PyMoveAnalysis {
+    pub(super) fn from_analysis(analysis: MoveAnalysis) -> Self {
+        PyMoveAnalysis { analysis }
+    }
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyMoveAnalysis {
+    pub fn captures(&self) -> u32 {
+        self.analysis.captures
+    }
+
+    pub fn liberties(&self) -> Option<u32> {
+        self.analysis.liberties
+    }
+
+    pub fn self_atari(&self) -> bool {
+        self.analysis.self_atari
+    }
+
+    pub fn ataris_created(&self) -> u32 {
+        self.analysis.ataris_created
+    }
+
+    pub fn ataris_resolved(&self) -> u32 {
+        self.analysis.ataris_resolved
+    }
+
+    /// Score margin for the mover after the shallow search.
+    pub fn evaluation(&self) -> f32 {
+        self.analysis.evaluation
+    }
+
+    pub fn __repr__(&self) -> String {
+        let a = &self.analysis;
+        format!(
+            "MoveAnalysis(captures={}, liberties={:?}, self_atari={}, ataris_created={}, ataris_resolved={}, evaluation={})",
+            a.captures, a.liberties, a.self_atari, a.ataris_created, a.ataris_resolved, a.evaluation
+        )
+    }
+}
@@ -0,0 +1,89 @@
+use pyo3::buffer::PyBuffer;
+use pyo3::prelude::*;
+
+use super::dispatch::*;
+use super::py_batch_game::same_size_games;
+use super::py_encode::writable_slice;
+use super::py_game::PyGame;
+use super::py_policy::cycle_detection;
+use crate::rollout::{self, PlayoutStats};
+use crate::selfplay::derive_game_seed;
+
+/// `stats` as numpy arrays: outcomes of shape `batch + (3,)` holding
+/// (black_wins, white_wins, draws), and ownership of shape
+/// `batch + (height, width)`.
+#[hotpath::measure]
+pub(super) fn stats_to_numpy<'py>(
+    py: Python<'py>,
+    stats: &[PlayoutStats],
+    batch: &[usize],
+    height: usize,
+    width: usize,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let numpy = py.import("numpy")?;
+    let shape = |tail: &[usize]| [batch, tail].concat();
+
+    let outcomes = numpy.call_method1("empty", (shape(&[3]), "int64"))?;
+    let buffer = PyBuffer::<i64>::get(&outcomes)?;
+    // SAFETY: the array was just created here and no Python code runs while
+    // the slice is alive.
+    let data = unsafe { writable_slice(&buffer, py, stats.len() * 3, "int64") }?;
+    for (row, s) in data.chunks_mut(3).zip(stats) {
+        row.copy_from_slice(&[
+            s.counts.black_wins as i64,
+            s.counts.white_wins as i64,
+            s.counts.draws as i64,
+        ]);
+    }
+
+    let ownership = numpy.call_method1("empty", (shape(&[height, width]), "float32"))?;
+    let buffer = PyBuffer::<f32>::get(&ownership)?;
+    // SAFETY: as above.
+    let data = unsafe { writable_slice(&buffer, py, stats.len() * height * width, "float32") }?;
+    for (row, s) in data.chunks_mut(height * width).zip(stats) {
+        row.copy_from_slice(&s.ownership);
+    }
+    Ok((outcomes, ownership))
+}
+
+/// `Game.random_playouts` for every game in `games`, which must share a
+/// board size. Game `i` uses the seed `derive_game_seed(seed, i)`. Returns
+/// outcomes of shape (len(games), 3) and ownership of shape
+/// (len(games), height, width). The GIL is released while playing.
+#[pyfunction]
+#[pyo3(signature = (
+    games,
+    playouts,
+    seed=0,
+    threads=1,
+    cycle_window=32,
+    cycle_outcome="score"
+))]
+#[hotpath::measure]
+pub fn random_playouts_batch<'py>(
+    py: Python<'py>,
+    games: Vec<PyRef<'py, PyGame>>,
+    playouts: u32,
+    seed: u64,
+    threads: usize,
+    cycle_window: usize,
+    cycle_outcome: &str,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let cycle = cycle_detection(cycle_window, cycle_outcome)?;
+    let inners = same_size_games(&games, "random_playouts_batch")?;
+    let size = dispatch_game!(&inners[0], g => (g.width(), g.height()));
+
+    let stats: Vec<PlayoutStats> = py.detach(|| {
+        inners
+            .iter()
+            .enumerate()
+            .map(|(index, inner)| {
+                let seed = derive_game_seed(seed, index as u64);
+                dispatch_game!(inner, g => {
+                    rollout::playout_stats(g, playouts, seed, threads, &cycle)
+                })
+            })
+            .collect()
+    });
+    stats_to_numpy(py, &stats, &[stats.len()], size.1 as usize, size.0 as usize)
+}
@@ -0,0 +1,397 @@
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::dispatch::*;
+use super::py_game::{load_config, PyGame};
+use super::py_game_outcome::PyGameOutcome;
+use super::py_move::PyMove;
+use crate::game::Game;
+use crate::policy::{
+    CycleDetection, CycleOutcome, HeuristicPolicy, MctsPolicy, MovePolicy, NoEyeFillPolicy,
+    UniformPolicy,
+};
+use crate::r#move::Move;
+use crate::selfplay::GameRecord;
+use crate::tournament::MatchResult;
+use crate::wire::{self, WireMessage};
+
+/// Base class for move policies. Subclass it in Python and implement
+/// `choose(game) -> Move` to play your own agent through `Game.play_match`
+/// and `Game.self_play`.
+#[pyclass(name = "Policy", subclass)]
+pub struct PyPolicy;
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyPolicy {
+    #[new]
+    pub fn new() -> Self {
+        PyPolicy
+    }
+
+    pub fn choose(&self, _game: &PyGame) -> PyResult<PyMove> {
+        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
+            "Policy subclasses must implement choose(game)",
+        ))
+    }
+}
+
+/// A built-in policy, run in Rust without calling back into Python.
+#[derive(Clone, Copy, Debug)]
+enum Builtin {
+    Uniform,
+    NoEyeFill,
+    Heuristic,
+    Mcts(MctsPolicy),
+}
+
+#[hotpath::measure_all]
+impl Builtin {
+    fn choose<const NW: usize>(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        match self {
+            Builtin::Uniform => UniformPolicy.choose(game, rng),
+            Builtin::NoEyeFill => NoEyeFillPolicy.choose(game, rng),
+            Builtin::Heuristic => HeuristicPolicy.choose(game, rng),
+            Builtin::Mcts(policy) => policy.choose(game, rng),
+        }
+    }
+}
+
+/// Playout cycle detection from the Python `cycle_window` and
+/// `cycle_outcome` arguments.
+#[hotpath::measure]
+pub(super) fn cycle_detection(window: usize, outcome: &str) -> PyResult<CycleDetection> {
+    let outcome = CycleOutcome::from_name(outcome).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown cycle outcome: {}",
+            outcome
+        ))
+    })?;
+    Ok(CycleDetection { window, outcome })
+}
+
+#[hotpath::measure]
+fn choose_builtin(builtin: &mut Builtin, rng: &mut StdRng, game: &PyGame) -> PyMove {
+    dispatch_game!(&game.inner, g => PyMove::from_move(builtin.choose(g, rng)))
+}
+
+/// A seeded built-in policy without further settings.
+macro_rules! builtin_policy {
+    ($ty:ident, $name:literal, $builtin:expr, $doc:literal) => {
+        #[doc = $doc]
+        #[pyclass(name = $name, extends = PyPolicy)]
+        pub struct $ty {
+            builtin: Builtin,
+            rng: StdRng,
+        }
+
+        #[hotpath::measure_all]
+        #[pymethods]
+        impl $ty {
+            #[new]
+            #[pyo3(signature = (seed=0))]
+            pub fn new(seed: u64) -> (Self, PyPolicy) {
+                let policy = $ty {
+                    builtin: $builtin,
+                    rng: StdRng::seed_from_u64(seed),
+                };
+                (policy, PyPolicy)
+            }
+
+            /// Pick a move for `game`'s side to play with this policy's own
+            /// random generator.
+            pub fn choose(&mut self, game: &PyGame) -> PyMove {
+                choose_builtin(&mut self.builtin, &mut self.rng, game)
+            }
+        }
+    };
+}
+
+builtin_policy!(
+    PyUniformPolicy,
+    "UniformPolicy",
+    Builtin::Uniform,
+    "Uniformly random legal moves."
+);
+builtin_policy!(
+    PyNoEyeFillPolicy,
+    "NoEyeFillPolicy",
+    Builtin::NoEyeFill,
+    "Random moves that never fill the mover's own eyes."
+);
+builtin_policy!(
+    PyHeuristicPolicy,
+    "HeuristicPolicy",
+    Builtin::Heuristic,
+    "Random moves that capture when possible and avoid self-atari."
+);
+
+/// UCT tree search with random playouts.
+#[pyclass(name = "MctsPolicy", extends = PyPolicy)]
+pub struct PyMctsPolicy {
+    builtin: Builtin,
+    rng: StdRng,
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyMctsPolicy {
+    /// Playouts that repeat a position from their last `cycle_window`
+    /// moves stop early and are scored as they stand, or count as draws with
+    /// `cycle_outcome="draw"`.
+    #[new]
+    #[pyo3(signature = (
+        iterations=1000,
+        exploration=std::f32::consts::SQRT_2,
+        seed=0,
+        cycle_window=32,
+        cycle_outcome="score"
+    ))]
+    pub fn new(
+        iterations: u32,
+        exploration: f32,
+        seed: u64,
+        cycle_window: usize,
+        cycle_outcome: &str,
+    ) -> PyResult<(Self, PyPolicy)> {
+        let policy = PyMctsPolicy {
+            builtin: Builtin::Mcts(MctsPolicy {
+                iterations,
+                exploration,
+                cycle: cycle_detection(cycle_window, cycle_outcome)?,
+            }),
+            rng: StdRng::seed_from_u64(seed),
+        };
+        Ok((policy, PyPolicy))
+    }
+
+    /// A policy with the `[search]` settings of a TOML or JSON config file,
+    /// seeded with its `[selfplay]` seed.
+    #[staticmethod]
+    pub fn from_config(py: Python<'_>, path: &str) -> PyResult<Py<PyMctsPolicy>> {
+        let config = load_config(path)?;
+        let policy = PyMctsPolicy {
+            builtin: Builtin::Mcts(config.search.mcts_policy()),
+            rng: StdRng::seed_from_u64(config.selfplay.seed),
+        };
+        Py::new(py, PyClassInitializer::from(PyPolicy).add_subclass(policy))
+    }
+
+    /// Pick a move for `game`'s side to play with this policy's own random
+    /// generator.
+    pub fn choose(&mut self, game: &PyGame) -> PyMove {
+        choose_builtin(&mut self.builtin, &mut self.rng, game)
+    }
+}
+
+enum PolicyKind<'py> {
+    Builtin(Builtin),
+    Python(Bound<'py, PyAny>),
+}
+
+/// Plays a Python policy object inside the Rust runners. Built-in policies
+/// run directly; anything else has its `choose(game)` method called.
+///
+/// A Python exception is kept and the policy resigns from then on, so the
+/// runner finishes quickly; check `take_error` afterwards.
+pub(super) struct PolicyAdapter<'py> {
+    kind: PolicyKind<'py>,
+    error: Option<PyErr>,
+}
+
+#[hotpath::measure_all]
+impl<'py> PolicyAdapter<'py> {
+    pub(super) fn new(policy: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let builtin = if let Ok(p) = policy.cast::<PyUniformPolicy>() {
+            Some(p.borrow().builtin)
+        } else if let Ok(p) = policy.cast::<PyNoEyeFillPolicy>() {
+            Some(p.borrow().builtin)
+        } else if let Ok(p) = policy.cast::<PyHeuristicPolicy>() {
+            Some(p.borrow().builtin)
+        } else if let Ok(p) = policy.cast::<PyMctsPolicy>() {
+            Some(p.borrow().builtin)
+        } else {
+            None
+        };
+
+        let kind = match builtin {
+            Some(builtin) => PolicyKind::Builtin(builtin),
+            None if policy.hasattr("choose")? => PolicyKind::Python(policy.clone()),
+            None => {
+                return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                    "policy must have a choose(game) method",
+                ))
+            }
+        };
+        Ok(PolicyAdapter { kind, error: None })
+    }
+
+    /// The built-in policy called `name`: "uniform", "noeyefill",
+    /// "heuristic", or "mcts" searching `visits` playouts per move.
+    pub(super) fn from_name(name: &str, visits: u32) -> PyResult<Self> {
+        let builtin = match name {
+            "uniform" => Builtin::Uniform,
+            "noeyefill" => Builtin::NoEyeFill,
+            "heuristic" => Builtin::Heuristic,
+            "mcts" => Builtin::Mcts(MctsPolicy::new(visits)),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown policy: {}",
+                    name
+                )))
+            }
+        };
+        Ok(PolicyAdapter {
+            kind: PolicyKind::Builtin(builtin),
+            error: None,
+        })
+    }
+
+    pub(super) fn take_error(&mut self) -> Option<PyErr> {
+        self.error.take()
+    }
+}
+
+#[hotpath::measure_all]
+impl<'py, const NW: usize> MovePolicy<NW> for PolicyAdapter<'py>
+where
+    GameInner: From<Game<NW>>,
+{
+    fn choose(&mut self, game: &Game<NW>, rng: &mut StdRng) -> Move {
+        let obj = match &mut self.kind {
+            PolicyKind::Builtin(builtin) => return builtin.choose(game, rng),
+            PolicyKind::Python(obj) => obj,
+        };
+        if self.error.is_some() {
+            return Move::resign();
+        }
+
+        let py_game = PyGame::from_inner(GameInner::from(game.clone()));
+        let chosen = obj
+            .call_method1("choose", (py_game,))
+            .and_then(|m| Ok(*m.cast::<PyMove>()?.borrow().as_inner()));
+        match chosen {
+            Ok(move_) => move_,
+            Err(e) => {
+                self.error = Some(e);
+                Move::resign()
+            }
+        }
+    }
+}
+
+/// One finished game played by `Game.play_match` or `Game.self_play`.
+#[pyclass(name = "GameRecord", skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyGameRecord {
+    record: GameRecord,
+}
+
+#[hotpath::measure_all]
+impl PyGameRecord {
+    pub(super) fn from_record(record: GameRecord) -> Self {
+        PyGameRecord { record }
+    }
+
+    pub(super) fn record(&self) -> &GameRecord {
+        &self.record
+    }
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyGameRecord {
+    pub fn index(&self) -> u64 {
+        self.record.index
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.record.seed
+    }
+
+    pub fn moves(&self) -> Vec<PyMove> {
+        self.record
+            .moves
+            .iter()
+            .map(|&m| PyMove::from_move(m))
+            .collect()
+    }
+
+    pub fn outcome(&self) -> Option<PyGameOutcome> {
+        self.record.outcome.map(PyGameOutcome::from_outcome)
+    }
+
+    /// This record as a wire message frame, for `decode_wire_messages`.
+    pub fn to_wire(&self) -> Vec<u8> {
+        wire::encode_message(&WireMessage::GameRecord(self.record.clone()))
+    }
+
+    pub fn __repr__(&self) -> String {
+        let result = self
+            .record
+            .outcome
+            .map_or("?".to_string(), |o| o.to_result_string());
+        format!(
+            "GameRecord(index={}, moves={}, result={})",
+            self.record.index,
+            self.record.moves.len(),
+            result
+        )
+    }
+}
+
+/// Results of `Game.play_match`, counted from the first policy's side.
+#[pyclass(name = "MatchResult", skip_from_py_object)]
+#[derive(Clone, Debug)]
+pub struct PyMatchResult {
+    result: MatchResult,
+}
+
+#[hotpath::measure_all]
+impl PyMatchResult {
+    pub(super) fn from_result(result: MatchResult) -> Self {
+        PyMatchResult { result }
+    }
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyMatchResult {
+    pub fn first_wins(&self) -> u32 {
+        self.result.first_wins
+    }
+
+    pub fn second_wins(&self) -> u32 {
+        self.result.second_wins
+    }
+
+    pub fn draws(&self) -> u32 {
+        self.result.draws
+    }
+
+    pub fn num_games(&self) -> usize {
+        self.result.num_games()
+    }
+
+    /// One point per win and half a point per draw for the first policy.
+    pub fn first_score(&self) -> f32 {
+        self.result.first_score()
+    }
+
+    pub fn records(&self) -> Vec<PyGameRecord> {
+        self.result
+            .records
+            .iter()
+            .cloned()
+            .map(PyGameRecord::from_record)
+            .collect()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "MatchResult(first_wins={}, second_wins={}, draws={})",
+            self.result.first_wins, self.result.second_wins, self.result.draws
+        )
+    }
+}
@@ -0,0 +1,152 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyInt, PyTuple};
+
+use crate::position::Position;
+
+#[pyclass(name = "Position", from_py_object)]
+#[derive(Clone, Copy, Debug)]
+pub struct PyPosition {
+    pub(super) position: Position,
+}
+
+#[hotpath::measure_all]
+impl PyPosition {
+    pub(super) fn from_position(position: Position) -> Self {
+        PyPosition { position }
+    }
+}
+
+/// `value` as a point: a Position or a (col, row) tuple.
+#[hotpath::measure]
+pub(super) fn extract_point(value: &Bound<'_, PyAny>) -> PyResult<Position> {
+    if let Ok(position) = value.cast::<PyPosition>() {
+        return Ok(position.borrow().position);
+    }
+    let (col, row) = value.extract::<(u8, u8)>().map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Expected a Position or a (col, row) tuple, got {}",
+            value
+        ))
+    })?;
+    Ok(Position::new(col, row))
+}
+
+/// `key` of `board[key]` as a point, raising IndexError if it is off a
+/// `width` x `height` board.
+#[hotpath::measure]
+pub(super) fn index_point(key: &Bound<'_, PyAny>, width: u8, height: u8) -> PyResult<Position> {
+    let position = extract_point(key)?;
+    if !position.is_valid(width, height) {
+        return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!(
+            "({}, {}) is off the {}x{} board",
+            position.col, position.row, width, height
+        )));
+    }
+    Ok(position)
+}
+
+/// The point at the front of `args`, given as a Position, a (col, row)
+/// tuple or separate col and row ints, and the arguments after it.
+#[hotpath::measure]
+pub(super) fn split_point<'py>(
+    args: &Bound<'py, PyTuple>,
+) -> PyResult<(Position, Vec<Bound<'py, PyAny>>)> {
+    let mut items = args.iter();
+    let first = items.next().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>("Expected a point: col, row or a Position")
+    })?;
+    if !first.is_instance_of::<PyInt>() {
+        return Ok((extract_point(&first)?, items.collect()));
+    }
+    let row = items.next().ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>("Expected a row after the column")
+    })?;
+    let position = Position::new(first.extract()?, row.extract()?);
+    Ok((position, items.collect()))
+}
+
+/// `args` as a lone point, raising TypeError if anything follows it.
+#[hotpath::measure]
+pub(super) fn point_only(args: &Bound<'_, PyTuple>) -> PyResult<Position> {
+    let (position, rest) = split_point(args)?;
+    if !rest.is_empty() {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Expected only a point, got {} more arguments",
+            rest.len()
+        )));
+    }
+    Ok(position)
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyPosition {
+    #[new]
+    pub fn new(col: u8, row: u8) -> Self {
+        PyPosition {
+            position: Position::new(col, row),
+        }
+    }
+
+    /// The point at `index` of the row-major layout of a board
+    /// `board_width` columns wide, as used by the action encoding.
+    #[staticmethod]
+    pub fn from_index(index: usize, board_width: u8) -> Self {
+        PyPosition {
+            position: Position::from_index(index, board_width),
+        }
+    }
+
+    /// Parse a GTP vertex such as "D4", case-insensitively.
+    #[staticmethod]
+    pub fn from_gtp(s: &str, board_width: u8, board_height: u8) -> PyResult<Self> {
+        Position::from_gtp(s, board_width, board_height)
+            .map(PyPosition::from_position)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "invalid GTP vertex: {}",
+                    s
+                ))
+            })
+    }
+
+    pub fn col(&self) -> u8 {
+        self.position.col
+    }
+
+    pub fn row(&self) -> u8 {
+        self.position.row
+    }
+
+    pub fn to_index(self, board_width: u8) -> usize {
+        self.position.to_index(board_width)
+    }
+
+    /// Whether the point lies on a `board_width` x `board_height` board.
+    pub fn is_valid(&self, board_width: u8, board_height: u8) -> bool {
+        self.position.is_valid(board_width, board_height)
+    }
+
+    pub fn to_gtp(self, board_height: u8) -> String {
+        self.position.to_gtp(board_height)
+    }
+
+    pub fn to_tuple(self) -> (u8, u8) {
+        (self.position.col, self.position.row)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Position({}, {})", self.position.col, self.position.row)
+    }
+
+    pub fn __eq__(&self, other: &PyPosition) -> bool {
+        self.position == other.position
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.position.hash(&mut hasher);
+        hasher.finish()
+    }
+}
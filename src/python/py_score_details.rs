@@ -0,0 +1,62 @@
+use pyo3::prelude::*;
+
+use super::py_errors::player_from_int;
+use crate::game::{ScoreBreakdown, ScoreDetails};
+
+#[pyclass(name = "ScoreDetails", skip_from_py_object)]
+#[derive(Clone, Copy, Debug)]
+pub struct PyScoreDetails {
+    details: ScoreDetails,
+}
+
+#[hotpath::measure_all]
+impl PyScoreDetails {
+    pub(super) fn from_details(details: ScoreDetails) -> Self {
+        PyScoreDetails { details }
+    }
+
+    fn breakdown(&self, player: i8) -> PyResult<&ScoreBreakdown> {
+        Ok(self.details.breakdown(player_from_int(player)?))
+    }
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyScoreDetails {
+    pub fn stones(&self, player: i8) -> PyResult<u32> {
+        Ok(self.breakdown(player)?.stones)
+    }
+
+    pub fn territory(&self, player: i8) -> PyResult<u32> {
+        Ok(self.breakdown(player)?.territory)
+    }
+
+    pub fn prisoners(&self, player: i8) -> PyResult<u32> {
+        Ok(self.breakdown(player)?.prisoners)
+    }
+
+    /// `player`'s score under the ruleset, with komi included for white.
+    pub fn total(&self, player: i8) -> PyResult<f32> {
+        Ok(self.details.total(player_from_int(player)?))
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.details.komi()
+    }
+
+    pub fn dame(&self) -> u32 {
+        self.details.dame
+    }
+
+    pub fn ruleset(&self) -> &'static str {
+        self.details.ruleset.name()
+    }
+
+    pub fn __repr__(&self) -> String {
+        let (b, w) = (&self.details.black, &self.details.white);
+        format!(
+            "ScoreDetails(black=(stones={}, territory={}, prisoners={}), white=(stones={}, territory={}, prisoners={}), komi={}, dame={})",
+            b.stones, b.territory, b.prisoners, w.stones, w.territory, w.prisoners, self.details.komi(), self.details.dame
+        )
+    }
+}
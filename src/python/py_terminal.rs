@@ -0,0 +1,174 @@
+use std::io::{self, BufRead, Read, Write};
+
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::dispatch::*;
+use super::py_game::PyGame;
+use super::py_policy::PolicyAdapter;
+use crate::game::{default_max_moves, DEFAULT_KOMI};
+use crate::player::Player;
+use crate::render::renderer_for_theme;
+use crate::terminal::play_in_terminal;
+
+/// Lines read with Python's `input()`, so they come from whatever
+/// `sys.stdin` is. EOFError ends the input; any other exception is kept for
+/// `play_interactive` to raise.
+struct PromptInput<'py> {
+    input: Bound<'py, PyAny>,
+    line: Vec<u8>,
+    pos: usize,
+    error: Option<PyErr>,
+}
+
+#[hotpath::measure_all]
+impl Read for PromptInput<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+#[hotpath::measure_all]
+impl BufRead for PromptInput<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.line.len() {
+            let py = self.input.py();
+            match self.input.call1(("",)).and_then(|l| l.extract::<String>()) {
+                Ok(line) => self.line = format!("{}\n", line).into_bytes(),
+                Err(e) if e.is_instance_of::<pyo3::exceptions::PyEOFError>(py) => self.line.clear(),
+                Err(e) => {
+                    self.error = Some(e);
+                    return Err(io::Error::other("input() raised an exception"));
+                }
+            }
+            self.pos = 0;
+        }
+        Ok(&self.line[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.line.len());
+    }
+}
+
+/// Text written to Python's `sys.stdout`.
+struct PromptOutput<'py> {
+    stdout: Bound<'py, PyAny>,
+    error: Option<PyErr>,
+}
+
+#[hotpath::measure_all]
+impl PromptOutput<'_> {
+    fn keep<T>(&mut self, result: PyResult<T>) -> io::Result<()> {
+        result.map(|_| ()).map_err(|e| {
+            self.error = Some(e);
+            io::Error::other("sys.stdout raised an exception")
+        })
+    }
+}
+
+#[hotpath::measure_all]
+impl Write for PromptOutput<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf).into_owned();
+        let result = self.stdout.call_method1("write", (text,));
+        self.keep(result)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.stdout.call_method0("flush");
+        self.keep(result)
+    }
+}
+
+/// Play a game in the terminal against `policy`, a built-in policy name
+/// ("mcts", "uniform", "noeyefill" or "heuristic") or a `Policy` object.
+/// `visits` is the MCTS playouts per move. Enter moves as GTP vertices
+/// (D4), `pass` or `resign`; `undo` takes back your last move and `quit`
+/// stops. The computer plays `computer` ("black", "white" or None for two
+/// humans). Returns the game as it ended.
+#[hotpath::measure]
+#[pyfunction]
+#[pyo3(signature = (
+    size=9,
+    policy=None,
+    visits=800,
+    computer=Some("white"),
+    komi=DEFAULT_KOMI,
+    seed=0,
+    theme="coordinates"
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn play_interactive(
+    py: Python<'_>,
+    size: u8,
+    policy: Option<&Bound<'_, PyAny>>,
+    visits: u32,
+    computer: Option<&str>,
+    komi: f32,
+    seed: u64,
+    theme: &str,
+) -> PyResult<PyGame> {
+    if !(2..=32).contains(&size) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Board size must be between 2 and 32",
+        ));
+    }
+    let mut policy = match policy {
+        None => PolicyAdapter::from_name("mcts", visits)?,
+        Some(p) => match p.extract::<String>() {
+            Ok(name) => PolicyAdapter::from_name(&name, visits)?,
+            Err(_) => PolicyAdapter::new(p)?,
+        },
+    };
+    let computer = match computer {
+        None => None,
+        Some("black") => Some(Player::Black),
+        Some("white") => Some(Player::White),
+        Some(other) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown color: {}",
+                other
+            )))
+        }
+    };
+    let renderer = renderer_for_theme(theme).ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown theme: {}", theme))
+    })?;
+
+    let mut input = PromptInput {
+        input: py.import("builtins")?.getattr("input")?,
+        line: Vec::new(),
+        pos: 0,
+        error: None,
+    };
+    let mut out = PromptOutput {
+        stdout: py.import("sys")?.getattr("stdout")?,
+        error: None,
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut inner =
+        make_game_inner_with_options(size, size, komi, 0, default_max_moves(size, size), true);
+    let result = dispatch_game_mut!(&mut inner, g => {
+        play_in_terminal(
+            g,
+            computer,
+            &mut policy,
+            &mut rng,
+            renderer.as_ref(),
+            &mut input,
+            &mut out,
+        )
+    });
+    if let Some(e) = policy.take_error().or(input.error).or(out.error) {
+        return Err(e);
+    }
+    result.map_err(|e| PyErr::new::<pyo3::exceptions::PyOSError, _>(e.to_string()))?;
+    Ok(PyGame::from_inner(inner))
+}
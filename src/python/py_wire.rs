@@ -0,0 +1,78 @@
+use pyo3::prelude::*;
+
+use super::py_policy::PyGameRecord;
+use crate::training::TrainingSample;
+use crate::wire::{self, WireMessage};
+
+/// One encoded position with its policy, value and moves-remaining
+/// targets.
+#[pyclass(name = "TrainingSample")]
+pub struct PyTrainingSample {
+    sample: TrainingSample,
+}
+
+#[hotpath::measure_all]
+#[pymethods]
+impl PyTrainingSample {
+    /// Input planes, flat in plane-major then row-major order.
+    pub fn planes(&self) -> Vec<f32> {
+        self.sample.planes.clone()
+    }
+
+    pub fn num_planes(&self) -> usize {
+        self.sample.num_planes
+    }
+
+    pub fn height(&self) -> u8 {
+        self.sample.height
+    }
+
+    pub fn width(&self) -> u8 {
+        self.sample.width
+    }
+
+    pub fn policy(&self) -> Vec<f32> {
+        self.sample.policy.clone()
+    }
+
+    pub fn value(&self) -> f32 {
+        self.sample.value
+    }
+
+    /// Moves played from this position to the end of the game.
+    pub fn moves_remaining(&self) -> u32 {
+        self.sample.moves_remaining
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "TrainingSample({}x{}, planes={}, value={})",
+            self.sample.width, self.sample.height, self.sample.num_planes, self.sample.value
+        )
+    }
+}
+
+/// Decode the complete wire messages at the front of `data`. Returns the
+/// `GameRecord`s and `TrainingSample`s with the number of bytes they used;
+/// the rest is the start of a message still to arrive.
+#[pyfunction]
+#[hotpath::measure]
+pub fn decode_wire_messages(py: Python<'_>, data: &[u8]) -> PyResult<(Vec<Py<PyAny>>, usize)> {
+    let mut messages = Vec::new();
+    let mut used = 0;
+    while let Some((message, len)) = wire::decode_message(&data[used..])
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+    {
+        let object = match message {
+            WireMessage::GameRecord(record) => {
+                Py::new(py, PyGameRecord::from_record(record))?.into_any()
+            }
+            WireMessage::TrainingSample(sample) => {
+                Py::new(py, PyTrainingSample { sample })?.into_any()
+            }
+        };
+        messages.push(object);
+        used += len;
+    }
+    Ok((messages, used))
+}
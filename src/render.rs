@@ -0,0 +1,241 @@
+use std::fmt::Write;
+
+use crate::board::Board;
+use crate::gtp::col_to_letter;
+use crate::player::Player;
+use crate::position::Position;
+
+/// A board as renderers see it: its size and the stone on each point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoardView {
+    width: u8,
+    height: u8,
+    /// Row-major from row 0.
+    points: Vec<Option<Player>>,
+}
+
+#[hotpath::measure_all]
+impl BoardView {
+    pub fn from_board<const NW: usize>(board: &Board<NW>) -> Self {
+        let (width, height) = (board.width(), board.height());
+        let points = (0..height)
+            .flat_map(|row| (0..width).map(move |col| Position::new(col, row)))
+            .map(|pos| board.get_piece(&pos))
+            .collect();
+        BoardView {
+            width,
+            height,
+            points,
+        }
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    pub fn get_piece(&self, pos: &Position) -> Option<Player> {
+        if !pos.is_valid(self.width, self.height) {
+            return None;
+        }
+        self.points[pos.to_index(self.width)]
+    }
+}
+
+/// Draws a board as text or an image. Implement it to add a theme; pick one
+/// by name with `renderer_for_theme`.
+pub trait BoardRenderer {
+    fn render(&self, board: &BoardView) -> String;
+}
+
+/// Plain ASCII. By default the compact `|B|.|W|` layout of `Board`'s
+/// `Display`, with column indices underneath.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextRenderer {
+    /// Label the rows and columns with GTP coordinates on every side
+    /// instead.
+    pub coordinates: bool,
+}
+
+#[hotpath::measure_all]
+impl TextRenderer {
+    fn render_compact(&self, board: &BoardView) -> String {
+        let mut text = String::new();
+        for row in (0..board.height()).rev() {
+            text.push('|');
+            for col in 0..board.width() {
+                let c = match board.get_piece(&Position::new(col, row)) {
+                    Some(player) => player.to_char(),
+                    None => '.',
+                };
+                text.push(c);
+                text.push('|');
+            }
+            text.push('\n');
+        }
+
+        // Column numbers
+        text.push(' ');
+        for col in 0..board.width() {
+            let _ = write!(text, "{} ", col);
+        }
+        text.push('\n');
+        text
+    }
+
+    fn render_with_coordinates(&self, board: &BoardView) -> String {
+        let letters: String = (0..board.width())
+            .map(|c| format!(" {}", col_to_letter(c)))
+            .collect();
+        let mut text = format!("   {}\n", letters);
+        for row in (0..board.height()).rev() {
+            let _ = write!(text, "{:>2} ", row + 1);
+            for col in 0..board.width() {
+                let point = match board.get_piece(&Position::new(col, row)) {
+                    Some(player) => player.to_char(),
+                    None => '.',
+                };
+                let _ = write!(text, " {}", point);
+            }
+            let _ = writeln!(text, "  {}", row + 1);
+        }
+        let _ = writeln!(text, "   {}", letters);
+        text
+    }
+}
+
+#[hotpath::measure_all]
+impl BoardRenderer for TextRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        if self.coordinates {
+            self.render_with_coordinates(board)
+        } else {
+            self.render_compact(board)
+        }
+    }
+}
+
+/// Box-drawing grid lines with `●` for Black and `○` for White, labelled
+/// with GTP coordinates. Needs a terminal font with those characters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnicodeRenderer;
+
+#[hotpath::measure_all]
+impl UnicodeRenderer {
+    /// The grid line character of an empty point.
+    fn intersection(col: u8, row: u8, width: u8, height: u8) -> char {
+        let (left, right) = (col == 0, col == width - 1);
+        let (bottom, top) = (row == 0, row == height - 1);
+        match (top, bottom, left, right) {
+            (true, _, true, _) => '┌',
+            (true, _, _, true) => '┐',
+            (_, true, true, _) => '└',
+            (_, true, _, true) => '┘',
+            (true, ..) => '┬',
+            (_, true, ..) => '┴',
+            (_, _, true, _) => '├',
+            (_, _, _, true) => '┤',
+            _ => '┼',
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl BoardRenderer for UnicodeRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        let (width, height) = (board.width(), board.height());
+        let letters: String = (0..width)
+            .map(|c| format!(" {}", col_to_letter(c)))
+            .collect();
+        let mut text = format!("  {}\n", letters);
+        for row in (0..height).rev() {
+            let _ = write!(text, "{:>2} ", row + 1);
+            for col in 0..width {
+                if col > 0 {
+                    text.push('─');
+                }
+                text.push(match board.get_piece(&Position::new(col, row)) {
+                    Some(Player::Black) => '●',
+                    Some(Player::White) => '○',
+                    None => Self::intersection(col, row, width, height),
+                });
+            }
+            let _ = writeln!(text, " {}", row + 1);
+        }
+        let _ = writeln!(text, "  {}", letters);
+        text
+    }
+}
+
+/// Theme names accepted by `renderer_for_theme`.
+#[cfg(feature = "analysis")]
+pub const THEMES: &[&str] = &["text", "coordinates", "unicode", "svg"];
+#[cfg(not(feature = "analysis"))]
+pub const THEMES: &[&str] = &["text", "coordinates", "unicode"];
+
+/// The built-in renderer called `name`: `text` (as `Display`),
+/// `coordinates` (text with GTP coordinates), `unicode`, and with the
+/// `analysis` feature `svg`.
+#[hotpath::measure]
+pub fn renderer_for_theme(name: &str) -> Option<Box<dyn BoardRenderer>> {
+    match name {
+        "text" => Some(Box::new(TextRenderer::default())),
+        "coordinates" => Some(Box::new(TextRenderer { coordinates: true })),
+        "unicode" => Some(Box::new(UnicodeRenderer)),
+        #[cfg(feature = "analysis")]
+        "svg" => Some(Box::new(crate::analysis::SvgRenderer)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+
+    fn board() -> Board<{ nw_for_board(3, 2) }> {
+        let mut board = Board::new(3, 2);
+        board.set_piece(&Position::new(0, 0), Some(Player::Black));
+        board.set_piece(&Position::new(2, 1), Some(Player::White));
+        board
+    }
+
+    #[test]
+    fn test_built_in_themes() {
+        let board = board();
+        let view = BoardView::from_board(&board);
+        assert_eq!(TextRenderer::default().render(&view), board.to_string());
+        assert_eq!(board.to_string(), "|.|.|W|\n|B|.|.|\n 0 1 2 \n");
+        assert_eq!(
+            TextRenderer { coordinates: true }.render(&view),
+            "    A B C\n 2  . . W  2\n 1  B . .  1\n    A B C\n"
+        );
+        assert_eq!(
+            UnicodeRenderer.render(&view),
+            "   A B C\n 2 ┌─┬─○ 2\n 1 ●─┴─┘ 1\n   A B C\n"
+        );
+
+        for name in THEMES {
+            assert!(renderer_for_theme(name).is_some(), "{}", name);
+        }
+        assert!(renderer_for_theme("html").is_none());
+    }
+
+    #[test]
+    fn test_custom_renderer() {
+        struct Count;
+        impl BoardRenderer for Count {
+            fn render(&self, board: &BoardView) -> String {
+                let stones = (0..board.height())
+                    .flat_map(|row| (0..board.width()).map(move |col| Position::new(col, row)))
+                    .filter(|pos| board.get_piece(pos).is_some())
+                    .count();
+                format!("{} stones", stones)
+            }
+        }
+        assert_eq!(board().render(&Count), "2 stones");
+    }
+}
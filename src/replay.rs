@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::game::Game;
+use crate::player::Player;
+use crate::r#move::Move;
+
+/// How to handle moves that cannot be played when replaying a recorded game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IllegalMovePolicy {
+    /// Stop at the first move that cannot be played.
+    #[default]
+    Fail,
+    /// Drop moves that cannot be played and continue with the rest.
+    Skip,
+    /// Insert a pass before out-of-turn moves so they can be played, and drop
+    /// moves that are illegal for any other reason.
+    Repair,
+}
+
+/// Why a recorded move could not be played as-is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayIssue {
+    /// The move was recorded for the player who is not to move.
+    OutOfTurn,
+    /// The move is illegal in the current position (occupied, ko, suicide, game over...).
+    Illegal,
+}
+
+/// What the replay did about a problematic move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayFix {
+    /// The move was dropped.
+    Skipped,
+    /// A pass was inserted for the other player, then the move was played.
+    InsertedPass,
+}
+
+/// A single repaired or skipped move, identified by its index in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayFixRecord {
+    pub index: usize,
+    pub player: Player,
+    pub move_: Move,
+    pub issue: ReplayIssue,
+    pub fix: ReplayFix,
+}
+
+/// Summary of a replay: how many recorded moves were played, and what was fixed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub applied: usize,
+    pub fixes: Vec<ReplayFixRecord>,
+}
+
+#[hotpath::measure_all]
+impl ReplayReport {
+    pub fn is_clean(&self) -> bool {
+        self.fixes.is_empty()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.fixes
+            .iter()
+            .filter(|f| f.fix == ReplayFix::Skipped)
+            .count()
+    }
+}
+
+/// Replay failed under `IllegalMovePolicy::Fail`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayError {
+    pub index: usize,
+    pub player: Player,
+    pub move_: Move,
+    pub issue: ReplayIssue,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let issue = match self.issue {
+            ReplayIssue::OutOfTurn => "out of turn",
+            ReplayIssue::Illegal => "illegal",
+        };
+        write!(
+            f,
+            "move {} ({} {}) is {}",
+            self.index + 1,
+            self.player,
+            self.move_,
+            issue
+        )
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Play recorded `(player, move)` pairs onto `game`, handling unplayable moves
+/// according to `policy`.
+#[hotpath::measure]
+pub fn replay_moves<const NW: usize>(
+    game: &mut Game<NW>,
+    moves: &[(Player, Move)],
+    policy: IllegalMovePolicy,
+) -> Result<ReplayReport, ReplayError> {
+    let mut report = ReplayReport::default();
+
+    for (index, &(player, move_)) in moves.iter().enumerate() {
+        let mut fix = None;
+
+        // Either player may resign regardless of whose turn it is.
+        if move_.is_resign() && game.resign(player) {
+            report.applied += 1;
+            continue;
+        }
+
+        if player != game.turn() {
+            let issue = ReplayIssue::OutOfTurn;
+            match policy {
+                IllegalMovePolicy::Fail => {
+                    return Err(ReplayError {
+                        index,
+                        player,
+                        move_,
+                        issue,
+                    });
+                }
+                IllegalMovePolicy::Repair if game.make_move(&Move::pass()) => {
+                    fix = Some((issue, ReplayFix::InsertedPass));
+                }
+                _ => {
+                    report.fixes.push(ReplayFixRecord {
+                        index,
+                        player,
+                        move_,
+                        issue,
+                        fix: ReplayFix::Skipped,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        if game.make_move(&move_) {
+            report.applied += 1;
+            if let Some((issue, fix)) = fix {
+                report.fixes.push(ReplayFixRecord {
+                    index,
+                    player,
+                    move_,
+                    issue,
+                    fix,
+                });
+            }
+            continue;
+        }
+
+        // The inserted pass did not make the move playable; take it back.
+        if fix.is_some() {
+            game.unmake_move();
+        }
+
+        let issue = ReplayIssue::Illegal;
+        if policy == IllegalMovePolicy::Fail {
+            return Err(ReplayError {
+                index,
+                player,
+                move_,
+                issue,
+            });
+        }
+        report.fixes.push(ReplayFixRecord {
+            index,
+            player,
+            move_,
+            issue,
+            fix: ReplayFix::Skipped,
+        });
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::game::DEFAULT_KOMI;
+
+    fn new_game() -> Game<{ nw_for_board(9, 9) }> {
+        Game::with_options(9, 9, DEFAULT_KOMI, 0, 1000, true)
+    }
+
+    #[test]
+    fn test_clean_replay() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::White, Move::place(6, 6)),
+            (Player::Black, Move::pass()),
+        ];
+        let report =
+            replay_moves(&mut game, &moves, IllegalMovePolicy::Fail).expect("clean replay");
+        assert_eq!(report.applied, 3);
+        assert!(report.is_clean());
+        assert_eq!(game.move_count(), 3);
+    }
+
+    #[test]
+    fn test_fail_on_occupied_point() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::White, Move::place(2, 2)),
+        ];
+        let err = replay_moves(&mut game, &moves, IllegalMovePolicy::Fail)
+            .expect_err("occupied point must fail");
+        assert_eq!(err.index, 1);
+        assert_eq!(err.issue, ReplayIssue::Illegal);
+    }
+
+    #[test]
+    fn test_skip_illegal_moves() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::White, Move::place(2, 2)),
+            (Player::White, Move::place(3, 3)),
+            (Player::White, Move::place(4, 4)),
+        ];
+        let report = replay_moves(&mut game, &moves, IllegalMovePolicy::Skip).expect("skip mode");
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.skipped(), 2);
+        assert_eq!(report.fixes[0].issue, ReplayIssue::Illegal);
+        assert_eq!(report.fixes[1].issue, ReplayIssue::OutOfTurn);
+        assert_eq!(report.fixes[1].index, 3);
+    }
+
+    #[test]
+    fn test_repair_inserts_pass_for_out_of_turn_move() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::Black, Move::place(3, 3)),
+        ];
+        let report = replay_moves(&mut game, &moves, IllegalMovePolicy::Repair).expect("repair");
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.fixes.len(), 1);
+        assert_eq!(report.fixes[0].fix, ReplayFix::InsertedPass);
+        assert_eq!(
+            game.move_history(),
+            vec![Move::place(2, 2), Move::pass(), Move::place(3, 3)]
+        );
+    }
+
+    #[test]
+    fn test_repair_drops_inserted_pass_when_move_still_illegal() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::Black, Move::place(2, 2)),
+        ];
+        let report = replay_moves(&mut game, &moves, IllegalMovePolicy::Repair).expect("repair");
+        assert_eq!(report.applied, 1);
+        assert_eq!(report.skipped(), 1);
+        assert_eq!(game.move_history(), vec![Move::place(2, 2)]);
+    }
+
+    #[test]
+    fn test_resign_out_of_turn_is_not_a_fix() {
+        let mut game = new_game();
+        let moves = [
+            (Player::Black, Move::place(2, 2)),
+            (Player::Black, Move::resign()),
+        ];
+        let report = replay_moves(&mut game, &moves, IllegalMovePolicy::Fail).expect("resign");
+        assert_eq!(report.applied, 2);
+        assert!(report.is_clean());
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::White));
+    }
+}
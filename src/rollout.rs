@@ -0,0 +1,274 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::bitboard::{Bitboard, BoardGeometry};
+use crate::game::{Game, ScoreDetails};
+use crate::player::Player;
+use crate::policy::{
+    derive_game_seed, finish_playout, playout, CycleDetection, CycleOutcome, PlayoutCounts,
+};
+use crate::position::Position;
+
+/// Settings for estimating dead stones with random playouts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RolloutConfig {
+    /// Number of playouts from the position.
+    pub rollouts: u32,
+    /// A group is dead when its points end up owned by the opponent in more
+    /// than this fraction of playouts.
+    pub dead_threshold: f32,
+    pub seed: u64,
+    /// How looping playouts are cut short.
+    pub cycle: CycleDetection,
+}
+
+#[hotpath::measure_all]
+impl Default for RolloutConfig {
+    fn default() -> Self {
+        RolloutConfig {
+            rollouts: 64,
+            dead_threshold: 0.5,
+            seed: 0,
+            cycle: CycleDetection::default(),
+        }
+    }
+}
+
+/// Fraction of playouts in which each point ends up owned by the opponent of
+/// the stone currently on it (0.0 for empty points). Row-major layout.
+/// Playouts stop at a position repeated within `config.cycle`; a drawn
+/// playout loses no stones.
+#[hotpath::measure]
+pub fn rollout_capture_rates<const NW: usize>(game: &Game<NW>, config: &RolloutConfig) -> Vec<f32> {
+    let geo = BoardGeometry::<NW>::cached(game.width(), game.height());
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let board = game.board();
+    let mut lost = vec![0u32; game.width() as usize * game.height() as usize];
+
+    for _ in 0..config.rollouts {
+        let (playout, cycled) = playout(game, game.komi(), &mut rng, &config.cycle);
+        if cycled && config.cycle.outcome == CycleOutcome::Draw {
+            continue;
+        }
+        let ownership = playout.ownership_map_absolute();
+        for (idx, count) in lost.iter_mut().enumerate() {
+            let owner = ownership[idx];
+            match board.get_piece(&Position::from_index(idx, geo.width)) {
+                Some(Player::Black) if owner < 0.0 => *count += 1,
+                Some(Player::White) if owner > 0.0 => *count += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let rollouts = config.rollouts.max(1) as f32;
+    lost.into_iter().map(|c| c as f32 / rollouts).collect()
+}
+
+/// Stones of the groups that random playouts judge dead: on average, their
+/// points are taken over by the opponent in more than `dead_threshold` of the
+/// playouts.
+#[hotpath::measure]
+pub fn estimate_dead_stones<const NW: usize>(
+    game: &Game<NW>,
+    config: &RolloutConfig,
+) -> Bitboard<NW> {
+    let geo = BoardGeometry::cached(game.width(), game.height());
+    let rates = rollout_capture_rates(game, config);
+    let board = game.board();
+
+    let mut dead = Bitboard::empty();
+    for player in [Player::Black, Player::White] {
+        let stones = board.stones_for(player);
+        let mut remaining = stones;
+        while let Some(idx) = remaining.lowest_bit_index() {
+            let group = geo.flood_fill(Bitboard::single(idx), stones);
+            remaining &= !group;
+
+            let total: f32 = group.iter_ones().map(|i| rates[i]).sum();
+            if total / group.count() as f32 > config.dead_threshold {
+                dead |= group;
+            }
+        }
+    }
+    dead
+}
+
+/// Score `game` after removing the stones that random playouts judge dead.
+/// Useful when a game ended by double pass with dead groups still on the board.
+#[hotpath::measure]
+pub fn score_with_rollouts<const NW: usize>(
+    game: &Game<NW>,
+    config: &RolloutConfig,
+) -> ScoreDetails {
+    let mut scored = game.clone();
+    scored.set_dead_stones(estimate_dead_stones(game, config));
+    scored.score_details()
+}
+
+/// One `NoEyeFillPolicy` playout of `game` seeded with `seed`: its winner,
+/// judged as `playout_stats` judges each playout, and the final
+/// `ownership_map_absolute`. A finished game gives its own result and
+/// ownership.
+#[hotpath::measure]
+pub fn random_playout<const NW: usize>(
+    game: &Game<NW>,
+    seed: u64,
+    cycle: &CycleDetection,
+) -> (Option<Player>, Vec<f32>) {
+    if game.is_over() {
+        let winner = game.outcome().and_then(|o| o.winner());
+        return (winner, game.ownership_map_absolute());
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (playout, winner) = finish_playout(game, &mut rng, cycle);
+    (winner, playout.ownership_map_absolute())
+}
+
+/// Winners and mean final ownership of a batch of playouts from one
+/// position.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayoutStats {
+    pub counts: PlayoutCounts,
+    /// Mean of each playout's `ownership_map_absolute`: from 1.0 when Black
+    /// always ends up owning the point to -1.0 when White always does.
+    /// Row-major layout.
+    pub ownership: Vec<f32>,
+}
+
+/// Play `game` out `playouts` times with `NoEyeFillPolicy`, split across up
+/// to `threads` threads. Playout `i` is seeded with `derive_game_seed(seed,
+/// i)`, so the result does not depend on `threads`. A finished game counts
+/// its own result and ownership every time.
+#[hotpath::measure]
+pub fn playout_stats<const NW: usize>(
+    game: &Game<NW>,
+    playouts: u32,
+    seed: u64,
+    threads: usize,
+    cycle: &CycleDetection,
+) -> PlayoutStats {
+    let points = game.width() as usize * game.height() as usize;
+    if game.is_over() {
+        let mut counts = PlayoutCounts::default();
+        let winner = game.outcome().and_then(|o| o.winner());
+        for _ in 0..playouts {
+            counts.record(winner);
+        }
+        let ownership = if playouts == 0 {
+            vec![0.0; points]
+        } else {
+            game.ownership_map_absolute()
+        };
+        return PlayoutStats { counts, ownership };
+    }
+
+    // Ownership is summed as whole points so the total is exact in any order.
+    let run = |range: std::ops::Range<u32>| {
+        let mut counts = PlayoutCounts::default();
+        let mut owned = vec![0i64; points];
+        for i in range {
+            let mut rng = StdRng::seed_from_u64(derive_game_seed(seed, i as u64));
+            let (playout, winner) = finish_playout(game, &mut rng, cycle);
+            counts.record(winner);
+            for (sum, owner) in owned.iter_mut().zip(playout.ownership_map_absolute()) {
+                *sum += owner as i64;
+            }
+        }
+        (counts, owned)
+    };
+    let threads = threads.clamp(1, playouts.max(1) as usize) as u32;
+    let per_thread = playouts.div_ceil(threads);
+    let parts: Vec<_> = if threads == 1 {
+        vec![run(0..playouts)]
+    } else {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|t| {
+                    let start = (t * per_thread).min(playouts);
+                    let end = (start + per_thread).min(playouts);
+                    scope.spawn(move || run(start..end))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("playout thread panicked"))
+                .collect()
+        })
+    };
+
+    let mut counts = PlayoutCounts::default();
+    let mut owned = vec![0i64; points];
+    for (part_counts, part_owned) in &parts {
+        counts.merge(part_counts);
+        for (sum, part) in owned.iter_mut().zip(part_owned) {
+            *sum += part;
+        }
+    }
+    let playouts = playouts.max(1) as f32;
+    PlayoutStats {
+        counts,
+        ownership: owned.into_iter().map(|s| s as f32 / playouts).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::r#move::Move;
+
+    #[test]
+    fn test_lone_invader_is_dead_and_removed_for_scoring() {
+        let mut game = Game::<{ nw_for_board(7, 7) }>::with_options(7, 7, 0.5, 0, 1000, false);
+
+        // Black walls off columns 0-2, White owns columns 4-6, and a single
+        // white stone sits inside Black's area.
+        for row in 0..7 {
+            game.make_move(&Move::place(3, row));
+            game.make_move(&Move::place(4, row));
+        }
+        game.make_move(&Move::pass());
+        game.make_move(&Move::place(1, 3));
+        game.make_move(&Move::pass());
+        game.make_move(&Move::pass());
+        assert!(game.is_over());
+
+        let config = RolloutConfig::default();
+        let dead = estimate_dead_stones(&game, &config);
+        assert_eq!(dead.count(), 1);
+        assert!(dead.get(Position::new(1, 3).to_index(7)));
+
+        // Area scoring: the invader spoils Black's area until it is removed
+        let details = score_with_rollouts(&game, &config);
+        assert_eq!(details.totals(), (28.0, 21.5));
+        assert_eq!(game.score(), (7.0, 22.5));
+    }
+
+    #[test]
+    fn test_playout_stats_do_not_depend_on_threads() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.make_move(&Move::place(2, 2));
+        let cycle = CycleDetection::default();
+        let stats = playout_stats(&game, 24, 3, 1, &cycle);
+        assert_eq!(stats.counts.total(), 24);
+        assert_eq!(stats.ownership.len(), 25);
+        assert!(stats.ownership.iter().all(|v| (-1.0..=1.0).contains(v)));
+        assert_eq!(playout_stats(&game, 24, 3, 5, &cycle), stats);
+        assert_ne!(playout_stats(&game, 24, 4, 1, &cycle), stats);
+    }
+
+    #[test]
+    fn test_random_playout_matches_a_single_playout_stat() {
+        let mut game = Game::<{ nw_for_board(5, 5) }>::new(5, 5);
+        game.make_move(&Move::place(2, 2));
+        let cycle = CycleDetection::default();
+        let (winner, ownership) = random_playout(&game, derive_game_seed(7, 0), &cycle);
+        let stats = playout_stats(&game, 1, 7, 1, &cycle);
+        assert_eq!(ownership, stats.ownership);
+        let mut counts = PlayoutCounts::default();
+        counts.record(winner);
+        assert_eq!(counts, stats.counts);
+        assert!(ownership.iter().all(|&v| v == 1.0 || v == -1.0 || v == 0.0));
+    }
+}
@@ -0,0 +1,99 @@
+/// Ruleset used for scoring a `Game`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "lowercase"))]
+pub enum Ruleset {
+    /// Area scoring: stones on the board plus surrounded empty points.
+    #[default]
+    Chinese,
+    /// Territory scoring: surrounded empty points plus prisoners.
+    Japanese,
+    /// AGA rules: every pass hands the opponent a prisoner and White must pass
+    /// last, so territory scoring gives the same result as area scoring.
+    Aga,
+}
+
+#[hotpath::measure_all]
+impl Ruleset {
+    pub fn uses_area_scoring(&self) -> bool {
+        match self {
+            Ruleset::Chinese => true,
+            Ruleset::Japanese | Ruleset::Aga => false,
+        }
+    }
+
+    /// Whether passing gives the opponent a prisoner and the game can only end
+    /// on a pass by White.
+    pub fn uses_pass_stones(&self) -> bool {
+        matches!(self, Ruleset::Aga)
+    }
+
+    /// Points added to White's score in a game with `handicap` stones. Area
+    /// scoring counts Black's handicap stones, so Chinese rules give White one
+    /// point per stone. The AGA pass-stone territory count already equals an
+    /// area count with the AGA's N-1 point compensation, so it adds nothing.
+    pub fn handicap_compensation(&self, handicap: u8) -> f32 {
+        self.handicap_compensation_half_points(handicap) as f32 / 2.0
+    }
+
+    /// `handicap_compensation()` in half points.
+    pub fn handicap_compensation_half_points(&self, handicap: u8) -> i32 {
+        match self {
+            Ruleset::Chinese => handicap as i32 * 2,
+            Ruleset::Japanese | Ruleset::Aga => 0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ruleset::Chinese => "chinese",
+            Ruleset::Japanese => "japanese",
+            Ruleset::Aga => "aga",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Ruleset> {
+        match name.trim().to_lowercase().as_str() {
+            "chinese" => Some(Ruleset::Chinese),
+            "japanese" => Some(Ruleset::Japanese),
+            "aga" => Some(Ruleset::Aga),
+            _ => None,
+        }
+    }
+}
+
+#[hotpath::measure_all]
+impl std::fmt::Display for Ruleset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_roundtrip() {
+        for ruleset in [Ruleset::Chinese, Ruleset::Japanese, Ruleset::Aga] {
+            assert_eq!(Ruleset::from_name(ruleset.name()), Some(ruleset));
+        }
+        assert_eq!(Ruleset::from_name("JAPANESE"), Some(Ruleset::Japanese));
+        assert_eq!(Ruleset::from_name("ing"), None);
+    }
+
+    #[test]
+    fn test_default_is_area_scoring() {
+        assert!(Ruleset::default().uses_area_scoring());
+        assert!(!Ruleset::Japanese.uses_area_scoring());
+        assert!(Ruleset::Aga.uses_pass_stones());
+        assert!(!Ruleset::Japanese.uses_pass_stones());
+    }
+
+    #[test]
+    fn test_handicap_compensation() {
+        assert_eq!(Ruleset::Chinese.handicap_compensation(4), 4.0);
+        assert_eq!(Ruleset::Japanese.handicap_compensation(4), 0.0);
+        assert_eq!(Ruleset::Aga.handicap_compensation(4), 0.0);
+    }
+}
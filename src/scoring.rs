@@ -0,0 +1,222 @@
+use crate::game::{Game, GamePhase, ScoreDetails};
+use crate::gtp::{position_to_vertex, vertex_to_position};
+use crate::player::Player;
+use crate::position::Position;
+
+/// The saved state of a `ScoringSession`: the stones marked dead, as GTP
+/// vertices, and which players have accepted them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoringState {
+    pub dead_stones: Vec<String>,
+    pub black_accepted: bool,
+    pub white_accepted: bool,
+}
+
+/// The end-of-game agreement on dead stones, as on online Go servers.
+///
+/// After both players pass in a game with dead-stone marking, either player
+/// toggles groups dead or alive and the score preview updates. Each change
+/// withdraws any acceptance; once both players accept the same marking, the
+/// game ends with that score.
+#[derive(Clone, Debug)]
+pub struct ScoringSession<const NW: usize> {
+    game: Game<NW>,
+    preview: ScoreDetails,
+    black_accepted: bool,
+    white_accepted: bool,
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> ScoringSession<NW> {
+    /// Start a session for `game`, which must be in `GamePhase::Scoring`.
+    pub fn new(game: Game<NW>) -> Option<Self> {
+        if game.phase() != GamePhase::Scoring {
+            return None;
+        }
+        let preview = game.score_details();
+        Some(ScoringSession {
+            game,
+            preview,
+            black_accepted: false,
+            white_accepted: false,
+        })
+    }
+
+    /// Rebuild a session for `game` from a saved `state`. Returns None if the
+    /// game is not in the scoring phase or a vertex is not a stone.
+    pub fn restore(game: Game<NW>, state: &ScoringState) -> Option<Self> {
+        let mut session = ScoringSession::new(game)?;
+        let height = session.game.height();
+        let mut dead = Vec::with_capacity(state.dead_stones.len());
+        for vertex in &state.dead_stones {
+            let pos = vertex_to_position(vertex, height).ok()?;
+            session.game.get_piece(&pos)?;
+            dead.push(pos);
+        }
+        session.game.mark_dead(&dead);
+        session.preview = session.game.score_details();
+        session.black_accepted = state.black_accepted;
+        session.white_accepted = state.white_accepted;
+        Some(session)
+    }
+
+    /// The state to save, for `restore`.
+    pub fn state(&self) -> ScoringState {
+        let height = self.game.height();
+        ScoringState {
+            dead_stones: self
+                .dead_stones()
+                .iter()
+                .map(|pos| position_to_vertex(pos, height))
+                .collect(),
+            black_accepted: self.black_accepted,
+            white_accepted: self.white_accepted,
+        }
+    }
+
+    pub fn game(&self) -> &Game<NW> {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game<NW> {
+        self.game
+    }
+
+    /// The score with the current marking.
+    pub fn preview(&self) -> &ScoreDetails {
+        &self.preview
+    }
+
+    /// The stones currently marked dead.
+    pub fn dead_stones(&self) -> Vec<Position> {
+        let (width, height) = (self.game.width(), self.game.height());
+        (0..height)
+            .flat_map(|row| (0..width).map(move |col| Position::new(col, row)))
+            .filter(|pos| self.game.is_dead(pos))
+            .collect()
+    }
+
+    /// Flip the life/death status of the group at `pos` and update the
+    /// preview. Withdraws both players' acceptance. Returns false if `pos` is
+    /// empty or the game is over.
+    pub fn toggle_dead(&mut self, pos: Position) -> bool {
+        if !self.game.toggle_dead_group(&pos) {
+            return false;
+        }
+        self.preview = self.game.score_details();
+        self.black_accepted = false;
+        self.white_accepted = false;
+        true
+    }
+
+    pub fn has_accepted(&self, player: Player) -> bool {
+        match player {
+            Player::Black => self.black_accepted,
+            Player::White => self.white_accepted,
+        }
+    }
+
+    /// `player` accepts the current marking. When both have accepted, the
+    /// game ends with the previewed score. Returns false if the game is
+    /// already over.
+    pub fn accept(&mut self, player: Player) -> bool {
+        if self.game.phase() != GamePhase::Scoring {
+            return false;
+        }
+        match player {
+            Player::Black => self.black_accepted = true,
+            Player::White => self.white_accepted = true,
+        }
+        if self.black_accepted && self.white_accepted {
+            self.game.accept_score();
+        }
+        true
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.game.phase() == GamePhase::Over
+    }
+
+    /// Give up on scoring and go back to playing: takes back the last pass,
+    /// which also clears the marking.
+    pub fn resume(mut self) -> Game<NW> {
+        if !self.is_finished() {
+            self.game.unmake_move();
+        }
+        self.game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::r#move::Move;
+
+    type Game5 = Game<{ nw_for_board(5, 5) }>;
+
+    /// Black wall on column 2, white wall on column 3, a white invader at
+    /// (0, 2), then two passes.
+    fn scoring_game() -> Game5 {
+        let mut game = Game5::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_dead_stone_marking(true);
+        for row in 0..5 {
+            assert!(game.make_move(&Move::place(2, row)));
+            assert!(game.make_move(&Move::place(3, row)));
+        }
+        for m in [Move::pass(), Move::place(0, 2), Move::pass(), Move::pass()] {
+            assert!(game.make_move(&m));
+        }
+        game
+    }
+
+    #[test]
+    fn test_toggle_updates_preview_and_withdraws_acceptance() {
+        assert!(ScoringSession::new(Game5::new(5, 5)).is_none());
+
+        let mut session = ScoringSession::new(scoring_game()).expect("scoring phase");
+        assert_eq!(session.preview().totals(), (5.0, 11.5));
+
+        assert!(session.accept(Player::White));
+        assert!(session.toggle_dead(Position::new(0, 2)));
+        assert_eq!(session.preview().totals(), (15.0, 10.5));
+        assert!(!session.has_accepted(Player::White));
+        assert!(!session.toggle_dead(Position::new(1, 1)));
+
+        assert!(session.accept(Player::Black));
+        assert!(!session.is_finished());
+        assert!(session.accept(Player::White));
+        assert!(session.is_finished());
+        assert_eq!(
+            session.game().outcome().and_then(|o| o.winner()),
+            Some(Player::Black)
+        );
+        assert!(!session.toggle_dead(Position::new(2, 2)));
+    }
+
+    #[test]
+    fn test_state_restores_marking() {
+        let mut session = ScoringSession::new(scoring_game()).expect("scoring phase");
+        session.toggle_dead(Position::new(0, 2));
+        session.accept(Player::Black);
+
+        let state = session.state();
+        assert_eq!(state.dead_stones, vec!["A3".to_string()]);
+
+        let restored = ScoringSession::restore(scoring_game(), &state).expect("restores");
+        assert_eq!(restored.dead_stones(), vec![Position::new(0, 2)]);
+        assert_eq!(restored.preview(), session.preview());
+        assert!(restored.has_accepted(Player::Black));
+        assert!(!restored.has_accepted(Player::White));
+
+        let bad = ScoringState {
+            dead_stones: vec!["B2".to_string()],
+            ..ScoringState::default()
+        };
+        assert!(ScoringSession::restore(scoring_game(), &bad).is_none());
+
+        let game = restored.resume();
+        assert_eq!(game.phase(), GamePhase::Playing);
+    }
+}
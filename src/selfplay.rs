@@ -0,0 +1,284 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::game::{Game, GamePhase};
+use crate::outcome::GameOutcome;
+use crate::player::Player;
+pub use crate::policy::derive_game_seed;
+use crate::policy::MovePolicy;
+use crate::position::Position;
+use crate::r#move::Move;
+
+/// One finished self-play game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    /// Position of the game in the run, from 0.
+    pub index: u64,
+    /// The game's RNG seed, from `derive_game_seed`; replaying with it
+    /// reproduces the game.
+    pub seed: u64,
+    pub moves: Vec<Move>,
+    pub outcome: Option<GameOutcome>,
+}
+
+#[hotpath::measure_all]
+impl GameRecord {
+    /// Moves left until the end of the game from the position before each
+    /// move of the record, for a moves-remaining training target. A final
+    /// resignation ends the game without being counted.
+    pub fn moves_remaining(&self) -> Vec<u32> {
+        let played = match self.moves.last() {
+            Some(move_) if move_.is_resign() => self.moves.len() - 1,
+            _ => self.moves.len(),
+        };
+        (0..self.moves.len())
+            .map(|i| played.saturating_sub(i) as u32)
+            .collect()
+    }
+}
+
+/// Play one game from `game`, with `policy` choosing the moves of both sides.
+/// The game stops early if the policy returns an illegal move.
+#[hotpath::measure]
+pub fn play_game<const NW: usize, P: MovePolicy<NW>>(
+    game: Game<NW>,
+    policy: &mut P,
+    index: u64,
+    seed: u64,
+) -> GameRecord {
+    play_with(game, index, seed, policy, None)
+}
+
+/// Play one game from `game` with `black` and `white` choosing the moves of
+/// their own color. The game stops early if a policy returns an illegal move.
+///
+/// With dead-stone marking, the scoring phase ends with the dead stones both
+/// policies report (`MovePolicy::dead_stones`). If they disagree, play
+/// resumes once in cleanup mode (`MovePolicy::begin_cleanup`) and the next
+/// scoring phase counts every stone on the board as alive.
+#[hotpath::measure]
+pub fn play_match_game<const NW: usize, B: MovePolicy<NW>, W: MovePolicy<NW>>(
+    game: Game<NW>,
+    black: &mut B,
+    white: &mut W,
+    index: u64,
+    seed: u64,
+) -> GameRecord {
+    play_with(game, index, seed, black, Some(white))
+}
+
+/// Whether two lists of dead stones mark the same points.
+#[hotpath::measure]
+fn same_stones(a: &[Position], b: &[Position]) -> bool {
+    let mut a: Vec<(u8, u8)> = a.iter().map(|p| (p.col, p.row)).collect();
+    let mut b: Vec<(u8, u8)> = b.iter().map(|p| (p.col, p.row)).collect();
+    a.sort_unstable();
+    a.dedup();
+    b.sort_unstable();
+    b.dedup();
+    a == b
+}
+
+/// Play `game` out; `white` is None when `black` plays both sides.
+#[hotpath::measure]
+fn play_with<const NW: usize>(
+    mut game: Game<NW>,
+    index: u64,
+    seed: u64,
+    black: &mut dyn MovePolicy<NW>,
+    mut white: Option<&mut dyn MovePolicy<NW>>,
+) -> GameRecord {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut cleaned_up = false;
+    while !game.is_over() {
+        if game.phase() == GamePhase::Scoring {
+            if cleaned_up {
+                game.accept_score();
+                break;
+            }
+            let black_dead = black.dead_stones(&game);
+            let agreed = match white.as_deref_mut() {
+                Some(white) => same_stones(&black_dead, &white.dead_stones(&game)),
+                None => true,
+            };
+            if agreed {
+                game.mark_dead(&black_dead);
+                game.accept_score();
+                break;
+            }
+            // Take back the second pass and let the players capture what
+            // they think is dead.
+            game.unmake_move();
+            black.begin_cleanup(&game);
+            if let Some(white) = white.as_deref_mut() {
+                white.begin_cleanup(&game);
+            }
+            cleaned_up = true;
+            continue;
+        }
+
+        let move_ = match (game.turn(), white.as_deref_mut()) {
+            (Player::White, Some(white)) => white.choose(&game, &mut rng),
+            _ => black.choose(&game, &mut rng),
+        };
+        if !game.make_move(&move_) {
+            break;
+        }
+    }
+    GameRecord {
+        index,
+        seed,
+        moves: game.move_history(),
+        outcome: game.outcome(),
+    }
+}
+
+/// Play `num_games` games on `threads` threads, each starting from
+/// `new_game()` and played by a fresh `new_policy()`. Records are returned in
+/// index order and are identical for any thread count.
+#[hotpath::measure]
+pub fn run_self_play<const NW: usize, F, G, P>(
+    new_game: F,
+    new_policy: G,
+    num_games: u64,
+    base_seed: u64,
+    threads: usize,
+) -> Vec<GameRecord>
+where
+    F: Fn() -> Game<NW> + Sync,
+    G: Fn() -> P + Sync,
+    P: MovePolicy<NW>,
+{
+    let next_index = AtomicU64::new(0);
+    let records = Mutex::new(Vec::with_capacity(num_games as usize));
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                if index >= num_games {
+                    break;
+                }
+                let record = play_game(
+                    new_game(),
+                    &mut new_policy(),
+                    index,
+                    derive_game_seed(base_seed, index),
+                );
+                records
+                    .lock()
+                    .expect("self-play record lock poisoned")
+                    .push(record);
+            });
+        }
+    });
+
+    let mut records = records
+        .into_inner()
+        .expect("self-play record lock poisoned");
+    records.sort_by_key(|r| r.index);
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::outcome::EndReason;
+    use crate::policy::{NoEyeFillPolicy, UniformPolicy};
+
+    /// Passes and reports a fixed list of dead stones.
+    struct Judge {
+        dead: Vec<Position>,
+        cleanups: usize,
+    }
+
+    impl MovePolicy<{ nw_for_board(5, 5) }> for Judge {
+        fn choose(&mut self, _game: &Game<{ nw_for_board(5, 5) }>, _rng: &mut StdRng) -> Move {
+            Move::pass()
+        }
+
+        fn dead_stones(&mut self, _game: &Game<{ nw_for_board(5, 5) }>) -> Vec<Position> {
+            self.dead.clone()
+        }
+
+        fn begin_cleanup(&mut self, _game: &Game<{ nw_for_board(5, 5) }>) {
+            self.cleanups += 1;
+        }
+    }
+
+    /// Black wall on column 2, white wall on column 3 and a white invader at
+    /// A3, with dead-stone marking.
+    fn walled_game() -> Game<{ nw_for_board(5, 5) }> {
+        let mut game = Game::with_options(5, 5, 0.5, 0, 1000, false);
+        game.set_dead_stone_marking(true);
+        for row in 0..5 {
+            assert!(game.make_move(&Move::place(2, row)));
+            assert!(game.make_move(&Move::place(3, row)));
+        }
+        assert!(game.make_move(&Move::pass()));
+        assert!(game.make_move(&Move::place(0, 2)));
+        game
+    }
+
+    fn new_game() -> Game<{ nw_for_board(7, 7) }> {
+        Game::with_options(7, 7, 7.5, 10, 150, true)
+    }
+
+    #[test]
+    fn test_results_independent_of_thread_count() {
+        let single = run_self_play(new_game, || UniformPolicy, 12, 42, 1);
+        let parallel = run_self_play(new_game, || UniformPolicy, 12, 42, 4);
+
+        assert_eq!(single.len(), 12);
+        assert_eq!(single, parallel);
+        for (i, record) in single.iter().enumerate() {
+            assert_eq!(record.index, i as u64);
+            assert_eq!(record.seed, derive_game_seed(42, i as u64));
+        }
+    }
+
+    #[test]
+    fn test_record_replays_from_its_seed() {
+        let records = run_self_play(new_game, || NoEyeFillPolicy, 3, 7, 2);
+        let replayed = play_game(new_game(), &mut NoEyeFillPolicy, 2, records[2].seed);
+        assert_eq!(replayed, records[2]);
+        assert_ne!(records[0].moves, records[1].moves);
+    }
+
+    #[test]
+    fn test_derived_seeds_differ() {
+        assert_ne!(derive_game_seed(0, 0), derive_game_seed(0, 1));
+        assert_ne!(derive_game_seed(0, 1), derive_game_seed(1, 0));
+    }
+
+    #[test]
+    fn test_scoring_settles_dead_stones() {
+        let invader = Position::new(0, 2);
+        let judge = |dead: &[Position]| Judge {
+            dead: dead.to_vec(),
+            cleanups: 0,
+        };
+
+        let (mut black, mut white) = (judge(&[invader]), judge(&[invader]));
+        let agreed = play_match_game(walled_game(), &mut black, &mut white, 0, 0);
+        let outcome = agreed.outcome.expect("scored");
+        assert_eq!(outcome.reason(), EndReason::DoublePass);
+        assert_eq!(outcome.winner(), Some(Player::Black));
+        assert_eq!(black.cleanups, 0);
+
+        // White disputes the marking, so the second pass is taken back. White
+        // passes again rather than capturing, and the invader counts as alive.
+        let (mut black, mut white) = (judge(&[invader]), judge(&[]));
+        let disputed = play_match_game(walled_game(), &mut black, &mut white, 0, 0);
+        assert_eq!((black.cleanups, white.cleanups), (1, 1));
+        assert_eq!(disputed.moves, agreed.moves);
+        assert_eq!(
+            disputed.outcome.and_then(|o| o.winner()),
+            Some(Player::White)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bitboard::nw_for_board;
+use crate::config::{from_json_str, Config, ConfigError};
+use crate::game::Game;
+use crate::gtp::{gtp_to_move, gtp_to_player, move_to_gtp, player_to_gtp};
+use crate::player::Player;
+use crate::r#move::Move;
+use crate::replay::{replay_moves, IllegalMovePolicy};
+
+/// Format version written by `EngineSession::save`.
+const SESSION_VERSION: u32 = 1;
+
+/// Thinking time left for each player, in milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockState {
+    pub black_ms: u64,
+    pub white_ms: u64,
+}
+
+/// Search statistics for one candidate move at the root.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CandidateMove {
+    /// The move as a GTP vertex, `pass`, `resign` or `swap`.
+    #[serde(rename = "move")]
+    pub move_: String,
+    pub visits: u32,
+    /// Mean playout value for the player to move, from 0 to 1.
+    pub value: f32,
+}
+
+/// What the last search found, kept so a restarted process can report it
+/// before searching again.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchSummary {
+    pub iterations: u32,
+    /// Candidates in the order the search ranked them.
+    pub candidates: Vec<CandidateMove>,
+}
+
+/// The state of a GTP or analysis process, saved so it can be restarted
+/// mid-game: the configuration, the game, and optionally the clock and a
+/// summary of the last search.
+///
+/// The game is stored as its moves and rebuilt from `config.rules` on load,
+/// so rule changes made directly on `game` (such as `set_komi`) must also be
+/// made in `config` to survive a restart.
+#[derive(Clone, Debug)]
+pub struct EngineSession<const NW: usize> {
+    pub config: Config,
+    pub game: Game<NW>,
+    pub clock: Option<ClockState>,
+    pub search: Option<SearchSummary>,
+}
+
+/// One move of the saved game.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SavedMove {
+    player: String,
+    #[serde(rename = "move")]
+    move_: String,
+}
+
+/// The on-disk form of a session.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SessionFile {
+    version: u32,
+    config: Config,
+    moves: Vec<SavedMove>,
+    clock: Option<ClockState>,
+    search: Option<SearchSummary>,
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> EngineSession<NW> {
+    /// A session with a new game from `config.rules`. `NW` must be
+    /// `nw_for_board(width, height)` for the configured board.
+    pub fn new(config: Config) -> Self {
+        EngineSession {
+            game: config.rules.new_game(),
+            config,
+            clock: None,
+            search: None,
+        }
+    }
+
+    /// Write the session to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let height = self.game.height();
+        let file = SessionFile {
+            version: SESSION_VERSION,
+            config: self.config,
+            moves: self
+                .game
+                .moves_by_player()
+                .map(|(player, move_)| SavedMove {
+                    player: player_to_gtp(player).to_string(),
+                    move_: move_to_gtp(&move_, height),
+                })
+                .collect(),
+            clock: self.clock,
+            search: self.search.clone(),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Read a session written by `save`, replaying its moves onto a new game.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let file: SessionFile = from_json_str(&std::fs::read_to_string(path)?)?;
+        if file.version != SESSION_VERSION {
+            return Err(ConfigError::Invalid(format!(
+                "unsupported session version {}",
+                file.version
+            )));
+        }
+        let rules = file.config.rules;
+        rules.validate()?;
+        if nw_for_board(rules.width, rules.height) != NW {
+            return Err(ConfigError::Invalid(format!(
+                "a {}x{} session does not fit this game type",
+                rules.width, rules.height
+            )));
+        }
+
+        let moves = file
+            .moves
+            .iter()
+            .map(|saved| parse_saved_move(saved, rules.height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut game = rules.new_game();
+        replay_moves(&mut game, &moves, IllegalMovePolicy::Fail)
+            .map_err(|e| ConfigError::Invalid(format!("cannot replay session: {}", e)))?;
+
+        Ok(EngineSession {
+            config: file.config,
+            game,
+            clock: file.clock,
+            search: file.search,
+        })
+    }
+}
+
+#[hotpath::measure]
+fn parse_saved_move(saved: &SavedMove, height: u8) -> Result<(Player, Move), ConfigError> {
+    let invalid = |what: &str| ConfigError::Invalid(format!("invalid {} in session", what));
+    let player = gtp_to_player(&saved.player).map_err(|_| invalid("player"))?;
+    let move_ = match saved.move_.trim().to_lowercase().as_str() {
+        "resign" => Move::resign(),
+        "swap" => Move::swap(),
+        _ => gtp_to_move(&saved.move_, height).map_err(|_| invalid("move"))?,
+    };
+    Ok((player, move_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+
+    type Session9 = EngineSession<{ nw_for_board(9, 9) }>;
+
+    fn nine_by_nine() -> Config {
+        Config {
+            rules: RulesConfig {
+                width: 9,
+                height: 9,
+                komi: 6.5,
+                swap_rule: true,
+                ..RulesConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let mut session = Session9::new(nine_by_nine());
+        for m in [Move::place(4, 4), Move::swap(), Move::place(2, 2)] {
+            assert!(session.game.make_move(&m));
+        }
+        session.clock = Some(ClockState {
+            black_ms: 60_000,
+            white_ms: 45_500,
+        });
+        session.search = Some(SearchSummary {
+            iterations: 800,
+            candidates: vec![CandidateMove {
+                move_: "G7".to_string(),
+                visits: 612,
+                value: 0.54,
+            }],
+        });
+
+        let path = std::env::temp_dir().join("spooky_go_session_round_trip.json");
+        session.save(&path).expect("saves");
+        let loaded = Session9::load(&path).expect("loads");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.config, session.config);
+        assert_eq!(loaded.game.move_history(), session.game.move_history());
+        assert_eq!(loaded.game.board(), session.game.board());
+        assert_eq!(loaded.game.komi(), session.game.komi());
+        assert_eq!(loaded.clock, session.clock);
+        assert_eq!(loaded.search, session.search);
+    }
+
+    #[test]
+    fn test_load_rejects_other_board_sizes() {
+        let session = EngineSession::<{ nw_for_board(19, 19) }>::new(Config::default());
+        let path = std::env::temp_dir().join("spooky_go_session_wrong_size.json");
+        session.save(&path).expect("saves");
+        let loaded = Session9::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(loaded, Err(ConfigError::Invalid(_))));
+    }
+}
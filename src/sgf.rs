@@ -0,0 +1,966 @@
+use std::fmt;
+use std::fmt::Write;
+
+use crate::bitboard::nw_for_board;
+use crate::game::{Game, GameInfo};
+use crate::outcome::{EndReason, GameOutcome};
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+use crate::replay::{replay_moves, IllegalMovePolicy, ReplayError};
+use crate::rules::Ruleset;
+
+/// Errors from reading an SGF game record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SgfError {
+    /// The text is not well-formed SGF. `offset` is a byte offset into the input.
+    Syntax {
+        offset: usize,
+        message: String,
+    },
+    /// The record is for another game than Go (`GM` other than 1).
+    UnsupportedGame(String),
+    InvalidBoardSize(String),
+    InvalidProperty {
+        property: String,
+        value: String,
+    },
+    /// Valid SGF that this crate cannot represent, e.g. setup stones mid-game.
+    Unsupported(String),
+    /// A recorded move could not be played.
+    Replay(ReplayError),
+}
+
+impl From<ReplayError> for SgfError {
+    fn from(e: ReplayError) -> Self {
+        SgfError::Replay(e)
+    }
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SgfError::Syntax { offset, message } => {
+                write!(f, "SGF syntax error at byte {}: {}", offset, message)
+            }
+            SgfError::UnsupportedGame(gm) => write!(f, "not a Go record (GM[{}])", gm),
+            SgfError::InvalidBoardSize(msg) => write!(f, "invalid board size: {}", msg),
+            SgfError::InvalidProperty { property, value } => {
+                write!(f, "invalid value for {}: [{}]", property, value)
+            }
+            SgfError::Unsupported(msg) => write!(f, "unsupported SGF: {}", msg),
+            SgfError::Replay(e) => write!(f, "cannot replay record: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SgfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SgfError::Replay(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A property of an SGF node: its identifier and one or more raw values.
+type Property = (String, Vec<String>);
+
+/// A node: the properties following one `;`.
+type Node = Vec<Property>;
+
+/// A node with the index of its parent node, `None` for the root.
+type TreeNode = (Option<usize>, Node);
+
+/// Reads the game trees of an SGF collection.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+#[hotpath::measure_all]
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> SgfError {
+        SgfError::Syntax {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SgfError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => Err(self.error(&format!("expected '{}'", expected))),
+            None => Err(self.error(&format!("unexpected end of input, expected '{}'", expected))),
+        }
+    }
+
+    /// A game tree: `(` nodes, then variations, then `)`. Returns every node
+    /// with the index of its parent, in document order, so a node's first
+    /// child (if any) directly follows it. Iterative so that deeply nested
+    /// records cannot overflow the stack.
+    fn game_tree(&mut self) -> Result<Vec<TreeNode>, SgfError> {
+        let mut nodes: Vec<TreeNode> = Vec::new();
+        // For each open tree: the last node read in it, which is the parent
+        // of its next node or variation.
+        let mut open: Vec<Option<usize>> = Vec::new();
+
+        self.expect('(')?;
+        open.push(None);
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(';') => {
+                    self.bump();
+                    let node = self.node()?;
+                    let last = open.last_mut().expect("a node is inside an open tree");
+                    nodes.push((*last, node));
+                    *last = Some(nodes.len() - 1);
+                }
+                Some('(') => {
+                    self.bump();
+                    let parent = *open.last().expect("a variation is inside an open tree");
+                    open.push(parent);
+                }
+                Some(')') => {
+                    self.bump();
+                    open.pop();
+                    if open.is_empty() {
+                        return Ok(nodes);
+                    }
+                }
+                Some(_) => return Err(self.error("expected ';', '(' or ')'")),
+                None => return Err(self.error("unexpected end of input, expected ')'")),
+            }
+        }
+    }
+
+    /// A game tree with at least one node.
+    fn nonempty_game_tree(&mut self) -> Result<Vec<TreeNode>, SgfError> {
+        let start = self.pos;
+        let nodes = self.game_tree()?;
+        if nodes.is_empty() {
+            return Err(SgfError::Syntax {
+                offset: start,
+                message: "game tree has no nodes".to_string(),
+            });
+        }
+        Ok(nodes)
+    }
+
+    fn node(&mut self) -> Result<Node, SgfError> {
+        let mut properties = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if !self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                return Ok(properties);
+            }
+
+            // FF[3] and older allow lowercase letters in identifiers
+            // (`AddBlack`); only the uppercase ones are significant.
+            let mut ident = String::new();
+            while let Some(c) = self.peek().filter(char::is_ascii_alphabetic) {
+                if c.is_ascii_uppercase() {
+                    ident.push(c);
+                }
+                self.bump();
+            }
+
+            let mut values = Vec::new();
+            self.skip_whitespace();
+            while self.peek() == Some('[') {
+                values.push(self.value()?);
+                self.skip_whitespace();
+            }
+            if values.is_empty() {
+                return Err(self.error("property without a value"));
+            }
+            properties.push((ident, values));
+        }
+    }
+
+    /// A bracketed value with `\` escapes resolved.
+    fn value(&mut self) -> Result<String, SgfError> {
+        self.expect('[')?;
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(']') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    // An escaped line break is a soft line break and is removed.
+                    Some('\n') | Some('\r') => {}
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+        Err(self.error("unterminated property value"))
+    }
+}
+
+/// A Go game record read from SGF, independent of the `Game` board type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SgfGame {
+    pub width: u8,
+    pub height: u8,
+    /// `KM`; 0 when the record has none.
+    pub komi: f32,
+    /// `HA`; 0 for an even game.
+    pub handicap: u8,
+    /// `RU`, when it names a ruleset this crate knows.
+    pub ruleset: Option<Ruleset>,
+    /// Setup stones placed before the first move, usually handicap stones:
+    /// the result of the `AB`, `AW` and `AE` properties in order.
+    pub black_setup: Vec<Position>,
+    pub white_setup: Vec<Position>,
+    /// `PL`, the player to move after setup, if given.
+    pub to_move: Option<Player>,
+    pub moves: Vec<(Player, Move)>,
+    /// `RE`, when it can be read.
+    pub result: Option<GameOutcome>,
+    /// Player names and ranks (`PB`, `PW`, `BR`, `WR`), date (`DT`), event
+    /// (`EV`) and the result as written.
+    pub info: GameInfo,
+}
+
+#[hotpath::measure]
+fn invalid(property: &str, value: &str) -> SgfError {
+    SgfError::InvalidProperty {
+        property: property.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// The index of an SGF coordinate letter: `a`-`z` are 0-25, `A`-`Z` are 26-51.
+#[hotpath::measure]
+fn coordinate(c: u8) -> Option<u8> {
+    match c {
+        b'a'..=b'z' => Some(c - b'a'),
+        b'A'..=b'Z' => Some(c - b'A' + 26),
+        _ => None,
+    }
+}
+
+/// A point such as `dp`, with the row counted from the top of the board.
+#[hotpath::measure]
+fn parse_point(value: &str, width: u8, height: u8) -> Option<Position> {
+    let &[c, r] = value.as_bytes() else {
+        return None;
+    };
+    let (col, row) = (coordinate(c)?, coordinate(r)?);
+    (col < width && row < height).then(|| Position::new(col, height - 1 - row))
+}
+
+/// A move value: a point, or a pass written as `[]` (or `[tt]` on boards up to 19x19).
+#[hotpath::measure]
+fn parse_move(property: &str, value: &str, width: u8, height: u8) -> Result<Move, SgfError> {
+    if value.is_empty() || (value == "tt" && width <= 19 && height <= 19) {
+        return Ok(Move::pass());
+    }
+    parse_point(value, width, height)
+        .map(|pos| Move::place(pos.col, pos.row))
+        .ok_or_else(|| invalid(property, value))
+}
+
+/// The SGF coordinates of `pos`, e.g. `dp`; the inverse of `parse_point`.
+#[hotpath::measure]
+fn format_point(pos: Position, height: u8) -> String {
+    let letter = |i: u8| (b'a' + i) as char;
+    format!("{}{}", letter(pos.col), letter(height - 1 - pos.row))
+}
+
+/// `value` with `\` and `]` escaped for a property value.
+#[hotpath::measure]
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// Points of a setup property, expanding compressed `aa:cc` rectangles.
+#[hotpath::measure]
+fn parse_points(
+    property: &str,
+    values: &[String],
+    width: u8,
+    height: u8,
+    out: &mut Vec<Position>,
+) -> Result<(), SgfError> {
+    for value in values {
+        let (from, to) = value.split_once(':').unwrap_or((value, value));
+        let a = parse_point(from, width, height).ok_or_else(|| invalid(property, value))?;
+        let b = parse_point(to, width, height).ok_or_else(|| invalid(property, value))?;
+        for col in a.col.min(b.col)..=a.col.max(b.col) {
+            for row in a.row.min(b.row)..=a.row.max(b.row) {
+                out.push(Position::new(col, row));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `SZ[19]` or `SZ[19:13]` (columns, then rows).
+#[hotpath::measure]
+fn parse_size(value: &str) -> Result<(u8, u8), SgfError> {
+    let (w, h) = value.split_once(':').unwrap_or((value, value));
+    let parse = |s: &str| s.trim().parse::<u8>().ok().filter(|n| (2..=32).contains(n));
+    match (parse(w), parse(h)) {
+        (Some(w), Some(h)) => Ok((w, h)),
+        _ => Err(SgfError::InvalidBoardSize(value.to_string())),
+    }
+}
+
+/// Find the first property `name` in `node`.
+#[hotpath::measure]
+fn find<'n>(node: &'n Node, name: &str) -> Option<&'n [String]> {
+    node.iter()
+        .find(|(ident, _)| ident == name)
+        .map(|(_, values)| values.as_slice())
+}
+
+/// The first game tree of an SGF collection.
+#[hotpath::measure]
+fn parse_nodes(text: &str) -> Result<Vec<TreeNode>, SgfError> {
+    let start = text.find('(').ok_or_else(|| SgfError::Syntax {
+        offset: 0,
+        message: "no game tree found".to_string(),
+    })?;
+    let mut parser = Parser { text, pos: start };
+    parser.nonempty_game_tree()
+}
+
+/// The number of nodes on the main line, which always come first in
+/// document order.
+#[hotpath::measure]
+fn main_line_len(nodes: &[TreeNode]) -> usize {
+    (1..nodes.len())
+        .find(|&i| nodes[i].0 != Some(i - 1))
+        .unwrap_or(nodes.len())
+}
+
+/// A `B` or `W` property as the move it records.
+#[hotpath::measure]
+fn read_move(
+    ident: &str,
+    values: &[String],
+    width: u8,
+    height: u8,
+) -> Result<(Player, Move), SgfError> {
+    let player = if ident == "B" {
+        Player::Black
+    } else {
+        Player::White
+    };
+    let value = values.first().map_or("", String::as_str);
+    Ok((player, parse_move(ident, value, width, height)?))
+}
+
+/// Parse the main line of the first game in an SGF collection.
+///
+/// Reads board size (`SZ`), komi (`KM`), handicap (`HA`), ruleset (`RU`),
+/// result (`RE`), setup stones (`AB`/`AW`/`AE`) and player to move (`PL`) before
+/// the first move, and the `B`/`W` moves. Variations other than the first are
+/// ignored, as are comments, markup and other properties.
+#[hotpath::measure]
+pub fn parse_sgf(text: &str) -> Result<SgfGame, SgfError> {
+    let nodes = parse_nodes(text)?;
+    read_record(&nodes[..main_line_len(&nodes)])
+}
+
+/// Parse the main line of every game in an SGF collection, as `parse_sgf`
+/// does for the first. Text after the last game tree is ignored.
+#[hotpath::measure]
+pub fn parse_sgf_collection(text: &str) -> Result<Vec<SgfGame>, SgfError> {
+    sgf_records(text).collect()
+}
+
+/// The games of an SGF collection, read one at a time. Stops after the
+/// first error.
+pub(crate) struct SgfRecords<'a> {
+    parser: Option<Parser<'a>>,
+}
+
+#[hotpath::measure]
+pub(crate) fn sgf_records(text: &str) -> SgfRecords<'_> {
+    let pos = text.find('(').unwrap_or(text.len());
+    SgfRecords {
+        parser: Some(Parser { text, pos }),
+    }
+}
+
+#[hotpath::measure_all]
+impl Iterator for SgfRecords<'_> {
+    type Item = Result<SgfGame, SgfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let parser = self.parser.as_mut()?;
+        // Only the first game can be missing: later ones are only read after
+        // a `(` was seen.
+        if parser.peek().is_none() {
+            self.parser = None;
+            return Some(Err(SgfError::Syntax {
+                offset: 0,
+                message: "no game tree found".to_string(),
+            }));
+        }
+
+        let record = parser
+            .nonempty_game_tree()
+            .and_then(|nodes| read_record(&nodes[..main_line_len(&nodes)]));
+        parser.skip_whitespace();
+        if record.is_err() || parser.peek() != Some('(') {
+            self.parser = None;
+        }
+        Some(record)
+    }
+}
+
+/// A node of an SGF game tree with the moves it records.
+pub(crate) struct SgfTreeNode {
+    pub(crate) parent: Option<usize>,
+    pub(crate) moves: Vec<(Player, Move)>,
+}
+
+/// Parse the first game in an SGF collection with all of its variations.
+/// The record holds the root properties and the main line; the nodes are the
+/// whole tree in document order, the root first. Setup stones are only
+/// supported on the main line before the first move.
+#[hotpath::measure]
+pub(crate) fn parse_sgf_tree(text: &str) -> Result<(SgfGame, Vec<SgfTreeNode>), SgfError> {
+    let nodes = parse_nodes(text)?;
+    let main_len = main_line_len(&nodes);
+    let record = read_record(&nodes[..main_len])?;
+
+    let mut tree = Vec::with_capacity(nodes.len());
+    for (i, (parent, node)) in nodes.iter().enumerate() {
+        let mut moves = Vec::new();
+        for (ident, values) in node {
+            match ident.as_str() {
+                "B" | "W" => moves.push(read_move(ident, values, record.width, record.height)?),
+                "AB" | "AW" | "AE" if i >= main_len => {
+                    return Err(SgfError::Unsupported(format!(
+                        "setup property {} in a variation",
+                        ident
+                    )));
+                }
+                _ => {}
+            }
+        }
+        tree.push(SgfTreeNode {
+            parent: *parent,
+            moves,
+        });
+    }
+    Ok((record, tree))
+}
+
+/// The record read from the root and the main line `nodes`.
+#[hotpath::measure]
+fn read_record(nodes: &[TreeNode]) -> Result<SgfGame, SgfError> {
+    let root = &nodes[0].1;
+
+    if let Some(gm) = find(root, "GM").and_then(|v| v.first()) {
+        if gm.trim() != "1" {
+            return Err(SgfError::UnsupportedGame(gm.clone()));
+        }
+    }
+
+    let (width, height) = match find(root, "SZ").and_then(|v| v.first()) {
+        Some(value) => parse_size(value)?,
+        None => (19, 19),
+    };
+    if width > 25 || height > 25 {
+        // Coordinates past `z` exist, but the `tt` pass makes these sizes ambiguous
+        // in older files and no engine plays them; keep to what `Game` supports.
+        return Err(SgfError::InvalidBoardSize(format!("{}x{}", width, height)));
+    }
+
+    let komi = match find(root, "KM").and_then(|v| v.first()) {
+        Some(value) => value.trim().parse().map_err(|_| invalid("KM", value))?,
+        None => 0.0,
+    };
+    let handicap = match find(root, "HA").and_then(|v| v.first()) {
+        Some(value) => value.trim().parse().map_err(|_| invalid("HA", value))?,
+        None => 0,
+    };
+    let ruleset = find(root, "RU")
+        .and_then(|v| v.first())
+        .and_then(|value| Ruleset::from_name(value));
+    let result = find(root, "RE")
+        .and_then(|v| v.first())
+        .and_then(|value| GameOutcome::from_result_string(value).ok());
+    let text = |name: &str| {
+        find(root, name)
+            .and_then(|v| v.first())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let info = GameInfo {
+        black_player: text("PB"),
+        white_player: text("PW"),
+        black_rank: text("BR"),
+        white_rank: text("WR"),
+        date: text("DT"),
+        event: text("EV"),
+        result: text("RE"),
+    };
+
+    let mut game = SgfGame {
+        width,
+        height,
+        komi,
+        handicap,
+        ruleset,
+        black_setup: Vec::new(),
+        white_setup: Vec::new(),
+        to_move: None,
+        moves: Vec::new(),
+        result,
+        info,
+    };
+
+    for (_, node) in nodes {
+        for (ident, values) in node {
+            match ident.as_str() {
+                "B" | "W" => game.moves.push(read_move(ident, values, width, height)?),
+                "AB" | "AW" | "AE" if !game.moves.is_empty() => {
+                    return Err(SgfError::Unsupported(format!(
+                        "setup property {} after move {}",
+                        ident,
+                        game.moves.len()
+                    )));
+                }
+                "AB" | "AW" | "AE" => {
+                    let mut points = Vec::new();
+                    parse_points(ident, values, width, height, &mut points)?;
+                    game.add_setup(ident, &points);
+                }
+                "PL" if game.moves.is_empty() => {
+                    let value = values.first().map_or("", String::as_str);
+                    game.to_move = match value.trim() {
+                        "B" | "b" => Some(Player::Black),
+                        "W" | "w" => Some(Player::White),
+                        _ => return Err(invalid(ident, value)),
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(game)
+}
+
+#[hotpath::measure_all]
+impl SgfGame {
+    /// Apply one `AB`, `AW` or `AE` property: each point ends up with the
+    /// color it names (empty for `AE`), replacing earlier setup there.
+    fn add_setup(&mut self, ident: &str, points: &[Position]) {
+        self.black_setup.retain(|p| !points.contains(p));
+        self.white_setup.retain(|p| !points.contains(p));
+        let setup = match ident {
+            "AB" => &mut self.black_setup,
+            "AW" => &mut self.white_setup,
+            _ => return,
+        };
+        for &pos in points {
+            if !setup.contains(&pos) {
+                setup.push(pos);
+            }
+        }
+    }
+
+    /// The player to move after setup: `PL` if given, otherwise the player of
+    /// the first move, otherwise White after handicap stones and Black if not.
+    pub fn first_player(&self) -> Player {
+        if let Some(player) = self.to_move {
+            return player;
+        }
+        if let Some(&(player, _)) = self.moves.first() {
+            return player;
+        }
+        if self.handicap > 0 && !self.black_setup.is_empty() {
+            Player::White
+        } else {
+            Player::Black
+        }
+    }
+
+    /// Apply the ruleset and setup stones to a fresh `game` of this size.
+    pub fn set_up<const NW: usize>(&self, game: &mut Game<NW>) {
+        if let Some(ruleset) = self.ruleset {
+            game.set_ruleset(ruleset);
+        }
+        game.set_info(self.info.clone());
+        game.set_up_position(
+            &self.black_setup,
+            &self.white_setup,
+            self.handicap,
+            self.first_player(),
+        );
+    }
+
+    /// Play the recorded moves onto `game` (already `set_up`), then record a
+    /// resignation from the result if the moves did not end the game.
+    /// Scored results are kept in `result` only, since the record rarely
+    /// includes the final passes.
+    pub fn replay_into<const NW: usize>(
+        &self,
+        game: &mut Game<NW>,
+        policy: IllegalMovePolicy,
+    ) -> Result<(), SgfError> {
+        replay_moves(game, &self.moves, policy)?;
+
+        if let Some(outcome) = self.result {
+            if outcome.reason() == EndReason::Resignation && !game.is_over() {
+                if let Some(winner) = outcome.winner() {
+                    game.resign(winner.opposite());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A fresh game for this record, set up but with no moves played: no
+    /// minimum before passing, no move limit and positional superko. `NW`
+    /// must be `nw_for_board(width, height)`.
+    pub fn start_game<const NW: usize>(&self) -> Result<Game<NW>, SgfError> {
+        if nw_for_board(self.width, self.height) != NW {
+            return Err(SgfError::InvalidBoardSize(format!(
+                "{}x{} does not fit this game type",
+                self.width, self.height
+            )));
+        }
+        let mut game = Game::with_options(self.width, self.height, self.komi, 0, u16::MAX, true);
+        self.set_up(&mut game);
+        Ok(game)
+    }
+
+    /// Build a game from this record with `start_game` and replay its moves.
+    pub fn to_game<const NW: usize>(
+        &self,
+        policy: IllegalMovePolicy,
+    ) -> Result<Game<NW>, SgfError> {
+        let mut game = self.start_game()?;
+        self.replay_into(&mut game, policy)?;
+        Ok(game)
+    }
+}
+
+#[hotpath::measure_all]
+impl<const NW: usize> Game<NW> {
+    /// Read a game from SGF (see `sgf::parse_sgf`), failing on any move that
+    /// cannot be played. `NW` must be `nw_for_board(width, height)` for the
+    /// record's board size.
+    pub fn from_sgf(text: &str) -> Result<Self, SgfError> {
+        parse_sgf(text)?.to_game(IllegalMovePolicy::Fail)
+    }
+
+    /// The game as an SGF (FF[4]) record: board size, komi, ruleset,
+    /// `info()`, handicap and any other starting stones, the moves, and the
+    /// result (from the outcome once the game is over, otherwise as recorded
+    /// in `info()`). Passes are written as `[]`. A resignation
+    /// appears only in `RE`, and a swap, which SGF has no move for, as a
+    /// comment node.
+    pub fn to_sgf(&self) -> String {
+        let width = self.width();
+        let height = self.height();
+        let mut sgf = String::from("(;GM[1]FF[4]CA[UTF-8]");
+        let _ = write!(sgf, "AP[spooky_go:{}]", env!("CARGO_PKG_VERSION"));
+        if width == height {
+            let _ = write!(sgf, "SZ[{}]", width);
+        } else {
+            let _ = write!(sgf, "SZ[{}:{}]", width, height);
+        }
+        let ruleset = match self.ruleset() {
+            Ruleset::Chinese => "Chinese",
+            Ruleset::Japanese => "Japanese",
+            Ruleset::Aga => "AGA",
+        };
+        let _ = write!(sgf, "KM[{}]RU[{}]", self.komi(), ruleset);
+        if self.handicap() > 0 {
+            let _ = write!(sgf, "HA[{}]", self.handicap());
+        }
+        let info = self.info();
+        for (ident, value) in [
+            ("PB", &info.black_player),
+            ("BR", &info.black_rank),
+            ("PW", &info.white_player),
+            ("WR", &info.white_rank),
+            ("DT", &info.date),
+            ("EV", &info.event),
+        ] {
+            if let Some(value) = value {
+                let _ = write!(sgf, "{}[{}]", ident, escape_text(value));
+            }
+        }
+        match self.outcome() {
+            Some(outcome) => {
+                let _ = write!(sgf, "RE[{}]", outcome.to_result_string());
+            }
+            None => {
+                if let Some(result) = &info.result {
+                    let _ = write!(sgf, "RE[{}]", escape_text(result));
+                }
+            }
+        }
+
+        let start = self
+            .board_history()
+            .last()
+            .expect("board history includes the current board");
+        let mut has_setup = false;
+        for (ident, player) in [("AB", Player::Black), ("AW", Player::White)] {
+            let stones = start.stones_for(player);
+            if stones.is_empty() {
+                continue;
+            }
+            has_setup = true;
+            sgf.push_str(ident);
+            for idx in stones.iter_ones() {
+                let pos = Position::from_index(idx, width);
+                let _ = write!(sgf, "[{}]", format_point(pos, height));
+            }
+        }
+        if has_setup {
+            let first = self
+                .moves_by_player()
+                .next()
+                .map_or(self.turn(), |(player, _)| player);
+            let _ = write!(sgf, "PL[{}]", first.to_char());
+        }
+
+        for (player, move_) in self.moves_by_player() {
+            match move_ {
+                Move::Place { col, row } => {
+                    let point = format_point(Position::new(col, row), height);
+                    let _ = write!(sgf, "\n;{}[{}]", player.to_char(), point);
+                }
+                Move::Pass => {
+                    let _ = write!(sgf, "\n;{}[]", player.to_char());
+                }
+                Move::Swap => sgf.push_str("\n;C[White swapped colors]"),
+                Move::Resign => {}
+            }
+        }
+        sgf.push_str(")\n");
+        sgf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Game9 = Game<{ nw_for_board(9, 9) }>;
+
+    #[test]
+    fn test_parse_main_line_and_root_properties() {
+        let sgf = "(;GM[1]FF[4]SZ[9]KM[6.5]RU[Japanese]RE[W+2.5]PB[Some\\]one]C[a comment]
+            ;B[ee];W[cc]
+            (;B[gc];W[tt])
+            (;B[cg]))";
+        let record = parse_sgf(sgf).expect("parses");
+
+        assert_eq!((record.width, record.height), (9, 9));
+        assert_eq!(record.komi, 6.5);
+        assert_eq!(record.ruleset, Some(Ruleset::Japanese));
+        assert_eq!(
+            record.result,
+            Some(GameOutcome::from_score(0.0, 2.5, EndReason::DoublePass))
+        );
+        assert_eq!(
+            record.moves,
+            vec![
+                (Player::Black, Move::place(4, 4)),
+                (Player::White, Move::place(2, 6)),
+                (Player::Black, Move::place(6, 6)),
+                (Player::White, Move::pass()),
+            ]
+        );
+
+        let game = Game9::from_sgf(sgf).expect("replays");
+        assert_eq!(game.move_count(), 4);
+        assert_eq!(game.ruleset(), Ruleset::Japanese);
+        assert_eq!(game.turn(), Player::Black);
+    }
+
+    #[test]
+    fn test_handicap_setup_and_resignation() {
+        let sgf = "(;SZ[9]HA[2]KM[0.5]AB[cg][gc]RE[B+Resign];W[ee];B[ce])";
+        let game = Game9::from_sgf(sgf).expect("replays");
+
+        assert_eq!(game.handicap(), 2);
+        assert_eq!(
+            game.get_piece(&Position::new(2, 2)),
+            Some(Player::Black as i8)
+        );
+        assert_eq!(
+            game.get_piece(&Position::new(6, 6)),
+            Some(Player::Black as i8)
+        );
+        assert!(game.is_over());
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::resignation(Player::White))
+        );
+    }
+
+    #[test]
+    fn test_to_sgf_round_trip() {
+        let mut game = Game9::with_options(9, 9, 5.5, 0, u16::MAX, true);
+        game.set_ruleset(Ruleset::Japanese);
+        game.place_handicap(2);
+        for m in [Move::place(4, 4), Move::place(2, 4), Move::pass()] {
+            assert!(game.make_move(&m));
+        }
+        game.resign(Player::White);
+
+        let sgf = game.to_sgf();
+        assert!(sgf.starts_with("(;GM[1]FF[4]"));
+        assert!(sgf.contains("SZ[9]KM[5.5]RU[Japanese]HA[2]RE[B+R]"));
+        assert!(sgf.contains(";W[]"));
+
+        let record = parse_sgf(&sgf).expect("parses");
+        assert_eq!(record.black_setup.len(), 2);
+        let read = Game9::from_sgf(&sgf).expect("replays");
+        assert_eq!(read.move_history(), game.move_history());
+        assert_eq!(read.board(), game.board());
+        assert_eq!(read.komi(), game.komi());
+        assert_eq!(read.ruleset(), Ruleset::Japanese);
+        assert_eq!(read.outcome(), game.outcome());
+    }
+
+    #[test]
+    fn test_game_info_round_trip() {
+        let sgf = "(;SZ[9]PB[Honinbo \\\\ Shusaku]BR[7d]PW[Gennan]WR[8d]DT[1846-09-11]\
+                   EV[Castle \\] Game]RE[B+2];B[ee])";
+        let game = Game9::from_sgf(sgf).expect("replays");
+        let info = game.info();
+        assert_eq!(info.black_player.as_deref(), Some("Honinbo \\ Shusaku"));
+        assert_eq!(info.white_rank.as_deref(), Some("8d"));
+        assert_eq!(info.date.as_deref(), Some("1846-09-11"));
+        assert_eq!(info.event.as_deref(), Some("Castle ] Game"));
+        assert_eq!(info.result.as_deref(), Some("B+2"));
+        assert!(!game.is_over());
+
+        let read = Game9::from_sgf(&game.to_sgf()).expect("replays");
+        assert_eq!(read.info(), game.info());
+    }
+
+    #[test]
+    fn test_compressed_setup_points() {
+        let record = parse_sgf("(;SZ[5]AB[aa:bb]AW[ee])").expect("parses");
+        assert_eq!(record.black_setup.len(), 4);
+        assert_eq!(record.white_setup, vec![Position::new(4, 0)]);
+        assert_eq!(record.first_player(), Player::Black);
+    }
+
+    #[test]
+    fn test_setup_properties_apply_in_order() {
+        let sgf = "(;SZ[5]AB[aa:bb][aa]AW[ee][bb];AE[ab]PL[W];W[cc])";
+        let record = parse_sgf(sgf).expect("parses");
+        assert_eq!(
+            record.black_setup,
+            vec![Position::new(0, 4), Position::new(1, 4)]
+        );
+        assert_eq!(
+            record.white_setup,
+            vec![Position::new(4, 0), Position::new(1, 3)]
+        );
+        assert_eq!(record.first_player(), Player::White);
+
+        let game = Game::<{ nw_for_board(5, 5) }>::from_sgf(sgf).expect("replays");
+        assert_eq!(game.get_piece(&Position::new(0, 3)), None);
+        assert_eq!(
+            game.get_piece(&Position::new(1, 3)),
+            Some(Player::White as i8)
+        );
+
+        let written = game.to_sgf();
+        assert!(written.contains("AB[aa][ba]"));
+        assert!(written.contains("PL[W]"));
+        let read = Game::<{ nw_for_board(5, 5) }>::from_sgf(&written).expect("replays");
+        assert_eq!(read.board(), game.board());
+        assert_eq!(read.move_history(), game.move_history());
+    }
+
+    #[test]
+    fn test_parse_collection() {
+        let games = parse_sgf_collection("(;SZ[9];B[ee])\n(;SZ[13]KM[6.5](;B[aa])(;B[bb]))\n")
+            .expect("parses");
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].moves, vec![(Player::Black, Move::place(4, 4))]);
+        assert_eq!(games[1].width, 13);
+        assert_eq!(games[1].moves, vec![(Player::Black, Move::place(0, 12))]);
+
+        assert!(matches!(
+            parse_sgf_collection("(;SZ[9])(;SZ[9]"),
+            Err(SgfError::Syntax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(matches!(
+            parse_sgf("no tree here"),
+            Err(SgfError::Syntax { .. })
+        ));
+        assert!(matches!(
+            parse_sgf("(;SZ[9];B[ee]"),
+            Err(SgfError::Syntax { .. })
+        ));
+        assert!(matches!(
+            parse_sgf("(;SZ[9];B[ee"),
+            Err(SgfError::Syntax { .. })
+        ));
+        assert!(matches!(
+            parse_sgf("(;GM[3])"),
+            Err(SgfError::UnsupportedGame(_))
+        ));
+        assert!(matches!(
+            parse_sgf("(;SZ[1])"),
+            Err(SgfError::InvalidBoardSize(_))
+        ));
+        assert!(matches!(
+            parse_sgf("(;SZ[9];B[zz])"),
+            Err(SgfError::InvalidProperty { .. })
+        ));
+        assert!(matches!(
+            parse_sgf("(;SZ[9];B[ee];AB[aa])"),
+            Err(SgfError::Unsupported(_))
+        ));
+
+        // Occupied point
+        assert!(matches!(
+            Game9::from_sgf("(;SZ[9];B[ee];W[ee])"),
+            Err(SgfError::Replay(_))
+        ));
+        // Wrong board type
+        assert!(matches!(
+            Game9::from_sgf("(;SZ[19];B[pd])"),
+            Err(SgfError::InvalidBoardSize(_))
+        ));
+    }
+}
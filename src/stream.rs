@@ -0,0 +1,112 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::outcome::GameOutcome;
+use crate::player::Player;
+use crate::position::Position;
+use crate::r#move::Move;
+
+/// What `Game::subscribe_stream` receivers are sent, in the order it
+/// happened.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamEvent {
+    /// `player` played `move_`. `captures` are the stones it removed: the
+    /// opponent's, or the mover's own after a suicide. A swap turns the
+    /// first stone white.
+    Move {
+        player: Player,
+        move_: Move,
+        captures: Vec<Position>,
+    },
+    /// The last move (`move_`, played by `player`) was taken back.
+    Unmake { player: Player, move_: Move },
+    /// A clock update reported with `Game::record_clock`.
+    Clock { player: Player, remaining_ms: u64 },
+    /// The game ended.
+    Result(GameOutcome),
+}
+
+/// The senders of a game's streams. Copies of a game, such as the ones a
+/// search plays out, start without subscribers.
+#[derive(Debug, Default)]
+pub(crate) struct Subscribers {
+    senders: Vec<Sender<StreamEvent>>,
+}
+
+#[hotpath::measure_all]
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Subscribers::default()
+    }
+}
+
+#[hotpath::measure_all]
+impl Subscribers {
+    pub(crate) fn subscribe(&mut self) -> Receiver<StreamEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.senders.push(sender);
+        receiver
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+
+    /// Send `event` to every subscriber, dropping the ones whose receiver
+    /// is gone.
+    pub(crate) fn publish(&mut self, event: StreamEvent) {
+        self.senders.retain(|s| s.send(event.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::game::Game;
+
+    #[test]
+    fn test_stream_reports_moves_captures_clock_and_result() {
+        let mut game = Game::<{ nw_for_board(3, 3) }>::with_options(3, 3, 0.5, 0, 100, true);
+        let stream = game.subscribe_stream();
+        let dropped = game.subscribe_stream();
+        drop(dropped);
+
+        for m in [Move::place(0, 1), Move::place(0, 0), Move::place(1, 0)] {
+            assert!(game.make_move(&m));
+        }
+        game.record_clock(Player::White, 1_500);
+        assert!(game.unmake_move());
+        let mut copy = game.clone();
+        copy.make_move(&Move::place(2, 2));
+        game.resign(Player::White);
+
+        let events: Vec<StreamEvent> = stream.try_iter().collect();
+        assert_eq!(
+            events[2],
+            StreamEvent::Move {
+                player: Player::Black,
+                move_: Move::place(1, 0),
+                captures: vec![Position::new(0, 0)],
+            }
+        );
+        assert_eq!(
+            events[3..],
+            [
+                StreamEvent::Clock {
+                    player: Player::White,
+                    remaining_ms: 1_500,
+                },
+                StreamEvent::Unmake {
+                    player: Player::Black,
+                    move_: Move::place(1, 0),
+                },
+                StreamEvent::Move {
+                    player: Player::White,
+                    move_: Move::Resign,
+                    captures: Vec::new(),
+                },
+                StreamEvent::Result(GameOutcome::resignation(Player::White)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::io::{self, BufRead, Write};
+
+use rand::rngs::StdRng;
+
+use crate::game::Game;
+use crate::player::Player;
+use crate::policy::MovePolicy;
+use crate::r#move::Move;
+use crate::render::BoardRenderer;
+
+/// Alternate between reading moves from `input` and, for the `computer`
+/// color, asking `policy`, until the game ends or the input does. The board
+/// is drawn with `renderer` before each move read. Moves are GTP vertices
+/// (D4), `pass` or `resign`; `undo` takes back the last move and the
+/// computer's reply, and `quit` stops.
+#[hotpath::measure]
+pub fn play_in_terminal<const NW: usize, R: BufRead, W: Write>(
+    game: &mut Game<NW>,
+    computer: Option<Player>,
+    policy: &mut dyn MovePolicy<NW>,
+    rng: &mut StdRng,
+    renderer: &dyn BoardRenderer,
+    input: R,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    while !game.is_over() {
+        let player = game.turn();
+        if computer == Some(player) {
+            let m = policy.choose(game, rng);
+            if !game.make_move(&m) {
+                game.resign(player);
+                break;
+            }
+            writeln!(out, "{} plays {}", player, m.to_gtp(game.height()))?;
+            continue;
+        }
+
+        write!(out, "{}{} to move: ", game.board().render(renderer), player)?;
+        out.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            writeln!(out)?;
+            return Ok(());
+        };
+        let line = line.trim();
+        match line.to_ascii_lowercase().as_str() {
+            "" => {}
+            "quit" | "exit" => return Ok(()),
+            "undo" => {
+                // Take back the computer's reply along with the move.
+                game.unmake_move();
+                if computer.is_some() && game.turn() != player {
+                    game.unmake_move();
+                }
+            }
+            _ => match Move::from_gtp(line, game.width(), game.height()) {
+                Some(m) if game.make_move(&m) => {}
+                Some(_) => writeln!(out, "illegal move: {}", line)?,
+                None => writeln!(out, "unknown move: {}", line)?,
+            },
+        }
+    }
+
+    write!(out, "{}", game.board().render(renderer))?;
+    if let Some(outcome) = game.outcome() {
+        writeln!(out, "Result: {}", outcome.to_result_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::bitboard::nw_for_board;
+use crate::encode::{
+    decode_move_for, encode_game_planes, encode_move, total_actions_for, TOTAL_INPUT_PLANES,
+};
+use crate::game::Game;
+
+/// An invariant that failed in `fuzz_encoding`: the game, the move number
+/// it failed at, and what went wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzFailure {
+    pub game: u64,
+    pub move_number: usize,
+    pub message: String,
+}
+
+/// What `fuzz_encoding` checked and any invariants that failed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FuzzReport {
+    pub games: u64,
+    pub moves_played: u64,
+    pub actions_checked: u64,
+    pub failures: Vec<FuzzFailure>,
+}
+
+#[hotpath::measure_all]
+impl FuzzReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[hotpath::measure_all]
+impl fmt::Display for FuzzReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} games, {} moves played, {} actions checked, {} failures",
+            self.games,
+            self.moves_played,
+            self.actions_checked,
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            writeln!(
+                f,
+                "  game {} move {}: {}",
+                failure.game, failure.move_number, failure.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Play `games` random 9x9 games from `seed` with the default rules and
+/// check the encoder and action map at every position. See
+/// `fuzz_encoding_with`.
+#[hotpath::measure]
+pub fn fuzz_encoding(games: u64, seed: u64) -> FuzzReport {
+    fuzz_encoding_with(|| Game::<{ nw_for_board(9, 9) }>::new(9, 9), games, seed)
+}
+
+/// Play `games` random games from `new_game` and check, at every position:
+/// the planes have the default encoder's shape; every legal move maps to a
+/// distinct action in range that decodes back to it; every legal move can
+/// be played; and unmaking it restores the encoding. A game stops at its
+/// first failure. The same `seed` plays the same games.
+#[hotpath::measure]
+pub fn fuzz_encoding_with<const NW: usize>(
+    new_game: impl Fn() -> Game<NW>,
+    games: u64,
+    seed: u64,
+) -> FuzzReport {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut report = FuzzReport {
+        games,
+        ..FuzzReport::default()
+    };
+    for index in 0..games {
+        let mut game = new_game();
+        while !game.is_over() {
+            let move_number = game.move_count();
+            if let Err(message) = check_position(&mut game, &mut report) {
+                report.failures.push(FuzzFailure {
+                    game: index,
+                    move_number,
+                    message,
+                });
+                break;
+            }
+            let legal_moves = game.legal_moves();
+            let Some(move_) = legal_moves.choose(&mut rng) else {
+                break;
+            };
+            game.make_move(move_);
+            report.moves_played += 1;
+        }
+    }
+    report
+}
+
+/// The invariants of `fuzz_encoding_with` at the current position of `game`,
+/// which is left as it was.
+#[hotpath::measure]
+fn check_position<const NW: usize>(
+    game: &mut Game<NW>,
+    report: &mut FuzzReport,
+) -> Result<(), String> {
+    let (width, height) = (game.width(), game.height());
+    let planes = encode_game_planes(game);
+    let (data, num_planes, rows, cols) = &planes;
+    let expected = (TOTAL_INPUT_PLANES, height as usize, width as usize);
+    if (*num_planes, *rows, *cols) != expected {
+        return Err(format!(
+            "planes have shape {:?}, expected {:?}",
+            (num_planes, rows, cols),
+            expected
+        ));
+    }
+    if data.len() != num_planes * rows * cols {
+        return Err(format!(
+            "{} plane values for shape {:?}",
+            data.len(),
+            expected
+        ));
+    }
+
+    let total_actions = total_actions_for(game);
+    let mut seen = vec![false; total_actions];
+    for move_ in game.legal_moves() {
+        let action = encode_move(&move_, width, height);
+        if action >= total_actions {
+            return Err(format!(
+                "{:?} maps to action {} of {}",
+                move_, action, total_actions
+            ));
+        }
+        if std::mem::replace(&mut seen[action], true) {
+            return Err(format!("two legal moves map to action {}", action));
+        }
+        if decode_move_for(action, game) != Some(move_) {
+            return Err(format!("action {} does not decode to {:?}", action, move_));
+        }
+        if !game.make_move(&move_) {
+            return Err(format!("legal move {:?} could not be played", move_));
+        }
+        game.unmake_move();
+        if encode_game_planes(game) != planes {
+            return Err(format!("unmaking {:?} changed the encoding", move_));
+        }
+        report.actions_checked += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_encoding_passes_and_is_deterministic() {
+        let report = fuzz_encoding(3, 7);
+        assert!(report.is_ok(), "{}", report);
+        assert!(report.moves_played > 0 && report.actions_checked > report.moves_played);
+        assert_eq!(fuzz_encoding(3, 7), report);
+    }
+
+    #[test]
+    fn test_fuzz_encoding_with_custom_games() {
+        // A two-move limit: each game checks the opening and one reply.
+        let report = fuzz_encoding_with(
+            || Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 2, true),
+            4,
+            0,
+        );
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.moves_played, 8);
+        assert!(report.actions_checked >= 4 * 26);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::game::Game;
+use crate::player::Player;
+use crate::policy::MovePolicy;
+use crate::selfplay::{derive_game_seed, play_match_game, GameRecord};
+
+/// Results of a match between two policies, counted from the first policy's
+/// side.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchResult {
+    pub first_wins: u32,
+    pub second_wins: u32,
+    /// Draws and games without a result.
+    pub draws: u32,
+    pub records: Vec<GameRecord>,
+}
+
+#[hotpath::measure_all]
+impl MatchResult {
+    /// Count a finished game. `first_played_black` says which color the first
+    /// policy had in it.
+    pub fn add_game(&mut self, record: GameRecord, first_played_black: bool) {
+        let winner = record.outcome.and_then(|o| o.winner());
+        match winner {
+            Some(player) if (player == Player::Black) == first_played_black => self.first_wins += 1,
+            Some(_) => self.second_wins += 1,
+            None => self.draws += 1,
+        }
+        self.records.push(record);
+    }
+
+    pub fn num_games(&self) -> usize {
+        self.records.len()
+    }
+
+    /// The first policy's score: one point per win, half a point per draw.
+    pub fn first_score(&self) -> f32 {
+        self.first_wins as f32 + 0.5 * self.draws as f32
+    }
+}
+
+/// Play `num_games` games between `first` and `second`, each starting from
+/// `new_game()`. The first policy plays Black in even-numbered games and White
+/// in odd-numbered ones; game seeds come from `derive_game_seed`.
+///
+/// Games are played one after another, since policies may keep state such as
+/// an engine process or a Python object.
+#[hotpath::measure]
+pub fn run_match<const NW: usize, F, A, B>(
+    new_game: F,
+    first: &mut A,
+    second: &mut B,
+    num_games: u64,
+    base_seed: u64,
+) -> MatchResult
+where
+    F: Fn() -> Game<NW>,
+    A: MovePolicy<NW>,
+    B: MovePolicy<NW>,
+{
+    let mut result = MatchResult::default();
+    for index in 0..num_games {
+        let seed = derive_game_seed(base_seed, index);
+        let first_played_black = index % 2 == 0;
+        let record = if first_played_black {
+            play_match_game(new_game(), first, second, index, seed)
+        } else {
+            play_match_game(new_game(), second, first, index, seed)
+        };
+        result.add_game(record, first_played_black);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::policy::{ExternalPolicy, HeuristicPolicy, UniformPolicy};
+    use crate::r#move::Move;
+
+    fn new_game() -> Game<{ nw_for_board(5, 5) }> {
+        Game::with_options(5, 5, 0.5, 0, 100, true)
+    }
+
+    #[test]
+    fn test_match_alternates_colors_and_counts_results() {
+        let result = run_match(new_game, &mut HeuristicPolicy, &mut UniformPolicy, 20, 9);
+
+        assert_eq!(result.num_games(), 20);
+        assert_eq!(result.first_wins + result.second_wins + result.draws, 20);
+        assert!(result.first_wins > result.second_wins);
+        for (i, record) in result.records.iter().enumerate() {
+            assert_eq!(record.index, i as u64);
+        }
+    }
+
+    #[test]
+    fn test_resigning_policy_loses_with_either_color() {
+        let mut resign = ExternalPolicy::new(|_: &Game<{ nw_for_board(5, 5) }>| Move::resign());
+        let result = run_match(new_game, &mut UniformPolicy, &mut resign, 4, 0);
+        assert_eq!(result.first_wins, 4);
+        assert_eq!(result.first_score(), 4.0);
+    }
+}
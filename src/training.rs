@@ -0,0 +1,351 @@
+use std::fmt;
+use std::io::Write;
+
+use crate::board::Board;
+use crate::encode::{encode_game_planes_with, encode_move, total_actions_for, EncoderConfig};
+use crate::game::Game;
+use crate::player::Player;
+use crate::replay::{ReplayError, ReplayIssue};
+use crate::rollout::{estimate_dead_stones, RolloutConfig};
+use crate::selfplay::GameRecord;
+
+/// Board size of Leela Zero networks.
+const LEELA_ZERO_SIZE: u8 = 19;
+
+/// Past positions in a Leela Zero training sample, the current one included.
+const LEELA_ZERO_HISTORY: usize = 8;
+
+/// Errors from writing training data.
+#[derive(Debug)]
+pub enum TrainingDataError {
+    Io(std::io::Error),
+    /// The format cannot represent this game, e.g. the board size.
+    Unsupported(String),
+    /// A move of the record could not be played from the start position.
+    Replay(ReplayError),
+}
+
+impl From<std::io::Error> for TrainingDataError {
+    fn from(e: std::io::Error) -> Self {
+        TrainingDataError::Io(e)
+    }
+}
+
+impl fmt::Display for TrainingDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrainingDataError::Io(e) => write!(f, "training data I/O error: {}", e),
+            TrainingDataError::Unsupported(msg) => {
+                write!(f, "unsupported by the training format: {}", msg)
+            }
+            TrainingDataError::Replay(e) => write!(f, "cannot replay record: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TrainingDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TrainingDataError::Io(e) => Some(e),
+            TrainingDataError::Replay(e) => Some(e),
+            TrainingDataError::Unsupported(_) => None,
+        }
+    }
+}
+
+/// One position of a game with its training targets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingSample {
+    /// Input planes as `encode_game_planes_with` lays them out, plane-major
+    /// then row-major.
+    pub planes: Vec<f32>,
+    pub num_planes: usize,
+    pub height: u8,
+    pub width: u8,
+    /// Probability of each action, laid out like `encode_move`.
+    pub policy: Vec<f32>,
+    /// The result for the player to move: 1.0 for a win, -1.0 for a loss
+    /// and 0.0 when the game had no winner.
+    pub value: f32,
+    /// Moves played from this position to the end of the game, an auxiliary
+    /// target for learning game length (see `GameRecord::moves_remaining`).
+    pub moves_remaining: u32,
+}
+
+/// The positions of a self-play game as training samples, replaying
+/// `record` from `start` and encoding each position with `config`. The
+/// policy target is the move played, since the record holds no search
+/// statistics. A resignation ends the samples.
+#[hotpath::measure]
+pub fn training_samples<const NW: usize>(
+    start: &Game<NW>,
+    record: &GameRecord,
+    config: &EncoderConfig,
+) -> Result<Vec<TrainingSample>, TrainingDataError> {
+    let (width, height) = (start.width(), start.height());
+    let winner = record.outcome.and_then(|outcome| outcome.winner());
+    let moves_remaining = record.moves_remaining();
+    let mut game = start.clone();
+    let mut samples = Vec::with_capacity(record.moves.len());
+    for (index, move_) in record.moves.iter().enumerate() {
+        if move_.is_resign() {
+            break;
+        }
+        let player = game.turn();
+        let (planes, num_planes, _, _) = encode_game_planes_with(&game, config);
+        let mut policy = vec![0.0; total_actions_for(&game)];
+        if let Some(p) = policy.get_mut(encode_move(move_, width, height)) {
+            *p = 1.0;
+        }
+        if !game.make_move(move_) {
+            return Err(TrainingDataError::Replay(ReplayError {
+                index,
+                player,
+                move_: *move_,
+                issue: ReplayIssue::Illegal,
+            }));
+        }
+        samples.push(TrainingSample {
+            planes,
+            num_planes,
+            height,
+            width,
+            policy,
+            value: winner.map_or(0.0, |w| if w == player { 1.0 } else { -1.0 }),
+            moves_remaining: moves_remaining[index],
+        });
+    }
+    Ok(samples)
+}
+
+/// Ownership targets from the final position of a finished game: 1.0 for
+/// points Black owns, -1.0 for White's and 0.0 for neutral ones, row-major
+/// like one input plane of `encode_game_planes_with`. Stones marked dead
+/// count for the opponent; if none are marked, the groups random playouts
+/// judge dead are removed first (see `rollout::estimate_dead_stones`).
+///
+/// The targets are from Black's side; negate them for samples with White
+/// to move, whose planes put White's stones first (see
+/// `ownership_target_for`).
+#[hotpath::measure]
+pub fn ownership_target<const NW: usize>(game: &Game<NW>) -> Vec<f32> {
+    if game.dead_stones().is_nonzero() {
+        return game.ownership_map_absolute();
+    }
+    let mut resolved = game.clone();
+    resolved.set_dead_stones(estimate_dead_stones(game, &RolloutConfig::default()));
+    resolved.ownership_map_absolute()
+}
+
+/// `ownership_target` from `perspective`'s side: 1.0 for the points it owns.
+#[hotpath::measure]
+pub fn ownership_target_for<const NW: usize>(game: &Game<NW>, perspective: Player) -> Vec<f32> {
+    let mut target = ownership_target(game);
+    if perspective == Player::White {
+        for v in &mut target {
+            *v = -*v;
+        }
+    }
+    target
+}
+
+/// One input plane as Leela Zero writes it: the first 360 points four to a
+/// hex digit, then the last point as `0` or `1`.
+#[hotpath::measure]
+fn write_plane<const NW: usize, W: Write>(
+    out: &mut W,
+    board: Option<&Board<NW>>,
+    player: Player,
+) -> std::io::Result<()> {
+    let stones = board.map(|b| b.stones_for(player));
+    let bit = |idx: usize| stones.is_some_and(|s| s.get(idx)) as u32;
+    let points = LEELA_ZERO_SIZE as usize * LEELA_ZERO_SIZE as usize;
+    let mut line = String::with_capacity(points / 4 + 2);
+    for idx in (0..points - 1).step_by(4) {
+        let nibble = bit(idx) << 3 | bit(idx + 1) << 2 | bit(idx + 2) << 1 | bit(idx + 3);
+        line.push(char::from_digit(nibble, 16).expect("a nibble is a hex digit"));
+    }
+    line.push(char::from_digit(bit(points - 1), 10).expect("a bit is a digit"));
+    writeln!(out, "{}", line)
+}
+
+/// Write the positions of a self-play game in the Leela Zero training format
+/// (version 1 text, as inside its `.gz` chunks), replaying `record` from
+/// `start`. Returns the number of positions written.
+///
+/// Each position is 19 lines: 8 planes of the mover's stones and 8 of the
+/// opponent's, current position first and empty before the start of the
+/// game; the side to move (`0` for Black); the policy target over the 361
+/// points and pass; and `1`, `-1` or `0` as the mover won, lost or the game
+/// had no winner. The record holds no search statistics, so the policy
+/// target is the move played. A resignation ends the positions.
+///
+/// Leela Zero only plays 19x19 and assumes 7.5 komi; other board sizes and
+/// games with the swap rule are rejected.
+#[hotpath::measure]
+pub fn write_leela_zero<const NW: usize, W: Write>(
+    start: &Game<NW>,
+    record: &GameRecord,
+    out: &mut W,
+) -> Result<usize, TrainingDataError> {
+    let (width, height) = (start.width(), start.height());
+    if (width, height) != (LEELA_ZERO_SIZE, LEELA_ZERO_SIZE) {
+        return Err(TrainingDataError::Unsupported(format!(
+            "{}x{} board",
+            width, height
+        )));
+    }
+    if start.swap_rule() {
+        return Err(TrainingDataError::Unsupported("swap rule".to_string()));
+    }
+
+    let winner = record.outcome.and_then(|outcome| outcome.winner());
+    let mut game = start.clone();
+    let mut positions = 0;
+    for (index, move_) in record.moves.iter().enumerate() {
+        if move_.is_resign() {
+            break;
+        }
+        let player = game.turn();
+        let history: Vec<Board<NW>> = game.board_history().take(LEELA_ZERO_HISTORY).collect();
+        if !game.make_move(move_) {
+            return Err(TrainingDataError::Replay(ReplayError {
+                index,
+                player,
+                move_: *move_,
+                issue: ReplayIssue::Illegal,
+            }));
+        }
+
+        for side in [player, player.opposite()] {
+            for t in 0..LEELA_ZERO_HISTORY {
+                write_plane(out, history.get(t), side)?;
+            }
+        }
+        writeln!(out, "{}", if player == Player::Black { 0 } else { 1 })?;
+
+        let played = encode_move(move_, width, height);
+        let policy: Vec<&str> = (0..=width as usize * height as usize)
+            .map(|action| if action == played { "1" } else { "0" })
+            .collect();
+        writeln!(out, "{}", policy.join(" "))?;
+
+        let result = match winner {
+            Some(w) if w == player => 1,
+            Some(_) => -1,
+            None => 0,
+        };
+        writeln!(out, "{}", result)?;
+        positions += 1;
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+    use crate::outcome::GameOutcome;
+    use crate::position::Position;
+    use crate::r#move::Move;
+
+    type Game19 = Game<{ nw_for_board(19, 19) }>;
+
+    #[test]
+    fn test_training_samples() {
+        let record = GameRecord {
+            index: 0,
+            seed: 0,
+            moves: vec![Move::place(3, 3), Move::place(5, 5), Move::resign()],
+            outcome: Some(GameOutcome::resignation(Player::Black)),
+        };
+        let game = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let samples = training_samples(&game, &record, &EncoderConfig::default()).expect("replays");
+        assert_eq!(samples.len(), 2);
+
+        let black = &samples[0];
+        assert_eq!((black.num_planes, black.height, black.width), (17, 9, 9));
+        assert_eq!(black.planes.len(), 17 * 81);
+        assert_eq!(black.policy.len(), 82);
+        assert_eq!(black.policy[3 * 9 + 3], 1.0);
+        assert_eq!(black.value, -1.0);
+        assert_eq!(samples[1].policy[5 * 9 + 5], 1.0);
+        assert_eq!(samples[1].value, 1.0);
+
+        assert_eq!(record.moves_remaining(), vec![2, 1, 0]);
+        assert_eq!(black.moves_remaining, 2);
+        assert_eq!(samples[1].moves_remaining, 1);
+    }
+
+    #[test]
+    fn test_ownership_target_resolves_dead_stones() {
+        // Black walls off columns 0-1 and White owns columns 3-4, with a
+        // white invader at A3 inside Black's area.
+        let walled = |marking: bool| {
+            let mut game = Game::<{ nw_for_board(5, 5) }>::with_options(5, 5, 0.5, 0, 1000, false);
+            game.set_dead_stone_marking(marking);
+            for row in 0..5 {
+                assert!(game.make_move(&Move::place(2, row)));
+                assert!(game.make_move(&Move::place(3, row)));
+            }
+            for m in [Move::pass(), Move::place(0, 2), Move::pass(), Move::pass()] {
+                assert!(game.make_move(&m));
+            }
+            game
+        };
+        let invader = 2 * 5;
+
+        let target = ownership_target(&walled(false));
+        assert_eq!(target.len(), 25);
+        assert_eq!(target[invader], 1.0);
+        assert_eq!(target[invader + 2], 1.0);
+        assert_eq!(target[invader + 4], -1.0);
+        assert!(target.iter().all(|&v| v != 0.0));
+        assert_eq!(
+            ownership_target_for(&walled(false), Player::White)[invader],
+            -1.0
+        );
+
+        // Marked stones are taken as given: with Black's wall marked dead,
+        // White owns the whole board.
+        let mut marked = walled(true);
+        assert!(marked.mark_dead(&[Position::new(2, 0)]));
+        assert!(ownership_target(&marked).iter().all(|&v| v == -1.0));
+    }
+
+    #[test]
+    fn test_leela_zero_samples() {
+        let record = GameRecord {
+            index: 0,
+            seed: 0,
+            moves: vec![Move::place(3, 3), Move::place(15, 15), Move::resign()],
+            outcome: Some(GameOutcome::resignation(Player::Black)),
+        };
+        let mut out = Vec::new();
+        let written = write_leela_zero(&Game19::new(19, 19), &record, &mut out).expect("writes");
+        assert_eq!(written, 2);
+
+        let text = String::from_utf8(out).expect("utf-8");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2 * 19);
+        assert_eq!(lines[0], "0".repeat(91));
+
+        // White to move: Black's D4 (point 60) is on the first opponent plane.
+        let white = &lines[19..];
+        assert_eq!(white[0], "0".repeat(91));
+        assert_eq!(white[8].find('8'), Some(15));
+        assert_eq!(white[8].matches('0').count(), 90);
+        assert_eq!(white[16], "1");
+        let policy: Vec<&str> = white[17].split(' ').collect();
+        assert_eq!(policy.len(), 362);
+        assert_eq!(policy.iter().position(|&p| p == "1"), Some(15 * 19 + 15));
+        assert_eq!(white[18], "1");
+        assert_eq!(lines[18], "-1");
+
+        let nine = Game::<{ nw_for_board(9, 9) }>::new(9, 9);
+        assert!(matches!(
+            write_leela_zero(&nine, &record, &mut Vec::new()),
+            Err(TrainingDataError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt;
+
+use crate::bitboard::{nw_for_board, Bitboard, BoardGeometry};
+use crate::game::{Game, GamePhase};
+use crate::handicap::max_fixed_handicap;
+use crate::outcome::GameOutcome;
+use crate::player::Player;
+use crate::r#move::Move;
+use crate::rules::Ruleset;
+
+/// The rules a transcript is checked against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TranscriptRules {
+    pub ruleset: Ruleset,
+    pub komi: f32,
+    pub superko: bool,
+    pub allow_suicide: bool,
+    /// Fixed handicap stones for Black (0 for an even game).
+    pub handicap: u8,
+    pub swap_rule: bool,
+}
+
+#[hotpath::measure_all]
+impl Default for TranscriptRules {
+    fn default() -> Self {
+        TranscriptRules {
+            ruleset: Ruleset::default(),
+            komi: crate::game::DEFAULT_KOMI,
+            superko: true,
+            allow_suicide: false,
+            handicap: 0,
+            swap_rule: false,
+        }
+    }
+}
+
+/// Why a move of a transcript is illegal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The game had already ended.
+    GameOver,
+    /// Both players had passed and only resignation was possible.
+    ScoringPhase,
+    OffBoard,
+    Occupied,
+    /// Immediate recapture of a ko.
+    Ko,
+    /// The move repeats an earlier position.
+    Superko,
+    Suicide,
+    /// Swap outside the swap rule, or not directly after Black's first stone.
+    Swap,
+}
+
+#[hotpath::measure_all]
+impl Violation {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Violation::GameOver => "the game is over",
+            Violation::ScoringPhase => "the game is in the scoring phase",
+            Violation::OffBoard => "the point is off the board",
+            Violation::Occupied => "the point is occupied",
+            Violation::Ko => "the move retakes a ko",
+            Violation::Superko => "the move repeats an earlier position",
+            Violation::Suicide => "the move is suicide",
+            Violation::Swap => "swap is not allowed here",
+        }
+    }
+}
+
+/// Errors from `validate_transcript`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptError {
+    /// The board size or rules cannot be played.
+    InvalidSetup(String),
+    /// The first illegal move, with the position it was played in (as a
+    /// position string, see `Game::to_position_string`).
+    IllegalMove {
+        index: usize,
+        player: Player,
+        move_: Move,
+        violation: Violation,
+        position: String,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::InvalidSetup(msg) => write!(f, "invalid transcript setup: {}", msg),
+            TranscriptError::IllegalMove {
+                index,
+                player,
+                move_,
+                violation,
+                position,
+            } => write!(
+                f,
+                "move {} ({} {}) is illegal: {} (position: {})",
+                index + 1,
+                player,
+                move_,
+                violation.description(),
+                position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+/// Summary of a legal transcript.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranscriptReport {
+    pub moves: usize,
+    /// The outcome, if the transcript ends the game.
+    pub outcome: Option<GameOutcome>,
+    /// The final position as a position string.
+    pub final_position: String,
+}
+
+/// Why `move_` cannot be played in `game`. Only called for illegal moves.
+#[hotpath::measure]
+fn violation<const NW: usize>(game: &Game<NW>, move_: &Move) -> Violation {
+    match game.phase() {
+        GamePhase::Over => return Violation::GameOver,
+        GamePhase::Scoring => return Violation::ScoringPhase,
+        GamePhase::Playing => {}
+    }
+    let pos = match move_ {
+        Move::Place { .. } => move_.position().expect("a placement has a position"),
+        Move::Swap => return Violation::Swap,
+        // Passes and resignations are always legal with no minimum before
+        // passing.
+        Move::Pass | Move::Resign => unreachable!("passes and resignations are legal"),
+    };
+    if !pos.is_valid(game.width(), game.height()) {
+        return Violation::OffBoard;
+    }
+    if game.get_piece(&pos).is_some() {
+        return Violation::Occupied;
+    }
+    if game.ko_point() == Some(pos) {
+        return Violation::Ko;
+    }
+    // A placement whose group keeps a liberty after its captures was not
+    // suicide, so it broke superko.
+    let geo = BoardGeometry::<NW>::cached(game.width(), game.height());
+    let idx = pos.to_index(game.width());
+    let player = game.turn();
+    let after = game.simulate_placement(idx, player);
+    let group = geo.flood_fill(Bitboard::single(idx), after.stones_for(player));
+    if (geo.neighbors(&group) & after.empty_squares(geo.board_mask)).is_nonzero() {
+        Violation::Superko
+    } else {
+        Violation::Suicide
+    }
+}
+
+/// Check a complete transcript of alternating moves, Black first (White
+/// first in handicap games), on a `width` x `height` board. Returns the
+/// first illegal move with its position. `NW` must be
+/// `nw_for_board(width, height)`.
+#[hotpath::measure]
+pub fn validate_transcript<const NW: usize>(
+    width: u8,
+    height: u8,
+    rules: &TranscriptRules,
+    moves: &[Move],
+) -> Result<TranscriptReport, TranscriptError> {
+    if !(2..=32).contains(&width) || !(2..=32).contains(&height) {
+        return Err(TranscriptError::InvalidSetup(format!(
+            "board size {}x{}",
+            width, height
+        )));
+    }
+    if nw_for_board(width, height) != NW {
+        return Err(TranscriptError::InvalidSetup(format!(
+            "{}x{} does not fit this game type",
+            width, height
+        )));
+    }
+    let max_handicap = max_fixed_handicap(width, height);
+    if rules.handicap != 0 && !(2..=max_handicap).contains(&rules.handicap) {
+        return Err(TranscriptError::InvalidSetup(format!(
+            "handicap {} on a {}x{} board",
+            rules.handicap, width, height
+        )));
+    }
+
+    let mut game = Game::<NW>::with_options(width, height, rules.komi, 0, u16::MAX, rules.superko);
+    game.set_ruleset(rules.ruleset);
+    game.set_allow_suicide(rules.allow_suicide);
+    game.set_swap_rule(rules.swap_rule);
+    if rules.handicap > 0 {
+        game.place_handicap(rules.handicap);
+    }
+
+    for (index, move_) in moves.iter().enumerate() {
+        if !game.make_move(move_) {
+            return Err(TranscriptError::IllegalMove {
+                index,
+                player: game.turn(),
+                move_: *move_,
+                violation: violation(&game, move_),
+                position: game.to_position_string(),
+            });
+        }
+    }
+
+    Ok(TranscriptReport {
+        moves: moves.len(),
+        outcome: game.outcome(),
+        final_position: game.to_position_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NW9: usize = nw_for_board(9, 9);
+
+    fn illegal_at(moves: &[Move], rules: &TranscriptRules) -> (usize, Violation) {
+        match validate_transcript::<NW9>(9, 9, rules, moves) {
+            Err(TranscriptError::IllegalMove {
+                index, violation, ..
+            }) => (index, violation),
+            other => panic!("expected an illegal move, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_legal_transcript() {
+        let moves = [
+            Move::place(2, 2),
+            Move::place(6, 6),
+            Move::pass(),
+            Move::pass(),
+        ];
+        let report =
+            validate_transcript::<NW9>(9, 9, &TranscriptRules::default(), &moves).expect("legal");
+        assert_eq!(report.moves, 4);
+        assert!(report.outcome.is_some());
+        assert!(report.final_position.starts_with("9/9/6W2/"));
+    }
+
+    #[test]
+    fn test_reports_first_violation() {
+        let rules = TranscriptRules::default();
+
+        assert_eq!(
+            illegal_at(&[Move::place(2, 2), Move::place(2, 2)], &rules),
+            (1, Violation::Occupied)
+        );
+        assert_eq!(
+            illegal_at(&[Move::place(9, 0)], &rules),
+            (0, Violation::OffBoard)
+        );
+        assert_eq!(illegal_at(&[Move::swap()], &rules), (0, Violation::Swap));
+        assert_eq!(
+            illegal_at(&[Move::pass(), Move::pass(), Move::place(0, 0)], &rules),
+            (2, Violation::GameOver)
+        );
+
+        // Black takes the ko at B2, White retakes at once.
+        let ko = [
+            Move::place(1, 0),
+            Move::place(2, 0),
+            Move::place(0, 1),
+            Move::place(3, 1),
+            Move::place(1, 2),
+            Move::place(2, 2),
+            Move::pass(),
+            Move::place(1, 1),
+            Move::place(2, 1),
+            Move::place(1, 1),
+        ];
+        assert_eq!(illegal_at(&ko, &rules), (9, Violation::Ko));
+
+        // White fills the last liberty of its own stone in the corner.
+        let suicide = [
+            Move::place(1, 0),
+            Move::pass(),
+            Move::place(0, 1),
+            Move::place(0, 0),
+        ];
+        assert_eq!(illegal_at(&suicide, &rules), (3, Violation::Suicide));
+        let lenient = TranscriptRules {
+            allow_suicide: true,
+            ..rules
+        };
+        assert!(validate_transcript::<NW9>(9, 9, &lenient, &suicide).is_ok());
+
+        let error = validate_transcript::<NW9>(9, 9, &rules, &suicide).expect_err("illegal");
+        assert!(error.to_string().contains("move 4 (White"));
+    }
+
+    #[test]
+    fn test_rejects_bad_setup() {
+        let rules = TranscriptRules {
+            handicap: 1,
+            ..TranscriptRules::default()
+        };
+        assert!(matches!(
+            validate_transcript::<NW9>(9, 9, &rules, &[]),
+            Err(TranscriptError::InvalidSetup(_))
+        ));
+        assert!(matches!(
+            validate_transcript::<NW9>(19, 19, &TranscriptRules::default(), &[]),
+            Err(TranscriptError::InvalidSetup(_))
+        ));
+    }
+}
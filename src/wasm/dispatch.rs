@@ -0,0 +1 @@
+pub(super) use crate::dispatch::*;
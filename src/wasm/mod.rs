@@ -0,0 +1,6 @@
+#[macro_use]
+mod dispatch;
+mod wasm_board;
+mod wasm_encode;
+mod wasm_game;
+mod wasm_move;
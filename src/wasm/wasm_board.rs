@@ -0,0 +1,82 @@
+use wasm_bindgen::prelude::*;
+
+use super::dispatch::*;
+use crate::board::BoardDiff;
+use crate::player::Player;
+use crate::position::Position;
+
+#[wasm_bindgen(js_name = Board)]
+#[derive(Clone)]
+pub struct WasmBoard {
+    inner: BoardInner,
+}
+
+#[hotpath::measure_all]
+impl WasmBoard {
+    pub(super) fn from_inner(inner: BoardInner) -> Self {
+        WasmBoard { inner }
+    }
+}
+
+#[hotpath::measure_all]
+#[wasm_bindgen(js_class = Board)]
+impl WasmBoard {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u8, height: u8) -> Result<WasmBoard, JsError> {
+        if !(2..=32).contains(&width) || !(2..=32).contains(&height) {
+            return Err(JsError::new(
+                "Board width and height must be between 2 and 32",
+            ));
+        }
+        Ok(WasmBoard {
+            inner: make_board_inner(width, height),
+        })
+    }
+
+    pub fn width(&self) -> u8 {
+        dispatch_board!(&self.inner, b => b.width())
+    }
+
+    pub fn height(&self) -> u8 {
+        dispatch_board!(&self.inner, b => b.height())
+    }
+
+    /// 1 for Black, -1 for White, undefined for an empty point.
+    #[wasm_bindgen(js_name = getPiece)]
+    pub fn get_piece(&self, col: u8, row: u8) -> Option<i8> {
+        let pos = Position::new(col, row);
+        dispatch_board!(&self.inner, b => b.get_piece(&pos).map(|p| p as i8))
+    }
+
+    #[wasm_bindgen(js_name = setPiece)]
+    pub fn set_piece(&mut self, col: u8, row: u8, piece: Option<i8>) -> Result<(), JsError> {
+        let pos = Position::new(col, row);
+        let player = match piece {
+            Some(p) => {
+                Some(Player::from_int(p).ok_or_else(|| JsError::new("Invalid player value"))?)
+            }
+            None => None,
+        };
+        dispatch_board_mut!(&mut self.inner, b => b.set_piece(&pos, player));
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        dispatch_board_mut!(&mut self.inner, b => b.clear())
+    }
+
+    /// Make the changes of a `BoardDiff` JSON string, as sent by
+    /// `Game.lastMoveDiff`. Throws, changing nothing, if it cannot be read
+    /// or is for another board size.
+    #[wasm_bindgen(js_name = applyDiff)]
+    pub fn apply_diff(&mut self, json: &str) -> Result<(), JsError> {
+        let diff = BoardDiff::from_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        dispatch_board_mut!(&mut self.inner, b => diff.apply(b))
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        dispatch_board!(&self.inner, b => b.to_string())
+    }
+}
@@ -0,0 +1,45 @@
+use wasm_bindgen::prelude::*;
+
+use super::wasm_move::WasmMove;
+use crate::encode;
+
+#[hotpath::measure]
+fn check_size(width: u8, height: u8) -> Result<(), JsError> {
+    if (2..=32).contains(&width) && (2..=32).contains(&height) {
+        Ok(())
+    } else {
+        Err(JsError::new(&format!(
+            "Board size must be between 2 and 32, got {}x{}",
+            width, height
+        )))
+    }
+}
+
+/// Size of the action space for a board: every point plus pass, and swap
+/// when `swap` is set.
+#[wasm_bindgen(js_name = totalActions)]
+#[hotpath::measure]
+pub fn total_actions(width: u8, height: u8, swap: bool) -> Result<usize, JsError> {
+    check_size(width, height)?;
+    Ok(if swap {
+        encode::total_actions_with_swap(width, height)
+    } else {
+        encode::total_actions(width, height)
+    })
+}
+
+/// The action index of `move_` on a `width` x `height` board.
+#[wasm_bindgen(js_name = encodeMove)]
+#[hotpath::measure]
+pub fn encode_move(move_: &WasmMove, width: u8, height: u8) -> Result<usize, JsError> {
+    check_size(width, height)?;
+    Ok(encode::encode_move(move_.as_inner(), width, height))
+}
+
+/// The move for action index `action`, or undefined if it is out of range.
+#[wasm_bindgen(js_name = decodeMove)]
+#[hotpath::measure]
+pub fn decode_move(action: usize, width: u8, height: u8) -> Result<Option<WasmMove>, JsError> {
+    check_size(width, height)?;
+    Ok(encode::decode_move(action, width, height).map(WasmMove::from_move))
+}
@@ -0,0 +1,221 @@
+use wasm_bindgen::prelude::*;
+
+use super::dispatch::*;
+use super::wasm_board::WasmBoard;
+use super::wasm_move::WasmMove;
+use crate::encode;
+use crate::game::validate_move_limits;
+use crate::position::Position;
+use crate::position_string;
+
+#[wasm_bindgen(js_name = Game)]
+#[derive(Clone)]
+pub struct WasmGame {
+    inner: GameInner,
+}
+
+#[hotpath::measure]
+fn check_size(width: u8, height: u8) -> Result<(), JsError> {
+    if (2..=32).contains(&width) && (2..=32).contains(&height) {
+        Ok(())
+    } else {
+        Err(JsError::new(
+            "Board width and height must be between 2 and 32",
+        ))
+    }
+}
+
+#[hotpath::measure_all]
+#[wasm_bindgen(js_class = Game)]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u8, height: u8) -> Result<WasmGame, JsError> {
+        check_size(width, height)?;
+        Ok(WasmGame {
+            inner: make_game_inner(width, height),
+        })
+    }
+
+    #[wasm_bindgen(js_name = withOptions)]
+    pub fn with_options(
+        width: u8,
+        height: u8,
+        komi: f32,
+        min_moves_before_pass_possible: u16,
+        max_moves: u16,
+        superko: bool,
+    ) -> Result<WasmGame, JsError> {
+        check_size(width, height)?;
+        validate_move_limits(min_moves_before_pass_possible, max_moves)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmGame {
+            inner: make_game_inner_with_options(
+                width,
+                height,
+                komi,
+                min_moves_before_pass_possible,
+                max_moves,
+                superko,
+            ),
+        })
+    }
+
+    /// Start a game with the default rules from a position string.
+    #[wasm_bindgen(js_name = fromPositionString)]
+    pub fn from_position_string(text: &str) -> Result<WasmGame, JsError> {
+        let record = position_string::parse_position_string(text)?;
+        let mut inner = make_game_inner(record.width, record.height);
+        dispatch_game_mut!(&mut inner, g => record.set_up(g));
+        Ok(WasmGame { inner })
+    }
+
+    #[wasm_bindgen(js_name = toPositionString)]
+    pub fn to_position_string(&self) -> String {
+        dispatch_game!(&self.inner, g => g.to_position_string())
+    }
+
+    pub fn width(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.width())
+    }
+
+    pub fn height(&self) -> u8 {
+        dispatch_game!(&self.inner, g => g.height())
+    }
+
+    pub fn komi(&self) -> f32 {
+        dispatch_game!(&self.inner, g => g.komi())
+    }
+
+    /// The player to move: 1 for Black, -1 for White.
+    pub fn turn(&self) -> i8 {
+        dispatch_game!(&self.inner, g => g.turn() as i8)
+    }
+
+    #[wasm_bindgen(js_name = moveCount)]
+    pub fn move_count(&self) -> usize {
+        dispatch_game!(&self.inner, g => g.move_count())
+    }
+
+    /// Moves left before the move limit ends the game, or 0 once it is over.
+    #[wasm_bindgen(js_name = movesRemaining)]
+    pub fn moves_remaining(&self) -> u16 {
+        dispatch_game!(&self.inner, g => g.moves_remaining())
+    }
+
+    pub fn board(&self) -> WasmBoard {
+        WasmBoard::from_inner(game_to_board_inner!(&self.inner))
+    }
+
+    #[wasm_bindgen(js_name = getPiece)]
+    pub fn get_piece(&self, col: u8, row: u8) -> Option<i8> {
+        let pos = Position::new(col, row);
+        dispatch_game!(&self.inner, g => g.board().get_piece(&pos).map(|p| p as i8))
+    }
+
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<WasmMove> {
+        dispatch_game!(&self.inner, g => g.legal_moves())
+            .into_iter()
+            .map(WasmMove::from_move)
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = isLegalMove)]
+    pub fn is_legal_move(&self, move_: &WasmMove) -> bool {
+        dispatch_game!(&self.inner, g => g.is_legal_move(move_.as_inner()))
+    }
+
+    /// Play `move_`. Returns false, leaving the game unchanged, if it is
+    /// illegal.
+    #[wasm_bindgen(js_name = makeMove)]
+    pub fn make_move(&mut self, move_: &WasmMove) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.make_move(move_.as_inner()))
+    }
+
+    /// The board changes of the last move as `BoardDiff` JSON, for sending
+    /// to spectators instead of the whole board; apply it on their side
+    /// with `Board.applyDiff`. Undefined before the first move.
+    #[wasm_bindgen(js_name = lastMoveDiff)]
+    pub fn last_move_diff(&self) -> Option<String> {
+        dispatch_game!(&self.inner, g => g.last_move_diff()).map(|d| d.to_json())
+    }
+
+    #[wasm_bindgen(js_name = unmakeMove)]
+    pub fn unmake_move(&mut self) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => g.unmake_move())
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        dispatch_game!(&self.inner, g => g.is_over())
+    }
+
+    /// Score as [black, white], with komi added to white.
+    pub fn score(&self) -> Vec<f32> {
+        let (black, white) = dispatch_game!(&self.inner, g => g.score());
+        vec![black, white]
+    }
+
+    /// The winner once the game is over (1 for Black, -1 for White), or
+    /// undefined for a draw or a game still in progress.
+    pub fn winner(&self) -> Option<i8> {
+        dispatch_game!(&self.inner, g => g.outcome())
+            .and_then(|o| o.winner())
+            .map(|p| p as i8)
+    }
+
+    /// The result as in SGF, such as `B+3.5` or `W+R`, once the game is over.
+    #[wasm_bindgen(js_name = resultString)]
+    pub fn result_string(&self) -> Option<String> {
+        dispatch_game!(&self.inner, g => g.outcome()).map(|o| o.to_result_string())
+    }
+
+    // ---------------------------------------------------------------------
+    // Encoding/decoding
+    // ---------------------------------------------------------------------
+
+    /// The input planes of `encode_game_planes`, plane-major then row-major:
+    /// `numPlanes()` planes of `height() * width()` values.
+    #[wasm_bindgen(js_name = encodePlanes)]
+    pub fn encode_planes(&self) -> Vec<f32> {
+        dispatch_game!(&self.inner, g => encode::encode_game_planes(g)).0
+    }
+
+    #[wasm_bindgen(js_name = numPlanes)]
+    pub fn num_planes(&self) -> usize {
+        encode::TOTAL_INPUT_PLANES
+    }
+
+    #[wasm_bindgen(js_name = totalActions)]
+    pub fn total_actions(&self) -> usize {
+        dispatch_game!(&self.inner, g => encode::total_actions_for(g))
+    }
+
+    #[wasm_bindgen(js_name = legalActionIndices)]
+    pub fn legal_action_indices(&self) -> Vec<usize> {
+        dispatch_game!(&self.inner, g => {
+            g.legal_moves()
+                .iter()
+                .map(|m| encode::encode_move(m, g.width(), g.height()))
+                .collect()
+        })
+    }
+
+    /// Play the move of action index `action`. Returns false if the index is
+    /// out of range or the move is illegal.
+    #[wasm_bindgen(js_name = applyAction)]
+    pub fn apply_action(&mut self, action: usize) -> bool {
+        dispatch_game_mut!(&mut self.inner, g => {
+            match encode::decode_move_for(action, g) {
+                Some(move_) => g.make_move(&move_),
+                None => false,
+            }
+        })
+    }
+
+    #[wasm_bindgen(js_name = decodeAction)]
+    pub fn decode_action(&self, action: usize) -> Option<WasmMove> {
+        dispatch_game!(&self.inner, g => encode::decode_move_for(action, g))
+            .map(WasmMove::from_move)
+    }
+}
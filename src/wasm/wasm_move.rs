@@ -0,0 +1,84 @@
+use wasm_bindgen::prelude::*;
+
+use crate::r#move::Move;
+
+#[wasm_bindgen(js_name = Move)]
+#[derive(Clone, Debug)]
+pub struct WasmMove {
+    move_: Move,
+}
+
+#[hotpath::measure_all]
+impl WasmMove {
+    pub(super) fn from_move(move_: Move) -> Self {
+        WasmMove { move_ }
+    }
+
+    pub(super) fn as_inner(&self) -> &Move {
+        &self.move_
+    }
+}
+
+#[hotpath::measure_all]
+#[wasm_bindgen(js_class = Move)]
+impl WasmMove {
+    pub fn place(col: u8, row: u8) -> Self {
+        WasmMove {
+            move_: Move::place(col, row),
+        }
+    }
+
+    #[wasm_bindgen(js_name = pass)]
+    pub fn pass_move() -> Self {
+        WasmMove {
+            move_: Move::pass(),
+        }
+    }
+
+    pub fn resign() -> Self {
+        WasmMove {
+            move_: Move::resign(),
+        }
+    }
+
+    pub fn swap() -> Self {
+        WasmMove {
+            move_: Move::swap(),
+        }
+    }
+
+    /// Parse a GTP move such as `D4` or `pass`; undefined if it is malformed
+    /// or off a `width` x `height` board.
+    #[wasm_bindgen(js_name = fromGtp)]
+    pub fn from_gtp(text: &str, width: u8, height: u8) -> Option<WasmMove> {
+        Move::from_gtp(text, width, height).map(WasmMove::from_move)
+    }
+
+    #[wasm_bindgen(js_name = toGtp)]
+    pub fn to_gtp(&self, height: u8) -> String {
+        self.move_.to_gtp(height)
+    }
+
+    #[wasm_bindgen(js_name = isPass)]
+    pub fn is_pass(&self) -> bool {
+        self.move_.is_pass()
+    }
+
+    #[wasm_bindgen(js_name = isResign)]
+    pub fn is_resign(&self) -> bool {
+        self.move_.is_resign()
+    }
+
+    #[wasm_bindgen(js_name = isSwap)]
+    pub fn is_swap(&self) -> bool {
+        self.move_.is_swap()
+    }
+
+    pub fn col(&self) -> Option<u8> {
+        self.move_.col()
+    }
+
+    pub fn row(&self) -> Option<u8> {
+        self.move_.row()
+    }
+}
@@ -0,0 +1,472 @@
+use std::fmt;
+use std::io::{Read, Write};
+
+use crate::outcome::{EndReason, GameOutcome};
+use crate::player::Player;
+use crate::r#move::Move;
+use crate::selfplay::GameRecord;
+use crate::training::TrainingSample;
+
+/// Version byte of the messages written by `write_message`.
+pub const WIRE_VERSION: u8 = 3;
+
+/// Largest message body `read_message` accepts, to fail fast on a corrupt
+/// stream instead of allocating its length.
+pub const MAX_MESSAGE_LEN: usize = 64 << 20;
+
+const KIND_GAME_RECORD: u8 = 1;
+const KIND_TRAINING_SAMPLE: u8 = 2;
+
+/// Marker in the column byte of a move that is not a placement.
+const SPECIAL_MOVE: u8 = 0xFF;
+
+/// A message exchanged between self-play workers and trainers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WireMessage {
+    GameRecord(GameRecord),
+    TrainingSample(TrainingSample),
+}
+
+/// Errors from reading or writing messages.
+#[derive(Debug)]
+pub enum WireError {
+    Io(std::io::Error),
+    /// The message was written by a version this reader does not know.
+    UnsupportedVersion(u8),
+    UnknownKind(u8),
+    /// The length prefix is above `MAX_MESSAGE_LEN`.
+    TooLarge(usize),
+    /// The body does not match its kind, e.g. it ends early.
+    Malformed(String),
+}
+
+impl From<std::io::Error> for WireError {
+    fn from(e: std::io::Error) -> Self {
+        WireError::Io(e)
+    }
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireError::Io(e) => write!(f, "wire I/O error: {}", e),
+            WireError::UnsupportedVersion(v) => write!(f, "unsupported wire version {}", v),
+            WireError::UnknownKind(k) => write!(f, "unknown wire message kind {}", k),
+            WireError::TooLarge(len) => write!(f, "wire message of {} bytes is too large", len),
+            WireError::Malformed(msg) => write!(f, "malformed wire message: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WireError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WireError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[hotpath::measure]
+fn end_reason_code(reason: EndReason) -> u8 {
+    match reason {
+        EndReason::DoublePass => 0,
+        EndReason::MaxMoves => 1,
+        EndReason::NoLegalMoves => 2,
+        EndReason::Resignation => 3,
+        EndReason::Timeout => 4,
+        EndReason::NoResult => 5,
+    }
+}
+
+#[hotpath::measure]
+fn end_reason_from_code(code: u8) -> Option<EndReason> {
+    Some(match code {
+        0 => EndReason::DoublePass,
+        1 => EndReason::MaxMoves,
+        2 => EndReason::NoLegalMoves,
+        3 => EndReason::Resignation,
+        4 => EndReason::Timeout,
+        5 => EndReason::NoResult,
+        _ => return None,
+    })
+}
+
+#[hotpath::measure]
+fn put_f32s(body: &mut Vec<u8>, values: &[f32]) {
+    body.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    for v in values {
+        body.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+#[hotpath::measure]
+fn game_record_body(record: &GameRecord, body: &mut Vec<u8>) {
+    body.extend_from_slice(&record.index.to_le_bytes());
+    body.extend_from_slice(&record.seed.to_le_bytes());
+    match record.outcome {
+        None => body.push(0),
+        Some(outcome) => {
+            body.push(1);
+            body.push(outcome.winner().map_or(0, |p| p as i8) as u8);
+            body.push(end_reason_code(outcome.reason()));
+            body.push(outcome.margin().is_some() as u8);
+            body.extend_from_slice(&outcome.margin_half_points().unwrap_or(0).to_le_bytes());
+        }
+    }
+    body.extend_from_slice(&(record.moves.len() as u32).to_le_bytes());
+    for move_ in &record.moves {
+        body.extend_from_slice(&match move_ {
+            Move::Place { col, row } => [*col, *row],
+            Move::Pass => [SPECIAL_MOVE, 0],
+            Move::Resign => [SPECIAL_MOVE, 1],
+            Move::Swap => [SPECIAL_MOVE, 2],
+        });
+    }
+}
+
+#[hotpath::measure]
+fn training_sample_body(sample: &TrainingSample, body: &mut Vec<u8>) {
+    body.push(sample.width);
+    body.push(sample.height);
+    body.extend_from_slice(&(sample.num_planes as u32).to_le_bytes());
+    put_f32s(body, &sample.planes);
+    put_f32s(body, &sample.policy);
+    body.extend_from_slice(&sample.value.to_le_bytes());
+    body.extend_from_slice(&sample.moves_remaining.to_le_bytes());
+}
+
+/// `message` as a complete frame, ready to send.
+///
+/// A frame is a little-endian `u32` length of the rest of the frame, the
+/// version byte (`WIRE_VERSION`), a kind byte, then the body. Integers and
+/// `f32`s are little-endian and lists are a `u32` count followed by the
+/// items.
+///
+/// Kind 1, a `GameRecord`: `u64` index, `u64` seed, then a `u8` that is 0
+/// without an outcome, or 1 followed by the winner (`i8`: 1 Black, -1 White,
+/// 0 none), the end reason (`u8`, in `EndReason` declaration order), a `u8`
+/// flag for the margin and the margin (`i32` half points, 0 when absent). Then the
+/// moves, two bytes each: column and row for a placement, or 255 followed
+/// by 0 for pass, 1 for resign and 2 for swap.
+///
+/// Kind 2, a `TrainingSample`: width and height (`u8`), the number of planes
+/// (`u32`), the planes and the policy (lists of `f32`), the value (`f32`)
+/// and the moves remaining (`u32`).
+#[hotpath::measure]
+pub fn encode_message(message: &WireMessage) -> Vec<u8> {
+    let mut frame = vec![0; 4];
+    frame.push(WIRE_VERSION);
+    match message {
+        WireMessage::GameRecord(record) => {
+            frame.push(KIND_GAME_RECORD);
+            game_record_body(record, &mut frame);
+        }
+        WireMessage::TrainingSample(sample) => {
+            frame.push(KIND_TRAINING_SAMPLE);
+            training_sample_body(sample, &mut frame);
+        }
+    }
+    let len = (frame.len() - 4) as u32;
+    frame[..4].copy_from_slice(&len.to_le_bytes());
+    frame
+}
+
+/// Write `message` as one frame (see `encode_message`).
+#[hotpath::measure]
+pub fn write_message<W: Write>(out: &mut W, message: &WireMessage) -> Result<(), WireError> {
+    out.write_all(&encode_message(message))?;
+    Ok(())
+}
+
+/// Reads the fields of a message body in order.
+struct BodyReader<'a> {
+    bytes: &'a [u8],
+}
+
+#[hotpath::measure_all]
+impl<'a> BodyReader<'a> {
+    fn take<const N: usize>(&mut self, what: &str) -> Result<[u8; N], WireError> {
+        let Some((head, rest)) = self.bytes.split_first_chunk::<N>() else {
+            return Err(WireError::Malformed(format!(
+                "body ends before the {}",
+                what
+            )));
+        };
+        self.bytes = rest;
+        Ok(*head)
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8, WireError> {
+        Ok(self.take::<1>(what)?[0])
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, WireError> {
+        Ok(u32::from_le_bytes(self.take(what)?))
+    }
+
+    fn i32(&mut self, what: &str) -> Result<i32, WireError> {
+        Ok(i32::from_le_bytes(self.take(what)?))
+    }
+
+    fn u64(&mut self, what: &str) -> Result<u64, WireError> {
+        Ok(u64::from_le_bytes(self.take(what)?))
+    }
+
+    fn f32(&mut self, what: &str) -> Result<f32, WireError> {
+        Ok(f32::from_le_bytes(self.take(what)?))
+    }
+
+    /// A count of items of `size` bytes, checked against the bytes left.
+    fn count(&mut self, what: &str, size: usize) -> Result<usize, WireError> {
+        let count = self.u32(what)? as usize;
+        if count.saturating_mul(size) > self.bytes.len() {
+            return Err(WireError::Malformed(format!(
+                "{} {} overruns the body",
+                what, count
+            )));
+        }
+        Ok(count)
+    }
+
+    fn f32s(&mut self, what: &str) -> Result<Vec<f32>, WireError> {
+        let count = self.count(what, 4)?;
+        (0..count).map(|_| self.f32(what)).collect()
+    }
+
+    fn finish(self) -> Result<(), WireError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(WireError::Malformed(format!(
+                "{} bytes after the body",
+                self.bytes.len()
+            )))
+        }
+    }
+}
+
+#[hotpath::measure]
+fn read_game_record(body: &mut BodyReader) -> Result<GameRecord, WireError> {
+    let index = body.u64("index")?;
+    let seed = body.u64("seed")?;
+    let outcome = match body.u8("outcome flag")? {
+        0 => None,
+        1 => {
+            let winner = match body.u8("winner")? as i8 {
+                0 => None,
+                w => Some(
+                    Player::from_int(w)
+                        .ok_or_else(|| WireError::Malformed(format!("winner {}", w)))?,
+                ),
+            };
+            let code = body.u8("end reason")?;
+            let reason = end_reason_from_code(code)
+                .ok_or_else(|| WireError::Malformed(format!("end reason {}", code)))?;
+            let has_margin = body.u8("margin flag")? != 0;
+            let margin = body.i32("margin")?;
+            Some(GameOutcome::from_parts(
+                winner,
+                has_margin.then_some(margin),
+                reason,
+            ))
+        }
+        flag => return Err(WireError::Malformed(format!("outcome flag {}", flag))),
+    };
+    let count = body.count("move count", 2)?;
+    let mut moves = Vec::with_capacity(count);
+    for _ in 0..count {
+        let move_ = match body.take::<2>("move")? {
+            [SPECIAL_MOVE, 0] => Move::pass(),
+            [SPECIAL_MOVE, 1] => Move::resign(),
+            [SPECIAL_MOVE, 2] => Move::swap(),
+            [SPECIAL_MOVE, code] => {
+                return Err(WireError::Malformed(format!("move code {}", code)))
+            }
+            [col, row] => Move::place(col, row),
+        };
+        moves.push(move_);
+    }
+    Ok(GameRecord {
+        index,
+        seed,
+        moves,
+        outcome,
+    })
+}
+
+#[hotpath::measure]
+fn read_training_sample(body: &mut BodyReader) -> Result<TrainingSample, WireError> {
+    let width = body.u8("width")?;
+    let height = body.u8("height")?;
+    let num_planes = body.u32("plane count")? as usize;
+    let planes = body.f32s("planes")?;
+    if planes.len() != num_planes * width as usize * height as usize {
+        return Err(WireError::Malformed(format!(
+            "{} plane values for {} {}x{} planes",
+            planes.len(),
+            num_planes,
+            width,
+            height
+        )));
+    }
+    Ok(TrainingSample {
+        planes,
+        num_planes,
+        height,
+        width,
+        policy: body.f32s("policy")?,
+        value: body.f32("value")?,
+        moves_remaining: body.u32("moves remaining")?,
+    })
+}
+
+/// Read a message from the front of `bytes`. Returns the message and the
+/// number of bytes of its frame, or None if `bytes` does not hold a whole
+/// frame yet.
+#[hotpath::measure]
+pub fn decode_message(bytes: &[u8]) -> Result<Option<(WireMessage, usize)>, WireError> {
+    let Some((len, rest)) = bytes.split_first_chunk::<4>() else {
+        return Ok(None);
+    };
+    let len = u32::from_le_bytes(*len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(WireError::TooLarge(len));
+    }
+    let Some(frame) = rest.get(..len) else {
+        return Ok(None);
+    };
+
+    let mut body = BodyReader { bytes: frame };
+    let version = body.u8("version")?;
+    if version != WIRE_VERSION {
+        return Err(WireError::UnsupportedVersion(version));
+    }
+    let message = match body.u8("kind")? {
+        KIND_GAME_RECORD => WireMessage::GameRecord(read_game_record(&mut body)?),
+        KIND_TRAINING_SAMPLE => WireMessage::TrainingSample(read_training_sample(&mut body)?),
+        kind => return Err(WireError::UnknownKind(kind)),
+    };
+    body.finish()?;
+    Ok(Some((message, 4 + len)))
+}
+
+/// Read the next message from `input`, or None at a clean end of stream
+/// between messages.
+#[hotpath::measure]
+pub fn read_message<R: Read>(input: &mut R) -> Result<Option<WireMessage>, WireError> {
+    let mut len = [0; 4];
+    let mut filled = 0;
+    while filled < len.len() {
+        match input.read(&mut len[filled..])? {
+            0 if filled == 0 => return Ok(None),
+            0 => {
+                return Err(WireError::Malformed(
+                    "stream ends in a length prefix".to_string(),
+                ))
+            }
+            n => filled += n,
+        }
+    }
+    let body_len = u32::from_le_bytes(len) as usize;
+    if body_len > MAX_MESSAGE_LEN {
+        return Err(WireError::TooLarge(body_len));
+    }
+    let mut frame = len.to_vec();
+    frame.resize(4 + body_len, 0);
+    input.read_exact(&mut frame[4..])?;
+    let (message, _) = decode_message(&frame)?.expect("the frame is complete");
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> GameRecord {
+        GameRecord {
+            index: 7,
+            seed: u64::MAX - 1,
+            moves: vec![
+                Move::place(3, 15),
+                Move::swap(),
+                Move::pass(),
+                Move::resign(),
+            ],
+            outcome: Some(GameOutcome::from_score(10.0, 16.5, EndReason::MaxMoves)),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_over_a_stream() {
+        let sample = TrainingSample {
+            planes: vec![0.0, 1.0, 1.0, 0.0, 0.5, 0.25, 0.0, 1.0],
+            num_planes: 2,
+            height: 2,
+            width: 2,
+            policy: vec![0.1, 0.2, 0.3, 0.4, 0.0],
+            value: -1.0,
+            moves_remaining: 12,
+        };
+        let messages = [
+            WireMessage::GameRecord(record()),
+            WireMessage::GameRecord(GameRecord {
+                outcome: None,
+                moves: Vec::new(),
+                ..record()
+            }),
+            WireMessage::TrainingSample(sample),
+        ];
+        let mut stream = Vec::new();
+        for message in &messages {
+            write_message(&mut stream, message).expect("writes");
+        }
+
+        let mut input = stream.as_slice();
+        for message in &messages {
+            assert_eq!(
+                read_message(&mut input).expect("reads").as_ref(),
+                Some(message)
+            );
+        }
+        assert!(read_message(&mut input).expect("clean end").is_none());
+
+        // A partial frame is not a message yet.
+        let frame = encode_message(&messages[0]);
+        assert!(decode_message(&frame[..frame.len() - 1])
+            .expect("partial")
+            .is_none());
+        let (decoded, used) = decode_message(&frame).expect("decodes").expect("whole");
+        assert_eq!((decoded, used), (messages[0].clone(), frame.len()));
+    }
+
+    #[test]
+    fn test_rejects_bad_frames() {
+        let mut frame = encode_message(&WireMessage::GameRecord(record()));
+        frame[4] = WIRE_VERSION + 1;
+        assert!(matches!(
+            decode_message(&frame),
+            Err(WireError::UnsupportedVersion(4))
+        ));
+
+        frame[4] = WIRE_VERSION;
+        frame[5] = 9;
+        assert!(matches!(
+            decode_message(&frame),
+            Err(WireError::UnknownKind(9))
+        ));
+
+        // Drop the last move but keep the frame length consistent.
+        let mut short = encode_message(&WireMessage::GameRecord(record()));
+        short.truncate(short.len() - 2);
+        let len = (short.len() - 4) as u32;
+        short[..4].copy_from_slice(&len.to_le_bytes());
+        assert!(matches!(
+            decode_message(&short),
+            Err(WireError::Malformed(_))
+        ));
+
+        let huge = u32::MAX.to_le_bytes();
+        assert!(matches!(decode_message(&huge), Err(WireError::TooLarge(_))));
+    }
+}
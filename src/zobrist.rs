@@ -0,0 +1,96 @@
+use crate::board::Board;
+use crate::player::Player;
+use crate::position::Position;
+
+/// Points on the largest board, 32x32.
+const MAX_POINTS: usize = 32 * 32;
+
+/// Random keys XORed together into a position hash: one per point for each
+/// color and for the ko point, and one for White to move. Generated at
+/// compile time from a fixed seed, so hashes are the same in every build
+/// and process.
+struct Keys {
+    stones: [[u64; MAX_POINTS]; 2],
+    ko: [u64; MAX_POINTS],
+    white_to_move: u64,
+}
+
+/// The splitmix64 step: advances `state` and returns the next key.
+const fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const fn generate_keys() -> Keys {
+    let mut state = 0x5370_6f6f_6b79_476f;
+    let mut keys = Keys {
+        stones: [[0; MAX_POINTS]; 2],
+        ko: [0; MAX_POINTS],
+        white_to_move: 0,
+    };
+    let mut i = 0;
+    while i < MAX_POINTS {
+        keys.stones[0][i] = splitmix64(&mut state);
+        keys.stones[1][i] = splitmix64(&mut state);
+        keys.ko[i] = splitmix64(&mut state);
+        i += 1;
+    }
+    keys.white_to_move = splitmix64(&mut state);
+    keys
+}
+
+static KEYS: Keys = generate_keys();
+
+/// The Zobrist hash of `board` with `to_move` to play and `ko_point`
+/// forbidden to them. Equal positions hash equal across processes and
+/// builds, which makes it a key for transposition tables.
+#[hotpath::measure]
+pub fn position_hash<const NW: usize>(
+    board: &Board<NW>,
+    to_move: Player,
+    ko_point: Option<Position>,
+) -> u64 {
+    let mut hash = 0;
+    for (color, player) in [Player::Black, Player::White].into_iter().enumerate() {
+        for idx in board.stones_for(player).iter_ones() {
+            hash ^= KEYS.stones[color][idx];
+        }
+    }
+    if let Some(ko) = ko_point {
+        hash ^= KEYS.ko[ko.to_index(board.width())];
+    }
+    if to_move == Player::White {
+        hash ^= KEYS.white_to_move;
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::nw_for_board;
+
+    #[test]
+    fn test_position_hash() {
+        let mut board = Board::<{ nw_for_board(9, 9) }>::new(9, 9);
+        let empty = position_hash(&board, Player::Black, None);
+        assert_eq!(empty, 0);
+        assert_ne!(position_hash(&board, Player::White, None), empty);
+
+        board.set_piece(&Position::new(2, 3), Some(Player::Black));
+        let black = position_hash(&board, Player::Black, None);
+        assert_ne!(black, empty);
+        assert_ne!(
+            position_hash(&board, Player::Black, Some(Position::new(4, 4))),
+            black
+        );
+
+        board.set_piece(&Position::new(2, 3), Some(Player::White));
+        assert_ne!(position_hash(&board, Player::Black, None), black);
+        board.set_piece(&Position::new(2, 3), None);
+        assert_eq!(position_hash(&board, Player::Black, None), empty);
+    }
+}
@@ -0,0 +1,86 @@
+from array import array
+
+import pytest
+
+from spooky_go import BLACK, WHITE, BatchGame, Game, InvalidPlayerError
+
+
+def _batch(num_games: int = 4, threads: int = 1) -> BatchGame:
+    return BatchGame(Game.with_options(5, 5, 0.5, 0, 1000, True), num_games, threads)
+
+
+class TestBatchGame:
+    def test_masks_match_single_games(self) -> None:
+        batch = _batch()
+        actions = batch.total_actions()
+        assert len(batch) == 4
+        assert actions == 26
+
+        masks = array("B", bytes(len(batch) * actions))
+        batch.legal_action_masks(masks)
+        assert masks.tolist() == [1] * (len(batch) * actions)
+
+        batch.apply_actions(array("q", [0, 1, 2, 3]))
+        batch.legal_action_masks(masks)
+        for i in range(len(batch)):
+            row = masks[i * actions : (i + 1) * actions]
+            legal = batch.game(i).legal_action_indices()
+            assert [a for a, m in enumerate(row) if m] == legal
+        turns = array("b", bytes(len(batch)))
+        batch.turns(turns)
+        assert turns.tolist() == [WHITE] * 4
+
+    @pytest.mark.parametrize("threads", [1, 3])
+    def test_encode_all_matches_single_games(self, threads: int) -> None:
+        batch = _batch(threads=threads)
+        batch.apply_actions(array("q", [12, 0, 24, 25]))
+        data, num_planes, height, width = batch.game(0).encode_game_planes()
+
+        out = array("f", [0.0] * (len(batch) * len(data)))
+        assert batch.encode_all(out) == (4, num_planes, height, width)
+        for i in range(len(batch)):
+            expected = batch.game(i).encode_game_planes()[0]
+            assert out[i * len(data) : (i + 1) * len(data)].tolist() == expected
+
+    def test_illegal_action_plays_nothing(self) -> None:
+        batch = _batch(num_games=2)
+        batch.apply_actions(array("q", [12, 12]))
+        with pytest.raises(ValueError, match="game 1"):
+            batch.apply_actions(array("q", [0, 12]))
+        assert [batch.game(i).move_count() for i in range(2)] == [1, 1]
+        with pytest.raises(ValueError):
+            batch.apply_actions(array("q", [0]))
+
+    def test_reset_finished(self) -> None:
+        batch = _batch(num_games=3)
+        pass_action = batch.total_actions() - 1
+        batch.apply_actions(array("q", [12, pass_action, 12]))
+        batch.apply_actions(array("q", [pass_action, pass_action, 0]))
+        batch.apply_actions(array("q", [pass_action, 0, 1]))
+
+        # Game 1 ended on its second pass and ignored the last action.
+        assert batch.dones() == [True, True, False]
+        rewards = array("f", [0.0] * 3)
+        batch.rewards(rewards)
+        assert rewards.tolist() == [1.0, -1.0, 0.0]
+        batch.rewards_from_perspective(array("b", [WHITE, WHITE, BLACK]), rewards)
+        assert rewards.tolist() == [-1.0, 1.0, 0.0]
+        outcomes = batch.outcomes()
+        assert [o.winner() if o else None for o in outcomes] == [BLACK, WHITE, None]
+        assert outcomes[0].reason() == "double_pass"
+        assert batch.reset_finished() == [0, 1]
+        assert batch.dones() == [False, False, False]
+        turns = array("b", bytes(3))
+        batch.turns(turns)
+        assert turns.tolist() == [BLACK, BLACK, WHITE]
+        assert batch.game(1).move_count() == 0
+
+    def test_rewards_from_perspective_invalid(self) -> None:
+        batch = _batch(num_games=2)
+        rewards = array("f", [0.0] * 2)
+        with pytest.raises(ValueError, match="Expected 2"):
+            batch.rewards_from_perspective(array("b", [BLACK]), rewards)
+        with pytest.raises(InvalidPlayerError):
+            batch.rewards_from_perspective(array("b", [BLACK, 0]), rewards)
+        with pytest.raises(ValueError):
+            batch.rewards_from_perspective(array("b", [BLACK, WHITE]), array("f", [0.0]))
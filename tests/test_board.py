@@ -1,6 +1,6 @@
 import pytest
 
-from spooky_go import BLACK, WHITE, Board
+from spooky_go import BLACK, WHITE, Board, InvalidPlayerError
 
 
 class TestBoardCreation:
@@ -66,3 +66,63 @@ class TestBoardPieces:
         for row in range(9):
             for col in range(9):
                 assert board.get_piece(col, row) is None
+
+
+class TestShowboard:
+    def test_from_showboard(self) -> None:
+        text = "= \n   A B C \n 3 . O . 3\n 2 . + . 2\n 1 X . . 1\n   A B C \n"
+        board = Board.from_showboard(text)
+        assert board.width() == 3
+        assert board.height() == 3
+        assert board.get_piece(1, 2) == WHITE
+        assert board.get_piece(0, 0) == BLACK
+        assert board.get_piece(1, 1) is None
+
+    def test_from_showboard_invalid(self) -> None:
+        with pytest.raises(ValueError, match="showboard"):
+            Board.from_showboard("not a board")
+
+    def test_to_svg(self) -> None:
+        board = Board(9, 9)
+        board.set_piece(2, 2, BLACK)
+        svg = board.to_svg()
+        assert svg.startswith("<svg")
+        assert 'fill="black" stroke="black"' in svg
+
+    def test_render_themes(self) -> None:
+        board = Board(3, 2)
+        board.set_piece(0, 0, BLACK)
+        assert board.render() == str(board)
+        assert board.render("unicode") == "   A B C\n 2 ┌─┬─┐ 2\n 1 ●─┴─┘ 1\n   A B C\n"
+        assert board.render("svg") == board.to_svg()
+        with pytest.raises(ValueError, match="theme"):
+            board.render("html")
+
+
+class TestNumpy:
+    def test_to_numpy(self) -> None:
+        np = pytest.importorskip("numpy")
+        board = Board(3, 2)
+        board.set_piece(2, 0, BLACK)
+        board.set_piece(0, 1, WHITE)
+        array = board.to_numpy()
+        assert array.dtype == np.int8
+        assert array.tolist() == [[0, 0, 1], [-1, 0, 0]]
+
+    def test_from_numpy_round_trip(self) -> None:
+        np = pytest.importorskip("numpy")
+        array = np.array([[1, 0, -1], [0, 0, 1]])
+        board = Board.from_numpy(array)
+        assert board.width() == 3
+        assert board.height() == 2
+        assert board.get_piece(0, 0) == BLACK
+        assert board.get_piece(2, 0) == WHITE
+        assert board.get_piece(2, 1) == BLACK
+        assert (board.to_numpy() == array).all()
+
+    def test_from_numpy_invalid(self) -> None:
+        np = pytest.importorskip("numpy")
+        with pytest.raises(ValueError, match="2 dimensions"):
+            Board.from_numpy(np.zeros(9))
+        with pytest.raises(InvalidPlayerError):
+            Board.from_numpy(np.full((3, 3), 2))
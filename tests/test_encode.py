@@ -1,4 +1,20 @@
-from spooky_go import TOTAL_INPUT_PLANES, Game, Move
+from array import array
+
+import pytest
+
+from spooky_go import (
+    HISTORY_LENGTH,
+    TOTAL_INPUT_PLANES,
+    EncodingCache,
+    Game,
+    Move,
+    Position,
+    decode_move,
+    encode_batch,
+    encode_move,
+    nw_for_board,
+    total_actions,
+)
 
 
 def get_plane_value(
@@ -16,6 +32,37 @@ class TestConstants:
     def test_total_input_planes(self) -> None:
         # Should be (HISTORY_LENGTH * 2) + 1 = 17
         assert TOTAL_INPUT_PLANES == 17
+        assert HISTORY_LENGTH * 2 + 1 == TOTAL_INPUT_PLANES
+
+
+class TestModuleFunctions:
+    def test_match_game_methods(self) -> None:
+        game = Game(9, 9)
+        assert total_actions(9, 9) == game.total_actions()
+        assert total_actions(9, 9, swap=True) == 83
+        assert nw_for_board(9, 9) == 2
+        assert nw_for_board(19, 19) == 6
+
+    def test_encode_decode_round_trip(self) -> None:
+        for move in [Move.place(0, 0), Move.place(8, 3), Move.pass_move()]:
+            action = encode_move(move, 9, 9)
+            assert action == move.encode(9, 9)
+            assert decode_move(action, 9, 9) == move
+        assert decode_move(total_actions(9, 9), 9, 9) is None
+
+    def test_encode_move_from_a_point(self) -> None:
+        action = encode_move(Move.place(8, 3), 9, 9)
+        assert encode_move(8, 3, 9, 9) == action
+        assert encode_move((8, 3), 9, 9) == action
+        assert encode_move(Position(8, 3), 9, 9) == action
+        with pytest.raises(TypeError):
+            encode_move(8, 3, 9)
+
+    def test_rejects_bad_sizes(self) -> None:
+        with pytest.raises(ValueError):
+            total_actions(0, 9)
+        with pytest.raises(ValueError):
+            decode_move(0, 9, 40)
 
 
 class TestGameEncoding:
@@ -87,6 +134,53 @@ class TestGameEncoding:
         assert width_19 == 19
         assert len(data_19) == num_planes_19 * 19 * 19
 
+    def test_encode_game_planes_into_buffer(self) -> None:
+        game = Game(5, 5)
+        game.make_move(Move.place(2, 2))
+        data, num_planes, height, width = game.encode_game_planes()
+
+        # A batch of two, filled row by row without intermediate lists.
+        batch = array("f", [9.0] * (2 * len(data)))
+        rows = memoryview(batch)
+        assert game.encode_game_planes_into(rows[: len(data)]) == (num_planes, height, width)
+        cache = EncodingCache()
+        assert game.encode_game_planes_into(rows[len(data) :], cache) == (num_planes, height, width)
+        assert batch.tolist() == data + data
+
+        with pytest.raises(ValueError):
+            game.encode_game_planes_into(array("f", [0.0] * 3))
+        with pytest.raises(BufferError):
+            game.encode_game_planes_into(bytes(4 * len(data)))
+
+    def test_encode_game_planes_numpy(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        data, num_planes, height, width = game.encode_game_planes()
+        planes = game.encode_game_planes_numpy()
+        assert planes.dtype == np.float32
+        assert planes.shape == (num_planes, height, width)
+        assert planes.ravel().tolist() == data
+
+    def test_encode_batch(self) -> None:
+        np = pytest.importorskip("numpy")
+        games = [Game(9, 9) for _ in range(5)]
+        for i, game in enumerate(games):
+            game.make_move(Move.place(i, i))
+
+        for threads in [1, 2]:
+            batch = encode_batch(games, threads=threads)
+            assert batch.dtype == np.float32
+            assert batch.shape == (5, *games[0].encode_game_planes_numpy().shape)
+            for planes, game in zip(batch, games):
+                assert np.array_equal(planes, game.encode_game_planes_numpy())
+
+    def test_encode_batch_needs_one_size(self) -> None:
+        with pytest.raises(ValueError):
+            encode_batch([])
+        with pytest.raises(ValueError):
+            encode_batch([Game(9, 9), Game(7, 7)])
+
 
 class TestActionDecoding:
     def test_decode_action_place(self) -> None:
@@ -117,6 +211,63 @@ class TestActionDecoding:
         assert game_19.total_actions() == 362  # 361 + pass
 
 
+    def test_resign_action(self) -> None:
+        game = Game(9, 9)
+        assert game.total_actions(resign=True) == 83
+        assert total_actions(9, 9, resign=True) == 83
+        assert 82 in game.legal_action_indices(resign=True)
+        assert 82 not in game.legal_action_indices()
+
+        move = game.decode_action(82, resign=True)
+        assert move is not None
+        assert move.is_resign()
+        assert game.decode_action(82) is None
+
+        game.apply_action(82, resign=True)
+        assert game.is_over()
+
+    def test_legal_action_mask_into(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        mask = bytearray(b"\x07" * game.total_actions(resign=True))
+        game.legal_action_mask_into(mask, resign=True)
+        legal = game.legal_action_indices(resign=True)
+        assert [i for i, v in enumerate(mask) if v] == sorted(legal)
+        assert set(mask) == {0, 1}
+        with pytest.raises(ValueError):
+            game.legal_action_mask_into(bytearray(10))
+
+    def test_legal_action_mask(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        mask = game.legal_action_mask()
+        assert mask.dtype == np.bool_ and mask.shape == (82,)
+        assert np.flatnonzero(mask).tolist() == sorted(game.legal_action_indices())
+
+    def test_symmetries(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(9, 9)
+        game.make_move(Move.place(1, 0))
+        symmetries = game.symmetries()
+        assert len(symmetries) == 8
+        planes, permutation = symmetries[0]
+        assert (planes == game.encode_game_planes_numpy()).all()
+        assert permutation.tolist() == list(range(82))
+
+        policy = np.zeros(82, dtype=np.float32)
+        policy[Move.place(1, 0).encode(9, 9)] = 1.0
+        for planes, permutation in symmetries:
+            assert planes.shape == (17, 9, 9)
+            transformed = policy[permutation]
+            assert transformed[81] == 0.0
+            # The stone and its policy mass move to the same point.
+            row, col = np.argwhere(planes[1])[0]
+            assert transformed[row * 9 + col] == 1.0
+
+        assert len(Game(9, 7).symmetries(resign=True)) == 4
+        assert Game(9, 7).symmetries(resign=True)[0][1].shape == (65,)
+
 class TestEncodingConsistency:
     def test_encoding_deterministic(self) -> None:
         game = Game(9, 9)
@@ -151,3 +302,34 @@ class TestEncodingConsistency:
         planes2 = game2.encode_game_planes()
 
         assert planes1 != planes2
+
+
+class TestEncodingCache:
+    def test_cache_shared_between_games(self) -> None:
+        cache = EncodingCache(capacity=4)
+        game1 = Game(9, 9)
+        game2 = Game(9, 9)
+        game1.make_move(Move.place(4, 4))
+        game2.make_move(Move.place(4, 4))
+
+        assert game1.encode_game_planes(cache) == game1.encode_game_planes()
+        assert game2.encode_game_planes(cache) == game2.encode_game_planes()
+        assert cache.hits() == 1
+        assert cache.misses() == 1
+        assert len(cache) == 1
+
+        cache.clear()
+        assert len(cache) == 0
+
+    def test_capture_plane(self) -> None:
+        game = Game(9, 9)
+        for col, row in [(1, 0), (0, 0), (5, 5), (6, 6)]:
+            game.make_move(Move.place(col, row))
+        assert [(m.col(), m.row()) for m in game.capturing_moves()] == [(0, 1)]
+
+        data, num_planes, height, width = game.encode_game_planes(
+            EncodingCache(capture_plane=True)
+        )
+        assert num_planes == TOTAL_INPUT_PLANES + 1
+        capture_plane = data[(num_planes - 1) * height * width :]
+        assert [i for i, v in enumerate(capture_plane) if v == 1.0] == [1 * width + 0]
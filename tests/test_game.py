@@ -1,6 +1,19 @@
+from pathlib import Path
+
 import pytest
 
-from spooky_go import BLACK, WHITE, Game, Move
+from spooky_go import (
+    BLACK,
+    WHITE,
+    Game,
+    GameInfo,
+    GameOverError,
+    IllegalMoveError,
+    InvalidPlayerError,
+    Move,
+    Position,
+    random_playouts_batch,
+)
 
 
 class TestGameCreation:
@@ -25,6 +38,46 @@ class TestGameCreation:
         with pytest.raises(ValueError, match="height"):
             Game(9, 1)
 
+    def test_move_limits(self) -> None:
+        game = Game(32, 32)
+        assert game.max_moves() == 3072
+        assert game.moves_remaining() == 3072
+        with pytest.raises(ValueError, match="max_moves"):
+            Game.with_options(9, 9, 7.5, 0, 0, True)
+        with pytest.raises(ValueError, match="must not exceed"):
+            Game.with_options(9, 9, 7.5, 50, 10, True)
+        with pytest.raises(OverflowError):
+            Game.with_options(9, 9, 7.5, 0, 70000, True)
+
+    def test_from_config(self, tmp_path: Path) -> None:
+        path = tmp_path / "experiment.toml"
+        path.write_text('[rules]\nwidth = 9\nheight = 9\nkomi = 6.5\nruleset = "japanese"\n')
+        game = Game.from_config(str(path))
+        assert game.width() == 9
+        assert game.komi() == 6.5
+        assert game.ruleset() == "japanese"
+
+    def test_from_config_invalid(self, tmp_path: Path) -> None:
+        path = tmp_path / "experiment.json"
+        path.write_text('{"rules": {"width": 1}}')
+        with pytest.raises(ValueError, match="width"):
+            Game.from_config(str(path))
+
+    def test_self_play_from_config(self, tmp_path: Path) -> None:
+        path = tmp_path / "experiment.toml"
+        path.write_text(
+            "[rules]\nwidth = 5\nheight = 5\n\n[search]\niterations = 20\n\n"
+            "[selfplay]\nseed = 3\ngames = 3\nthreads = 2\n"
+        )
+        records = Game.self_play_from_config(str(path))
+        assert [r.index() for r in records] == [0, 1, 2]
+        assert all(r.outcome() is not None for r in records)
+        again = Game.self_play_from_config(str(path))
+        assert [r.moves() for r in again] == [r.moves() for r in records]
+        path.write_text("[selfplay]\nthreads = 0\n")
+        with pytest.raises(ValueError, match="thread"):
+            Game.self_play_from_config(str(path))
+
 
 class TestGameState:
     def test_initial_turn(self) -> None:
@@ -52,6 +105,22 @@ class TestGameState:
         assert board.width() == 9
         assert board.height() == 9
 
+    def test_strict_set_piece(self) -> None:
+        game = Game(9, 9, strict=True)
+        assert game.strict()
+        game.set_piece(0, 0, WHITE)
+        game.make_move(Move.place(4, 4))
+        with pytest.raises(RuntimeError, match="strict"):
+            game.set_piece(1, 1, WHITE)
+        assert game.get_piece(1, 1) is None
+        assert game.clone().strict()
+
+        lenient = Game(9, 9)
+        assert not lenient.strict()
+        lenient.make_move(Move.place(4, 4))
+        lenient.set_piece(1, 1, WHITE)
+        assert lenient.get_piece(1, 1) == WHITE
+
 
 class TestGameMoves:
     def test_make_move(self) -> None:
@@ -59,8 +128,8 @@ class TestGameMoves:
         move = Move.place(4, 4)
 
         assert game.is_legal_move(move)
-        result = game.make_move(move)
-        assert result is True
+        game.make_move(move)
+        assert game.board().get_piece(4, 4) == BLACK
 
     def test_make_illegal_move_occupied(self) -> None:
         game = Game(9, 9)
@@ -69,8 +138,65 @@ class TestGameMoves:
 
         same_move = Move.place(4, 4)
         assert not game.is_legal_move(same_move)
-        result = game.make_move(same_move)
-        assert result is False
+        with pytest.raises(IllegalMoveError, match="occupied"):
+            game.make_move(same_move)
+        assert game.move_count() == 1
+
+    def test_make_move_after_game_over(self) -> None:
+        game = Game(9, 9)
+        game.resign(BLACK)
+        with pytest.raises(GameOverError, match="game is over"):
+            game.make_move(Move.place(4, 4))
+        with pytest.raises(GameOverError):
+            game.apply_action(0)
+        with pytest.raises(GameOverError):
+            game.resign(WHITE)
+
+    def test_why_illegal(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 100, True)
+        game.set_allow_suicide(False)
+        assert game.why_illegal(Move.place(2, 2)) is None
+        for col, row in [(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2), (4, 4), (1, 1)]:
+            game.make_move(Move.place(col, row))
+
+        assert game.why_illegal(Move.place(1, 0)) == "occupied"
+        assert game.why_illegal(Move.place(9, 9)) == "off_board"
+        assert game.why_illegal(Move.swap()) == "swap_not_allowed"
+        # Black takes the ko, leaving White no retake and a suicide point
+        game.make_move(Move.place(2, 1))
+        assert game.why_illegal(Move.place(1, 1)) == "ko"
+        assert game.why_illegal(Move.place(0, 0)) == "suicide"
+        game.resign(BLACK)
+        assert game.why_illegal(Move.pass_move()) == "game_over"
+
+    def test_apply_action_out_of_range(self) -> None:
+        game = Game(9, 9)
+        with pytest.raises(IllegalMoveError, match="out of range"):
+            game.apply_action(1000)
+
+    def test_invalid_player(self) -> None:
+        game = Game(9, 9)
+        with pytest.raises(InvalidPlayerError, match="2"):
+            game.passes(2)
+        with pytest.raises(InvalidPlayerError):
+            game.set_piece(0, 0, 0)
+
+    def test_play_sequence(self) -> None:
+        game = Game(9, 9)
+        assert game.play_sequence([Move.place(4, 4), 2 * 9 + 2, Move.place(6, 6)]) == 3
+        assert game.move_count() == 3
+        assert game.board().get_piece(2, 2) == WHITE
+
+        before = game.to_position_string()
+        with pytest.raises(ValueError, match="move 2"):
+            game.play_sequence([Move.place(0, 0), 40])
+        assert game.to_position_string() == before
+        assert game.move_count() == 3
+        with pytest.raises(TypeError):
+            game.play_sequence(["D4"])
+
+        assert game.play_sequence([82], resign=True) == 1
+        assert game.is_over()
 
     def test_legal_moves_initial(self) -> None:
         game = Game(9, 9)
@@ -100,6 +226,34 @@ class TestGameMoves:
         result = game.unmake_move()
         assert result is False
 
+    def test_play_frequency_map(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        game.make_move(Move.place(2, 1))
+        game.make_move(Move.pass_move())
+        game.make_move(Move.place(0, 3))
+
+        counts = game.play_frequency_map()
+        assert len(counts) == 25
+        assert counts[1 * 5 + 2] == 1
+        assert counts[3 * 5 + 0] == 1
+        assert sum(counts) == 2
+
+    def test_rewind_and_reset(self) -> None:
+        game = Game.with_options(9, 9, 7.5, 0, 1000, True, handicap=2)
+        for col in range(5):
+            game.make_move(Move.place(col, 4))
+
+        assert game.rewind(3) == 3
+        assert game.move_count() == 2
+        assert game.board().get_piece(2, 4) is None
+        assert game.board().get_piece(1, 4) == BLACK
+
+        game.reset_keep_config()
+        assert game.move_count() == 0
+        assert game.turn() == WHITE
+        assert game.handicap() == 2
+        assert game.rewind(5) == 0
+
 
 class TestPassMove:
     def test_pass_is_legal(self) -> None:
@@ -134,6 +288,207 @@ class TestPassMove:
         # Pass not legal before min moves
         assert not game.is_legal_move(Move.pass_move())
 
+    def test_pass_counts(self) -> None:
+        game = Game.with_options(9, 9, 7.5, 0, 1000, False)
+        game.make_move(Move.pass_move())
+        game.make_move(Move.place(4, 4))
+        game.make_move(Move.pass_move())
+
+        assert game.passes(BLACK) == 2
+        assert game.passes(WHITE) == 0
+        assert game.consecutive_passes() == 1
+
+
+class TestPolicySvg:
+    def test_policy_to_svg(self) -> None:
+        game = Game(9, 9)
+        policy = [0.0] * 82
+        policy[4 * 9 + 4] = 0.6
+        policy[81] = 0.1
+        svg = game.policy_to_svg(policy, top_k=1)
+        assert ">60%</text>" in svg
+        assert "pass 10.0%" in svg
+
+    def test_policy_too_short(self) -> None:
+        game = Game(9, 9)
+        with pytest.raises(ValueError, match="Policy"):
+            game.policy_to_svg([0.5] * 10)
+
+
+class TestMoveList:
+    def test_movelist_string(self) -> None:
+        game = Game.with_options(9, 9, 7.5, 0, 1000, False)
+        game.make_move(Move.place(2, 3))
+        game.make_move(Move.pass_move())
+
+        assert game.movelist_string() == "1. B C4  2. W pass"
+        assert game.movelist_string("index") == "1. B (2, 3)  2. W pass"
+
+    def test_unknown_style(self) -> None:
+        game = Game(9, 9)
+        with pytest.raises(ValueError, match="style"):
+            game.movelist_string("kifu")
+
+    def test_history(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 100, False)
+        assert game.history() == []
+        moves = [Move.place(1, 0), Move.place(0, 0), Move.pass_move(), Move.place(0, 1)]
+        for move in moves:
+            game.make_move(move)
+
+        history = game.history()
+        assert [move for move, _ in history] == moves
+        assert history[0][1][1, 0] is None
+        assert history[3][1][0, 0] == WHITE
+        # The capture shows up on the current board, not in the history
+        assert game[0, 0] is None
+        assert game.move_count() == 4
+
+
+class TestResign:
+    def test_resign_ends_game(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+
+        game.resign(BLACK)
+        assert game.is_over()
+        assert game.outcome().winner() == WHITE
+        assert game.legal_moves() == []
+
+    def test_resign_move_for_current_player(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.resign())
+        assert game.outcome().winner() == WHITE
+
+    def test_unmake_resign(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        game.resign(BLACK)
+
+        assert game.unmake_move()
+        assert not game.is_over()
+        assert game.turn() == WHITE
+        assert game.move_count() == 1
+
+
+class TestScoringPhase:
+    def _play_to_scoring(self) -> Game:
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        game.set_dead_stone_marking(True)
+        for row in range(5):
+            game.make_move(Move.place(2, row))
+            game.make_move(Move.place(3, row))
+        game.make_move(Move.pass_move())
+        game.make_move(Move.place(0, 2))
+        game.make_move(Move.pass_move())
+        game.make_move(Move.pass_move())
+        return game
+
+    def test_double_pass_enters_scoring(self) -> None:
+        game = self._play_to_scoring()
+        assert game.phase() == "scoring"
+        assert not game.is_over()
+        assert game.legal_moves() == []
+
+    def test_mark_dead_and_accept(self) -> None:
+        game = self._play_to_scoring()
+        assert game.score() == (5.0, 11.5)
+
+        assert game.mark_dead([(0, 2)])
+        assert game.is_dead(0, 2)
+        assert game.score() == (15.0, 10.5)
+
+        assert game.accept_score()
+        assert game.phase() == "over"
+        assert game.outcome().winner() == BLACK
+
+    def test_estimate_dead_stones_with_rollouts(self) -> None:
+        game = self._play_to_scoring()
+        assert game.estimate_dead_stones(rollouts=32, seed=1) == [(0, 2)]
+
+        details = game.score_with_rollouts(rollouts=32, seed=1)
+        assert (details.total(BLACK), details.total(WHITE)) == (15.0, 10.5)
+
+    def test_toggle_dead_group(self) -> None:
+        game = self._play_to_scoring()
+        assert game.toggle_dead_group(2, 2)
+        assert game.is_dead(2, 0)
+        assert game.toggle_dead_group(2, 2)
+        assert not game.is_dead(2, 0)
+        assert not game.toggle_dead_group(1, 1)
+
+    def test_ownership(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        for row in range(5):
+            game.make_move(Move.place(2, row))
+            game.make_move(Move.place(3, row))
+
+        ownership = game.ownership()
+        assert ownership.shape == (5, 5) and ownership.dtype == np.float32
+        assert (ownership[:, :3] == 1.0).all() and (ownership[:, 3:] == -1.0).all()
+        assert np.array_equal(game.ownership(WHITE), -ownership)
+
+    def test_pass_alive(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        for col in range(5):
+            game.make_move(Move.place(col, 1))
+            game.make_move(Move.place(col, 3))
+        game.make_move(Move.place(1, 0))
+        game.make_move(Move.place(1, 4))
+        game.make_move(Move.place(3, 0))
+        game.make_move(Move.place(3, 4))
+
+        black = game.pass_alive(BLACK)
+        assert black.shape == (5, 5) and black.dtype == np.bool_
+        assert black[:2].all() and not black[2:].any()
+        assert game.pass_alive(WHITE)[3:].all()
+        assert not game.pass_alive(WHITE)[2].any()
+
+
+class TestRandomPlayouts:
+    def test_random_playouts(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game.with_options(5, 5, min_moves_before_pass_possible=0)
+        game.make_move(Move.place(2, 2))
+        outcomes, ownership = game.random_playouts(16, seed=3)
+        assert outcomes.dtype == np.int64 and outcomes.shape == (3,)
+        assert outcomes.sum() == 16
+        assert ownership.dtype == np.float32 and ownership.shape == (5, 5)
+        threaded = game.random_playouts(16, seed=3, threads=4)
+        assert (threaded[0] == outcomes).all() and (threaded[1] == ownership).all()
+
+    def test_random_playout(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(5, 5)
+        game.make_move(Move.place(2, 2))
+        winner, ownership = game.random_playout(seed=3)
+        assert winner in (BLACK, WHITE, None)
+        assert ownership.dtype == np.float32 and ownership.shape == (5, 5)
+        assert set(ownership.flatten().tolist()) <= {-1.0, 0.0, 1.0}
+        again = game.random_playout(seed=3)
+        assert again[0] == winner and (again[1] == ownership).all()
+        assert game.move_count() == 1
+        game.random_playout()
+
+    def test_random_playouts_batch(self) -> None:
+        np = pytest.importorskip("numpy")
+        games = [
+            Game.with_options(5, 5, min_moves_before_pass_possible=0) for _ in range(3)
+        ]
+        outcomes, ownership = random_playouts_batch(games, 8, seed=1, threads=2)
+        assert outcomes.shape == (3, 3) and (outcomes.sum(axis=1) == 8).all()
+        assert ownership.shape == (3, 5, 5) and ownership.dtype == np.float32
+
+    def test_random_playouts_batch_needs_one_size(self) -> None:
+        with pytest.raises(ValueError):
+            random_playouts_batch([], 8)
+        with pytest.raises(ValueError):
+            random_playouts_batch([Game(5, 5), Game(7, 7)], 8)
+        with pytest.raises(ValueError):
+            random_playouts_batch([Game(5, 5)], 8, cycle_outcome="loop")
+
 
 class TestCaptures:
     def test_simple_capture_corner(self) -> None:
@@ -146,6 +501,8 @@ class TestCaptures:
 
         board = game.board()
         assert board.get_piece(0, 0) is None  # Captured
+        assert game.captures() == (1, 0)
+        assert "captures=(1, 0)" in repr(game)
 
     def test_capture_restores_on_unmake(self) -> None:
         game = Game(9, 9)
@@ -158,6 +515,105 @@ class TestCaptures:
 
         game.unmake_move()
         assert game.board().get_piece(0, 0) == WHITE
+        assert game.captures() == (0, 0)
+
+
+class TestGroups:
+    def test_group_and_liberties_at(self) -> None:
+        game = Game(9, 9)
+        for col, row in [(0, 0), (4, 4), (1, 0), (4, 3)]:
+            game.make_move(Move.place(col, row))
+
+        assert game.group_at(1, 0) == [(0, 0), (1, 0)]
+        assert game.group_at((4, 4)) == [(4, 3), (4, 4)]
+        assert game.liberties_at(0, 0) == 3
+        assert game.liberties_at(Position(4, 3)) == 6
+        assert game.group_at(5, 5) == []
+        assert game.liberties_at(5, 5) is None
+
+
+class TestRuleset:
+    def test_default_ruleset(self) -> None:
+        game = Game(9, 9)
+        assert game.ruleset() == "chinese"
+
+    def test_set_ruleset(self) -> None:
+        game = Game(9, 9)
+        game.set_ruleset("japanese")
+        assert game.ruleset() == "japanese"
+
+    def test_unknown_ruleset(self) -> None:
+        game = Game(9, 9)
+        with pytest.raises(ValueError, match="ruleset"):
+            game.set_ruleset("ing")
+
+    def test_japanese_scoring_ignores_stones(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        game.set_ruleset("japanese")
+        game.make_move(Move.place(2, 2))
+        game.make_move(Move.pass_move())
+        game.make_move(Move.pass_move())
+
+        # Black's lone stone surrounds the whole board: 24 points of territory
+        assert game.score() == (24.0, 0.5)
+        # The fast score always counts area.
+        assert game.score_fast() == (25.0, 0.5)
+
+    def test_aga_pass_stones(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 1000, False)
+        game.set_ruleset("aga")
+        game.make_move(Move.place(2, 2))
+        game.make_move(Move.pass_move())  # W
+        game.make_move(Move.pass_move())  # B - White must pass last
+        assert not game.is_over()
+
+        game.make_move(Move.pass_move())  # W
+        assert game.is_over()
+        # 24 points of territory plus two pass stones against one
+        assert game.score() == (26.0, 1.5)
+
+
+class TestHandicap:
+    def test_place_handicap(self) -> None:
+        game = Game.with_options(9, 9, 0.5, 0, 1000, True)
+        assert game.place_handicap(2)
+        assert game.handicap() == 2
+        assert game.turn() == WHITE
+        assert game.board().get_piece(2, 2) == BLACK
+        assert game.board().get_piece(6, 6) == BLACK
+
+    def test_compensation_depends_on_ruleset(self) -> None:
+        game = Game.with_options(9, 9, 0.5, 0, 1000, True)
+        game.place_handicap(3)
+        assert game.effective_komi() == 3.5
+
+        game.set_ruleset("japanese")
+        assert game.effective_komi() == 0.5
+
+    def test_handicap_requires_empty_board(self) -> None:
+        game = Game.with_options(9, 9, 0.5, 0, 1000, True)
+        game.make_move(Move.place(4, 4))
+        assert not game.place_handicap(2)
+
+    def test_with_options_rules_and_name(self) -> None:
+        game = Game.with_options(9, 9, 0.5, 0, 1000, True, handicap=2)
+        assert game.handicap() == 2
+        assert game.turn() == WHITE
+        assert game.name() == "go_9x9_h2_chinese"
+
+        game = Game.with_options(
+            13, 13, 6.5, 0, 1000, True, ruleset="japanese", scoring="marking"
+        )
+        assert game.ruleset() == "japanese"
+        assert game.dead_stone_marking()
+        assert game.name() == "go_13x13_japanese_marking"
+
+        with pytest.raises(ValueError):
+            Game.with_options(9, 9, 0.5, 0, 1000, True, handicap=1)
+        with pytest.raises(ValueError):
+            Game.with_options(9, 9, 0.5, 0, 1000, True, ruleset="ing")
+        with pytest.raises(ValueError):
+            Game.with_options(9, 9, 0.5, 0, 1000, True, scoring="stones")
 
 
 class TestKoRule:
@@ -225,6 +681,47 @@ class TestSuicide:
         assert game.board().get_piece(1, 1) is None  # W(1,1) captured
         assert game.board().get_piece(2, 1) == BLACK  # B(2,1) survives
 
+    def test_suicide_allowed_when_enabled(self) -> None:
+        game = Game.with_options(5, 5, 7.5, 0, 1000, True)
+        game.set_allow_suicide(True)
+        assert game.allow_suicide()
+
+        game.make_move(Move.place(1, 0))  # B
+        game.make_move(Move.place(0, 0))  # W
+        game.make_move(Move.place(1, 1))  # B
+        game.make_move(Move.pass_move())  # W
+        game.make_move(Move.place(0, 2))  # B
+
+        # White (0, 1) leaves the W(0,0)-W(0,1) group without liberties
+        assert game.is_legal_move(Move.place(0, 1))
+        game.make_move(Move.place(0, 1))
+
+        assert game.board().get_piece(0, 0) is None
+        assert game.board().get_piece(0, 1) is None
+
+    def test_swap_rule(self) -> None:
+        game = Game.with_options(5, 5, 7.5, 0, 1000, True)
+        assert game.total_actions() == 26
+        game.set_swap_rule(True)
+        assert game.swap_rule()
+        assert game.total_actions() == 27
+
+        game.make_move(Move.place(2, 2))
+        swap = Move.swap()
+        assert swap.is_swap()
+        assert swap.encode(5, 5) in game.legal_action_indices()
+        assert game.decode_action(26) == swap
+
+        game.make_move(swap)
+        assert game.board().get_piece(2, 2) == WHITE
+        assert game.turn() == BLACK
+        assert game.komi() == -7.5
+        assert not game.is_legal_move(swap)
+
+        assert game.unmake_move()
+        assert game.board().get_piece(2, 2) == BLACK
+        assert game.komi() == 7.5
+
 
 class TestGameClone:
     def test_clone(self) -> None:
@@ -264,3 +761,147 @@ class TestGameHash:
         game2.make_move(Move.place(4, 4))
 
         assert hash(game1) != hash(game2)
+
+    def test_position_hash_transposes(self) -> None:
+        game1 = Game(9, 9)
+        game2 = Game(9, 9)
+        for col, row in [(2, 2), (6, 6), (2, 6)]:
+            game1.make_move(Move.place(col, row))
+        for col, row in [(2, 6), (6, 6), (2, 2)]:
+            game2.make_move(Move.place(col, row))
+
+        assert game1.position_hash() == game2.position_hash()
+        game1.make_move(Move.pass_move())
+        assert game1.position_hash() != game2.position_hash()
+        assert 0 <= game1.position_hash() < 2**64
+
+
+class TestSgf:
+    def test_from_sgf(self) -> None:
+        game = Game.from_sgf("(;GM[1]SZ[9]KM[6.5]HA[2]AB[cg][gc];W[ee];B[ce]C[x])")
+        assert game.komi() == 6.5
+        assert game.handicap() == 2
+        assert game.board().get_piece(2, 2) == BLACK
+        assert game.board().get_piece(4, 4) == WHITE
+        assert game.move_count() == 2
+        assert game.board().get_piece(2, 4) == BLACK
+
+    def test_from_sgf_rejects_malformed_input(self) -> None:
+        with pytest.raises(ValueError, match="syntax"):
+            Game.from_sgf("(;SZ[9];B[ee]")
+        with pytest.raises(ValueError):
+            Game.from_sgf("(;SZ[9];B[ee];W[ee])")
+
+    def test_to_sgf_round_trip(self) -> None:
+        game = Game.with_options(9, 9, 6.5, 0, 100, True)
+        game.make_move(Move.place(4, 4))
+        game.make_move(Move.pass_move())
+        sgf = game.to_sgf()
+        assert "SZ[9]KM[6.5]" in sgf
+        assert ";B[ee]" in sgf
+        assert ";W[]" in sgf
+
+        copy = Game.from_sgf(sgf)
+        assert copy.move_count() == 2
+        assert copy.board().get_piece(4, 4) == BLACK
+
+    def test_handicap_round_trip(self) -> None:
+        game = Game.from_sgf(
+            "(;GM[1]SZ[9]KM[0.5]HA[2]RU[Japanese]PB[Alice]AB[cg][gc];W[ee];B[ce])"
+        )
+        copy = Game.from_sgf(game.to_sgf())
+        assert (copy.handicap(), copy.komi(), copy.ruleset()) == (2, 0.5, "japanese")
+        assert copy.info().black_player() == "Alice"
+        assert copy.to_position_string() == game.to_position_string()
+        assert copy.move_count() == 2
+
+    def test_game_info_round_trip(self) -> None:
+        game = Game(9, 9)
+        assert game.info().black_player() is None
+        game.set_info(GameInfo(black_player="Alice", white_rank="3k", date="2024-05-01"))
+
+        copy = Game.from_sgf(game.to_sgf())
+        assert copy.info() == game.info()
+        assert copy.info().black_player() == "Alice"
+        assert copy.info().white_rank() == "3k"
+
+
+class TestPositionString:
+    def test_round_trip(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        game.make_move(Move.place(2, 6))
+        text = game.to_position_string()
+        assert text == "9/9/2W6/9/4B4/9/9/9/9 b - 0 0 2"
+
+        copy = Game.from_position_string(text)
+        assert copy.board().get_piece(2, 6) == WHITE
+        assert copy.move_number() == 2
+        assert copy.move_count() == 0
+        assert copy.to_position_string() == text
+
+    def test_rejects_malformed_strings(self) -> None:
+        with pytest.raises(ValueError, match="fields"):
+            Game.from_position_string("9/9 b -")
+
+
+class TestAnalyzeMove:
+    def test_capture_preview(self) -> None:
+        game = Game.with_options(5, 5, 0.5, 0, 100, True)
+        for col, row in [(2, 1), (2, 2), (1, 2), (3, 3), (2, 3), (4, 4)]:
+            game.make_move(Move.place(col, row))
+
+        analysis = game.analyze_move(Move.place(3, 2), depth=0)
+        assert analysis is not None
+        assert analysis.captures() == 1
+        assert analysis.liberties() == 3
+        assert not analysis.self_atari()
+        assert game.analyze_move(Move.place(2, 2)) is None
+        # Analysis does not change the game
+        assert game.move_count() == 6
+
+    def test_count_ko_threats(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(8, 8))
+        game.make_move(Move.place(0, 0))
+        assert game.count_ko_threats(BLACK) == 1
+        assert game.count_ko_threats(WHITE) == 1
+
+    def test_opening_tree(self) -> None:
+        game = Game(3, 3)
+        tree = game.opening_tree(2)
+        assert len(tree) == 16
+        assert tree[0] == []
+        assert len(game.opening_tree(1, symmetry_reduce=False)) == 10
+
+
+class TestSetPosition:
+    def test_ko_position(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(4, 4)
+        game.make_move(Move.place(3, 3))
+        board = np.zeros((4, 4), dtype=np.int8)
+        for col, row in [(1, 0), (0, 1), (1, 2)]:
+            board[row, col] = BLACK
+        for col, row in [(2, 0), (3, 1), (2, 2), (1, 1)]:
+            board[row, col] = WHITE
+
+        game.set_position(board, BLACK, ko=(2, 1), move_number=10)
+        assert game.move_count() == 0
+        assert game.turn() == BLACK
+        assert game[1, 1] == WHITE
+        assert game[3, 3] is None
+        assert not game.is_legal_move(Move.place(2, 1))
+        assert np.array_equal(game.board().to_numpy(), board)
+
+    def test_rejects_invalid_positions(self) -> None:
+        np = pytest.importorskip("numpy")
+        game = Game(3, 3)
+        with pytest.raises(ValueError):
+            game.set_position(np.zeros((4, 4)), BLACK)
+        with pytest.raises(ValueError):
+            game.set_position(np.ones((3, 3)), WHITE)
+        with pytest.raises(ValueError):
+            game.set_position(np.zeros((3, 3)), BLACK, ko=Position(5, 5))
+        with pytest.raises(InvalidPlayerError):
+            game.set_position(np.zeros((3, 3)), 0)
@@ -126,6 +126,24 @@ class TestGtpEngineGenmove:
         engine.quit()
 
 
+class TestGtpEngineScoring:
+    def test_final_status_list(self) -> None:
+        engine = make_engine()
+        engine.play(Move.place(4, 4))
+        assert engine.final_status_list("dead") == []
+        assert (4, 4) in engine.final_status_list("alive")
+        with pytest.raises(ValueError):
+            engine.final_status_list("unknown")
+        engine.quit()
+
+    def test_genmove_cleanup(self) -> None:
+        engine = make_engine()
+        result = engine.genmove_cleanup()
+        assert result is not None
+        assert engine.turn() == WHITE
+        engine.quit()
+
+
 class TestGtpEngineGenmoveAs:
     def test_genmove_as_black(self) -> None:
         engine = make_engine()
@@ -16,6 +16,35 @@ class TestMoveCreation:
         assert move.col() is None
         assert move.row() is None
 
+    def test_resign_move(self) -> None:
+        move = Move.resign()
+        assert move.is_resign()
+        assert not move.is_pass()
+        assert move.col() is None
+        assert repr(move) == "Move.resign()"
+
+
+class TestMoveGtp:
+    def test_to_gtp(self) -> None:
+        assert Move.place(3, 3).to_gtp(19) == "D4"
+        assert Move.place(8, 0).to_gtp(9) == "J1"
+        assert Move.pass_move().to_gtp(9) == "pass"
+
+    def test_from_gtp(self) -> None:
+        assert Move.from_gtp("q16", 19, 19) == Move.place(15, 15)
+        assert Move.from_gtp("pass", 9, 9).is_pass()
+
+    def test_round_trip(self) -> None:
+        moves = [Move.place(0, 0), Move.place(18, 18), Move.resign(), Move.swap()]
+        for move in moves:
+            assert Move.from_gtp(move.to_gtp(19), 19, 19) == move
+
+    def test_from_gtp_invalid(self) -> None:
+        with pytest.raises(ValueError):
+            Move.from_gtp("I5", 19, 19)
+        with pytest.raises(ValueError):
+            Move.from_gtp("Q16", 9, 9)
+
 
 class TestMoveEquality:
     def test_place_moves_equal(self) -> None:
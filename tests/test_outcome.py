@@ -1,4 +1,6 @@
-from spooky_go import BLACK, WHITE, Game, Move
+import pytest
+
+from spooky_go import BLACK, WHITE, Game, GameOutcome, Move
 
 
 def _game_with_pass(width: int = 9, height: int = 9, komi: float = 7.5) -> Game:
@@ -68,12 +70,67 @@ class TestOutcomeProperties:
         assert outcome.winner() == BLACK
         assert outcome.encode_winner_absolute() == 1.0
 
+    def test_margin_and_reason_after_double_pass(self) -> None:
+        game = _game_with_pass()
+        game.make_move(Move.pass_move())
+        game.make_move(Move.pass_move())
+
+        outcome = game.outcome()
+        assert outcome is not None
+        assert outcome.reason() == "double_pass"
+        assert outcome.margin() == -7.5
+        assert outcome.margin_half_points() == -15
+        assert game.score_half_points() == (0, 15)
+        assert outcome.margin_from_perspective(WHITE) == 7.5
+        assert str(outcome) == "White wins by 7.5"
+
+    def test_resignation_has_no_margin(self) -> None:
+        game = Game(9, 9)
+        game.resign(WHITE)
+
+        outcome = game.outcome()
+        assert outcome is not None
+        assert outcome.winner() == BLACK
+        assert outcome.reason() == "resignation"
+        assert outcome.margin() is None
+
+    def test_result_string(self) -> None:
+        game = _game_with_pass()
+        game.make_move(Move.pass_move())
+        game.make_move(Move.pass_move())
+
+        outcome = game.outcome()
+        assert outcome is not None
+        assert outcome.to_result_string() == "W+7.5"
+        assert GameOutcome.from_result_string("W+7.5") == outcome
+
+    def test_parse_resignation_result(self) -> None:
+        outcome = GameOutcome.from_result_string("B+R")
+        assert outcome.winner() == BLACK
+        assert outcome.reason() == "resignation"
+
+        with pytest.raises(ValueError, match="result"):
+            GameOutcome.from_result_string("nonsense")
+
     def test_score_method(self) -> None:
         game = Game.with_options(5, 5, 7.5, 0, 1000, True)
         black_score, white_score = game.score()
         assert black_score == 0.0  # Empty board, no Black stones/territory
         assert white_score == 7.5  # Just komi
 
+    def test_score_details(self) -> None:
+        game = Game.with_options(5, 5, 7.5, 0, 1000, True)
+        game.make_move(Move.place(2, 2))  # Black
+
+        details = game.score_details()
+        assert details.stones(BLACK) == 1
+        assert details.territory(BLACK) == 24
+        assert details.territory(WHITE) == 0
+        assert details.dame() == 0
+        assert details.komi() == 7.5
+        assert details.ruleset() == "chinese"
+        assert (details.total(BLACK), details.total(WHITE)) == game.score()
+
 
 class TestOutcomeEquality:
     def test_same_outcomes_equal(self) -> None:
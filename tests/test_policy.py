@@ -0,0 +1,174 @@
+import io
+import sys
+from pathlib import Path
+
+import pytest
+
+from spooky_go import (
+    Game,
+    GameRecord,
+    HeuristicPolicy,
+    MctsPolicy,
+    Move,
+    NoEyeFillPolicy,
+    Policy,
+    UniformPolicy,
+    decode_wire_messages,
+    play_interactive,
+)
+
+
+def _small_game() -> Game:
+    return Game.with_options(5, 5, 0.5, 0, 100, True)
+
+
+class FirstLegalMove(Policy):
+    def __init__(self) -> None:
+        self.calls = 0
+
+    def choose(self, game: Game) -> Move:
+        self.calls += 1
+        return game.legal_moves()[0]
+
+
+class TestBuiltinPolicies:
+    def test_builtins_choose_legal_moves(self) -> None:
+        game = _small_game()
+        for policy in [
+            UniformPolicy(),
+            NoEyeFillPolicy(seed=1),
+            HeuristicPolicy(),
+            MctsPolicy(iterations=50),
+        ]:
+            assert isinstance(policy, Policy)
+            assert game.is_legal_move(policy.choose(game))
+
+    def test_mcts_cycle_options(self) -> None:
+        game = _small_game()
+        policy = MctsPolicy(iterations=50, cycle_window=8, cycle_outcome="draw")
+        assert game.is_legal_move(policy.choose(game))
+        with pytest.raises(ValueError):
+            MctsPolicy(cycle_outcome="loop")
+
+    def test_mcts_from_config(self, tmp_path: Path) -> None:
+        path = tmp_path / "experiment.toml"
+        path.write_text('[search]\niterations = 50\ncycle_outcome = "draw"\n\n[selfplay]\nseed = 4\n')
+        policy = MctsPolicy.from_config(str(path))
+        assert isinstance(policy, MctsPolicy)
+        assert _small_game().is_legal_move(policy.choose(_small_game()))
+        path.write_text("[search]\niterations = 0\n")
+        with pytest.raises(ValueError, match="iterations"):
+            MctsPolicy.from_config(str(path))
+
+    def test_base_policy_is_abstract(self) -> None:
+        with pytest.raises(NotImplementedError):
+            Policy().choose(_small_game())
+
+
+class TestPlayMatch:
+    def test_python_policy_against_builtin(self) -> None:
+        agent = FirstLegalMove()
+        result = _small_game().play_match(agent, HeuristicPolicy(), num_games=4, seed=3)
+
+        assert result.num_games() == 4
+        assert result.first_wins() + result.second_wins() + result.draws() == 4
+        assert agent.calls > 0
+        records = result.records()
+        assert [r.index() for r in records] == [0, 1, 2, 3]
+        assert all(r.outcome() is not None for r in records)
+
+    def test_match_is_reproducible(self) -> None:
+        game = _small_game()
+        a = game.play_match(UniformPolicy(), NoEyeFillPolicy(), num_games=2, seed=5)
+        b = game.play_match(UniformPolicy(), NoEyeFillPolicy(), num_games=2, seed=5)
+        assert [r.moves() for r in a.records()] == [r.moves() for r in b.records()]
+
+    def test_policy_errors_are_raised(self) -> None:
+        class Broken(Policy):
+            def choose(self, game: Game) -> Move:
+                raise RuntimeError("boom")
+
+        with pytest.raises(RuntimeError, match="boom"):
+            _small_game().play_match(Broken(), UniformPolicy())
+
+    def test_rejects_object_without_choose(self) -> None:
+        with pytest.raises(TypeError):
+            _small_game().play_match(object(), UniformPolicy())  # type: ignore[arg-type]
+
+
+class TestSelfPlay:
+    def test_self_play_records(self) -> None:
+        records = _small_game().self_play(NoEyeFillPolicy(), num_games=3, seed=1)
+        assert len(records) == 3
+        assert records[0].seed() != records[1].seed()
+        assert all(r.outcome() is not None for r in records)
+
+    def test_write_leela_zero(self, tmp_path: Path) -> None:
+        game = Game.with_options(19, 19, 7.5, 0, 40, True)
+        records = game.self_play(UniformPolicy(), num_games=2, seed=3)
+        path = tmp_path / "games.txt"
+        positions = game.write_leela_zero(records, str(path))
+        lines = path.read_text().splitlines()
+        assert positions > 0
+        assert len(lines) == 19 * positions
+
+        with pytest.raises(ValueError):
+            _small_game().write_leela_zero(records, str(path))
+
+    def test_wire_messages_round_trip(self) -> None:
+        records = _small_game().self_play(NoEyeFillPolicy(), num_games=2, seed=4)
+        data = b"".join(r.to_wire() for r in records)
+
+        decoded, used = decode_wire_messages(data + data[:5])
+        assert used == len(data)
+        assert all(isinstance(r, GameRecord) for r in decoded)
+        assert [r.index() for r in decoded] == [0, 1]
+
+        with pytest.raises(ValueError):
+            decode_wire_messages(b"\x02\x00\x00\x00\x09\x01")
+
+
+class TestPlayInteractive:
+    def _play(self, monkeypatch: pytest.MonkeyPatch, lines: list[str], **kwargs) -> tuple[Game, str]:
+        replies = iter(lines)
+
+        def fake_input(prompt: str = "") -> str:
+            try:
+                return next(replies)
+            except StopIteration:
+                raise EOFError from None
+
+        out = io.StringIO()
+        monkeypatch.setattr("builtins.input", fake_input)
+        monkeypatch.setattr(sys, "stdout", out)
+        game = play_interactive(**kwargs)
+        return game, out.getvalue()
+
+    def test_plays_against_builtin(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        game, text = self._play(
+            monkeypatch, ["e5", "z9", "undo", "c3", "quit"], policy="uniform", seed=2
+        )
+
+        assert "unknown move: z9" in text
+        assert "White plays" in text
+        assert game.move_count() == 2
+        assert game.board().get_piece(2, 2) == 1
+
+    def test_python_policy_and_two_humans(self, monkeypatch: pytest.MonkeyPatch) -> None:
+        agent = FirstLegalMove()
+        game, _ = self._play(monkeypatch, ["b2"], size=5, policy=agent, computer="black")
+        # The computer opens and answers b2 before the input runs out.
+        assert agent.calls == 2
+        assert game.move_count() == 3
+
+        game, text = self._play(monkeypatch, ["a1", "b1", "a1"], size=5, computer=None)
+        assert "illegal move: a1" in text
+        assert game.move_count() == 2
+
+    def test_rejects_bad_arguments(self) -> None:
+        with pytest.raises(ValueError):
+            play_interactive(policy="alphago")
+        with pytest.raises(ValueError):
+            play_interactive(computer="red")
+        with pytest.raises(ValueError):
+            play_interactive(theme="html")
@@ -0,0 +1,97 @@
+import pytest
+
+from spooky_go import BLACK, WHITE, Board, Game, Move, Position
+
+
+class TestPosition:
+    def test_accessors(self) -> None:
+        pos = Position(3, 5)
+        assert pos.col() == 3
+        assert pos.row() == 5
+        assert pos.to_tuple() == (3, 5)
+        assert repr(pos) == "Position(3, 5)"
+
+    def test_index_round_trip(self) -> None:
+        pos = Position(3, 5)
+        assert pos.to_index(9) == 48
+        assert Position.from_index(48, 9) == pos
+        assert hash(Position.from_index(48, 9)) == hash(pos)
+
+    def test_is_valid(self) -> None:
+        assert Position(8, 8).is_valid(9, 9)
+        assert not Position(9, 0).is_valid(9, 9)
+        assert not Position(0, 5).is_valid(9, 5)
+        # Sizes past 255 are rejected rather than wrapped around.
+        with pytest.raises(OverflowError):
+            Position(0, 0).is_valid(256, 9)
+
+    def test_gtp(self) -> None:
+        assert Position(3, 3).to_gtp(19) == "D4"
+        assert Position(8, 0).to_gtp(9) == "J1"
+        assert Position.from_gtp("d4", 19, 19) == Position(3, 3)
+        with pytest.raises(ValueError, match="GTP"):
+            Position.from_gtp("Z99", 19, 19)
+
+
+class TestPointArguments:
+    def test_board_accepts_points(self) -> None:
+        board = Board(9, 9)
+        board.set_piece(Position(2, 3), BLACK)
+        board.set_piece((4, 5), WHITE)
+        assert board.get_piece(2, 3) == BLACK
+        assert board.get_piece((4, 5)) == WHITE
+        assert board.get_piece(Position(4, 5)) == WHITE
+        board.set_piece((4, 5), None)
+        assert board.get_piece(4, 5) is None
+
+    def test_game_accepts_points(self) -> None:
+        game = Game(9, 9)
+        game.set_piece(Position(0, 0), BLACK)
+        assert game.get_piece((0, 0)) == BLACK
+        assert not game.is_dead(Position(0, 0))
+
+    def test_move_place_accepts_points(self) -> None:
+        assert Move.place(Position(4, 5)) == Move.place(4, 5)
+        assert Move.place((4, 5)) == Move.place(4, 5)
+        assert Move.place(4, 5).position() == Position(4, 5)
+        assert Move.pass_move().position() is None
+
+    def test_bad_points(self) -> None:
+        board = Board(9, 9)
+        with pytest.raises(TypeError):
+            board.get_piece("D4")
+        with pytest.raises(TypeError):
+            board.get_piece(1)
+        with pytest.raises(TypeError):
+            board.get_piece(1, 2, 3)
+        with pytest.raises(TypeError):
+            board.set_piece(Position(1, 2))
+
+
+class TestIndexing:
+    def test_board_indexing(self) -> None:
+        board = Board(9, 9)
+        board[(2, 3)] = BLACK
+        board[Position(4, 5)] = WHITE
+        assert board[(2, 3)] == BLACK
+        assert board[Position(4, 5)] == WHITE
+        assert board.get_piece(4, 5) == WHITE
+        board[(4, 5)] = None
+        assert board[(4, 5)] is None
+
+    def test_game_indexing_is_read_only(self) -> None:
+        game = Game(9, 9)
+        game.make_move(Move.place(4, 4))
+        assert game[(4, 4)] == BLACK
+        assert game[Position(0, 0)] is None
+        with pytest.raises(TypeError):
+            game[(0, 0)] = WHITE  # type: ignore[index]
+
+    def test_index_off_board(self) -> None:
+        board = Board(9, 9)
+        with pytest.raises(IndexError, match="off the 9x9 board"):
+            board[(9, 0)]
+        with pytest.raises(IndexError):
+            board[(0, 9)] = BLACK
+        with pytest.raises(IndexError):
+            Game(5, 5)[(5, 5)]